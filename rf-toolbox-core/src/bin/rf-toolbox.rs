@@ -0,0 +1,1184 @@
+use clap::{Args, Parser, Subcommand};
+use rf_toolbox_core::conjugate::{calc_match, calc_match_touchstone};
+use rf_toolbox_core::differential::{
+    calc_balanced_port, calc_balun, calc_mixed_mode, design_balun, PortPairing,
+};
+use rf_toolbox_core::element::{
+    balun::BalunType,
+    measured::{read_library, PartFile},
+    ElementType,
+};
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::frequency::FrequencySweep;
+use rf_toolbox_core::matching::ladder::{calc_ladder, Topology};
+use rf_toolbox_core::matching::xfmr::calc_xfmr;
+use rf_toolbox_core::matching::{calc_networks, ImpedanceType};
+use rf_toolbox_core::mna::{calc_nport, Param};
+use rf_toolbox_core::rf_utils::{calc_impedance, ComplexType, ImpedanceFormat, ZScale};
+use rf_toolbox_core::richards::calc_richards;
+use rf_toolbox_core::smith::calc_smith_cascade;
+use rf_toolbox_core::spice::{
+    network_netlist, network_solution, parse_circuit, parse_ladder, schematic_netlist, Network,
+    DEFAULT_PORTS,
+};
+use rf_toolbox_core::touchstone::ports_from_path;
+use rf_toolbox_core::unit::Unit;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{stdout, ErrorKind, Read, Write};
+use std::process::ExitCode;
+
+fn default_z0() -> f64 {
+    50.0
+}
+
+fn default_f_scale() -> Unit {
+    Unit::Giga
+}
+
+fn default_c_scale() -> Unit {
+    Unit::Femto
+}
+
+fn default_l_scale() -> Unit {
+    Unit::Pico
+}
+
+fn default_imp() -> ImpedanceFormat {
+    ImpedanceFormat::Z
+}
+
+fn default_network_imp() -> ImpedanceType {
+    ImpedanceType::ZReIm
+}
+
+fn default_z_scale() -> ZScale {
+    ZScale::SingleEnded
+}
+
+fn default_match_imp() -> ComplexType {
+    ComplexType::MagAng
+}
+
+fn default_ports() -> Vec<String> {
+    DEFAULT_PORTS.map(String::from).to_vec()
+}
+
+fn default_spice_name() -> String {
+    "smith_chart".to_string()
+}
+
+fn default_rin() -> f64 {
+    1.0
+}
+
+fn default_er() -> f64 {
+    1.0
+}
+
+fn default_z_diff() -> f64 {
+    100.0
+}
+
+fn default_z0e() -> f64 {
+    150.0
+}
+
+fn default_points() -> usize {
+    101
+}
+
+fn default_param() -> Param {
+    Param::S
+}
+
+fn default_length_unit() -> Unit {
+    Unit::Milli
+}
+
+#[derive(Parser)]
+#[command(
+    name = "rf-toolbox",
+    version,
+    about = "Headless RF toolbox calculators"
+)]
+struct Cli {
+    #[arg(long, global = true, help = "Print JSON instead of a table")]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Convert an impedance or reflection coefficient (calc_impedance)")]
+    Impedance(ImpedanceArgs),
+    #[command(about = "Calculate the lumped matching networks (calc_networks)")]
+    Networks(NetworksArgs),
+    #[command(about = "Solve a ladder of series/shunt L and C, e.g. sC-pL-sL-pC (calc_ladder)")]
+    Ladder(LadderArgs),
+    #[command(about = "Transformer with a tuning capacitor across each winding (calc_xfmr)")]
+    Xfmr(XfmrArgs),
+    #[command(about = "Simultaneous conjugate match of a 2-port (calc_match)")]
+    Match(MatchArgs),
+    #[command(about = "Conjugate match of a Touchstone 2-port, or of Sdd with paired ports")]
+    MatchFile(MatchFileArgs),
+    #[command(about = "Mixed-mode S-parameters and impedances of a balanced port")]
+    Balanced(BalancedArgs),
+    #[command(about = "Mixed-mode S-parameters of a Touchstone file with paired ports")]
+    MixedMode(MixedModeArgs),
+    #[command(about = "Single-ended to differential response and imbalance of a balun")]
+    Balun(BalunArgs),
+    #[command(about = "Lattice or Marchand balun between real terminations")]
+    BalunDesign(BalunDesignArgs),
+    #[command(about = "Impedance after each element of a Smith chart schematic")]
+    Smith(SmithArgs),
+    #[command(about = "Stubs and unit elements of a lumped L/C ladder by Richards and Kuroda")]
+    Richards(RichardsArgs),
+    #[command(about = "Nominal values and files of a vendor part library index")]
+    Parts(PartsArgs),
+    #[command(about = "SPICE subcircuit of a calc_networks topology")]
+    SpiceNetwork(SpiceNetworkArgs),
+    #[command(about = "SPICE subcircuit of a saved Smith chart schematic")]
+    SpiceSchematic(SpiceSchematicArgs),
+    #[command(about = "Series/shunt cascade of a two-port SPICE subcircuit")]
+    SpiceImport(SpiceImportArgs),
+    #[command(about = "N-port S, Y or Z parameters of a SPICE subcircuit by nodal analysis")]
+    Nport(NportArgs),
+    #[command(about = "Run a JSON list of {\"command\": ..., \"args\": {...}} jobs")]
+    Batch {
+        #[arg(help = "JSON file, or - for stdin")]
+        file: String,
+    },
+}
+
+// Field names follow the arguments of the matching Tauri commands so that batch files can be
+// written the same way as the frontend invokes them
+#[derive(Args, Deserialize)]
+struct ImpedanceArgs {
+    #[arg(long, allow_negative_numbers = true)]
+    re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    im: f64,
+    #[arg(long, default_value = "z", help = "z, ri, ma, db or rc")]
+    #[serde(default = "default_imp")]
+    imp: ImpedanceFormat,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    c_scale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct NetworksArgs {
+    #[arg(long, allow_negative_numbers = true)]
+    rs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    rl: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xl: f64,
+    #[arg(long, default_value = "zri", help = "zri, yri, gma, gri or rc")]
+    #[serde(default = "default_network_imp")]
+    imp: ImpedanceType,
+    #[arg(long, default_value_t = 0.0)]
+    #[serde(default)]
+    q_net: f64,
+    #[arg(long, default_value_t = 0.0)]
+    #[serde(default)]
+    q: f64,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    c_scale: Unit,
+    #[arg(long, default_value = "pH")]
+    #[serde(default = "default_l_scale")]
+    l_scale: Unit,
+    #[arg(long, default_value = "se", help = "se or diff")]
+    #[serde(default = "default_z_scale")]
+    z_scale: ZScale,
+}
+
+#[derive(Args, Deserialize)]
+struct LadderArgs {
+    #[arg(long, help = "Elements from source to load, e.g. sC-pL-sL-pC")]
+    topology: Topology,
+    #[arg(long, allow_negative_numbers = true)]
+    rs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    rl: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xl: f64,
+    #[arg(long, default_value = "zri", help = "zri, yri, gma, gri or rc")]
+    #[serde(default = "default_network_imp")]
+    imp: ImpedanceType,
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        help = "Q of the split element in odd ladders"
+    )]
+    #[serde(default)]
+    q: f64,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    c_scale: Unit,
+    #[arg(long, default_value = "pH")]
+    #[serde(default = "default_l_scale")]
+    l_scale: Unit,
+    #[arg(long, default_value = "se", help = "se or diff")]
+    #[serde(default = "default_z_scale")]
+    z_scale: ZScale,
+}
+
+#[derive(Args, Deserialize)]
+struct XfmrArgs {
+    #[arg(long, allow_negative_numbers = true)]
+    rs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xs: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    rl: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    xl: f64,
+    #[arg(long, default_value = "zri", help = "zri, yri, gma, gri or rc")]
+    #[serde(default = "default_network_imp")]
+    imp: ImpedanceType,
+    #[arg(long, help = "Coupling factor between the windings")]
+    k: f64,
+    #[arg(long)]
+    q_net: f64,
+    #[arg(long, default_value_t = 0.0, help = "Winding Q, 0 for lossless")]
+    #[serde(default)]
+    q: f64,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    c_scale: Unit,
+    #[arg(long, default_value = "pH")]
+    #[serde(default = "default_l_scale")]
+    l_scale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct MatchArgs {
+    #[arg(long, allow_negative_numbers = true)]
+    s11re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s11im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s12re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s12im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s21re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s21im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s22re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s22im: f64,
+    #[arg(long, default_value = "ma", help = "ri, ma or db")]
+    #[serde(default = "default_match_imp")]
+    imp: ComplexType,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    fscale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    cscale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct MatchFileArgs {
+    #[arg(long, help = "Touchstone .sNp file")]
+    file: String,
+    #[arg(long, help = "Terminals of the two balanced ports, e.g. 1-3,2-4")]
+    #[serde(default)]
+    pairing: Option<PortPairing>,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    fscale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    cscale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct BalancedArgs {
+    #[arg(long, allow_negative_numbers = true)]
+    s11re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s11im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s12re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s12im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s21re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s21im: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s22re: f64,
+    #[arg(long, allow_negative_numbers = true)]
+    s22im: f64,
+    #[arg(long, default_value = "ma", help = "ri, ma or db")]
+    #[serde(default = "default_match_imp")]
+    imp: ComplexType,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+}
+
+// Reads the Touchstone file itself where the Tauri command is passed its text
+#[derive(Args, Deserialize)]
+struct MixedModeArgs {
+    #[arg(long, help = "Touchstone .sNp file")]
+    file: String,
+    #[arg(long, help = "Terminals of each balanced port, e.g. 1-3,2-4")]
+    pairing: PortPairing,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct PartsArgs {
+    #[arg(long, help = "Library index .json")]
+    file: String,
+}
+
+#[derive(Args, Deserialize)]
+struct SmithArgs {
+    #[arg(
+        long = "element",
+        required = true,
+        help = "Element as code:vals:units, e.g. pi:20,10:Q,pH (repeat, load first)"
+    )]
+    elements: Vec<String>,
+    #[arg(long, default_value_t = 1.0, allow_negative_numbers = true)]
+    #[serde(default = "default_rin")]
+    rin: f64,
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    #[serde(default)]
+    xin: f64,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    freq_unit: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct RichardsArgs {
+    #[arg(
+        long = "element",
+        required = true,
+        help = "Series or shunt L or C as code:vals:units, e.g. si:0,1.6:Q,nH (repeat)"
+    )]
+    elements: Vec<String>,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+    #[arg(long, help = "Design frequency, where the stubs are λ/8 long")]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    freq_unit: Unit,
+    #[arg(long, default_value_t = default_er())]
+    #[serde(default = "default_er")]
+    er: f64,
+    #[arg(long, default_value = "mm")]
+    #[serde(default = "default_length_unit")]
+    length_unit: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct BalunArgs {
+    #[arg(
+        long,
+        help = "Balun as code:vals:units, e.g. balun:5.63,2.25:lattice,nH,pF or balun:150,50,2:marchand,GHz"
+    )]
+    element: String,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z_se: f64,
+    #[arg(long, default_value_t = default_z_diff())]
+    #[serde(default = "default_z_diff")]
+    z_diff: f64,
+    #[arg(long)]
+    start: f64,
+    #[arg(long)]
+    stop: f64,
+    #[arg(long, default_value_t = default_points())]
+    #[serde(default = "default_points")]
+    points: usize,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    freq_unit: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct BalunDesignArgs {
+    #[arg(long, help = "lattice or marchand")]
+    kind: BalunType,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z_se: f64,
+    #[arg(long, default_value_t = default_z_diff())]
+    #[serde(default = "default_z_diff")]
+    z_diff: f64,
+    #[arg(
+        long,
+        default_value_t = default_z0e(),
+        help = "Even mode impedance of Marchand sections"
+    )]
+    #[serde(default = "default_z0e")]
+    z0e: f64,
+    #[arg(long)]
+    freq: f64,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    f_scale: Unit,
+    #[arg(long, default_value = "fF")]
+    #[serde(default = "default_c_scale")]
+    c_scale: Unit,
+    #[arg(long, default_value = "pH")]
+    #[serde(default = "default_l_scale")]
+    l_scale: Unit,
+}
+
+#[derive(Args, Deserialize)]
+struct SpiceNetworkArgs {
+    #[arg(long, help = "Topology, e.g. hp1, pi_lp or lp_ell_cl_w_q")]
+    network: Network,
+    #[arg(long, default_value_t = 0, help = "Index of the solution to export")]
+    #[serde(default)]
+    solution: usize,
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        help = "Capacitor Q for a series loss resistance, 0 for none"
+    )]
+    #[serde(default)]
+    q_c: f64,
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        help = "Inductor Q for a series loss resistance, 0 for none"
+    )]
+    #[serde(default)]
+    q_l: f64,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "in,out",
+        help = "Source and load port names"
+    )]
+    #[serde(default = "default_ports")]
+    ports: Vec<String>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    networks: NetworksArgs,
+}
+
+#[derive(Args, Deserialize)]
+struct SpiceSchematicArgs {
+    #[arg(long, help = "Schematic JSON as downloaded from the Smith chart tool")]
+    file: String,
+    #[arg(long, default_value_t = default_spice_name())]
+    #[serde(default = "default_spice_name")]
+    name: String,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "in,out",
+        help = "Source and load port names"
+    )]
+    #[serde(default = "default_ports")]
+    ports: Vec<String>,
+}
+
+#[derive(Args, Deserialize)]
+struct SpiceImportArgs {
+    #[arg(long, help = "Netlist with a two-port .SUBCKT")]
+    file: String,
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        help = "Relative permittivity for line lengths"
+    )]
+    #[serde(default = "default_er")]
+    er: f64,
+}
+
+#[derive(Args, Deserialize)]
+struct NportArgs {
+    #[arg(
+        long,
+        help = "Netlist with a .SUBCKT, one port from each of its nodes to ground"
+    )]
+    file: String,
+    #[arg(long)]
+    start: f64,
+    #[arg(long)]
+    stop: f64,
+    #[arg(long, default_value_t = default_points())]
+    #[serde(default = "default_points")]
+    points: usize,
+    #[arg(long, default_value = "GHz")]
+    #[serde(default = "default_f_scale")]
+    freq_unit: Unit,
+    #[arg(long, default_value = "s", help = "s, y or z")]
+    #[serde(default = "default_param")]
+    param: Param,
+    #[arg(long, default_value_t = default_z0())]
+    #[serde(default = "default_z0")]
+    z0: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", content = "args")]
+enum Job {
+    #[serde(rename = "calc_impedance")]
+    Impedance(ImpedanceArgs),
+    #[serde(rename = "calc_networks")]
+    Networks(NetworksArgs),
+    #[serde(rename = "calc_ladder")]
+    Ladder(LadderArgs),
+    #[serde(rename = "calc_xfmr")]
+    Xfmr(XfmrArgs),
+    #[serde(rename = "calc_match")]
+    Match(MatchArgs),
+    #[serde(rename = "calc_match_touchstone")]
+    MatchFile(MatchFileArgs),
+    #[serde(rename = "calc_balanced_port")]
+    Balanced(BalancedArgs),
+    #[serde(rename = "calc_mixed_mode")]
+    MixedMode(MixedModeArgs),
+    #[serde(rename = "calc_smith_cascade")]
+    SmithCascade(SmithArgs),
+    #[serde(rename = "calc_richards")]
+    Richards(RichardsArgs),
+    #[serde(rename = "spice_network")]
+    SpiceNetwork(SpiceNetworkArgs),
+    #[serde(rename = "spice_schematic")]
+    SpiceSchematic(SpiceSchematicArgs),
+    #[serde(rename = "spice_import")]
+    SpiceImport(SpiceImportArgs),
+    #[serde(rename = "spice_nport")]
+    Nport(NportArgs),
+    #[serde(rename = "calc_balun")]
+    Balun(BalunArgs),
+    #[serde(rename = "design_balun")]
+    BalunDesign(BalunDesignArgs),
+    #[serde(rename = "read_part_library")]
+    Parts(PartsArgs),
+}
+
+// "sc:0,20:Q,fF"  =>  (SeriesCap, [0.0, 20.0], ["Q", "fF"], None)
+// "ss2p:2.2:pF:parts.json"  =>  (SeriesMeasured, [2.2], ["pF"], Some(parts.json))
+type ElementSpec<'a> = (ElementType, Vec<f64>, Vec<&'a str>, Option<PartFile>);
+
+fn parse_element(spec: &str) -> Result<ElementSpec<'_>, Error> {
+    // The part file is last so its path may itself hold ':'
+    let mut parts = spec.splitn(4, ':');
+    let element = parts.next().unwrap_or_default().parse::<ElementType>()?;
+    let vals = match parts.next() {
+        Some(val) if !val.is_empty() => val
+            .split(',')
+            .map(|x| {
+                x.trim().parse::<f64>().map_err(|_| {
+                    Error::Parse(format!("invalid value '{}' in element '{}'", x, spec))
+                })
+            })
+            .collect::<Result<Vec<f64>, Error>>()?,
+        _ => return Err(Error::Parse(format!("element '{}' has no values", spec))),
+    };
+    let units = match parts.next() {
+        Some(val) => val.split(',').map(|x| x.trim()).collect(),
+        None => vec![],
+    };
+    let part = match parts.next() {
+        Some(val)
+            if matches!(
+                element,
+                ElementType::SeriesMeasured | ElementType::ShuntMeasured
+            ) =>
+        {
+            Some(val.parse::<PartFile>()?)
+        }
+        Some(_) => {
+            return Err(Error::Parse(format!(
+                "element '{}' has too many fields",
+                spec
+            )))
+        }
+        None => None,
+    };
+
+    Ok((element, vals, units, part))
+}
+
+fn to_value<T: serde::Serialize>(val: T) -> Result<Value, Error> {
+    serde_json::to_value(val).map_err(|err| Error::Parse(err.to_string()))
+}
+
+fn impedance(args: &ImpedanceArgs) -> Result<Value, Error> {
+    let out = calc_impedance(
+        args.re,
+        args.im,
+        args.imp,
+        args.z0,
+        args.freq,
+        args.f_scale,
+        args.c_scale,
+    );
+    to_value(out)
+}
+
+fn networks(args: &NetworksArgs) -> Result<Value, Error> {
+    let out = calc_networks(
+        args.rs,
+        args.xs,
+        args.rl,
+        args.xl,
+        args.imp,
+        args.q_net,
+        args.q,
+        args.z0,
+        args.freq,
+        args.f_scale,
+        args.c_scale,
+        args.l_scale,
+        args.z_scale,
+    )?;
+    to_value(out)
+}
+
+fn ladder(args: &LadderArgs) -> Result<Value, Error> {
+    let out = calc_ladder(
+        &args.topology,
+        args.rs,
+        args.xs,
+        args.rl,
+        args.xl,
+        args.imp,
+        args.q,
+        args.z0,
+        args.freq,
+        args.f_scale,
+        args.c_scale,
+        args.l_scale,
+        args.z_scale,
+    )?;
+    to_value(out)
+}
+
+fn xfmr(args: &XfmrArgs) -> Result<Value, Error> {
+    let out = calc_xfmr(
+        args.rs,
+        args.xs,
+        args.rl,
+        args.xl,
+        args.imp,
+        args.k,
+        args.q_net,
+        args.q,
+        args.z0,
+        args.freq,
+        args.f_scale,
+        args.c_scale,
+        args.l_scale,
+    )?;
+    to_value(out)
+}
+
+fn conjugate_match(args: &MatchArgs) -> Result<Value, Error> {
+    let out = calc_match(
+        args.s11re,
+        args.s11im,
+        args.s12re,
+        args.s12im,
+        args.s21re,
+        args.s21im,
+        args.s22re,
+        args.s22im,
+        args.imp,
+        args.z0,
+        args.freq,
+        args.fscale,
+        args.cscale,
+    )?;
+    to_value(out)
+}
+
+fn match_file(args: &MatchFileArgs) -> Result<Value, Error> {
+    let ports = ports_from_path(&args.file)?;
+    let text =
+        std::fs::read_to_string(&args.file).map_err(|err| Error::from(err).context(&args.file))?;
+    let out = calc_match_touchstone(
+        &text,
+        ports,
+        args.pairing.as_ref(),
+        args.freq,
+        args.fscale,
+        args.cscale,
+    )
+    .map_err(|err| err.context(&args.file))?;
+    to_value(out)
+}
+
+fn balanced(args: &BalancedArgs) -> Result<Value, Error> {
+    let out = calc_balanced_port(
+        args.s11re, args.s11im, args.s12re, args.s12im, args.s21re, args.s21im, args.s22re,
+        args.s22im, args.imp, args.z0,
+    )?;
+    to_value(out)
+}
+
+fn mixed_mode(args: &MixedModeArgs) -> Result<Value, Error> {
+    let ports = ports_from_path(&args.file)?;
+    let text =
+        std::fs::read_to_string(&args.file).map_err(|err| Error::from(err).context(&args.file))?;
+    let out = calc_mixed_mode(&text, ports, &args.pairing, args.freq, args.f_scale)
+        .map_err(|err| err.context(&args.file))?;
+    to_value(out)
+}
+
+fn parts(args: &PartsArgs) -> Result<Value, Error> {
+    to_value(read_library(&args.file)?)
+}
+
+fn smith(args: &SmithArgs) -> Result<Value, Error> {
+    let specs = args
+        .elements
+        .iter()
+        .map(|val| parse_element(val))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let out = calc_smith_cascade(
+        specs.iter().map(|val| val.0).collect(),
+        specs.iter().map(|val| val.1.clone()).collect(),
+        specs.iter().map(|val| val.2.clone()).collect(),
+        specs.iter().map(|val| val.3.clone()).collect(),
+        args.rin,
+        args.xin,
+        args.z0,
+        args.freq,
+        args.freq_unit,
+    )?;
+    to_value(out)
+}
+
+fn richards(args: &RichardsArgs) -> Result<Value, Error> {
+    let specs = args
+        .elements
+        .iter()
+        .map(|val| parse_element(val))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let out = calc_richards(
+        specs.iter().map(|val| val.0).collect(),
+        specs.iter().map(|val| val.1.clone()).collect(),
+        specs.iter().map(|val| val.2.clone()).collect(),
+        args.z0,
+        args.freq,
+        args.freq_unit,
+        args.er,
+        args.length_unit,
+    )?;
+    to_value(out)
+}
+
+fn balun(args: &BalunArgs) -> Result<Value, Error> {
+    let (element, vals, units, _) = parse_element(&args.element)?;
+    if element != ElementType::Balun {
+        return Err(Error::InvalidInput(format!(
+            "element {} is not a balun",
+            element
+        )));
+    }
+    let sweep = FrequencySweep::linear(args.start, args.stop, args.points, args.freq_unit)?;
+    let out = calc_balun(&vals, &units, args.z_se, args.z_diff, &sweep)?;
+    to_value(out)
+}
+
+fn balun_design(args: &BalunDesignArgs) -> Result<Value, Error> {
+    let out = design_balun(
+        args.kind,
+        args.z_se,
+        args.z_diff,
+        args.z0e,
+        args.freq,
+        args.f_scale,
+        args.c_scale,
+        args.l_scale,
+    )?;
+    to_value(out)
+}
+
+fn ports(vals: &[String]) -> Result<[&str; 2], Error> {
+    match vals {
+        [source, load] => Ok([source, load]),
+        _ => Err(Error::InvalidInput(format!(
+            "expected a source and a load port, got {}",
+            vals.len()
+        ))),
+    }
+}
+
+fn spice_network(args: &SpiceNetworkArgs) -> Result<Value, Error> {
+    let net = &args.networks;
+    let networks = networks(net)?;
+    let values = network_solution(&networks, args.network, args.solution)?;
+    let out = network_netlist(
+        args.network,
+        values,
+        net.freq,
+        net.f_scale,
+        args.q_c,
+        args.q_l,
+        ports(&args.ports)?,
+    )?;
+    Ok(Value::String(out))
+}
+
+fn spice_schematic(args: &SpiceSchematicArgs) -> Result<Value, Error> {
+    let text =
+        std::fs::read_to_string(&args.file).map_err(|err| Error::from(err).context(&args.file))?;
+    let elements: Vec<Value> = serde_json::from_str(&text)
+        .map_err(|err| Error::Parse(err.to_string()).context(&args.file))?;
+    let out = schematic_netlist(&elements, &args.name, ports(&args.ports)?)
+        .map_err(|err| err.context(&args.file))?;
+    Ok(Value::String(out))
+}
+
+fn spice_import(args: &SpiceImportArgs) -> Result<Value, Error> {
+    let text =
+        std::fs::read_to_string(&args.file).map_err(|err| Error::from(err).context(&args.file))?;
+    let out = parse_ladder(&text, args.er).map_err(|err| err.context(&args.file))?;
+    to_value(out)
+}
+
+fn nport(args: &NportArgs) -> Result<Value, Error> {
+    let text =
+        std::fs::read_to_string(&args.file).map_err(|err| Error::from(err).context(&args.file))?;
+    let circuit = parse_circuit(&text, args.z0).map_err(|err| err.context(&args.file))?;
+    let sweep = FrequencySweep::linear(args.start, args.stop, args.points, args.freq_unit)?;
+    let out = calc_nport(&circuit, args.param, &sweep).map_err(|err| err.context(&args.file))?;
+    to_value(out)
+}
+
+fn run_job(job: &Job) -> Result<Value, Error> {
+    match job {
+        Job::Impedance(args) => impedance(args),
+        Job::Networks(args) => networks(args),
+        Job::Ladder(args) => ladder(args),
+        Job::Xfmr(args) => xfmr(args),
+        Job::Match(args) => conjugate_match(args),
+        Job::MatchFile(args) => match_file(args),
+        Job::Balanced(args) => balanced(args),
+        Job::MixedMode(args) => mixed_mode(args),
+        Job::SmithCascade(args) => smith(args),
+        Job::Richards(args) => richards(args),
+        Job::SpiceNetwork(args) => spice_network(args),
+        Job::SpiceSchematic(args) => spice_schematic(args),
+        Job::SpiceImport(args) => spice_import(args),
+        Job::Nport(args) => nport(args),
+        Job::Balun(args) => balun(args),
+        Job::BalunDesign(args) => balun_design(args),
+        Job::Parts(args) => parts(args),
+    }
+}
+
+fn read_jobs(file: &str) -> Result<Vec<Job>, Error> {
+    let mut text = String::new();
+    if file == "-" {
+        std::io::stdin().read_to_string(&mut text)?;
+    } else {
+        text = std::fs::read_to_string(file).map_err(|err| Error::from(err).context(file))?;
+    }
+    serde_json::from_str(&text).map_err(|err| Error::Parse(err.to_string()))
+}
+
+// Nested results are flattened to dotted keys, e.g. "hp1.cs" or "0.z.re"
+fn flatten(prefix: &str, val: &Value, out: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match val {
+        Value::Object(map) => map.iter().for_each(|(k, v)| flatten(&join(k), v, out)),
+        Value::Array(vec) => vec
+            .iter()
+            .enumerate()
+            .for_each(|(i, v)| flatten(&join(&i.to_string()), v, out)),
+        // serde_json writes NaN, used for unrealizable networks, as null
+        Value::Null => out.push((prefix.to_string(), "NaN".to_string())),
+        Value::String(s) => out.push((prefix.to_string(), s.clone())),
+        _ => out.push((prefix.to_string(), val.to_string())),
+    }
+}
+
+fn print_table(out: &mut impl Write, val: &Value) -> std::io::Result<()> {
+    let mut rows = vec![];
+    flatten("", val, &mut rows);
+    let width = rows.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    for (k, v) in rows {
+        writeln!(out, "{:width$}  {}", k, v, width = width)?;
+    }
+    Ok(())
+}
+
+fn print(out: &mut impl Write, val: &Value, json: bool) -> Result<(), Error> {
+    // Netlists print as they are
+    let written = if let (Value::String(s), false) = (val, json) {
+        write!(out, "{}", s)
+    } else if json {
+        serde_json::to_writer_pretty(&mut *out, val)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(out))
+    } else {
+        print_table(out, val)
+    };
+    match written.and_then(|_| out.flush()) {
+        // The reader stopping early, as head does, is not an error
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        written => written.map_err(Error::from),
+    }
+}
+
+fn fail(err: Error) -> ExitCode {
+    eprintln!("error: {}", err);
+    ExitCode::FAILURE
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Impedance(args) => impedance(args),
+        Command::Networks(args) => networks(args),
+        Command::Ladder(args) => ladder(args),
+        Command::Xfmr(args) => xfmr(args),
+        Command::Match(args) => conjugate_match(args),
+        Command::MatchFile(args) => match_file(args),
+        Command::Balanced(args) => balanced(args),
+        Command::MixedMode(args) => mixed_mode(args),
+        Command::Smith(args) => smith(args),
+        Command::Richards(args) => richards(args),
+        Command::Parts(args) => parts(args),
+        Command::SpiceNetwork(args) => spice_network(args),
+        Command::SpiceSchematic(args) => spice_schematic(args),
+        Command::SpiceImport(args) => spice_import(args),
+        Command::Nport(args) => nport(args),
+        Command::Balun(args) => balun(args),
+        Command::BalunDesign(args) => balun_design(args),
+        Command::Batch { file } => match read_jobs(file) {
+            Ok(jobs) => {
+                let mut failed = false;
+                let results = jobs
+                    .iter()
+                    .map(|job| match run_job(job) {
+                        Ok(val) => val,
+                        Err(err) => {
+                            failed = true;
+                            serde_json::json!({ "error": err })
+                        }
+                    })
+                    .collect::<Vec<Value>>();
+                return match print(&mut stdout().lock(), &Value::Array(results), cli.json) {
+                    Ok(()) if failed => ExitCode::FAILURE,
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(err) => fail(err),
+                };
+            }
+            Err(err) => Err(err),
+        },
+    };
+
+    match result.and_then(|val| print(&mut stdout().lock(), &val, cli.json)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => fail(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rf_toolbox_core::element::Orientation;
+
+    // Output whose reader has gone away, or that fails in some other way
+    struct Closed(ErrorKind);
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(self.0.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_print() {
+        let val = serde_json::json!({"z": {"re": 50, "im": null}});
+        let mut out = vec![];
+        print(&mut out, &val, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "z.im  NaN\nz.re  50\n");
+        let mut out = vec![];
+        print(&mut out, &val, true).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("}\n"));
+
+        for json in [false, true] {
+            assert!(print(&mut Closed(ErrorKind::BrokenPipe), &val, json).is_ok());
+            let err = print(&mut Closed(ErrorKind::Other), &val, json).unwrap_err();
+            assert_eq!(err.code(), "io");
+        }
+    }
+
+    #[test]
+    fn test_parse_element() {
+        assert_eq!(
+            parse_element("sc:0,20:Q,fF").unwrap(),
+            (
+                ElementType::SeriesCap,
+                vec![0.0, 20.0],
+                vec!["Q", "fF"],
+                None
+            )
+        );
+        assert_eq!(
+            parse_element("bb:25,-10").unwrap(),
+            (ElementType::BlackBox, vec![25.0, -10.0], vec![], None)
+        );
+        assert_eq!(
+            parse_element("ps2p:2.2:pF:C:/parts/c.s2p,shunt").unwrap(),
+            (
+                ElementType::ShuntMeasured,
+                vec![2.2],
+                vec!["pF"],
+                Some(PartFile::new("C:/parts/c.s2p", Some(Orientation::Shunt)))
+            )
+        );
+        assert!(parse_element("ss2p:2.2:pF:c.s2p,sideways").is_err());
+        assert!(parse_element("sc").is_err());
+        assert!(parse_element("sc:0,x:Q,fF").is_err());
+        assert!(parse_element("sc:0,20:Q,fF:1").is_err());
+        assert!(parse_element("xx:0,20:Q,fF").is_err());
+    }
+
+    #[test]
+    fn test_flatten() {
+        let mut test = vec![];
+        flatten(
+            "",
+            &serde_json::json!([{ "z": { "re": 1.5, "im": null } }]),
+            &mut test,
+        );
+        assert_eq!(
+            test,
+            vec![
+                ("0.z.im".to_string(), "NaN".to_string()),
+                ("0.z.re".to_string(), "1.5".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_job() {
+        let jobs: Vec<Job> = serde_json::from_str(
+            r#"[{"command": "calc_smith_cascade", "args": {"elements": ["pi:20,10:Q,pH", "sc:0,20:Q,fF"], "freq": 280}}]"#,
+        )
+        .unwrap();
+        let test = run_job(&jobs[0]).unwrap();
+        assert_eq!(test.as_array().unwrap().len(), 2);
+
+        let err = serde_json::from_str::<Vec<Job>>(
+            r#"[{"command": "calc_impedance", "args": {"re": 1, "im": 0, "freq": 1, "f_scale": "gigs"}}]"#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("unit \"gigs\" not recognized"));
+
+        let jobs: Vec<Job> = serde_json::from_str(
+            r#"[{"command": "calc_ladder", "args": {"topology": "pC-sL-pC", "rs": 50, "xs": 0, "rl": 200, "xl": 0, "q": 3, "freq": 1}}]"#,
+        )
+        .unwrap();
+        let test = run_job(&jobs[0]).unwrap();
+        assert_eq!(test["solutions"].as_array().unwrap().len(), 1);
+
+        let err = serde_json::from_str::<Vec<Job>>(
+            r#"[{"command": "calc_ladder", "args": {"topology": "sC-sL", "rs": 50, "xs": 0, "rl": 200, "xl": 0, "freq": 1}}]"#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("adjacent sC and sL"));
+
+        let jobs: Vec<Job> = serde_json::from_str(
+            r#"[{"command": "calc_richards", "args": {"elements": ["si:0,1.99:Q,nH", "pc:0,1.59:Q,pF", "si:0,1.99:Q,nH"], "freq": 4}}]"#,
+        )
+        .unwrap();
+        let test = run_job(&jobs[0]).unwrap();
+        let test = test.as_array().unwrap();
+        assert_eq!(test.len(), 5);
+        assert_eq!(test[1]["element"], "tl");
+
+        let jobs: Vec<Job> = serde_json::from_str(
+            r#"[{"command": "spice_network", "args": {"network": "lp_ell_lc", "ports": ["a", "b"], "rs": 10, "xs": 0, "rl": 50, "xl": 0, "freq": 1}}]"#,
+        )
+        .unwrap();
+        let test = run_job(&jobs[0]).unwrap();
+        let test = test.as_str().unwrap().lines().collect::<Vec<&str>>();
+        assert_eq!(test[1], ".SUBCKT lp_ell_lc a b");
+        assert!(test[2].starts_with("L1 a b "));
+        assert!(test[3].starts_with("C1 b 0 "));
+    }
+}
@@ -1,5 +1,8 @@
 #![allow(unused)]
-use crate::rf_utils::{calc_rc, calc_z, gen_complex, ComplexReturn};
+use crate::differential::{mixed_mode_nport, PortPairing};
+use crate::error::Error;
+use crate::rf_utils::{calc_rc, calc_z, gen_complex, unscale, ComplexReturn, ComplexType};
+use crate::touchstone::parse_touchstone;
 use crate::unit::{get_unit, Unit, UnitType};
 use float_cmp::F64Margin;
 use num_complex::Complex;
@@ -28,7 +31,6 @@ pub struct ResultsReturn {
     pub load: ImpedanceReturn,
 }
 
-#[tauri::command]
 pub fn calc_match(
     s11re: f64,
     s11im: f64,
@@ -38,19 +40,16 @@ pub fn calc_match(
     s21im: f64,
     s22re: f64,
     s22im: f64,
-    imp: &str,
+    imp: ComplexType,
     z0: f64,
     freq: f64,
-    fscale: &str,
-    cscale: &str,
-) -> Result<ResultsReturn, String> {
-    let freq_unit = Unit::from_str(fscale).unwrap();
-    let cap_unit = Unit::from_str(cscale).unwrap();
-
-    let s11 = gen_complex(s11re, s11im, imp)?;
-    let s12 = gen_complex(s12re, s12im, imp)?;
-    let s21 = gen_complex(s21re, s21im, imp)?;
-    let s22 = gen_complex(s22re, s22im, imp)?;
+    freq_unit: Unit,
+    cap_unit: Unit,
+) -> Result<ResultsReturn, Error> {
+    let s11 = gen_complex(s11re, s11im, imp);
+    let s12 = gen_complex(s12re, s12im, imp);
+    let s21 = gen_complex(s21re, s21im, imp);
+    let s22 = gen_complex(s22re, s22im, imp);
 
     let ds = s11 * s22 - s12 * s21;
 
@@ -68,7 +67,7 @@ pub fn calc_match(
 
     let gamma_load_mag =
         (b2 - b2.signum() * (b2.powi(2) - 4.0 * c2.norm().powi(2)).sqrt()) / (2.0 * c2.norm());
-    let gamma_load_ang = -1.0 * c2.arg();
+    let gamma_load_ang = -c2.arg();
 
     let gamma_load = Complex::from_polar(gamma_load_mag, gamma_load_ang);
     let z_load = calc_z(gamma_load, z0);
@@ -79,10 +78,10 @@ pub fn calc_match(
     let (rs, cs) = calc_rc(gamma_src, freq, &freq_unit, &Unit::Base, &cap_unit);
 
     Ok(ResultsReturn {
-        k: k,
-        b1: b1,
-        b2: b2,
-        mag: mag,
+        k,
+        b1,
+        b2,
+        mag,
         src: ImpedanceReturn {
             gamma: ComplexReturn {
                 re: gamma_src.re,
@@ -94,8 +93,8 @@ pub fn calc_match(
             },
             r: rs,
             c: cs,
-            z0: z0,
-            freq: freq,
+            z0,
+            freq,
             freq_unit: get_unit(&freq_unit, &UnitType::Hz),
             res_unit: get_unit(&Unit::Base, &UnitType::Ohm),
             cap_unit: get_unit(&cap_unit, &UnitType::Farad),
@@ -111,8 +110,8 @@ pub fn calc_match(
             },
             r: rl,
             c: cl,
-            z0: z0,
-            freq: freq,
+            z0,
+            freq,
             freq_unit: get_unit(&freq_unit, &UnitType::Hz),
             res_unit: get_unit(&Unit::Base, &UnitType::Ohm),
             cap_unit: get_unit(&cap_unit, &UnitType::Farad),
@@ -120,22 +119,164 @@ pub fn calc_match(
     })
 }
 
+// Conjugate match of a 2-port read from Touchstone data at freq. With a pairing the terminals are
+// paired into two balanced ports and Sdd is matched, referenced to twice the z0 of the data.
+pub fn calc_match_touchstone(
+    text: &str,
+    ports: usize,
+    pairing: Option<&PortPairing>,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+) -> Result<ResultsReturn, Error> {
+    let data = parse_touchstone(text, ports)?;
+    let s = data.s_at(unscale(freq, &freq_unit))?;
+    let (s, z0) = match pairing {
+        Some(pairing) => (mixed_mode_nport(&s, pairing)?[0].clone(), 2.0 * data.z0),
+        None => (s, data.z0),
+    };
+    if s.len() != 2 {
+        return Err(Error::InvalidInput(format!(
+            "conjugate match needs a 2-port, got {} {}",
+            s.len(),
+            match pairing {
+                Some(_) => "balanced ports",
+                None => "ports without a pairing",
+            }
+        )));
+    }
+
+    calc_match(
+        s[0][0].re,
+        s[0][0].im,
+        s[0][1].re,
+        s[0][1].im,
+        s[1][0].re,
+        s[1][0].im,
+        s[1][1].re,
+        s[1][1].im,
+        ComplexType::ReIm,
+        z0,
+        freq,
+        freq_unit,
+        cap_unit,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::rf_utils::comp_f64;
 
+    #[test]
+    fn test_calc_match_touchstone() {
+        let s = [
+            [Complex::new(0.34, 0.21), Complex::new(0.0434, -0.0052)],
+            [Complex::new(0.32, -3.4), Complex::new(0.34, -0.52)],
+        ];
+        let exemplar = |z0: f64| {
+            calc_match(
+                s[0][0].re,
+                s[0][0].im,
+                s[0][1].re,
+                s[0][1].im,
+                s[1][0].re,
+                s[1][0].im,
+                s[1][1].re,
+                s[1][1].im,
+                ComplexType::ReIm,
+                z0,
+                275.0,
+                Unit::Giga,
+                Unit::Femto,
+            )
+            .unwrap()
+        };
+
+        // 2-ports are column major
+        let text = format!(
+            "# GHz S RI R 50\n275 {} {} {} {} {} {} {} {}\n",
+            s[0][0].re,
+            s[0][0].im,
+            s[1][0].re,
+            s[1][0].im,
+            s[0][1].re,
+            s[0][1].im,
+            s[1][1].re,
+            s[1][1].im
+        );
+        let test = calc_match_touchstone(&text, 2, None, 275.0, Unit::Giga, Unit::Femto).unwrap();
+        assert_eq!(test, exemplar(50.0));
+
+        // A purely differential 4-port with s as its Sdd, balanced ports 1-3 and 2-4
+        let pair = [(0, 2), (1, 3)];
+        let mut se = vec![vec![Complex::new(0.0, 0.0); 4]; 4];
+        for (i, (p, n)) in pair.iter().enumerate() {
+            for (j, (q, m)) in pair.iter().enumerate() {
+                se[*p][*q] = s[i][j] / 2.0;
+                se[*n][*m] = s[i][j] / 2.0;
+                se[*p][*m] = -s[i][j] / 2.0;
+                se[*n][*q] = -s[i][j] / 2.0;
+            }
+        }
+        let text = format!(
+            "# GHz S RI R 50\n275{}\n",
+            se.iter()
+                .map(|row| row
+                    .iter()
+                    .map(|val| format!(" {} {}", val.re, val.im))
+                    .collect::<String>())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        let pairing = "1-3,2-4".parse::<PortPairing>().unwrap();
+        let test = calc_match_touchstone(&text, 4, Some(&pairing), 275.0, Unit::Giga, Unit::Femto)
+            .unwrap();
+        let margin = F64Margin::from((1e-9, 4));
+        let exemplar = exemplar(100.0);
+        comp_f64(&test.k, &exemplar.k, margin, "calc_match_touchstone()", "k");
+        comp_f64(
+            &test.mag,
+            &exemplar.mag,
+            margin,
+            "calc_match_touchstone()",
+            "mag",
+        );
+        comp_f64(
+            &test.src.z.re,
+            &exemplar.src.z.re,
+            margin,
+            "calc_match_touchstone()",
+            "src.z.re",
+        );
+        comp_f64(
+            &test.load.z.im,
+            &exemplar.load.z.im,
+            margin,
+            "calc_match_touchstone()",
+            "load.z.im",
+        );
+        assert_eq!(test.src.z0, 100.0);
+
+        assert!(calc_match_touchstone(&text, 4, None, 275.0, Unit::Giga, Unit::Femto).is_err());
+        let pairing = "1-3".parse::<PortPairing>().unwrap();
+        assert!(
+            calc_match_touchstone(&text, 4, Some(&pairing), 275.0, Unit::Giga, Unit::Femto)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_calc_match() {
         let s11 = Complex::new(0.34, 0.21);
         let s12 = Complex::new(0.0434, -0.0052);
         let s21 = Complex::new(0.32, -3.4);
         let s22 = Complex::new(0.34, -0.52);
-        let imp = "ri";
+        let imp = ComplexType::ReIm;
         let z0 = 100.0;
         let freq = 275.0;
-        let fscale = "giga";
-        let cscale = "femto";
+        let fscale = Unit::Giga;
+        let cscale = Unit::Femto;
         let exemplar = ResultsReturn {
             k: 1.7031802961437423,
             b1: 0.7195251545599999,
@@ -0,0 +1,809 @@
+use crate::element::balun::{Balun, BalunType};
+use crate::element::{Element, ElementType};
+use crate::error::Error;
+use crate::frequency::{Frequency, FrequencySweep};
+use crate::matching::ladder::{Kind, Placement, Topology};
+use crate::rf_utils::{calc_z, gen_complex, scale, unscale, ComplexReturn, ComplexType};
+use crate::smith::gen_balun;
+use crate::touchstone::parse_touchstone;
+use crate::unit::{get_unit, Unit, UnitType};
+use num_complex::Complex;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct BalancedPortReturn {
+    pub sdd: ComplexReturn,
+    pub sdc: ComplexReturn,
+    pub scd: ComplexReturn,
+    pub scc: ComplexReturn,
+    pub zdiff: ComplexReturn,
+    pub zcm: ComplexReturn,
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct MixedModeReturn {
+    pub sdd: Vec<Vec<ComplexReturn>>,
+    pub sdc: Vec<Vec<ComplexReturn>>,
+    pub scd: Vec<Vec<ComplexReturn>>,
+    pub scc: Vec<Vec<ComplexReturn>>,
+    // input impedance of each balanced port, from Sdd referenced to 2*z0
+    pub zdiff: Vec<ComplexReturn>,
+    pub z0: f64,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct BalancedElement {
+    pub element: String,
+    // value in each leg: half of a series element, or one of a pair of shunt elements to ground
+    pub leg: f64,
+    // shunt element floating across the pair, NaN for series elements
+    pub floating: f64,
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct BalunReturn {
+    pub freq: f64,
+    // single-ended match and transmission into the differential and common modes
+    pub sss: ComplexReturn,
+    pub sds: ComplexReturn,
+    pub scs: ComplexReturn,
+    pub sdd: ComplexReturn,
+    // into the single-ended port with the balanced port terminated
+    pub zin: ComplexReturn,
+    // positive over negative output, in dB and in ° away from 180°
+    pub amplitude: f64,
+    pub phase: f64,
+    // differential over common-mode transmission in dB
+    pub cmrr: f64,
+}
+
+// A balun as a Smith chart element, ready for the schematic or calc_balun
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct BalunDesignReturn {
+    pub element: String,
+    pub vals: Vec<f64>,
+    pub units: Vec<String>,
+}
+
+fn to_return(val: Complex<f64>) -> ComplexReturn {
+    ComplexReturn {
+        re: val.re,
+        im: val.im,
+    }
+}
+
+// Mixed-mode S-parameters [[dd, dc], [cd, cc]] of a balanced port from the single-ended
+// S-parameters of its two terminals. Differential waves are referenced to 2*z0 and common-mode
+// waves to z0/2.
+pub fn mixed_mode(s: &[[Complex<f64>; 2]; 2]) -> [[Complex<f64>; 2]; 2] {
+    let dd = (s[0][0] - s[0][1] - s[1][0] + s[1][1]) / 2.0;
+    let dc = (s[0][0] + s[0][1] - s[1][0] - s[1][1]) / 2.0;
+    let cd = (s[0][0] - s[0][1] + s[1][0] - s[1][1]) / 2.0;
+    let cc = (s[0][0] + s[0][1] + s[1][0] + s[1][1]) / 2.0;
+    [[dd, dc], [cd, cc]]
+}
+
+// Single-ended terminals (positive, negative) of each balanced port, numbered from 0. Written
+// numbered from 1 as "1-3,2-4".
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PortPairing {
+    pub pairs: Vec<(usize, usize)>,
+}
+
+impl FromStr for PortPairing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pairs = s
+            .split(',')
+            .map(|pair| {
+                let ports = pair
+                    .split('-')
+                    .map(|val| val.trim().parse::<usize>().ok().filter(|val| *val > 0))
+                    .collect::<Option<Vec<usize>>>()
+                    .filter(|val| val.len() == 2)
+                    .ok_or_else(|| {
+                        Error::Parse(format!("port pair {:?} not recognized", pair.trim()))
+                    })?;
+                Ok((ports[0] - 1, ports[1] - 1))
+            })
+            .collect::<Result<Vec<(usize, usize)>, Error>>()?;
+
+        let mut ports = pairs
+            .iter()
+            .flat_map(|(p, n)| [*p, *n])
+            .collect::<Vec<usize>>();
+        ports.sort();
+        if let Some(val) = ports.windows(2).find(|val| val[0] == val[1]) {
+            return Err(Error::InvalidInput(format!(
+                "port {} is used in more than one pair",
+                val[0] + 1
+            )));
+        }
+
+        Ok(PortPairing { pairs })
+    }
+}
+
+impl TryFrom<String> for PortPairing {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+// Mixed-mode S-parameters (dd, dc, cd, cc) between the balanced ports of an N-port, as in
+// mixed_mode for each pair of balanced ports
+pub fn mixed_mode_nport(
+    s: &[Vec<Complex<f64>>],
+    pairing: &PortPairing,
+) -> Result<[Vec<Vec<Complex<f64>>>; 4], Error> {
+    if let Some((p, n)) = pairing
+        .pairs
+        .iter()
+        .find(|(p, n)| *p >= s.len() || *n >= s.len())
+    {
+        return Err(Error::InvalidInput(format!(
+            "port pair {}-{} is outside a {}-port",
+            p + 1,
+            n + 1,
+            s.len()
+        )));
+    }
+
+    let mut out: [Vec<Vec<Complex<f64>>>; 4] = Default::default();
+    for (p, n) in pairing.pairs.iter() {
+        let mut row: [Vec<Complex<f64>>; 4] = Default::default();
+        for (q, m) in pairing.pairs.iter() {
+            let smm = mixed_mode(&[[s[*p][*q], s[*p][*m]], [s[*n][*q], s[*n][*m]]]);
+            row[0].push(smm[0][0]);
+            row[1].push(smm[0][1]);
+            row[2].push(smm[1][0]);
+            row[3].push(smm[1][1]);
+        }
+        for (val, r) in out.iter_mut().zip(row) {
+            val.push(r);
+        }
+    }
+    Ok(out)
+}
+
+// Mixed-mode S-parameters at freq of Touchstone data whose terminals are paired into balanced
+// ports. Sdd of two balanced ports is the differential 2-port the conjugate match and Smith chart
+// tools work with.
+pub fn calc_mixed_mode(
+    text: &str,
+    ports: usize,
+    pairing: &PortPairing,
+    freq: f64,
+    freq_unit: Unit,
+) -> Result<MixedModeReturn, Error> {
+    let data = parse_touchstone(text, ports)?;
+    let s = data.s_at(unscale(freq, &freq_unit))?;
+    let [dd, dc, cd, cc] = mixed_mode_nport(&s, pairing)?;
+
+    let to_matrix = |val: &Vec<Vec<Complex<f64>>>| {
+        val.iter()
+            .map(|row| row.iter().map(|x| to_return(*x)).collect())
+            .collect()
+    };
+    Ok(MixedModeReturn {
+        zdiff: (0..dd.len())
+            .map(|k| to_return(calc_z(dd[k][k], 2.0 * data.z0)))
+            .collect(),
+        sdd: to_matrix(&dd),
+        sdc: to_matrix(&dc),
+        scd: to_matrix(&cd),
+        scc: to_matrix(&cc),
+        z0: data.z0,
+    })
+}
+
+// Mixed-mode view of a balanced termination given as the 2-port S-parameters of its two
+// terminals to ground. zdiff is the impedance between the terminals and zcm the impedance of both
+// tied together, each with the other mode terminated in its reference.
+pub fn calc_balanced_port(
+    s11re: f64,
+    s11im: f64,
+    s12re: f64,
+    s12im: f64,
+    s21re: f64,
+    s21im: f64,
+    s22re: f64,
+    s22im: f64,
+    imp: ComplexType,
+    z0: f64,
+) -> Result<BalancedPortReturn, Error> {
+    if z0 <= 0.0 {
+        return Err(Error::InvalidInput(format!(
+            "reference impedance must be positive, got {} Ω",
+            z0
+        )));
+    }
+
+    let s = [
+        [
+            gen_complex(s11re, s11im, imp),
+            gen_complex(s12re, s12im, imp),
+        ],
+        [
+            gen_complex(s21re, s21im, imp),
+            gen_complex(s22re, s22im, imp),
+        ],
+    ];
+    let smm = mixed_mode(&s);
+
+    Ok(BalancedPortReturn {
+        sdd: to_return(smm[0][0]),
+        sdc: to_return(smm[0][1]),
+        scd: to_return(smm[1][0]),
+        scc: to_return(smm[1][1]),
+        zdiff: to_return(calc_z(smm[0][0], 2.0 * z0)),
+        zcm: to_return(calc_z(smm[1][1], z0 / 2.0)),
+    })
+}
+
+// Single-ended to differential response of a balun given by its Smith chart values, see
+// smith::gen_balun
+pub fn calc_balun(
+    vals: &[f64],
+    units: &[&str],
+    z_se: f64,
+    z_diff: f64,
+    sweep: &FrequencySweep,
+) -> Result<Vec<BalunReturn>, Error> {
+    let balun = gen_balun(vals, units, z_se, z_diff)?;
+    sweep
+        .iter()
+        .map(|freq| {
+            let [[ss, _, _], [ds, dd, _], [cs, _, _]] = balun.mixed_mode(freq)?;
+            let (amplitude, phase) = balun.imbalance(freq)?;
+            Ok(BalunReturn {
+                freq: freq.freq(),
+                sss: to_return(ss),
+                sds: to_return(ds),
+                scs: to_return(cs),
+                sdd: to_return(dd),
+                zin: to_return(calc_z(ss, z_se)),
+                amplitude,
+                phase,
+                cmrr: 20.0 * (ds.norm() / cs.norm()).log10(),
+            })
+        })
+        .collect()
+}
+
+// Balun matching z_se to z_diff at freq, both real. A lattice sets its arms to √(z_se z_diff) and
+// a Marchand balun its odd mode impedance to √(z_se z_diff)/2 under the given even mode
+// impedance z0e. Transformer baluns are tuned with matching::xfmr.
+pub fn design_balun(
+    kind: BalunType,
+    z_se: f64,
+    z_diff: f64,
+    z0e: f64,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+    ind_unit: Unit,
+) -> Result<BalunDesignReturn, Error> {
+    if !(z_se > 0.0 && z_diff > 0.0) {
+        return Err(Error::InvalidInput(format!(
+            "balun terminations must be positive, got {} Ω and {} Ω",
+            z_se, z_diff
+        )));
+    }
+    let f = Frequency::new(freq, freq_unit);
+    let (vals, units) = match kind {
+        BalunType::Lattice => {
+            let val = Balun::lattice(z_se, z_diff, f).vals();
+            (
+                vec![scale(val[0], &ind_unit), scale(val[1], &cap_unit), z_diff],
+                vec![
+                    get_unit(&ind_unit, &UnitType::Henry),
+                    get_unit(&cap_unit, &UnitType::Farad),
+                ],
+            )
+        }
+        BalunType::Marchand => {
+            let val = Balun::marchand(z_se, z_diff, z0e, f).vals();
+            if val[0] <= val[1] {
+                return Err(Error::NonPhysical(format!(
+                    "even mode impedance {} Ω must be above the odd mode impedance {} Ω",
+                    val[0], val[1]
+                )));
+            }
+            (
+                vec![val[0], val[1], freq, z_diff],
+                vec![get_unit(&freq_unit, &UnitType::Hz)],
+            )
+        }
+        BalunType::Transformer => {
+            return Err(Error::InvalidInput(
+                "transformer baluns are tuned by calc_xfmr".to_string(),
+            ))
+        }
+    };
+    Ok(BalunDesignReturn {
+        element: ElementType::Balun.to_string(),
+        vals,
+        units: [vec![kind.to_string()], units].concat(),
+    })
+}
+
+// Balanced form of a single-ended ladder designed between differential impedances. A series
+// element is split between the two legs at half its impedance. A shunt element either floats
+// across the pair unchanged or becomes a pair to ground, or to the virtual ground, at half its
+// impedance.
+//
+//  ---sL---+---        ---sL/2---+---
+//          |                     |
+//          pC          ====>    pC*2 ... or pC floating between the legs
+//          |                     |
+//  --------+---        ---sL/2---+---
+pub fn balance(topology: &Topology, vals: &[f64]) -> Vec<BalancedElement> {
+    topology
+        .elements
+        .iter()
+        .zip(vals.iter())
+        .map(|(element, val)| {
+            let leg = match element.kind {
+                Kind::Capacitor => val * 2.0,
+                Kind::Inductor => val / 2.0,
+            };
+            let floating = match element.placement {
+                Placement::Series => f64::NAN,
+                Placement::Shunt => *val,
+            };
+            BalancedElement {
+                element: element.to_string(),
+                leg,
+                floating,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{calc_gamma, comp_c64, comp_f64};
+    use crate::smith::calc_smith_cascade;
+    use float_cmp::F64Margin;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_calc_balanced_port() {
+        // Two uncoupled 30-j10 Ω terminals: 60-j20 Ω between them, 15-j5 Ω tied together
+        let z = Complex::new(30.0, -10.0);
+        let g = calc_gamma(z, 50.0);
+        let test = calc_balanced_port(
+            g.re,
+            g.im,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            g.re,
+            g.im,
+            ComplexType::ReIm,
+            50.0,
+        )
+        .unwrap();
+        comp_f64(
+            &test.zdiff.re,
+            &60.0,
+            F64Margin::default(),
+            "calc_balanced_port()",
+            "zdiff.re",
+        );
+        comp_f64(
+            &test.zdiff.im,
+            &-20.0,
+            F64Margin::default(),
+            "calc_balanced_port()",
+            "zdiff.im",
+        );
+        comp_f64(
+            &test.zcm.re,
+            &15.0,
+            F64Margin::default(),
+            "calc_balanced_port()",
+            "zcm.re",
+        );
+        comp_f64(
+            &test.zcm.im,
+            &-5.0,
+            F64Margin::default(),
+            "calc_balanced_port()",
+            "zcm.im",
+        );
+        assert_eq!(test.sdc, ComplexReturn::default());
+        assert_eq!(test.scd, ComplexReturn::default());
+
+        // A 100 Ω resistor floating between the terminals is open to the common mode
+        let test = calc_balanced_port(
+            0.5,
+            0.0,
+            0.5,
+            0.0,
+            0.5,
+            0.0,
+            0.5,
+            0.0,
+            ComplexType::ReIm,
+            50.0,
+        )
+        .unwrap();
+        comp_f64(
+            &test.zdiff.re,
+            &100.0,
+            F64Margin::default(),
+            "calc_balanced_port()",
+            "zdiff.re",
+        );
+        assert!(test.zcm.re.is_infinite() || test.zcm.re.is_nan());
+
+        assert_eq!(
+            calc_balanced_port(
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                ComplexType::ReIm,
+                0.0
+            )
+            .unwrap_err()
+            .code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn test_mixed_mode() {
+        let s = [
+            [Complex::new(0.1, 0.2), Complex::new(0.3, -0.1)],
+            [Complex::new(0.2, 0.0), Complex::new(-0.4, 0.1)],
+        ];
+        let test = mixed_mode(&s);
+        comp_c64(
+            &test[0][0],
+            &Complex::new(-0.4, 0.2),
+            F64Margin::default(),
+            "mixed_mode()",
+            "dd",
+        );
+        comp_c64(
+            &test[0][1],
+            &Complex::new(0.3, 0.0),
+            F64Margin::default(),
+            "mixed_mode()",
+            "dc",
+        );
+        comp_c64(
+            &test[1][0],
+            &Complex::new(0.2, 0.1),
+            F64Margin::default(),
+            "mixed_mode()",
+            "cd",
+        );
+        comp_c64(
+            &test[1][1],
+            &Complex::new(0.1, 0.1),
+            F64Margin::default(),
+            "mixed_mode()",
+            "cc",
+        );
+    }
+
+    #[test]
+    fn test_balance() {
+        let topology = "sL-pC-sC-pL".parse::<Topology>().unwrap();
+        let test = balance(&topology, &[100.0, 20.0, 30.0, 400.0]);
+        assert_eq!(test.len(), 4);
+        assert_eq!(test[0].element, "sL");
+        comp_f64(
+            &test[0].leg,
+            &50.0,
+            F64Margin::default(),
+            "balance()",
+            "sL.leg",
+        );
+        assert!(test[0].floating.is_nan());
+        comp_f64(
+            &test[1].leg,
+            &40.0,
+            F64Margin::default(),
+            "balance()",
+            "pC.leg",
+        );
+        comp_f64(
+            &test[1].floating,
+            &20.0,
+            F64Margin::default(),
+            "balance()",
+            "pC.floating",
+        );
+        comp_f64(
+            &test[2].leg,
+            &60.0,
+            F64Margin::default(),
+            "balance()",
+            "sC.leg",
+        );
+        assert!(test[2].floating.is_nan());
+        comp_f64(
+            &test[3].leg,
+            &200.0,
+            F64Margin::default(),
+            "balance()",
+            "pL.leg",
+        );
+        comp_f64(
+            &test[3].floating,
+            &400.0,
+            F64Margin::default(),
+            "balance()",
+            "pL.floating",
+        );
+    }
+
+    #[test]
+    fn test_port_pairing() {
+        let test = "1-3, 2-4".parse::<PortPairing>().unwrap();
+        assert_eq!(test.pairs, vec![(0, 2), (1, 3)]);
+        assert_eq!(
+            "1-3,3-4".parse::<PortPairing>().unwrap_err().code(),
+            "invalid_input"
+        );
+        assert_eq!("1-3,2".parse::<PortPairing>().unwrap_err().code(), "parse");
+        assert_eq!("0-1".parse::<PortPairing>().unwrap_err().code(), "parse");
+    }
+
+    #[test]
+    fn test_mixed_mode_nport() {
+        // A pair of identical uncoupled lines, ports 1/3 at one end and 2/4 at the other
+        let t = Complex::new(0.0, -0.9);
+        let r = Complex::new(0.1, 0.0);
+        let z = Complex::new(0.0, 0.0);
+        let s = vec![
+            vec![r, t, z, z],
+            vec![t, r, z, z],
+            vec![z, z, r, t],
+            vec![z, z, t, r],
+        ];
+        let pairing = "1-3,2-4".parse::<PortPairing>().unwrap();
+        let [dd, dc, cd, cc] = mixed_mode_nport(&s, &pairing).unwrap();
+        assert_eq!(dd, vec![vec![r, t], vec![t, r]]);
+        assert_eq!(cc, vec![vec![r, t], vec![t, r]]);
+        assert_eq!(dc, vec![vec![z, z], vec![z, z]]);
+        assert_eq!(cd, vec![vec![z, z], vec![z, z]]);
+
+        // One balanced port is the 2-terminal case
+        let s = vec![
+            vec![Complex::new(0.1, 0.2), Complex::new(0.3, -0.1)],
+            vec![Complex::new(0.2, 0.0), Complex::new(-0.4, 0.1)],
+        ];
+        let [dd, dc, cd, cc] = mixed_mode_nport(&s, &"1-2".parse().unwrap()).unwrap();
+        let exemplar = mixed_mode(&[[s[0][0], s[0][1]], [s[1][0], s[1][1]]]);
+        assert_eq!(
+            [dd[0][0], dc[0][0], cd[0][0], cc[0][0]],
+            [
+                exemplar[0][0],
+                exemplar[0][1],
+                exemplar[1][0],
+                exemplar[1][1]
+            ]
+        );
+
+        assert_eq!(
+            mixed_mode_nport(&s, &"1-3".parse().unwrap())
+                .unwrap_err()
+                .code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn test_calc_mixed_mode() {
+        // A pair of through lines at 1 GHz, and a 100 Ω resistor floating across each balanced
+        // port at 2 GHz
+        let text = "# GHz S RI R 50
+1 0 0 1 0 0 0 0 0
+  1 0 0 0 0 0 0 0
+  0 0 0 0 0 0 1 0
+  0 0 0 0 1 0 0 0
+2 0.5 0 0 0 0.5 0 0 0
+  0 0 0.5 0 0 0 0.5 0
+  0.5 0 0 0 0.5 0 0 0
+  0 0 0.5 0 0 0 0.5 0
+";
+        let pairing = "1-3,2-4".parse::<PortPairing>().unwrap();
+        let test = calc_mixed_mode(text, 4, &pairing, 1.0, Unit::Giga).unwrap();
+        assert_eq!(test.sdd.len(), 2);
+        comp_f64(
+            &test.sdd[1][0].re,
+            &1.0,
+            F64Margin::default(),
+            "calc_mixed_mode()",
+            "sdd21",
+        );
+        comp_f64(
+            &test.zdiff[0].re,
+            &100.0,
+            F64Margin::default(),
+            "calc_mixed_mode()",
+            "zdiff",
+        );
+        assert_eq!(test.z0, 50.0);
+
+        // Interpolated half way between the two records
+        let test = calc_mixed_mode(text, 4, &pairing, 1.5, Unit::Giga).unwrap();
+        comp_f64(
+            &test.sdd[0][0].re,
+            &0.0,
+            F64Margin::default(),
+            "calc_mixed_mode()",
+            "sdd11",
+        );
+        comp_f64(
+            &test.scc[0][0].re,
+            &0.5,
+            F64Margin::default(),
+            "calc_mixed_mode()",
+            "scc11",
+        );
+
+        assert!(calc_mixed_mode(text, 4, &pairing, 3.0, Unit::Giga).is_err());
+    }
+
+    #[test]
+    fn test_calc_balun() {
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        let design = design_balun(
+            BalunType::Lattice,
+            50.0,
+            100.0,
+            0.0,
+            2.0,
+            Unit::Giga,
+            Unit::Pico,
+            Unit::Nano,
+        )
+        .unwrap();
+        assert_eq!(design.element, "balun");
+        assert_eq!(design.units, vec!["lattice", "nH", "pF"]);
+        comp_f64(
+            &design.vals[0],
+            &(50.0 * 2.0_f64.sqrt() / (4.0 * PI)),
+            margin,
+            "design_balun()",
+            "l",
+        );
+
+        let units = design
+            .units
+            .iter()
+            .map(|val| val.as_str())
+            .collect::<Vec<_>>();
+        let sweep = FrequencySweep::linear(1.0, 2.0, 3, Unit::Giga).unwrap();
+        let test = calc_balun(&design.vals, &units, 50.0, 100.0, &sweep).unwrap();
+        assert_eq!(test.len(), 3);
+        comp_f64(&test[2].zin.re, &50.0, margin, "calc_balun()", "zin.re");
+        comp_f64(&test[2].zin.im, &0.0, margin, "calc_balun()", "zin.im");
+        comp_f64(&test[2].phase, &0.0, margin, "calc_balun()", "phase");
+        assert!(test[2].cmrr > 100.0);
+        // At half the design frequency the high-pass arm is down 12 dB on the low-pass one
+        comp_f64(
+            &test[0].amplitude,
+            &(40.0 * 2.0_f64.log10()),
+            margin,
+            "calc_balun()",
+            "amplitude",
+        );
+
+        // In the Smith chart the differential side faces the source at 2 z0
+        let test = calc_smith_cascade(
+            vec![ElementType::Balun],
+            vec![design.vals.clone()],
+            vec![units.clone()],
+            vec![],
+            1.0,
+            0.0,
+            50.0,
+            2.0,
+            Unit::Giga,
+        )
+        .unwrap();
+        comp_f64(
+            &test[0].z.re,
+            &100.0,
+            margin,
+            "calc_smith_cascade()",
+            "balun",
+        );
+
+        // The balun keeps the differential impedance it was designed for, else it takes z_diff
+        let design = design_balun(
+            BalunType::Lattice,
+            50.0,
+            200.0,
+            0.0,
+            2.0,
+            Unit::Giga,
+            Unit::Pico,
+            Unit::Nano,
+        )
+        .unwrap();
+        let balun = gen_balun(&design.vals, &units, 50.0, 100.0).unwrap();
+        assert_eq!(balun.z_diff(), 200.0);
+        let balun = gen_balun(&design.vals[..2], &units, 50.0, 100.0).unwrap();
+        assert_eq!(balun.z_diff(), 100.0);
+
+        let design = design_balun(
+            BalunType::Marchand,
+            50.0,
+            100.0,
+            150.0,
+            2.0,
+            Unit::Giga,
+            Unit::Pico,
+            Unit::Nano,
+        )
+        .unwrap();
+        assert_eq!(design.units, vec!["marchand", "GHz"]);
+        assert_eq!(design.vals[2..], [2.0, 100.0]);
+        comp_f64(
+            &design.vals[1],
+            &(5000.0_f64.sqrt() / 2.0),
+            margin,
+            "design_balun()",
+            "z0o",
+        );
+        assert_eq!(
+            design_balun(
+                BalunType::Marchand,
+                50.0,
+                100.0,
+                20.0,
+                2.0,
+                Unit::Giga,
+                Unit::Pico,
+                Unit::Nano,
+            )
+            .unwrap_err()
+            .code(),
+            "non_physical"
+        );
+        assert!(design_balun(
+            BalunType::Transformer,
+            50.0,
+            100.0,
+            0.0,
+            2.0,
+            Unit::Giga,
+            Unit::Pico,
+            Unit::Nano,
+        )
+        .is_err());
+        assert!(calc_balun(&[1.0], &["lattice", "nH", "pF"], 50.0, 100.0, &sweep).is_err());
+        assert!(calc_balun(&[1.0, 1.0], &["bazooka"], 50.0, 100.0, &sweep).is_err());
+        assert!(calc_balun(
+            &[1.0, 1.0, -1.0],
+            &["lattice", "nH", "pF"],
+            50.0,
+            100.0,
+            &sweep
+        )
+        .is_err());
+    }
+}
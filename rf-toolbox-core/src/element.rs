@@ -0,0 +1,186 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::frequency::Frequency;
+use crate::rf_utils::{calc_z_norm, scale, unscale};
+use crate::smith::{find_smith_coord, find_smith_coord_c64};
+use crate::unit::Unit;
+use float_cmp::{approx_eq, F64Margin};
+use num_complex::{c64, Complex};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod balun;
+pub mod blackbox;
+pub mod capacitor;
+pub mod inductor;
+pub mod measured;
+pub mod openstub;
+pub mod resistor;
+pub mod rlc;
+pub mod shortedstub;
+pub mod tline;
+pub mod transformer;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    Series,
+    Shunt,
+}
+
+impl From<Orientation> for bool {
+    fn from(f: Orientation) -> bool {
+        match f {
+            Orientation::Series => false,
+            Orientation::Shunt => true,
+        }
+    }
+}
+
+// Element codes used by the schematic and the calculators
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ElementType {
+    #[serde(rename = "si")]
+    SeriesInd,
+    #[serde(rename = "pi")]
+    ShuntInd,
+    #[serde(rename = "sc")]
+    SeriesCap,
+    #[serde(rename = "pc")]
+    ShuntCap,
+    #[serde(rename = "sr")]
+    SeriesRes,
+    #[serde(rename = "pr")]
+    ShuntRes,
+    #[serde(rename = "srlc")]
+    SeriesRlc,
+    #[serde(rename = "prlc")]
+    ShuntRlc,
+    #[serde(rename = "rlc")]
+    Rlc,
+    #[serde(rename = "rl")]
+    Rl,
+    #[serde(rename = "rc")]
+    Rc,
+    #[serde(rename = "bb")]
+    BlackBox,
+    #[serde(rename = "tl")]
+    TLine,
+    #[serde(rename = "ss")]
+    ShortedStub,
+    #[serde(rename = "so")]
+    OpenStub,
+    #[serde(rename = "xfmr")]
+    Transformer,
+    #[serde(rename = "balun")]
+    Balun,
+    #[serde(rename = "customZ")]
+    CustomZ,
+    #[serde(rename = "ss2p")]
+    SeriesMeasured,
+    #[serde(rename = "ps2p")]
+    ShuntMeasured,
+}
+
+impl FromStr for ElementType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "si" => Ok(ElementType::SeriesInd),
+            "pi" => Ok(ElementType::ShuntInd),
+            "sc" => Ok(ElementType::SeriesCap),
+            "pc" => Ok(ElementType::ShuntCap),
+            "sr" => Ok(ElementType::SeriesRes),
+            "pr" => Ok(ElementType::ShuntRes),
+            "srlc" => Ok(ElementType::SeriesRlc),
+            "prlc" => Ok(ElementType::ShuntRlc),
+            "rlc" => Ok(ElementType::Rlc),
+            "rl" => Ok(ElementType::Rl),
+            "rc" => Ok(ElementType::Rc),
+            "bb" => Ok(ElementType::BlackBox),
+            "tl" => Ok(ElementType::TLine),
+            "ss" => Ok(ElementType::ShortedStub),
+            "so" => Ok(ElementType::OpenStub),
+            "xfmr" => Ok(ElementType::Transformer),
+            "balun" => Ok(ElementType::Balun),
+            "customZ" => Ok(ElementType::CustomZ),
+            "ss2p" => Ok(ElementType::SeriesMeasured),
+            "ps2p" => Ok(ElementType::ShuntMeasured),
+            _ => Err(Error::Parse(format!("element {:?} not recognized", s))),
+        }
+    }
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = match self {
+            ElementType::SeriesInd => "si",
+            ElementType::ShuntInd => "pi",
+            ElementType::SeriesCap => "sc",
+            ElementType::ShuntCap => "pc",
+            ElementType::SeriesRes => "sr",
+            ElementType::ShuntRes => "pr",
+            ElementType::SeriesRlc => "srlc",
+            ElementType::ShuntRlc => "prlc",
+            ElementType::Rlc => "rlc",
+            ElementType::Rl => "rl",
+            ElementType::Rc => "rc",
+            ElementType::BlackBox => "bb",
+            ElementType::TLine => "tl",
+            ElementType::ShortedStub => "ss",
+            ElementType::OpenStub => "so",
+            ElementType::Transformer => "xfmr",
+            ElementType::Balun => "balun",
+            ElementType::CustomZ => "customZ",
+            ElementType::SeriesMeasured => "ss2p",
+            ElementType::ShuntMeasured => "ps2p",
+        };
+        write!(f, "{}", val)
+    }
+}
+
+// Smith chart x and y points of the arc, followed by its start and end in z or y
+pub type SmithArc = (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64));
+
+pub trait Element {
+    fn labels(&self) -> Vec<&str>;
+    fn vals(&self) -> Vec<f64>;
+    fn units(&self) -> Vec<Unit>;
+    fn tols(&self) -> Vec<f64>;
+    fn orientation(&self) -> Orientation;
+
+    fn r(&self, freq: Frequency) -> f64 {
+        self.z(freq).re
+    }
+    fn x(&self, freq: Frequency) -> f64 {
+        self.z(freq).im
+    }
+
+    fn z(&self, freq: Frequency) -> Complex<f64>;
+
+    fn z_norm(&self, freq: Frequency, z0: f64) -> Complex<f64> {
+        self.z(freq) / z0
+    }
+
+    fn abcd(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        match self.orientation() {
+            Orientation::Series => [[Complex::ONE, self.z(freq)], [Complex::ZERO, Complex::ONE]],
+            Orientation::Shunt => [
+                [Complex::ONE, Complex::ZERO],
+                [self.z(freq).inv(), Complex::ONE],
+            ],
+        }
+    }
+
+    fn calc_arc(
+        &self,
+        freq: Frequency,
+        zin_norm: Complex<f64>,
+        z0: f64,
+        npts: usize,
+        verbose: bool,
+    ) -> SmithArc;
+}
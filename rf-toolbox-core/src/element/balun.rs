@@ -0,0 +1,394 @@
+use crate::differential::mixed_mode;
+use crate::element::{
+    capacitor::Capacitor, inductor::Inductor, transformer::Transformer, Element, Orientation,
+    SmithArc,
+};
+use crate::error::Error;
+use crate::frequency::Frequency;
+use crate::mna::{Circuit, CoupledLine};
+use crate::smith::find_smith_coord;
+use crate::unit::Unit;
+use num_complex::{c64, Complex};
+use serde::Deserialize;
+use std::f64::consts::{PI, SQRT_2};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum BalunType {
+    #[serde(rename = "lattice")]
+    Lattice,
+    #[serde(rename = "marchand")]
+    Marchand,
+    #[serde(rename = "xfmr")]
+    Transformer,
+}
+
+impl FromStr for BalunType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lattice" => Ok(BalunType::Lattice),
+            "marchand" => Ok(BalunType::Marchand),
+            "xfmr" => Ok(BalunType::Transformer),
+            _ => Err(Error::Parse(format!(
+                "balun {:?} not recognized, expected lattice, marchand or xfmr",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for BalunType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = match self {
+            BalunType::Lattice => "lattice",
+            BalunType::Marchand => "marchand",
+            BalunType::Transformer => "xfmr",
+        };
+        write!(f, "{}", val)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BalunModel {
+    // Lumped lattice of l and c in H and F: L to the positive output and C to ground there, C to
+    // the negative output and L to ground there, for ±90° at the outputs
+    //
+    //          +---L---+---o p       +---C---+---o n
+    //     in o-+       |          in-+       |
+    //                  C                     L
+    //                  |                     |
+    //                 GND                   GND
+    Lattice { l: f64, c: f64 },
+    // Two quarter wave coupled sections. The input line runs through both and ends open, the
+    // coupled lines are grounded at the outer ends and give p and n at the inner ends.
+    //
+    //     in o=========+=========o open
+    //     GND o========o p   n o========o GND
+    Marchand(CoupledLine),
+    // Primary from the input to ground, secondary floating between p and n
+    Transformer(Transformer),
+}
+
+// Single-ended (port 1) to differential (port 2) balun with its ports referenced to z_se and
+// z_diff. The terminals of the differential port are each referenced to z_diff/2 to ground, so
+// the common mode sees z_diff/4 as in the mixed-mode parameters of crate::differential.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Balun {
+    model: BalunModel,
+    z_se: f64,
+    z_diff: f64,
+    orientation: Orientation,
+}
+
+impl Balun {
+    pub fn new(model: BalunModel, z_se: f64, z_diff: f64) -> Self {
+        Balun {
+            model,
+            z_se,
+            z_diff,
+            orientation: Orientation::Series,
+        }
+    }
+
+    // Lattice for real terminations at freq, each arm with a reactance of √(z_se z_diff)
+    pub fn lattice(z_se: f64, z_diff: f64, freq: Frequency) -> Self {
+        let x = (z_se * z_diff).sqrt();
+        Balun::new(
+            BalunModel::Lattice {
+                l: x / freq.w(),
+                c: 1.0 / (freq.w() * x),
+            },
+            z_se,
+            z_diff,
+        )
+    }
+
+    // Marchand balun centred at freq. With the even mode impedance z0e large, the input sees
+    // 4 z0o² / z_diff.
+    pub fn marchand(z_se: f64, z_diff: f64, z0e: f64, freq: Frequency) -> Self {
+        Balun::new(
+            BalunModel::Marchand(CoupledLine {
+                z0e,
+                z0o: (z_se * z_diff).sqrt() / 2.0,
+                f0: freq.freq(),
+            }),
+            z_se,
+            z_diff,
+        )
+    }
+
+    pub fn model(&self) -> BalunModel {
+        self.model
+    }
+
+    pub fn kind(&self) -> BalunType {
+        match self.model {
+            BalunModel::Lattice { .. } => BalunType::Lattice,
+            BalunModel::Marchand(_) => BalunType::Marchand,
+            BalunModel::Transformer(_) => BalunType::Transformer,
+        }
+    }
+
+    pub fn z_se(&self) -> f64 {
+        self.z_se
+    }
+
+    pub fn z_diff(&self) -> f64 {
+        self.z_diff
+    }
+
+    // Ports in, p and n to ground, referenced to z_se, z_diff/2 and z_diff/2
+    pub fn circuit(&self) -> Result<Circuit, Error> {
+        let mut circuit = Circuit::new();
+        match self.model {
+            BalunModel::Lattice { l, c } => {
+                let series = Orientation::Series;
+                let ind = || Box::new(Inductor::new(0.0, l, Unit::Q, Unit::Base, 0.0, 0.0, series));
+                let cap = || {
+                    Box::new(Capacitor::new(
+                        0.0,
+                        c,
+                        Unit::Q,
+                        Unit::Base,
+                        0.0,
+                        0.0,
+                        series,
+                    ))
+                };
+                circuit.add_branch("L1", ind(), ["in", "p"]);
+                circuit.add_branch("C1", cap(), ["p", "0"]);
+                circuit.add_branch("C2", cap(), ["in", "n"]);
+                circuit.add_branch("L2", ind(), ["n", "0"]);
+            }
+            BalunModel::Marchand(line) => {
+                circuit.add_coupled_line("M1", line, ["in", "0", "mid", "p"]);
+                circuit.add_coupled_line("M2", line, ["mid", "n", "open", "0"]);
+            }
+            BalunModel::Transformer(xfmr) => {
+                circuit.add_two_port("X1", Box::new(xfmr), ["in", "0", "p", "n"]);
+            }
+        }
+        circuit.add_port(["in", "0"], self.z_se)?;
+        circuit.add_port(["p", "0"], self.z_diff / 2.0)?;
+        circuit.add_port(["n", "0"], self.z_diff / 2.0)?;
+        Ok(circuit)
+    }
+
+    // Single-ended S-parameters of in, p and n
+    pub fn s3(&self, freq: Frequency) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        self.circuit()?.s(freq)
+    }
+
+    // Mixed-mode S-parameters with the single-ended port first, then the differential and the
+    // common mode of the balanced port: [[ss, sd, sc], [ds, dd, dc], [cs, cd, cc]]
+    pub fn mixed_mode(&self, freq: Frequency) -> Result<[[Complex<f64>; 3]; 3], Error> {
+        let s = self.s3(freq)?;
+        let [[dd, dc], [cd, cc]] = mixed_mode(&[[s[1][1], s[1][2]], [s[2][1], s[2][2]]]);
+        Ok([
+            [
+                s[0][0],
+                (s[0][1] - s[0][2]) / SQRT_2,
+                (s[0][1] + s[0][2]) / SQRT_2,
+            ],
+            [(s[1][0] - s[2][0]) / SQRT_2, dd, dc],
+            [(s[1][0] + s[2][0]) / SQRT_2, cd, cc],
+        ])
+    }
+
+    // Amplitude (dB) and phase (°) of the positive over the negative output, the phase taken
+    // from the ideal 180°
+    pub fn imbalance(&self, freq: Frequency) -> Result<(f64, f64), Error> {
+        let s = self.s3(freq)?;
+        let ratio = s[1][0] / s[2][0];
+        let phase = (-ratio).arg() * 180.0 / PI;
+        Ok((20.0 * ratio.norm().log10(), phase))
+    }
+
+    // Single-ended to differential S-parameters, referenced to z_se and z_diff
+    pub fn s(&self, freq: Frequency) -> Result<[[Complex<f64>; 2]; 2], Error> {
+        let s = self.mixed_mode(freq)?;
+        Ok([[s[0][0], s[0][1]], [s[1][0], s[1][1]]])
+    }
+}
+
+impl Element for Balun {
+    fn labels(&self) -> Vec<&str> {
+        match &self.model {
+            BalunModel::Lattice { .. } => vec!["l", "c"],
+            BalunModel::Marchand(_) => vec!["z0e", "z0o", "f0"],
+            BalunModel::Transformer(xfmr) => xfmr.labels(),
+        }
+    }
+
+    fn vals(&self) -> Vec<f64> {
+        match &self.model {
+            BalunModel::Lattice { l, c } => vec![*l, *c],
+            BalunModel::Marchand(line) => vec![line.z0e, line.z0o, line.f0],
+            BalunModel::Transformer(xfmr) => xfmr.vals(),
+        }
+    }
+
+    fn units(&self) -> Vec<Unit> {
+        match &self.model {
+            BalunModel::Transformer(xfmr) => xfmr.units(),
+            _ => vec![Unit::Base; self.vals().len()],
+        }
+    }
+
+    fn tols(&self) -> Vec<f64> {
+        vec![0.0; self.vals().len()]
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // Impedance into the differential port with the single-ended port shorted
+    fn z(&self, freq: Frequency) -> Complex<f64> {
+        let [[a, b], _] = self.abcd(freq);
+        b / a
+    }
+
+    // Single-ended port 1, which faces the load in the Smith chart cascade, with the common mode
+    // terminated. NaN when the circuit cannot be solved.
+    fn abcd(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let [[s11, s12], [s21, s22]] = match self.s(freq) {
+            Ok(val) => val,
+            Err(_) => return [[c64(f64::NAN, f64::NAN); 2]; 2],
+        };
+        let (z1, z2) = (self.z_se, self.z_diff);
+        let den = 2.0 * s21;
+        [
+            [
+                ((1.0 + s11) * (1.0 - s22) + s12 * s21) / den * (z1 / z2).sqrt(),
+                ((1.0 + s11) * (1.0 + s22) - s12 * s21) / den * (z1 * z2).sqrt(),
+            ],
+            [
+                ((1.0 - s11) * (1.0 - s22) - s12 * s21) / den / (z1 * z2).sqrt(),
+                ((1.0 - s11) * (1.0 + s22) + s12 * s21) / den * (z2 / z1).sqrt(),
+            ],
+        ]
+    }
+
+    fn calc_arc(
+        &self,
+        freq: Frequency,
+        zin_norm: Complex<f64>,
+        z0: f64,
+        npts: usize,
+        verbose: bool,
+    ) -> SmithArc {
+        let mut gx: Vec<f64> = vec![0.0; npts + 1];
+        let mut gy: Vec<f64> = vec![0.0; npts + 1];
+
+        let [[a, b], [c, d]] = self.abcd(freq);
+        let zin = zin_norm * z0;
+        let start = zin_norm;
+        let end = (d * zin + b) / (c * zin + a) / z0;
+        for i in 0..=npts {
+            let xpt = start.re + ((end.re - start.re) * (i as f64)) / (npts as f64);
+            let ypt = start.im + ((end.im - start.im) * (i as f64)) / (npts as f64);
+            let pt = find_smith_coord(xpt, ypt, false, verbose).unwrap();
+            gx[i] = pt.re;
+            gy[i] = pt.im;
+        }
+
+        (gx, gy, (start.re, start.im), (end.re, end.im))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    #[test]
+    fn test_balun() {
+        let freq = Frequency::new(2e9, Unit::Base);
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+
+        // Designed baluns are matched and balanced at freq, all of the power going differential
+        let lattice = Balun::lattice(50.0, 200.0, freq);
+        let marchand = Balun::marchand(50.0, 200.0, 1e12, freq);
+        for (name, balun) in [("lattice", lattice), ("marchand", marchand)] {
+            let s = balun.mixed_mode(freq).unwrap();
+            comp_c64(&s[0][0], &Complex::ZERO, margin, name, "sss");
+            comp_f64(&s[1][0].norm(), &1.0, margin, name, "|sds|");
+            comp_c64(&s[2][0], &Complex::ZERO, margin, name, "scs");
+            let (amplitude, phase) = balun.imbalance(freq).unwrap();
+            comp_f64(&amplitude, &0.0, margin, name, "amplitude");
+            comp_f64(&phase, &0.0, margin, name, "phase");
+
+            // As a cascade element the differential load comes back as z_se
+            let [[a, b], [c, d]] = balun.abcd(freq);
+            let zl = c64(200.0, 0.0);
+            comp_c64(
+                &((a * zl + b) / (c * zl + d)),
+                &c64(50.0, 0.0),
+                F64Margin::from((1e-6, 4)),
+                name,
+                "zin",
+            );
+        }
+        comp_f64(&marchand.vals()[1], &50.0, margin, "marchand", "z0o");
+
+        // Off centre the lattice stays at 180° but its low-pass output falls as (f0/f)² against
+        // its high-pass one. Ideal Marchand sections stay balanced, a finite even mode impedance
+        // costing the match instead.
+        let off = Frequency::new(2.6e9, Unit::Base);
+        let (amplitude, phase) = lattice.imbalance(off).unwrap();
+        comp_f64(&phase, &0.0, margin, "lattice", "phase off centre");
+        comp_f64(
+            &amplitude,
+            &(-40.0 * 1.3_f64.log10()),
+            margin,
+            "lattice",
+            "amplitude off centre",
+        );
+        let marchand = Balun::marchand(50.0, 200.0, 150.0, freq);
+        let (amplitude, phase) = marchand.imbalance(off).unwrap();
+        comp_f64(&amplitude, &0.0, margin, "marchand", "amplitude off centre");
+        comp_f64(&phase, &0.0, margin, "marchand", "phase off centre");
+        assert!(marchand.mixed_mode(off).unwrap()[0][0].norm() > 0.3);
+
+        // A tightly coupled 1:4 transformer is balanced, its secondary floating
+        let xfmr = Transformer::new(
+            0.0,
+            1.0,
+            4.0,
+            1.0 - 1e-9,
+            Unit::Q,
+            Unit::Micro,
+            Unit::Micro,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        let balun = Balun::new(BalunModel::Transformer(xfmr), 50.0, 200.0);
+        let s = balun.mixed_mode(freq).unwrap();
+        comp_c64(&s[2][0], &Complex::ZERO, margin, "xfmr", "scs");
+        assert!(s[0][0].norm() < 0.01);
+        let s3 = balun.s3(freq).unwrap();
+        comp_c64(&s3[1][0], &(-s3[2][0]), margin, "xfmr", "s21");
+        assert_eq!(balun.kind(), BalunType::Transformer);
+        assert_eq!(
+            balun.labels(),
+            vec!["res", "indp", "inds", "m", "res_s", "cap"]
+        );
+
+        assert_eq!(
+            "marchand".parse::<BalunType>().unwrap(),
+            BalunType::Marchand
+        );
+        assert!("rat-race".parse::<BalunType>().is_err());
+    }
+}
@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -119,7 +119,7 @@ impl Element for BlackBox {
         z0: f64,
         _npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let pt = find_smith_coord_c64(self.z_norm(freq, self.z0), self.orientation.into(), verbose)
             .unwrap();
 
@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -16,6 +16,10 @@ pub struct Capacitor {
     cap_unit: Unit,
     res_tol: f64,
     cap_tol: f64,
+    esl: f64,
+    esl_unit: Unit,
+    q_freq: f64,
+    q_freq_unit: Unit,
     orientation: Orientation,
 }
 
@@ -36,6 +40,10 @@ impl Capacitor {
             cap_unit,
             res_tol,
             cap_tol,
+            esl: 0.0,
+            esl_unit: Unit::Base,
+            q_freq: 0.0,
+            q_freq_unit: Unit::Base,
             orientation,
         }
     }
@@ -64,6 +72,36 @@ impl Capacitor {
         self.cap_tol
     }
 
+    pub fn esl(&self) -> f64 {
+        self.esl
+    }
+
+    pub fn esl_unit(&self) -> Unit {
+        self.esl_unit
+    }
+
+    pub fn q_freq(&self) -> f64 {
+        self.q_freq
+    }
+
+    pub fn q_freq_unit(&self) -> Unit {
+        self.q_freq_unit
+    }
+
+    // Series resonance of the capacitance with its ESL, None for an ideal capacitor
+    pub fn srf(&self) -> Option<Frequency> {
+        let esl = unscale(self.esl, &self.esl_unit);
+        if esl > 0.0 {
+            let cap = unscale(self.cap, &self.cap_unit);
+            Some(Frequency::new(
+                1.0 / (2.0 * PI * (esl * cap).sqrt()),
+                Unit::Base,
+            ))
+        } else {
+            None
+        }
+    }
+
     pub fn set_res(&mut self, val: f64) -> &Self {
         self.res = val;
         self
@@ -104,6 +142,26 @@ impl Capacitor {
         self
     }
 
+    pub fn set_esl(&mut self, val: f64) -> &Self {
+        self.esl = val;
+        self
+    }
+
+    pub fn set_esl_unit(&mut self, val: Unit) -> &Self {
+        self.esl_unit = val;
+        self
+    }
+
+    pub fn set_q_freq(&mut self, val: f64) -> &Self {
+        self.q_freq = val;
+        self
+    }
+
+    pub fn set_q_freq_unit(&mut self, val: Unit) -> &Self {
+        self.q_freq_unit = val;
+        self
+    }
+
     pub fn set_orientation(&mut self, val: Orientation) -> &Self {
         self.orientation = val;
         self
@@ -119,6 +177,10 @@ impl Default for Capacitor {
             cap_unit: Unit::Femto,
             res_tol: 0.0,
             cap_tol: 0.0,
+            esl: 0.0,
+            esl_unit: Unit::Base,
+            q_freq: 0.0,
+            q_freq_unit: Unit::Base,
             orientation: Orientation::Series,
         }
     }
@@ -145,18 +207,26 @@ impl Element for Capacitor {
         self.orientation
     }
 
+    // A Q quoted at q_freq fixes the ESR there, otherwise Q holds at every frequency
     fn z(&self, freq: Frequency) -> Complex<f64> {
         let res = match self.res_unit {
             Unit::Q => {
                 if approx_eq!(f64, self.res, 0_f64, F64Margin::default()) {
                     0.0
+                } else if self.q_freq > 0.0 {
+                    let w_q = Frequency::new(self.q_freq, self.q_freq_unit).w();
+                    1.0 / (w_q * unscale(self.cap, &self.cap_unit) * self.res)
                 } else {
                     1.0 / (freq.w() * unscale(self.cap, &self.cap_unit) * self.res)
                 }
             }
             _ => unscale(self.res, &self.res_unit),
         };
-        c64(res, -1.0 / (freq.w() * unscale(self.cap, &self.cap_unit)))
+        c64(
+            res,
+            freq.w() * unscale(self.esl, &self.esl_unit)
+                - 1.0 / (freq.w() * unscale(self.cap, &self.cap_unit)),
+        )
     }
 
     fn calc_arc(
@@ -166,7 +236,7 @@ impl Element for Capacitor {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let mut gx: Vec<f64> = vec![0.0; npts + 1];
         let mut gy: Vec<f64> = vec![0.0; npts + 1];
 
@@ -414,4 +484,52 @@ mod tests {
         comp_vec_f64(test.0, x_coord, margin, testname, "x_coord");
         comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
     }
+
+    #[test]
+    fn test_capacitor_parasitics() {
+        let testname = "capacitor_parasitics";
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        let mut element = Capacitor::new(
+            100.0,
+            1.0,
+            Unit::Q,
+            Unit::Pico,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        assert_eq!(element.srf(), None);
+
+        element.set_esl(0.5);
+        element.set_esl_unit(Unit::Nano);
+        element.set_q_freq(1.0);
+        element.set_q_freq_unit(Unit::Giga);
+        let srf = element.srf().unwrap();
+        comp_f64(
+            &srf.freq(),
+            &(1.0 / (2.0 * PI * (0.5e-9_f64 * 1e-12).sqrt())),
+            margin,
+            testname,
+            "srf",
+        );
+
+        // ESR quoted at 1 GHz holds at every frequency
+        let esr = 1.0 / (2.0 * PI * 1e9 * 1e-12 * 100.0);
+        comp_f64(&element.z(srf).re, &esr, margin, testname, "esr at srf");
+        comp_f64(&element.z(srf).im, &0.0, margin, testname, "x at srf");
+        comp_c64(
+            &element.z(Frequency::new(1.0, Unit::Giga)),
+            &c64(
+                esr,
+                2.0 * PI * 1e9 * 0.5e-9 - 1.0 / (2.0 * PI * 1e9 * 1e-12),
+            ),
+            margin,
+            testname,
+            "z below srf",
+        );
+        assert!(element.z(Frequency::new(10.0, Unit::Giga)).im > 0.0);
+    }
 }
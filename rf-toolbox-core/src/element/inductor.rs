@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -16,6 +16,10 @@ pub struct Inductor {
     ind_unit: Unit,
     res_tol: f64,
     ind_tol: f64,
+    cap: f64,
+    cap_unit: Unit,
+    q_freq: f64,
+    q_freq_unit: Unit,
     orientation: Orientation,
 }
 
@@ -36,6 +40,10 @@ impl Inductor {
             ind_unit,
             res_tol,
             ind_tol,
+            cap: 0.0,
+            cap_unit: Unit::Base,
+            q_freq: 0.0,
+            q_freq_unit: Unit::Base,
             orientation,
         }
     }
@@ -64,6 +72,36 @@ impl Inductor {
         self.ind_tol
     }
 
+    pub fn cap(&self) -> f64 {
+        self.cap
+    }
+
+    pub fn cap_unit(&self) -> Unit {
+        self.cap_unit
+    }
+
+    pub fn q_freq(&self) -> f64 {
+        self.q_freq
+    }
+
+    pub fn q_freq_unit(&self) -> Unit {
+        self.q_freq_unit
+    }
+
+    // Parallel resonance of the inductance with its winding capacitance, None for an ideal inductor
+    pub fn srf(&self) -> Option<Frequency> {
+        let cap = unscale(self.cap, &self.cap_unit);
+        if cap > 0.0 {
+            let ind = unscale(self.ind, &self.ind_unit);
+            Some(Frequency::new(
+                1.0 / (2.0 * PI * (ind * cap).sqrt()),
+                Unit::Base,
+            ))
+        } else {
+            None
+        }
+    }
+
     pub fn set_res(&mut self, val: f64) -> &Self {
         self.res = val;
         self
@@ -104,6 +142,26 @@ impl Inductor {
         self
     }
 
+    pub fn set_cap(&mut self, val: f64) -> &Self {
+        self.cap = val;
+        self
+    }
+
+    pub fn set_cap_unit(&mut self, val: Unit) -> &Self {
+        self.cap_unit = val;
+        self
+    }
+
+    pub fn set_q_freq(&mut self, val: f64) -> &Self {
+        self.q_freq = val;
+        self
+    }
+
+    pub fn set_q_freq_unit(&mut self, val: Unit) -> &Self {
+        self.q_freq_unit = val;
+        self
+    }
+
     pub fn set_orientation(&mut self, val: Orientation) -> &Self {
         self.orientation = val;
         self
@@ -119,6 +177,10 @@ impl Default for Inductor {
             ind_unit: Unit::Pico,
             res_tol: 0.0,
             ind_tol: 0.0,
+            cap: 0.0,
+            cap_unit: Unit::Base,
+            q_freq: 0.0,
+            q_freq_unit: Unit::Base,
             orientation: Orientation::Series,
         }
     }
@@ -145,18 +207,35 @@ impl Element for Inductor {
         self.orientation
     }
 
+    // A loss quoted at q_freq follows the skin effect, the resistance growing as the root of
+    // frequency, otherwise Q (or the resistance) holds at every frequency
     fn z(&self, freq: Frequency) -> Complex<f64> {
-        let res = match self.res_unit {
-            Unit::Q => {
-                if approx_eq!(f64, self.res, 0_f64, F64Margin::default()) {
-                    0.0
-                } else {
-                    freq.w() * unscale(self.ind, &self.ind_unit) / self.res
-                }
+        let (w, skin) = match self.q_freq > 0.0 {
+            true => {
+                let w_q = Frequency::new(self.q_freq, self.q_freq_unit).w();
+                (w_q, (freq.w() / w_q).sqrt())
             }
-            _ => unscale(self.res, &self.res_unit),
+            false => (freq.w(), 1.0),
         };
-        c64(res, freq.w() * unscale(self.ind, &self.ind_unit))
+        let res = skin
+            * match self.res_unit {
+                Unit::Q => {
+                    if approx_eq!(f64, self.res, 0_f64, F64Margin::default()) {
+                        0.0
+                    } else {
+                        w * unscale(self.ind, &self.ind_unit) / self.res
+                    }
+                }
+                _ => unscale(self.res, &self.res_unit),
+            };
+        let z = c64(res, freq.w() * unscale(self.ind, &self.ind_unit));
+
+        let cap = unscale(self.cap, &self.cap_unit);
+        if cap > 0.0 {
+            z / (1.0 + c64(0.0, freq.w() * cap) * z)
+        } else {
+            z
+        }
     }
 
     fn calc_arc(
@@ -166,7 +245,7 @@ impl Element for Inductor {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let mut gx: Vec<f64> = vec![0.0; npts + 1];
         let mut gy: Vec<f64> = vec![0.0; npts + 1];
 
@@ -412,4 +491,64 @@ mod tests {
         comp_vec_f64(test.0, x_coord, margin, testname, "x_coord");
         comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
     }
+
+    #[test]
+    fn test_inductor_parasitics() {
+        let testname = "inductor_parasitics";
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        let mut element = Inductor::new(
+            40.0,
+            10.0,
+            Unit::Q,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        assert_eq!(element.srf(), None);
+
+        // Skin effect doubles Q for four times the frequency
+        element.set_q_freq(100.0);
+        element.set_q_freq_unit(Unit::Mega);
+        let q = |z: Complex<f64>| z.im / z.re;
+        comp_f64(
+            &q(element.z(Frequency::new(100.0, Unit::Mega))),
+            &40.0,
+            margin,
+            testname,
+            "q at q_freq",
+        );
+        comp_f64(
+            &q(element.z(Frequency::new(400.0, Unit::Mega))),
+            &80.0,
+            margin,
+            testname,
+            "q skin",
+        );
+
+        element.set_cap(0.2);
+        element.set_cap_unit(Unit::Pico);
+        let srf = element.srf().unwrap();
+        comp_f64(
+            &srf.freq(),
+            &(1.0 / (2.0 * PI * (10e-9_f64 * 0.2e-12).sqrt())),
+            margin,
+            testname,
+            "srf",
+        );
+
+        // Near resonance the loss is magnified by roughly Q squared, above it the part is capacitive
+        let res = element.z(Frequency::new(100.0, Unit::Mega)).re;
+        assert!(element.z(srf).re > 100.0 * res);
+        assert!(element.z(Frequency::new(2.0 * srf.freq(), Unit::Base)).im < 0.0);
+        let mut ideal = element;
+        ideal.set_cap(0.0);
+        assert!(
+            element.z(Frequency::new(100.0, Unit::Mega)).im
+                > ideal.z(Frequency::new(100.0, Unit::Mega)).im
+        );
+    }
 }
@@ -0,0 +1,699 @@
+use crate::element::{Element, Orientation, SmithArc};
+use crate::error::Error;
+use crate::freq_data::{Extrapolation, FreqData};
+use crate::frequency::Frequency;
+use crate::rf_utils::{s2abcd, scale, unscale};
+use crate::smith::find_smith_coord;
+use crate::touchstone::{read_touchstone, Touchstone};
+use crate::unit::Unit;
+use num_complex::{c64, Complex};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::SystemTime;
+
+// Relative difference below which a nominal value picks a library part
+const NOMINAL_TOL: f64 = 1e-6;
+
+// Part measured on a two-port fixture, e.g. a vendor .s2p, placed in series or shunt. Its
+// impedance is B of the fixture's ABCD for a series-thru measurement and 1 / C for a shunt one,
+// so a part measured either way can be used either way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measured {
+    s: [[FreqData; 2]; 2],
+    z0: f64,
+    fixture: Orientation,
+    val: f64,
+    unit: Unit,
+    orientation: Orientation,
+}
+
+impl Measured {
+    pub fn new(
+        data: &Touchstone,
+        fixture: Orientation,
+        val: f64,
+        unit: Unit,
+        orientation: Orientation,
+    ) -> Result<Self, Error> {
+        if data.ports() != 2 {
+            return Err(Error::InvalidInput(format!(
+                "a measured part needs 2-port data, got {} ports",
+                data.ports()
+            )));
+        }
+        // Outside the measured band the data reads as NaN unless set to hold or extend
+        let param = |i: usize, j: usize| -> Result<FreqData, Error> {
+            let mut out = data.param(i, j)?;
+            out.set_extrapolation(Extrapolation::default());
+            Ok(out)
+        };
+
+        Ok(Measured {
+            s: [[param(0, 0)?, param(0, 1)?], [param(1, 0)?, param(1, 1)?]],
+            z0: data.z0,
+            fixture,
+            val,
+            unit,
+            orientation,
+        })
+    }
+
+    pub fn read(
+        path: &str,
+        fixture: Orientation,
+        val: f64,
+        unit: Unit,
+        orientation: Orientation,
+    ) -> Result<Self, Error> {
+        let data = load_touchstone(Path::new(path))?;
+        Measured::new(&data, fixture, val, unit, orientation).map_err(|err| err.context(path))
+    }
+
+    pub fn val(&self) -> f64 {
+        self.val
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn z0(&self) -> f64 {
+        self.z0
+    }
+
+    pub fn fixture(&self) -> Orientation {
+        self.fixture
+    }
+
+    // Measured frequencies in Hz
+    pub fn freqs(&self) -> &[f64] {
+        self.s[0][0].freqs()
+    }
+
+    // S-parameters of the fixture, referenced to z0
+    pub fn s(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let interp = |val: &FreqData| val.interp(freq).unwrap_or(c64(f64::NAN, f64::NAN));
+        [
+            [interp(&self.s[0][0]), interp(&self.s[0][1])],
+            [interp(&self.s[1][0]), interp(&self.s[1][1])],
+        ]
+    }
+
+    pub fn set_orientation(&mut self, val: Orientation) -> &Self {
+        self.orientation = val;
+        self
+    }
+
+    pub fn set_extrapolation(&mut self, val: Extrapolation) -> &Self {
+        for param in self.s.iter_mut().flatten() {
+            param.set_extrapolation(val);
+        }
+        self
+    }
+}
+
+impl Element for Measured {
+    fn labels(&self) -> Vec<&str> {
+        vec!["val"]
+    }
+
+    fn vals(&self) -> Vec<f64> {
+        vec![self.val]
+    }
+
+    fn units(&self) -> Vec<Unit> {
+        vec![self.unit]
+    }
+
+    fn tols(&self) -> Vec<f64> {
+        vec![0.0]
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    fn z(&self, freq: Frequency) -> Complex<f64> {
+        let abcd = s2abcd(&self.s(freq), self.z0);
+        match self.fixture {
+            Orientation::Series => abcd[0][1],
+            Orientation::Shunt => abcd[1][0].inv(),
+        }
+    }
+
+    fn calc_arc(
+        &self,
+        freq: Frequency,
+        zin_norm: Complex<f64>,
+        z0: f64,
+        npts: usize,
+        verbose: bool,
+    ) -> SmithArc {
+        let mut gx: Vec<f64> = vec![0.0; npts + 1];
+        let mut gy: Vec<f64> = vec![0.0; npts + 1];
+
+        let (start, end) = match self.orientation {
+            Orientation::Series => (zin_norm, self.z_norm(freq, z0) + zin_norm),
+            Orientation::Shunt => (zin_norm.inv(), self.z_norm(freq, z0).inv() + zin_norm.inv()),
+        };
+        for i in 0..=npts {
+            let xpt = start.re + ((end.re - start.re) * (i as f64)) / (npts as f64);
+            let ypt = start.im + ((end.im - start.im) * (i as f64)) / (npts as f64);
+            let pt = find_smith_coord(xpt, ypt, self.orientation.into(), verbose).unwrap();
+            gx[i] = pt.re;
+            gy[i] = pt.im;
+        }
+
+        (gx, gy, (start.re, start.im), (end.re, end.im))
+    }
+}
+
+// Vendor library index mapping nominal values to measured parts, files relative to the index
+//  {"unit": "pF", "fixture": "series", "parts": [{"value": 1.0, "file": "c_1p0.s2p"}, ...]}
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartLibrary {
+    unit: Unit,
+    unit_label: String,
+    fixture: Orientation,
+    parts: Vec<(f64, PathBuf)>,
+}
+
+#[derive(Deserialize)]
+struct LibraryIndex {
+    unit: String,
+    #[serde(default)]
+    fixture: Option<String>,
+    parts: Vec<LibraryEntry>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LibraryEntry {
+    value: f64,
+    file: String,
+}
+
+impl Serialize for PartLibrary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let parts = self
+            .parts
+            .iter()
+            .map(|(value, file)| LibraryEntry {
+                value: *value,
+                file: file.display().to_string(),
+            })
+            .collect::<Vec<_>>();
+        let mut s = serializer.serialize_struct("PartLibrary", 3)?;
+        s.serialize_field("unit", &self.unit_label)?;
+        s.serialize_field(
+            "fixture",
+            match self.fixture {
+                Orientation::Series => "series",
+                Orientation::Shunt => "shunt",
+            },
+        )?;
+        s.serialize_field("parts", &parts)?;
+        s.end()
+    }
+}
+
+impl PartLibrary {
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn fixture(&self) -> Orientation {
+        self.fixture
+    }
+
+    // Nominal values in the library unit, in the order of the index
+    pub fn values(&self) -> Vec<f64> {
+        self.parts.iter().map(|val| val.0).collect()
+    }
+
+    pub fn parts(&self) -> &[(f64, PathBuf)] {
+        &self.parts
+    }
+
+    // File of the part of nominal value val in unit, which has to be in the library
+    pub fn find(&self, val: f64, unit: Unit) -> Result<&Path, Error> {
+        let val = scale(unscale(val, &unit), &self.unit);
+        let dist = |part: &(f64, PathBuf)| (part.0 - val).abs();
+        if let Some(part) = self
+            .parts
+            .iter()
+            .find(|part| dist(part) <= NOMINAL_TOL * val.abs())
+        {
+            return Ok(&part.1);
+        }
+        let nearest = self
+            .parts
+            .iter()
+            .min_by(|a, b| dist(a).total_cmp(&dist(b)))
+            .map(|part| part.0);
+        Err(Error::InvalidInput(match nearest {
+            Some(nearest) => format!(
+                "no {} {} part in the library, the nearest is {} {}",
+                val, self.unit_label, nearest, self.unit_label
+            ),
+            None => "the library has no parts".to_string(),
+        }))
+    }
+
+    pub fn part(&self, val: f64, unit: Unit, orientation: Orientation) -> Result<Measured, Error> {
+        let path = self.find(val, unit)?;
+        Measured::read(
+            &path.to_string_lossy(),
+            self.fixture,
+            scale(unscale(val, &unit), &self.unit),
+            self.unit,
+            orientation,
+        )
+    }
+}
+
+fn parse_fixture(val: &str) -> Result<Orientation, Error> {
+    match val {
+        "series" => Ok(Orientation::Series),
+        "shunt" => Ok(Orientation::Shunt),
+        _ => Err(Error::Parse(format!(
+            "fixture {:?} not recognized, expected series or shunt",
+            val
+        ))),
+    }
+}
+
+pub fn parse_library(text: &str, dir: &Path) -> Result<PartLibrary, Error> {
+    let index: LibraryIndex =
+        serde_json::from_str(text).map_err(|err| Error::Parse(err.to_string()))?;
+    let unit = Unit::from_str(&index.unit)?;
+    let fixture = match index.fixture.as_deref() {
+        Some(val) => parse_fixture(val)?,
+        None => Orientation::Series,
+    };
+    if let Some(entry) = index
+        .parts
+        .iter()
+        .find(|val| !(val.value.is_finite() && val.value > 0.0))
+    {
+        return Err(Error::InvalidInput(format!(
+            "part {} has a nominal value of {}",
+            entry.file, entry.value
+        )));
+    }
+
+    Ok(PartLibrary {
+        unit,
+        unit_label: index.unit,
+        fixture,
+        parts: index
+            .parts
+            .into_iter()
+            .map(|val| (val.value, dir.join(val.file)))
+            .collect(),
+    })
+}
+
+pub fn read_library(path: &str) -> Result<PartLibrary, Error> {
+    let text = std::fs::read_to_string(path).map_err(|err| Error::from(err).context(path))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    parse_library(&text, dir).map_err(|err| err.context(path))
+}
+
+type Cache<T> = Mutex<HashMap<PathBuf, (SystemTime, Arc<T>)>>;
+
+static TOUCHSTONES: OnceLock<Cache<Touchstone>> = OnceLock::new();
+static LIBRARIES: OnceLock<Cache<PartLibrary>> = OnceLock::new();
+
+// File at path as read last time, unless it has been modified since
+fn cached<T>(
+    cache: &OnceLock<Cache<T>>,
+    path: &Path,
+    read: fn(&str) -> Result<T, Error>,
+) -> Result<Arc<T>, Error> {
+    let modified = std::fs::metadata(path).and_then(|val| val.modified()).ok();
+    let mut cache = cache
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let (Some(modified), Some((time, val))) = (modified, cache.get(path)) {
+        if modified == *time {
+            return Ok(val.clone());
+        }
+    }
+    let val = Arc::new(read(&path.to_string_lossy())?);
+    if let Some(modified) = modified {
+        cache.insert(path.to_path_buf(), (modified, val.clone()));
+    }
+    Ok(val)
+}
+
+pub fn load_touchstone(path: &Path) -> Result<Arc<Touchstone>, Error> {
+    cached(&TOUCHSTONES, path, read_touchstone)
+}
+
+pub fn load_library(path: &Path) -> Result<Arc<PartLibrary>, Error> {
+    cached(&LIBRARIES, path, read_library)
+}
+
+// Where a measured element's data comes from, a library index (.json) picking the part by its
+// nominal value or a single Touchstone file. The fixture of a single file is series-thru unless
+// given, a library names its own.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PartFile {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub fixture: Option<Orientation>,
+    #[serde(default)]
+    pub extrapolation: Extrapolation,
+}
+
+impl PartFile {
+    pub fn new(path: impl Into<PathBuf>, fixture: Option<Orientation>) -> Self {
+        PartFile {
+            path: path.into(),
+            fixture,
+            extrapolation: Extrapolation::default(),
+        }
+    }
+
+    pub fn set_extrapolation(&mut self, val: Extrapolation) -> &Self {
+        self.extrapolation = val;
+        self
+    }
+
+    pub fn is_library(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|val| val.eq_ignore_ascii_case("json"))
+    }
+
+    // Part of nominal value val in unit, the files read once until they change
+    pub fn part(&self, val: f64, unit: Unit, orientation: Orientation) -> Result<Measured, Error> {
+        let path = self.path.to_string_lossy();
+        let mut out = match self.is_library() {
+            true => load_library(&self.path)?.part(val, unit, orientation),
+            false => Measured::new(
+                &*load_touchstone(&self.path)?,
+                self.fixture.unwrap_or(Orientation::Series),
+                val,
+                unit,
+                orientation,
+            ),
+        }
+        .map_err(|err| err.context(&path))?;
+        out.set_extrapolation(self.extrapolation);
+        Ok(out)
+    }
+}
+
+// "vendor/parts.json" or "c_2p2.s2p,shunt"
+impl FromStr for PartFile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.rsplit_once(',') {
+            Some((path, fixture)) => Ok(PartFile::new(
+                path.trim(),
+                Some(parse_fixture(fixture.trim())?),
+            )),
+            None => Ok(PartFile::new(s.trim(), None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::{capacitor::Capacitor, ElementType};
+    use crate::rf_utils::{abcd2s, comp_c64};
+    use crate::smith::gen_part;
+    use crate::touchstone::parse_touchstone;
+    use float_cmp::F64Margin;
+
+    // Touchstone text of a lumped part measured in a series-thru or shunt fixture
+    fn fixture_s2p(part: &dyn Element, freqs: &[f64], z0: f64) -> String {
+        let mut out = format!("# Hz S RI R {}\n", z0);
+        for f in freqs {
+            let [[s11, s12], [s21, s22]] = abcd2s(&part.abcd(Frequency::new(*f, Unit::Base)), z0);
+            out += &format!(
+                "{} {} {} {} {} {} {} {} {}\n",
+                f, s11.re, s11.im, s21.re, s21.im, s12.re, s12.im, s22.re, s22.im
+            );
+        }
+        out
+    }
+
+    #[test]
+    fn test_measured() {
+        let testname = "measured";
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        let mut cap = Capacitor::new(
+            50.0,
+            10.0,
+            Unit::Q,
+            Unit::Pico,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        cap.set_esl(0.6);
+        cap.set_esl_unit(Unit::Nano);
+        let freqs = [0.5e9, 1e9, 1.5e9, 2e9, 2.5e9];
+
+        for fixture in [Orientation::Series, Orientation::Shunt] {
+            cap.set_orientation(fixture);
+            let data = parse_touchstone(&fixture_s2p(&cap, &freqs, 50.0), 2).unwrap();
+            for orientation in [Orientation::Series, Orientation::Shunt] {
+                let part = Measured::new(&data, fixture, 10.0, Unit::Pico, orientation).unwrap();
+                cap.set_orientation(orientation);
+                for f in freqs {
+                    let freq = Frequency::new(f, Unit::Base);
+                    comp_c64(&part.z(freq), &cap.z(freq), margin, testname, "z");
+                    let [[a, b], [c, d]] = part.abcd(freq);
+                    let [[a0, b0], [c0, d0]] = cap.abcd(freq);
+                    for (test, exemplar) in [(a, a0), (b, b0), (c, c0), (d, d0)] {
+                        comp_c64(&test, &exemplar, margin, testname, "abcd");
+                    }
+                }
+            }
+        }
+
+        // NaN past the end of the band unless held at the band edges
+        let mut part = Measured::new(
+            &parse_touchstone(&fixture_s2p(&cap, &freqs, 50.0), 2).unwrap(),
+            Orientation::Shunt,
+            10.0,
+            Unit::Pico,
+            Orientation::Series,
+        )
+        .unwrap();
+        assert!(part.z(Frequency::new(5.0, Unit::Giga)).is_nan());
+        part.set_extrapolation(Extrapolation::Hold);
+        comp_c64(
+            &part.z(Frequency::new(5.0, Unit::Giga)),
+            &part.z(Frequency::new(2.5, Unit::Giga)),
+            margin,
+            testname,
+            "hold",
+        );
+
+        let data = parse_touchstone("1.0 0.5 -30", 1).unwrap();
+        assert_eq!(
+            Measured::new(
+                &data,
+                Orientation::Series,
+                1.0,
+                Unit::Pico,
+                Orientation::Series
+            )
+            .unwrap_err()
+            .message(),
+            "a measured part needs 2-port data, got 1 ports"
+        );
+    }
+
+    #[test]
+    fn test_part_library() {
+        let text = r#"{"unit": "pF", "fixture": "shunt", "parts": [
+            {"value": 1.0, "file": "c_1p0.s2p"},
+            {"value": 2.2, "file": "c_2p2.s2p"},
+            {"value": 4.7, "file": "c_4p7.s2p"}
+        ]}"#;
+        let library = parse_library(text, Path::new("vendor")).unwrap();
+        assert_eq!(library.unit(), Unit::Pico);
+        assert_eq!(library.fixture(), Orientation::Shunt);
+        assert_eq!(library.values(), vec![1.0, 2.2, 4.7]);
+        assert_eq!(
+            serde_json::to_value(&library).unwrap()["parts"][1],
+            serde_json::json!({"value": 2.2, "file": "vendor/c_2p2.s2p"})
+        );
+        assert_eq!(
+            library.find(2.2, Unit::Pico).unwrap(),
+            Path::new("vendor/c_2p2.s2p")
+        );
+        assert_eq!(
+            library.find(0.0047, Unit::Nano).unwrap(),
+            Path::new("vendor/c_4p7.s2p")
+        );
+        assert_eq!(
+            library.find(2.0, Unit::Pico).unwrap_err().message(),
+            "no 2 pF part in the library, the nearest is 2.2 pF"
+        );
+        assert_eq!(
+            library
+                .part(1.0, Unit::Pico, Orientation::Series)
+                .unwrap_err()
+                .code(),
+            "io"
+        );
+
+        assert!(parse_library(
+            r#"{"unit": "pF", "fixture": "thru", "parts": []}"#,
+            Path::new("")
+        )
+        .is_err());
+        assert!(parse_library(r#"{"unit": "pX", "parts": []}"#, Path::new("")).is_err());
+        assert!(parse_library(
+            r#"{"unit": "pF", "parts": [{"value": -1, "file": "a.s2p"}]}"#,
+            Path::new("")
+        )
+        .is_err());
+
+        // Picked by nominal value from a library on disk into a Smith chart cascade
+        let dir = std::env::temp_dir().join(format!("rf-toolbox-parts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let freqs = [0.5e9, 1e9, 2e9];
+        let mut cap = Capacitor::new(0.0, 2.2, Unit::Q, Unit::Pico, 0.0, 0.0, Orientation::Shunt);
+        std::fs::write(dir.join("c_2p2.s2p"), fixture_s2p(&cap, &freqs, 50.0)).unwrap();
+        std::fs::write(dir.join("parts.json"), text).unwrap();
+        let index = PartFile::new(dir.join("parts.json"), None);
+
+        let part = gen_part(
+            ElementType::SeriesMeasured,
+            &[2.2],
+            &["pF"],
+            Some(&index),
+            Complex::ONE,
+            50.0,
+        )
+        .unwrap();
+        cap.set_orientation(Orientation::Series);
+        let freq = Frequency::new(1.0, Unit::Giga);
+        comp_c64(
+            &part.z(freq),
+            &cap.z(freq),
+            F64Margin::from((1e-9, 4)),
+            "part_library",
+            "z",
+        );
+        assert_eq!(part.orientation(), Orientation::Series);
+        assert_eq!(part.vals(), vec![2.2]);
+        assert_eq!(
+            gen_part(
+                ElementType::ShuntMeasured,
+                &[1.0],
+                &["pF"],
+                Some(&index),
+                Complex::ONE,
+                50.0
+            )
+            .err()
+            .unwrap()
+            .code(),
+            "io"
+        );
+        assert_eq!(
+            gen_part(
+                ElementType::ShuntMeasured,
+                &[2.2],
+                &["pF"],
+                None,
+                Complex::ONE,
+                50.0
+            )
+            .err()
+            .unwrap()
+            .message(),
+            "element ps2p has no library or Touchstone file"
+        );
+
+        // Read once and shared until the file changes
+        let path = dir.join("parts.json");
+        assert!(Arc::ptr_eq(
+            &load_library(&path).unwrap(),
+            &load_library(&path).unwrap()
+        ));
+        let path = dir.join("c_2p2.s2p");
+        assert!(Arc::ptr_eq(
+            &load_touchstone(&path).unwrap(),
+            &load_touchstone(&path).unwrap()
+        ));
+        let part = PartFile::new(&path, Some(Orientation::Shunt))
+            .part(2.2, Unit::Pico, Orientation::Series)
+            .unwrap();
+        comp_c64(
+            &part.z(freq),
+            &cap.z(freq),
+            F64Margin::from((1e-9, 4)),
+            "part_file",
+            "z",
+        );
+        let past = Frequency::new(5.0, Unit::Giga);
+        assert!(part.z(past).is_nan());
+        let mut file = PartFile::new(&path, Some(Orientation::Shunt));
+        file.set_extrapolation(Extrapolation::Hold);
+        let part = file.part(2.2, Unit::Pico, Orientation::Series).unwrap();
+        comp_c64(
+            &part.z(past),
+            &part.z(Frequency::new(2.0, Unit::Giga)),
+            F64Margin::from((1e-9, 4)),
+            "part_file",
+            "hold",
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_part_file() {
+        assert_eq!(
+            "vendor/parts.json".parse::<PartFile>().unwrap(),
+            PartFile::new("vendor/parts.json", None)
+        );
+        assert!(PartFile::new("vendor/parts.json", None).is_library());
+        assert_eq!(
+            "c.s2p,shunt".parse::<PartFile>().unwrap(),
+            PartFile::new("c.s2p", Some(Orientation::Shunt))
+        );
+        assert!(!PartFile::new("c.s2p", None).is_library());
+        assert_eq!(
+            "c.s2p,thru".parse::<PartFile>().unwrap_err().message(),
+            "fixture \"thru\" not recognized, expected series or shunt"
+        );
+        assert_eq!(
+            serde_json::from_value::<PartFile>(
+                serde_json::json!({"path": "c.s2p", "fixture": "series"})
+            )
+            .unwrap(),
+            PartFile::new("c.s2p", Some(Orientation::Series))
+        );
+        let mut exemplar = PartFile::new("c.s2p", None);
+        exemplar.set_extrapolation(Extrapolation::Hold);
+        assert_eq!(
+            serde_json::from_value::<PartFile>(
+                serde_json::json!({"path": "c.s2p", "extrapolation": "hold"})
+            )
+            .unwrap(),
+            exemplar
+        );
+    }
+}
@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -78,6 +78,11 @@ impl OpenStub {
         self.length_unit = val;
         self
     }
+
+    pub fn set_orientation(&mut self, val: Orientation) -> &Self {
+        self.orientation = val;
+        self
+    }
 }
 
 impl Default for OpenStub {
@@ -125,7 +130,7 @@ impl Element for OpenStub {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let mut gx: Vec<f64> = vec![0.0; npts + 1];
         let mut gy: Vec<f64> = vec![0.0; npts + 1];
         let yout_norm = c64(
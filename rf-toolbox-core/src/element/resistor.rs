@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -107,7 +107,7 @@ impl Element for Resistor {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let mut gx: Vec<f64> = vec![0.0; npts + 1];
         let mut gy: Vec<f64> = vec![0.0; npts + 1];
 
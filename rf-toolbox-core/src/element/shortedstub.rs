@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -88,6 +88,11 @@ impl ShortedStub {
         self.length_unit = val;
         self
     }
+
+    pub fn set_orientation(&mut self, val: Orientation) -> &Self {
+        self.orientation = val;
+        self
+    }
 }
 
 impl Default for ShortedStub {
@@ -134,7 +139,7 @@ impl Element for ShortedStub {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let wave =
             match unscale(self.length, &self.length_unit) < (0.5 * freq.wavelength(self.er())) {
                 true => freq.wavelength(self.er) / 4.0,
@@ -1,5 +1,5 @@
 #![allow(unused)]
-use crate::element::{Element, Orientation};
+use crate::element::{Element, Orientation, SmithArc};
 use crate::frequency::Frequency;
 use crate::rf_utils::{calc_z_norm, scale, unscale};
 use crate::smith::{find_smith_coord, find_smith_coord_c64};
@@ -128,6 +128,14 @@ impl Element for TLine {
             / (self.z0() + c64(0, 1) * self.zl * self.betal(freq).tan())
     }
 
+    fn abcd(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let betal = self.betal(freq);
+        [
+            [c64(betal.cos(), 0.0), c64(0.0, self.z0 * betal.sin())],
+            [c64(0.0, betal.sin() / self.z0), c64(betal.cos(), 0.0)],
+        ]
+    }
+
     fn calc_arc(
         &self,
         freq: Frequency,
@@ -135,7 +143,7 @@ impl Element for TLine {
         z0: f64,
         npts: usize,
         verbose: bool,
-    ) -> (Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)) {
+    ) -> SmithArc {
         let mut gx: Vec<f64> = vec![0.0; npts + 1];
         let mut gy: Vec<f64> = vec![0.0; npts + 1];
 
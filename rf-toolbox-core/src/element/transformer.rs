@@ -0,0 +1,815 @@
+#![allow(unused)]
+use crate::element::{Element, Orientation, SmithArc};
+use crate::frequency::Frequency;
+use crate::rf_utils::{abcd2s, calc_z_norm, scale, unscale};
+use crate::smith::{find_smith_coord, find_smith_coord_c64};
+use crate::unit::Unit;
+use float_cmp::{approx_eq, F64Margin};
+use num_complex::{c64, Complex};
+use std::f64::consts::PI;
+
+// Coupled primary (port 1) and secondary (port 2) windings sharing their negative terminal. The
+// secondary is given as an inductance or, with inds_unit N, as the turns ratio to the primary,
+// and the coupling as k with m_unit K or as the mutual inductance otherwise. res is the loss of
+// the primary and res_s of the secondary, as a Q with res_unit Q. The interwinding capacitance
+// cap bridges the positive terminals.
+//
+//     p1 o---+---||---+---o p2
+//            |   cap  |
+//            )|      |(
+//         Lp )|  M   |( Ls
+//            )|      |(
+//            |        |
+//     0  o---+--------+---o 0
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transformer {
+    res: f64,
+    res_s: f64,
+    indp: f64,
+    inds: f64,
+    m: f64,
+    cap: f64,
+    res_unit: Unit,
+    indp_unit: Unit,
+    inds_unit: Unit,
+    m_unit: Unit,
+    cap_unit: Unit,
+    res_tol: f64,
+    indp_tol: f64,
+    inds_tol: f64,
+    m_tol: f64,
+    orientation: Orientation,
+}
+
+impl Transformer {
+    pub fn new(
+        res: f64,
+        indp: f64,
+        inds: f64,
+        m: f64,
+        res_unit: Unit,
+        indp_unit: Unit,
+        inds_unit: Unit,
+        m_unit: Unit,
+        res_tol: f64,
+        indp_tol: f64,
+        inds_tol: f64,
+        m_tol: f64,
+    ) -> Self {
+        Transformer {
+            res,
+            res_s: res,
+            indp,
+            inds,
+            m,
+            cap: 0.0,
+            res_unit,
+            indp_unit,
+            inds_unit,
+            m_unit,
+            cap_unit: Unit::Femto,
+            res_tol,
+            indp_tol,
+            inds_tol,
+            m_tol,
+            orientation: Orientation::Series,
+        }
+    }
+
+    pub fn res(&self) -> f64 {
+        self.res
+    }
+
+    pub fn res_s(&self) -> f64 {
+        self.res_s
+    }
+
+    pub fn indp(&self) -> f64 {
+        self.indp
+    }
+
+    pub fn inds(&self) -> f64 {
+        self.inds
+    }
+
+    pub fn m(&self) -> f64 {
+        self.m
+    }
+
+    pub fn cap(&self) -> f64 {
+        self.cap
+    }
+
+    pub fn res_unit(&self) -> Unit {
+        self.res_unit
+    }
+
+    pub fn indp_unit(&self) -> Unit {
+        self.indp_unit
+    }
+
+    pub fn inds_unit(&self) -> Unit {
+        self.inds_unit
+    }
+
+    pub fn m_unit(&self) -> Unit {
+        self.m_unit
+    }
+
+    pub fn cap_unit(&self) -> Unit {
+        self.cap_unit
+    }
+
+    pub fn res_tol(&self) -> f64 {
+        self.res_tol
+    }
+
+    pub fn indp_tol(&self) -> f64 {
+        self.indp_tol
+    }
+
+    pub fn inds_tol(&self) -> f64 {
+        self.inds_tol
+    }
+
+    pub fn m_tol(&self) -> f64 {
+        self.m_tol
+    }
+
+    // Sets the loss of both windings, set_res_s after it for a different secondary
+    pub fn set_res(&mut self, val: f64) -> &Self {
+        self.res = val;
+        self.res_s = val;
+        self
+    }
+
+    pub fn set_res_s(&mut self, val: f64) -> &Self {
+        self.res_s = val;
+        self
+    }
+
+    pub fn set_indp(&mut self, val: f64) -> &Self {
+        self.indp = val;
+        self
+    }
+
+    pub fn set_inds(&mut self, val: f64) -> &Self {
+        self.inds = val;
+        self
+    }
+
+    pub fn set_m(&mut self, val: f64) -> &Self {
+        self.m = val;
+        self
+    }
+
+    pub fn set_cap(&mut self, val: f64) -> &Self {
+        self.cap = val;
+        self
+    }
+
+    pub fn set_res_unscaled(&mut self, val: f64) -> &Self {
+        self.set_res(scale(val, &self.res_unit))
+    }
+
+    pub fn set_res_s_unscaled(&mut self, val: f64) -> &Self {
+        self.res_s = scale(val, &self.res_unit);
+        self
+    }
+
+    pub fn set_indp_unscaled(&mut self, val: f64) -> &Self {
+        self.indp = scale(val, &self.indp_unit);
+        self
+    }
+
+    pub fn set_inds_unscaled(&mut self, val: f64) -> &Self {
+        self.inds = scale(val, &self.inds_unit);
+        self
+    }
+
+    pub fn set_m_unscaled(&mut self, val: f64) -> &Self {
+        self.m = scale(val, &self.m_unit);
+        self
+    }
+
+    pub fn set_cap_unscaled(&mut self, val: f64) -> &Self {
+        self.cap = scale(val, &self.cap_unit);
+        self
+    }
+
+    pub fn set_res_unit(&mut self, val: Unit) -> &Self {
+        self.res_unit = val;
+        self
+    }
+
+    pub fn set_indp_unit(&mut self, val: Unit) -> &Self {
+        self.indp_unit = val;
+        self
+    }
+
+    pub fn set_inds_unit(&mut self, val: Unit) -> &Self {
+        self.inds_unit = val;
+        self
+    }
+
+    pub fn set_m_unit(&mut self, val: Unit) -> &Self {
+        self.m_unit = val;
+        self
+    }
+
+    pub fn set_cap_unit(&mut self, val: Unit) -> &Self {
+        self.cap_unit = val;
+        self
+    }
+
+    pub fn set_res_tol(&mut self, val: f64) -> &Self {
+        self.res_tol = val;
+        self
+    }
+
+    pub fn set_indp_tol(&mut self, val: f64) -> &Self {
+        self.indp_tol = val;
+        self
+    }
+
+    pub fn set_inds_tol(&mut self, val: f64) -> &Self {
+        self.inds_tol = val;
+        self
+    }
+
+    pub fn set_m_tol(&mut self, val: f64) -> &Self {
+        self.m_tol = val;
+        self
+    }
+
+    // Primary, secondary and mutual inductance in H
+    pub fn inductances(&self) -> (f64, f64, f64) {
+        let lp = unscale(self.indp, &self.indp_unit);
+        let ls = match self.inds_unit {
+            Unit::N => self.inds.powi(2) * lp,
+            _ => unscale(self.inds, &self.inds_unit),
+        };
+        let m = match self.m_unit {
+            Unit::K => self.m * (lp * ls).sqrt(),
+            _ => unscale(self.m, &self.m_unit),
+        };
+        (lp, ls, m)
+    }
+
+    pub fn coupling(&self) -> f64 {
+        let (lp, ls, m) = self.inductances();
+        m / (lp * ls).sqrt()
+    }
+
+    // Secondary to primary turns ratio
+    pub fn turns_ratio(&self) -> f64 {
+        let (lp, ls, _) = self.inductances();
+        (ls / lp).sqrt()
+    }
+
+    // Winding resistances in Ω
+    pub fn losses(&self, freq: Frequency) -> (f64, f64) {
+        let (lp, ls, _) = self.inductances();
+        match self.res_unit {
+            Unit::Q => {
+                let q = |res: f64, ind: f64| match approx_eq!(f64, res, 0_f64, F64Margin::default())
+                {
+                    true => 0.0,
+                    false => freq.w() * ind / res,
+                };
+                (q(self.res, lp), q(self.res_s, ls))
+            }
+            _ => (
+                unscale(self.res, &self.res_unit),
+                unscale(self.res_s, &self.res_unit),
+            ),
+        }
+    }
+
+    // Open-circuit parameters of the coupled windings alone
+    fn z_windings(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let (lp, ls, m) = self.inductances();
+        let (rp, rs) = self.losses(freq);
+        let zm = c64(0.0, freq.w() * m);
+        [[c64(rp, freq.w() * lp), zm], [zm, c64(rs, freq.w() * ls)]]
+    }
+
+    pub fn z_matrix(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let [[a, b], [c, d]] = self.abcd(freq);
+        [[a / c, (a * d - b * c) / c], [c.inv(), d / c]]
+    }
+
+    pub fn y_matrix(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let [[a, b], [c, d]] = self.abcd(freq);
+        [[d / b, -(a * d - b * c) / b], [-b.inv(), a / b]]
+    }
+
+    pub fn s(&self, freq: Frequency, z0: f64) -> [[Complex<f64>; 2]; 2] {
+        abcd2s(&self.abcd(freq), z0)
+    }
+
+    // Impedance into the primary with zl on the secondary
+    pub fn z_primary(&self, freq: Frequency, zl: Complex<f64>) -> Complex<f64> {
+        let [[a, b], [c, d]] = self.abcd(freq);
+        (a * zl + b) / (c * zl + d)
+    }
+
+    // Impedance into the secondary with zl on the primary
+    pub fn z_secondary(&self, freq: Frequency, zl: Complex<f64>) -> Complex<f64> {
+        let [[a, b], [c, d]] = self.abcd(freq);
+        (d * zl + b) / (c * zl + a)
+    }
+
+    // Lossless, perfectly coupled transformer of infinite inductance with the same turns ratio
+    pub fn ideal_abcd(&self) -> [[Complex<f64>; 2]; 2] {
+        let n = self.turns_ratio();
+        [
+            [c64(1.0 / n, 0.0), Complex::ZERO],
+            [Complex::ZERO, c64(n, 0.0)],
+        ]
+    }
+
+    // The same transformer driven from its secondary, in base units
+    pub fn reversed(&self) -> Self {
+        let (lp, ls, m) = self.inductances();
+        let (res_unit, res, res_s) = match self.res_unit {
+            Unit::Q => (Unit::Q, self.res_s, self.res),
+            _ => (
+                Unit::Base,
+                unscale(self.res_s, &self.res_unit),
+                unscale(self.res, &self.res_unit),
+            ),
+        };
+        let mut out = Transformer::new(
+            res,
+            ls,
+            lp,
+            m,
+            res_unit,
+            Unit::Base,
+            Unit::Base,
+            Unit::Base,
+            self.res_tol,
+            self.inds_tol,
+            self.indp_tol,
+            self.m_tol,
+        );
+        out.set_res_s(res_s);
+        out.set_cap(unscale(self.cap, &self.cap_unit));
+        out.set_cap_unit(Unit::Base);
+        out
+    }
+
+    pub fn z_cascade(&self, freq: Frequency, zin: Complex<f64>) -> Complex<f64> {
+        self.z_secondary(freq, zin)
+    }
+
+    pub fn z_cascade_norm(&self, freq: Frequency, zin_norm: Complex<f64>, z0: f64) -> Complex<f64> {
+        self.z_cascade(freq, zin_norm * z0) / z0
+    }
+}
+
+impl Default for Transformer {
+    fn default() -> Self {
+        Self {
+            res: 0.0,
+            res_s: 0.0,
+            indp: 20.0,
+            inds: 20.0,
+            m: 0.5,
+            cap: 0.0,
+            res_unit: Unit::Base,
+            indp_unit: Unit::Pico,
+            inds_unit: Unit::Pico,
+            m_unit: Unit::K,
+            cap_unit: Unit::Femto,
+            res_tol: 0.0,
+            indp_tol: 0.0,
+            inds_tol: 0.0,
+            m_tol: 0.0,
+            orientation: Orientation::Series,
+        }
+    }
+}
+
+impl Element for Transformer {
+    fn labels(&self) -> Vec<&str> {
+        vec!["res", "indp", "inds", "m", "res_s", "cap"]
+    }
+
+    fn vals(&self) -> Vec<f64> {
+        vec![self.res, self.indp, self.inds, self.m, self.res_s, self.cap]
+    }
+
+    fn units(&self) -> Vec<Unit> {
+        vec![
+            self.res_unit,
+            self.indp_unit,
+            self.inds_unit,
+            self.m_unit,
+            self.res_unit,
+            self.cap_unit,
+        ]
+    }
+
+    fn tols(&self) -> Vec<f64> {
+        vec![
+            self.res_tol,
+            self.indp_tol,
+            self.inds_tol,
+            self.m_tol,
+            self.res_tol,
+            0.0,
+        ]
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // Short-circuit impedance into the secondary, i.e. its leakage with the primary shorted
+    fn z(&self, freq: Frequency) -> Complex<f64> {
+        self.z_secondary(freq, Complex::ZERO)
+    }
+
+    // Primary at port 1, which faces the load in the Smith chart cascade
+    fn abcd(&self, freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+        let [[z11, z12], [z21, z22]] = self.z_windings(freq);
+        let cap = unscale(self.cap, &self.cap_unit);
+        if cap == 0.0 {
+            return [
+                [z11 / z21, (z11 * z22 - z12 * z21) / z21],
+                [z21.inv(), z22 / z21],
+            ];
+        }
+
+        // The capacitance bridges the windings, so it adds to their admittance matrix
+        let det = z11 * z22 - z12 * z21;
+        let yc = c64(0.0, freq.w() * cap);
+        let [[y11, y12], [y21, y22]] = [
+            [z22 / det + yc, -z12 / det - yc],
+            [-z21 / det - yc, z11 / det + yc],
+        ];
+        [
+            [-y22 / y21, -y21.inv()],
+            [-(y11 * y22 - y12 * y21) / y21, -y11 / y21],
+        ]
+    }
+
+    fn calc_arc(
+        &self,
+        freq: Frequency,
+        zin_norm: Complex<f64>,
+        z0: f64,
+        npts: usize,
+        verbose: bool,
+    ) -> SmithArc {
+        let mut gx: Vec<f64> = vec![0.0; npts + 1];
+        let mut gy: Vec<f64> = vec![0.0; npts + 1];
+
+        let start = zin_norm;
+        let end = self.z_cascade_norm(freq, zin_norm, z0);
+        for i in 0..=npts {
+            let xpt = start.re + ((end.re - start.re) * (i as f64)) / (npts as f64);
+            let ypt = start.im + ((end.im - start.im) * (i as f64)) / (npts as f64);
+            let pt = find_smith_coord(xpt, ypt, false, verbose).unwrap();
+            gx[i] = pt.re;
+            gy[i] = pt.im;
+        }
+
+        (gx, gy, (start.re, start.im), (end.re, end.im))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{calc_z, comp_c64, comp_f64, comp_vec_f64};
+
+    #[test]
+    fn test_transformer() {
+        let testname = "transformer";
+        let freq = Frequency::new(280.0, Unit::Giga);
+        let z0 = 50.0;
+        // let zl = c64(1.0, 0.0);
+        // let zl_denorm = zl * z0;
+        let res = 20.0;
+        let indp = 10.0;
+        let inds = 25.0;
+        let m = 0.35;
+        let res_unit = Unit::Q;
+        let indp_unit = Unit::Pico;
+        let inds_unit = Unit::Pico;
+        let m_unit = Unit::K;
+        let zout = c64(2.467834628131633, 38.607901737881555);
+        let zout_norm = zout / z0;
+        let element = Transformer::new(
+            res, indp, inds, m, res_unit, indp_unit, inds_unit, m_unit, 0.0, 0.0, 0.0, 0.0,
+        );
+
+        let margin = F64Margin::default();
+
+        comp_f64(&element.res(), &res, margin, testname, "res()");
+        comp_f64(&element.indp(), &indp, margin, testname, "indp()");
+        comp_f64(&element.inds(), &inds, margin, testname, "inds()");
+        comp_f64(&element.m(), &m, margin, testname, "m()");
+        comp_f64(
+            &unscale(element.indp(), &element.indp_unit()),
+            &(indp * 1e-12),
+            margin,
+            testname,
+            "indp_unscale()",
+        );
+        comp_f64(
+            &unscale(element.inds(), &element.inds_unit()),
+            &(inds * 1e-12),
+            margin,
+            testname,
+            "inds_unscale()",
+        );
+        comp_f64(
+            &unscale(element.m(), &element.m_unit()),
+            &m,
+            margin,
+            testname,
+            "m_unscale()",
+        );
+        assert_eq!(&element.res_unit(), &res_unit);
+        assert_eq!(&element.indp_unit(), &indp_unit);
+        assert_eq!(&element.inds_unit(), &inds_unit);
+        assert_eq!(&element.m_unit(), &m_unit);
+        comp_c64(&element.z(freq), &zout, margin, testname, "z");
+        comp_c64(
+            &element.z_norm(freq, z0),
+            &zout_norm,
+            margin,
+            testname,
+            "z_norm",
+        );
+
+        let end_x_coord = -0.12557273923087667;
+        let end_y_coord = 0.9070700735284939;
+        // let real_old = 0.0;
+        // let imag_old = 0.0;
+        // let start_x_coord = 0.0;
+        // let start_y_coord = 0.0;
+        let x1 = 1.0;
+        let y1 = 0.0;
+        let x2 = 0.07726282865103465;
+        let y2 = 0.8681383611526633;
+        let x_coord = vec![
+            0.0,
+            -0.04620191289340646,
+            -0.091668432746296,
+            -0.13472472183338424,
+            -0.17312828829235238,
+            -0.20402989225662477,
+            -0.22403100581093902,
+            -0.2293990143631755,
+            -0.21648979179286468,
+            -0.18237492324617782,
+            -0.12557273923087667,
+        ];
+        let y_coord = vec![
+            0.0,
+            0.04760892703800349,
+            0.10440583929498334,
+            0.17150246380546133,
+            0.2497846502470636,
+            0.3396734681582891,
+            0.4408155493411283,
+            0.5517397169425321,
+            0.6695659309420381,
+            0.7899013747369822,
+            0.9070700735284939,
+        ];
+        let freq_int = Frequency::new(280.0, Unit::Giga);
+        let zin = c64(1.0, 0.0);
+        let z0 = 50.0;
+        let npts = 10;
+        let test = element.calc_arc(freq_int, zin, z0, npts, false);
+
+        let margin = F64Margin::from((1e-15, 1));
+
+        comp_f64(&test.2 .0, &x1, margin, testname, "x1");
+        comp_f64(&test.2 .1, &y1, margin, testname, "y1");
+        comp_f64(&test.3 .0, &x2, margin, testname, "x2");
+        comp_f64(&test.3 .1, &y2, margin, testname, "y2");
+        comp_f64(
+            test.0.last().unwrap(),
+            &end_x_coord,
+            margin,
+            testname,
+            "end_x_coord",
+        );
+        comp_f64(
+            test.1.last().unwrap(),
+            &end_y_coord,
+            margin,
+            testname,
+            "end_y_coord",
+        );
+        comp_vec_f64(test.0, x_coord, margin, testname, "x_coord");
+        comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
+    }
+
+    #[test]
+    fn test_transformer_abcd() {
+        let testname = "transformer_abcd";
+        let freq = Frequency::new(280.0, Unit::Giga);
+        let zin = c64(50.0, -20.0);
+        let element = Transformer::new(
+            20.0,
+            10.0,
+            25.0,
+            0.35,
+            Unit::Q,
+            Unit::Pico,
+            Unit::Pico,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        let [[a, b], [c, d]] = element.abcd(freq);
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_c64(
+            &((d * zin + b) / (c * zin + a)),
+            &element.z_cascade(freq, zin),
+            margin,
+            testname,
+            "zout",
+        );
+        comp_c64(
+            &(a * d - b * c),
+            &Complex::ONE,
+            margin,
+            testname,
+            "reciprocity",
+        );
+    }
+
+    #[test]
+    fn test_transformer_two_port() {
+        let testname = "transformer_two_port";
+        let freq = Frequency::new(60.0, Unit::Giga);
+        let w = freq.w();
+        let margin = F64Margin::from((1e-12, 4));
+        let mut element = Transformer::new(
+            20.0,
+            100.0,
+            2.0,
+            0.6,
+            Unit::Q,
+            Unit::Pico,
+            Unit::N,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        element.set_res_s(10.0);
+
+        let (lp, ls, m) = element.inductances();
+        comp_f64(&ls, &400e-12, margin, testname, "ls");
+        comp_f64(&m, &120e-12, margin, testname, "m");
+        comp_f64(
+            &element.turns_ratio(),
+            &2.0,
+            margin,
+            testname,
+            "turns_ratio()",
+        );
+        let z = element.z_matrix(freq);
+        comp_c64(
+            &z[0][0],
+            &c64(w * lp / 20.0, w * lp),
+            margin,
+            testname,
+            "z11",
+        );
+        comp_c64(
+            &z[1][1],
+            &c64(w * ls / 10.0, w * ls),
+            margin,
+            testname,
+            "z22",
+        );
+        comp_c64(&z[0][1], &c64(0.0, w * m), margin, testname, "z12");
+        let s = element.s(freq, 50.0);
+        comp_c64(&s[0][1], &s[1][0], margin, testname, "s reciprocity");
+
+        // The same coupling given as a mutual inductance
+        let mutual = Transformer::new(
+            20.0,
+            100.0,
+            400.0,
+            120.0,
+            Unit::Q,
+            Unit::Pico,
+            Unit::Pico,
+            Unit::Pico,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        comp_f64(&mutual.coupling(), &0.6, margin, testname, "coupling()");
+        let zl = c64(20.0, -35.0);
+        comp_c64(
+            &mutual.z_secondary(freq, zl),
+            &Transformer::new(
+                20.0,
+                100.0,
+                400.0,
+                0.6,
+                Unit::Q,
+                Unit::Pico,
+                Unit::Pico,
+                Unit::K,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+            )
+            .z_secondary(freq, zl),
+            margin,
+            testname,
+            "mutual z_secondary",
+        );
+
+        // Driving the secondary swaps the ports
+        element.set_cap(8.0);
+        let reversed = element.reversed();
+        let [[a, b], [c, d]] = element.abcd(freq);
+        let abcd = reversed.abcd(freq);
+        comp_c64(&abcd[0][0], &d, margin, testname, "reversed a");
+        comp_c64(&abcd[0][1], &b, margin, testname, "reversed b");
+        comp_c64(&abcd[1][0], &c, margin, testname, "reversed c");
+        comp_c64(&abcd[1][1], &a, margin, testname, "reversed d");
+        comp_c64(
+            &reversed.z_primary(freq, zl),
+            &element.z_secondary(freq, zl),
+            margin,
+            testname,
+            "reversed z_primary",
+        );
+
+        // The interwinding capacitance bridges the ports
+        let y = element.y_matrix(freq);
+        let open = {
+            let mut val = element;
+            val.set_cap(0.0);
+            val.y_matrix(freq)
+        };
+        comp_c64(
+            &(y[0][0] - open[0][0]),
+            &c64(0.0, w * 8e-15),
+            margin,
+            testname,
+            "y11",
+        );
+        comp_c64(
+            &(y[1][0] - open[1][0]),
+            &c64(0.0, -w * 8e-15),
+            margin,
+            testname,
+            "y21",
+        );
+
+        // Tight coupling of large windings approaches the ideal transformer
+        let ideal = Transformer::new(
+            0.0,
+            1.0,
+            3.0,
+            1.0 - 1e-10,
+            Unit::Base,
+            Unit::Micro,
+            Unit::N,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        let margin = F64Margin::from((1e-3, 4));
+        let [[a, b], [c, d]] = ideal.abcd(freq);
+        let [[ia, ib], [ic, id]] = ideal.ideal_abcd();
+        comp_c64(&a, &ia, margin, testname, "ideal a");
+        comp_c64(&b, &ib, margin, testname, "ideal b");
+        comp_c64(&c, &ic, margin, testname, "ideal c");
+        comp_c64(&d, &id, margin, testname, "ideal d");
+        // Less the magnetizing inductance in parallel
+        comp_c64(
+            &ideal.z_primary(freq, c64(450.0, 0.0)),
+            &c64(50.0, 0.0),
+            F64Margin::from((1e-2, 4)),
+            testname,
+            "ideal z_primary",
+        );
+    }
+}
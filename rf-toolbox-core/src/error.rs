@@ -0,0 +1,104 @@
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::fmt;
+
+// Errors shared by all calculators. They reach the frontend as {code, message} so that the UI can
+// branch on the code and show the message as is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    InvalidUnit(String),
+    InvalidInput(String),
+    Unrealizable(String),
+    NonPhysical(String),
+    Numerical(String),
+    Parse(String),
+    Io(String),
+}
+
+impl Error {
+    pub fn code(&self) -> &str {
+        match self {
+            Error::InvalidUnit(_) => "invalid_unit",
+            Error::InvalidInput(_) => "invalid_input",
+            Error::Unrealizable(_) => "unrealizable",
+            Error::NonPhysical(_) => "non_physical",
+            Error::Numerical(_) => "numerical",
+            Error::Parse(_) => "parse",
+            Error::Io(_) => "io",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Error::InvalidUnit(msg)
+            | Error::InvalidInput(msg)
+            | Error::Unrealizable(msg)
+            | Error::NonPhysical(msg)
+            | Error::Numerical(msg)
+            | Error::Parse(msg)
+            | Error::Io(msg) => msg,
+        }
+    }
+
+    // Prefix the message with where the error happened, keeping its kind
+    pub fn context(self, ctx: &str) -> Self {
+        let msg = format!("{}: {}", ctx, self.message());
+        match self {
+            Error::InvalidUnit(_) => Error::InvalidUnit(msg),
+            Error::InvalidInput(_) => Error::InvalidInput(msg),
+            Error::Unrealizable(_) => Error::Unrealizable(msg),
+            Error::NonPhysical(_) => Error::NonPhysical(msg),
+            Error::Numerical(_) => Error::Numerical(msg),
+            Error::Parse(_) => Error::Parse(msg),
+            Error::Io(_) => Error::Io(msg),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Error", 2)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", self.message())?;
+        s.end()
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        let err = Error::InvalidUnit("unit \"x\" not recognized".to_string()).context("element pi");
+        assert_eq!(err.code(), "invalid_unit");
+        assert_eq!(err.to_string(), "element pi: unit \"x\" not recognized");
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"code":"invalid_unit","message":"element pi: unit \"x\" not recognized"}"#
+        );
+    }
+}
@@ -0,0 +1,583 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::frequency::{Frequency, FrequencySweep};
+use crate::rf_utils::unscale;
+use crate::unit::Unit;
+use num_complex::{c64, Complex};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+// Order of the blending polynomials used by the barycentric rational interpolant
+const RATIONAL_ORDER: usize = 3;
+
+// "sah" is the name the Smith chart custom impedance table stores for sample and hold
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum Interpolation {
+    #[serde(rename = "linear", alias = "lin")]
+    Linear,
+    #[serde(rename = "spline", alias = "cubic")]
+    CubicSpline,
+    #[serde(rename = "rational")]
+    Rational,
+    #[serde(rename = "sah", alias = "hold")]
+    SampleHold,
+}
+
+impl FromStr for Interpolation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lin" | "linear" => Ok(Interpolation::Linear),
+            "spline" | "cubic" => Ok(Interpolation::CubicSpline),
+            "rational" => Ok(Interpolation::Rational),
+            "sah" | "hold" => Ok(Interpolation::SampleHold),
+            _ => Err(Error::Parse(format!(
+                "interpolation {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InterpDomain {
+    ReIm,
+    MagAng,
+}
+
+impl FromStr for InterpDomain {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ri" | "reim" => Ok(InterpDomain::ReIm),
+            "ma" | "magang" => Ok(InterpDomain::MagAng),
+            _ => Err(Error::Parse(format!(
+                "interpolation domain {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+// Outside the data an error unless asked to hold the end values or extend the interpolant
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum Extrapolation {
+    #[default]
+    #[serde(rename = "error")]
+    Error,
+    #[serde(rename = "hold", alias = "clamp")]
+    Hold,
+    #[serde(rename = "extend")]
+    Extend,
+}
+
+impl FromStr for Extrapolation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Extrapolation::Error),
+            "hold" | "clamp" => Ok(Extrapolation::Hold),
+            "extend" => Ok(Extrapolation::Extend),
+            _ => Err(Error::Parse(format!(
+                "extrapolation {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FreqData {
+    freqs: Vec<f64>,
+    vals: Vec<Complex<f64>>,
+    interpolation: Interpolation,
+    domain: InterpDomain,
+    extrapolation: Extrapolation,
+}
+
+impl FreqData {
+    pub fn new(freqs: Vec<f64>, vals: Vec<Complex<f64>>, unit: Unit) -> Result<Self, Error> {
+        if freqs.is_empty() {
+            return Err(Error::InvalidInput(
+                "frequency data contains no points".to_string(),
+            ));
+        }
+        if freqs.len() != vals.len() {
+            return Err(Error::InvalidInput(format!(
+                "frequency data has {} frequencies but {} values",
+                freqs.len(),
+                vals.len()
+            )));
+        }
+        if freqs.iter().any(|val| !val.is_finite())
+            || vals
+                .iter()
+                .any(|val| !val.re.is_finite() || !val.im.is_finite())
+        {
+            return Err(Error::InvalidInput(
+                "frequency data contains non-finite values".to_string(),
+            ));
+        }
+        if let Some(i) = (1..freqs.len()).find(|&i| freqs[i] <= freqs[i - 1]) {
+            return Err(Error::InvalidInput(format!(
+                "frequencies must be strictly increasing, {} follows {}",
+                freqs[i],
+                freqs[i - 1]
+            )));
+        }
+
+        Ok(FreqData {
+            freqs: freqs.iter().map(|val| unscale(*val, &unit)).collect(),
+            vals,
+            interpolation: Interpolation::Linear,
+            domain: InterpDomain::ReIm,
+            extrapolation: Extrapolation::Error,
+        })
+    }
+
+    pub fn from_ri(table: &[[f64; 3]], unit: Unit) -> Result<Self, Error> {
+        FreqData::new(
+            table.iter().map(|val| val[0]).collect(),
+            table.iter().map(|val| c64(val[1], val[2])).collect(),
+            unit,
+        )
+    }
+
+    pub fn freqs(&self) -> &[f64] {
+        &self.freqs
+    }
+
+    pub fn vals(&self) -> &[Complex<f64>] {
+        &self.vals
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn domain(&self) -> InterpDomain {
+        self.domain
+    }
+
+    pub fn extrapolation(&self) -> Extrapolation {
+        self.extrapolation
+    }
+
+    pub fn set_interpolation(&mut self, val: Interpolation) -> &Self {
+        self.interpolation = val;
+        self
+    }
+
+    pub fn set_domain(&mut self, val: InterpDomain) -> &Self {
+        self.domain = val;
+        self
+    }
+
+    pub fn set_extrapolation(&mut self, val: Extrapolation) -> &Self {
+        self.extrapolation = val;
+        self
+    }
+
+    pub fn interp(&self, freq: Frequency) -> Result<Complex<f64>, Error> {
+        Ok(self.interp_hz(&[freq.freq()])?[0])
+    }
+
+    pub fn interp_sweep(&self, sweep: &FrequencySweep) -> Result<Vec<Complex<f64>>, Error> {
+        self.interp_hz(&sweep.iter().map(|freq| freq.freq()).collect::<Vec<f64>>())
+    }
+
+    pub fn interp_hz(&self, freqs: &[f64]) -> Result<Vec<Complex<f64>>, Error> {
+        let (a, b) = self.components();
+        let (a, b) = match self.interpolation {
+            Interpolation::Linear => (
+                self.eval(freqs, &a, interp_linear)?,
+                self.eval(freqs, &b, interp_linear)?,
+            ),
+            Interpolation::CubicSpline => {
+                let (ma, mb) = (
+                    spline_coeffs(&self.freqs, &a),
+                    spline_coeffs(&self.freqs, &b),
+                );
+                (
+                    self.eval(freqs, &a, |x, y, f| interp_spline(x, y, &ma, f))?,
+                    self.eval(freqs, &b, |x, y, f| interp_spline(x, y, &mb, f))?,
+                )
+            }
+            Interpolation::Rational => {
+                let w = rational_weights(&self.freqs, RATIONAL_ORDER);
+                (
+                    self.eval(freqs, &a, |x, y, f| interp_rational(x, y, &w, f))?,
+                    self.eval(freqs, &b, |x, y, f| interp_rational(x, y, &w, f))?,
+                )
+            }
+            Interpolation::SampleHold => (
+                self.eval(freqs, &a, interp_hold)?,
+                self.eval(freqs, &b, interp_hold)?,
+            ),
+        };
+
+        Ok(a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| match self.domain {
+                InterpDomain::ReIm => c64(*a, *b),
+                InterpDomain::MagAng => Complex::from_polar(*a, *b),
+            })
+            .collect())
+    }
+
+    // Splits the data into the two real quantities that are interpolated, with the phase
+    // unwrapped so that it is continuous across ±180°
+    fn components(&self) -> (Vec<f64>, Vec<f64>) {
+        match self.domain {
+            InterpDomain::ReIm => (
+                self.vals.iter().map(|val| val.re).collect(),
+                self.vals.iter().map(|val| val.im).collect(),
+            ),
+            InterpDomain::MagAng => {
+                let mut ang: Vec<f64> = self.vals.iter().map(|val| val.arg()).collect();
+                for i in 1..ang.len() {
+                    let delta = ang[i] - ang[i - 1];
+                    ang[i] -= 2.0 * PI * (delta / (2.0 * PI)).round();
+                }
+                (self.vals.iter().map(|val| val.norm()).collect(), ang)
+            }
+        }
+    }
+
+    fn eval<F>(&self, freqs: &[f64], y: &[f64], method: F) -> Result<Vec<f64>, Error>
+    where
+        F: Fn(&[f64], &[f64], f64) -> f64,
+    {
+        let first = self.freqs[0];
+        let last = *self.freqs.last().unwrap();
+        // Absorbs the rounding from unit scaling so the end points remain inside the range
+        let tol = 1e-12 * last.abs();
+
+        freqs
+            .iter()
+            .map(|&freq| {
+                if self.freqs.len() == 1 && (freq - first).abs() <= tol {
+                    return Ok(y[0]);
+                }
+                if freq >= first - tol && freq <= last + tol {
+                    return Ok(method(&self.freqs, y, freq));
+                }
+                match self.extrapolation {
+                    Extrapolation::Error => Err(Error::InvalidInput(format!(
+                        "frequency {} Hz is outside of the data range {} Hz to {} Hz",
+                        freq, first, last
+                    ))),
+                    Extrapolation::Hold => match freq < first {
+                        true => Ok(y[0]),
+                        false => Ok(*y.last().unwrap()),
+                    },
+                    Extrapolation::Extend => match self.freqs.len() {
+                        1 => Ok(y[0]),
+                        _ => Ok(method(&self.freqs, y, freq)),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+// Index of the interval [x[i], x[i + 1]] containing f, clamped to the end intervals
+fn interval(x: &[f64], f: f64) -> usize {
+    x.partition_point(|val| *val <= f).clamp(1, x.len() - 1) - 1
+}
+
+fn interp_linear(x: &[f64], y: &[f64], f: f64) -> f64 {
+    if x.len() == 1 {
+        return y[0];
+    }
+    let i = interval(x, f);
+    y[i] + (y[i + 1] - y[i]) * (f - x[i]) / (x[i + 1] - x[i])
+}
+
+// Value of the last point at or below f, held until the next one
+fn interp_hold(x: &[f64], y: &[f64], f: f64) -> f64 {
+    y[x.partition_point(|val| *val <= f).max(1) - 1]
+}
+
+// Second derivatives of the natural cubic spline through (x, y)
+fn spline_coeffs(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let mut m = vec![0.0; n];
+    if n < 3 {
+        return m;
+    }
+
+    let mut diag = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let h0 = x[i] - x[i - 1];
+        let h1 = x[i + 1] - x[i];
+        diag[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h1 - (y[i] - y[i - 1]) / h0);
+        if i > 1 {
+            let factor = h0 / diag[i - 1];
+            diag[i] -= factor * h0;
+            rhs[i] -= factor * rhs[i - 1];
+        }
+    }
+    for i in (1..n - 1).rev() {
+        let h1 = x[i + 1] - x[i];
+        m[i] = (rhs[i] - h1 * m[i + 1]) / diag[i];
+    }
+
+    m
+}
+
+fn interp_spline(x: &[f64], y: &[f64], m: &[f64], f: f64) -> f64 {
+    if x.len() == 1 {
+        return y[0];
+    }
+    let i = interval(x, f);
+    let h = x[i + 1] - x[i];
+    let a = (x[i + 1] - f) / h;
+    let b = (f - x[i]) / h;
+
+    a * y[i]
+        + b * y[i + 1]
+        + ((a.powi(3) - a) * m[i] + (b.powi(3) - b) * m[i + 1]) * h.powi(2) / 6.0
+}
+
+// Floater-Hormann barycentric weights, the interpolant has no real poles for any order
+fn rational_weights(x: &[f64], order: usize) -> Vec<f64> {
+    let n = x.len() - 1;
+    let d = order.min(n);
+
+    (0..=n)
+        .map(|k| {
+            let sum: f64 = (k.saturating_sub(d)..=k.min(n - d))
+                .map(|i| {
+                    (i..=i + d)
+                        .filter(|&j| j != k)
+                        .map(|j| 1.0 / (x[k] - x[j]).abs())
+                        .product::<f64>()
+                })
+                .sum();
+            match (k + d) % 2 {
+                0 => sum,
+                _ => -sum,
+            }
+        })
+        .collect()
+}
+
+fn interp_rational(x: &[f64], y: &[f64], w: &[f64], f: f64) -> f64 {
+    let mut num = 0.0;
+    let mut den = 0.0;
+    for k in 0..x.len() {
+        if f == x[k] {
+            return y[k];
+        }
+        let t = w[k] / (f - x[k]);
+        num += t * y[k];
+        den += t;
+    }
+
+    num / den
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    #[test]
+    fn test_freq_data_errors() {
+        assert!(FreqData::new(vec![], vec![], Unit::Giga).is_err());
+        assert!(FreqData::new(vec![1.0, 2.0], vec![c64(1.0, 0.0)], Unit::Giga).is_err());
+        assert!(FreqData::new(
+            vec![1.0, 1.0],
+            vec![c64(1.0, 0.0), c64(2.0, 0.0)],
+            Unit::Giga
+        )
+        .is_err());
+        assert!(FreqData::new(
+            vec![1.0, 2.0],
+            vec![c64(1.0, 0.0), c64(f64::NAN, 0.0)],
+            Unit::Giga
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_freq_data_linear() {
+        let testname = "freq_data_linear";
+        let mut data = FreqData::from_ri(
+            &[[1.0, 10.0, -5.0], [2.0, 20.0, -3.0], [4.0, 40.0, 1.0]],
+            Unit::Giga,
+        )
+        .unwrap();
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_f64(&data.freqs()[2], &4e9, margin, testname, "freqs()");
+        comp_c64(
+            &data.interp(Frequency::new(1.5, Unit::Giga)).unwrap(),
+            &c64(15.0, -4.0),
+            margin,
+            testname,
+            "first_interval",
+        );
+        comp_c64(
+            &data.interp(Frequency::new(3.0, Unit::Giga)).unwrap(),
+            &c64(30.0, -1.0),
+            margin,
+            testname,
+            "last_interval",
+        );
+        comp_c64(
+            &data.interp(Frequency::new(4000.0, Unit::Mega)).unwrap(),
+            &c64(40.0, 1.0),
+            margin,
+            testname,
+            "last_point",
+        );
+
+        assert!(data.interp(Frequency::new(5.0, Unit::Giga)).is_err());
+
+        data.set_extrapolation(Extrapolation::Hold);
+        comp_c64(
+            &data.interp(Frequency::new(0.5, Unit::Giga)).unwrap(),
+            &c64(10.0, -5.0),
+            margin,
+            testname,
+            "hold_low",
+        );
+        comp_c64(
+            &data.interp(Frequency::new(5.0, Unit::Giga)).unwrap(),
+            &c64(40.0, 1.0),
+            margin,
+            testname,
+            "hold_high",
+        );
+
+        data.set_extrapolation(Extrapolation::Extend);
+        comp_c64(
+            &data.interp(Frequency::new(5.0, Unit::Giga)).unwrap(),
+            &c64(50.0, 3.0),
+            margin,
+            testname,
+            "extend_high",
+        );
+    }
+
+    #[test]
+    fn test_freq_data_sample_hold() {
+        let testname = "freq_data_sample_hold";
+        let mut data = FreqData::from_ri(
+            &[[1.0, 10.0, -5.0], [2.0, 20.0, -3.0], [4.0, 40.0, 1.0]],
+            Unit::Giga,
+        )
+        .unwrap();
+        data.set_interpolation("sah".parse().unwrap());
+
+        let margin = F64Margin::from((1e-12, 4));
+        let exemplar = [
+            (1.0, c64(10.0, -5.0)),
+            (1.9, c64(10.0, -5.0)),
+            (2.0, c64(20.0, -3.0)),
+            (3.9, c64(20.0, -3.0)),
+            (4.0, c64(40.0, 1.0)),
+        ];
+        for (f, val) in exemplar.iter() {
+            comp_c64(
+                &data.interp(Frequency::new(*f, Unit::Giga)).unwrap(),
+                val,
+                margin,
+                testname,
+                &format!("{}", f),
+            );
+        }
+
+        data.set_extrapolation(Extrapolation::Extend);
+        comp_c64(
+            &data.interp(Frequency::new(0.5, Unit::Giga)).unwrap(),
+            &c64(10.0, -5.0),
+            margin,
+            testname,
+            "extend_low",
+        );
+    }
+
+    #[test]
+    fn test_freq_data_spline() {
+        let testname = "freq_data_spline";
+        let freqs: Vec<f64> = (0..=40).map(|i| 1.0 + 0.1 * (i as f64)).collect();
+        let vals = freqs.iter().map(|f| c64(f.sin(), 2.0 * f)).collect();
+        let mut data = FreqData::new(freqs, vals, Unit::Giga).unwrap();
+        data.set_interpolation(Interpolation::CubicSpline);
+
+        let sweep = FrequencySweep::linear(1.55, 4.45, 30, Unit::Giga).unwrap();
+        let test = data.interp_sweep(&sweep).unwrap();
+
+        for (i, freq) in sweep.iter().enumerate() {
+            let f = freq.freq_scaled();
+            comp_c64(
+                &test[i],
+                &c64(f.sin(), 2.0 * f),
+                F64Margin::from((1e-4, 4)),
+                testname,
+                &format!("[{}]", i),
+            );
+        }
+    }
+
+    #[test]
+    fn test_freq_data_rational() {
+        let testname = "freq_data_rational";
+        let freqs: Vec<f64> = vec![1.0, 1.5, 2.5, 3.0, 4.5, 5.0];
+        let vals = freqs
+            .iter()
+            .map(|f| c64(f * f - 3.0 * f + 1.0, 0.5 * f.powi(3)))
+            .collect();
+        let mut data = FreqData::new(freqs, vals, Unit::Giga).unwrap();
+        data.set_interpolation(Interpolation::Rational);
+
+        // Floater-Hormann interpolants of order 3 reproduce polynomials up to degree 3
+        for f in [1.2, 2.0, 3.7, 4.9] {
+            comp_c64(
+                &data.interp(Frequency::new(f, Unit::Giga)).unwrap(),
+                &c64(f * f - 3.0 * f + 1.0, 0.5 * f.powi(3)),
+                F64Margin::from((1e-9, 4)),
+                testname,
+                &format!("{}", f),
+            );
+        }
+        comp_c64(
+            &data.interp(Frequency::new(2.5, Unit::Giga)).unwrap(),
+            &c64(-0.25, 7.8125),
+            F64Margin::default(),
+            testname,
+            "node",
+        );
+    }
+
+    #[test]
+    fn test_freq_data_mag_ang() {
+        let testname = "freq_data_mag_ang";
+        let freqs = vec![1.0, 2.0];
+        let vals = vec![
+            Complex::from_polar(0.8, 170.0 * PI / 180.0),
+            Complex::from_polar(0.8, -170.0 * PI / 180.0),
+        ];
+        let mut data = FreqData::new(freqs, vals, Unit::Giga).unwrap();
+        data.set_domain(InterpDomain::MagAng);
+
+        comp_c64(
+            &data.interp(Frequency::new(1.5, Unit::Giga)).unwrap(),
+            &c64(-0.8, 0.0),
+            F64Margin::from((1e-12, 4)),
+            testname,
+            "wrap",
+        );
+    }
+}
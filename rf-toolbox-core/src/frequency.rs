@@ -0,0 +1,465 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::rf_utils::{scale, unscale};
+use crate::unit::{get_unit, Unit, UnitType};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frequency {
+    val: f64,
+    unit: Unit,
+}
+
+impl Frequency {
+    pub fn new(freq: f64, unit: Unit) -> Self {
+        Frequency { val: freq, unit }
+    }
+
+    pub fn freq(&self) -> f64 {
+        unscale(self.val, &self.unit)
+    }
+
+    pub fn freq_scaled(&self) -> f64 {
+        self.val
+    }
+
+    pub fn set_freq(&mut self, val: f64) -> &Self {
+        self.val = val;
+        self
+    }
+
+    pub fn set_freq_scaled(&mut self, val: f64) -> &Self {
+        self.val = unscale(val, &self.unit);
+        self
+    }
+
+    pub fn set_freq_unit(&mut self, val: Unit) -> &Self {
+        let tmp_val = scale(self.val, &self.unit);
+        self.unit = val;
+        self.val = unscale(tmp_val, &self.unit);
+        self
+    }
+
+    pub fn w(&self) -> f64 {
+        2.0 * PI * unscale(self.val, &self.unit)
+    }
+
+    pub fn wavelength(&self, er: f64) -> f64 {
+        3e8 / (unscale(self.val, &self.unit) * er.sqrt())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SweepType {
+    Linear,
+    Log,
+    List(Vec<f64>),
+}
+
+impl FromStr for SweepType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lin" | "linear" => Ok(SweepType::Linear),
+            "log" | "logarithmic" => Ok(SweepType::Log),
+            "list" => Ok(SweepType::List(vec![])),
+            _ => Err(Error::Parse(format!("sweep type {:?} not recognized", s))),
+        }
+    }
+}
+
+impl fmt::Display for SweepType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SweepType::Linear => write!(f, "linear"),
+            SweepType::Log => write!(f, "log"),
+            SweepType::List(_) => write!(f, "list"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "FrequencySweepArgs")]
+pub struct FrequencySweep {
+    start: f64,
+    stop: f64,
+    points: usize,
+    unit: Unit,
+    sweep_type: SweepType,
+}
+
+impl FrequencySweep {
+    pub fn linear(start: f64, stop: f64, points: usize, unit: Unit) -> Result<Self, Error> {
+        if points == 0 {
+            return Err(Error::InvalidInput(
+                "sweep must have at least one point".to_string(),
+            ));
+        }
+        if !(start >= 0.0 && stop >= 0.0) {
+            return Err(Error::InvalidInput(
+                "linear sweep requires start and stop frequencies of 0 or above".to_string(),
+            ));
+        }
+        if start > stop {
+            let unit = get_unit(&unit, &UnitType::Hz);
+            return Err(Error::InvalidInput(format!(
+                "sweep starts at {} {}, above its stop at {} {}",
+                start, unit, stop, unit
+            )));
+        }
+
+        Ok(FrequencySweep {
+            start,
+            stop,
+            points,
+            unit,
+            sweep_type: SweepType::Linear,
+        })
+    }
+
+    pub fn log(start: f64, stop: f64, points: usize, unit: Unit) -> Result<Self, Error> {
+        if points == 0 {
+            return Err(Error::InvalidInput(
+                "sweep must have at least one point".to_string(),
+            ));
+        }
+        if start <= 0.0 || stop <= 0.0 {
+            return Err(Error::InvalidInput(
+                "log sweep requires positive start and stop frequencies".to_string(),
+            ));
+        }
+
+        Ok(FrequencySweep {
+            start,
+            stop,
+            points,
+            unit,
+            sweep_type: SweepType::Log,
+        })
+    }
+
+    pub fn list(vals: Vec<f64>, unit: Unit) -> Result<Self, Error> {
+        if vals.is_empty() {
+            return Err(Error::InvalidInput(
+                "sweep must have at least one point".to_string(),
+            ));
+        }
+
+        Ok(FrequencySweep {
+            start: vals.iter().cloned().fold(f64::INFINITY, f64::min),
+            stop: vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            points: vals.len(),
+            unit,
+            sweep_type: SweepType::List(vals),
+        })
+    }
+
+    pub fn from_center_span(
+        center: f64,
+        span: f64,
+        points: usize,
+        unit: Unit,
+        sweep_type: SweepType,
+    ) -> Result<Self, Error> {
+        let (start, stop) = (center - span / 2.0, center + span / 2.0);
+        match sweep_type {
+            SweepType::Linear => FrequencySweep::linear(start, stop, points, unit),
+            SweepType::Log => FrequencySweep::log(start, stop, points, unit),
+            SweepType::List(_) => Err(Error::InvalidInput(
+                "list sweep cannot be defined by center and span".to_string(),
+            )),
+        }
+    }
+
+    pub fn single(freq: Frequency) -> Self {
+        FrequencySweep {
+            start: freq.freq_scaled(),
+            stop: freq.freq_scaled(),
+            points: 1,
+            unit: freq.unit,
+            sweep_type: SweepType::Linear,
+        }
+    }
+
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    pub fn stop(&self) -> f64 {
+        self.stop
+    }
+
+    pub fn center(&self) -> f64 {
+        (self.start + self.stop) / 2.0
+    }
+
+    pub fn span(&self) -> f64 {
+        self.stop - self.start
+    }
+
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn sweep_type(&self) -> &SweepType {
+        &self.sweep_type
+    }
+
+    pub fn freq_scaled(&self, idx: usize) -> f64 {
+        let frac = match self.points {
+            1 => 0.0,
+            _ => (idx as f64) / ((self.points - 1) as f64),
+        };
+        match &self.sweep_type {
+            SweepType::Linear => self.start + (self.stop - self.start) * frac,
+            SweepType::Log => self.start * (self.stop / self.start).powf(frac),
+            SweepType::List(vals) => vals[idx],
+        }
+    }
+
+    pub fn freq(&self, idx: usize) -> Frequency {
+        Frequency::new(self.freq_scaled(idx), self.unit)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Frequency> + '_ {
+        (0..self.points).map(move |i| self.freq(i))
+    }
+
+    pub fn freqs_scaled(&self) -> Vec<f64> {
+        (0..self.points).map(|i| self.freq_scaled(i)).collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a FrequencySweep {
+    type Item = Frequency;
+    type IntoIter = Box<dyn Iterator<Item = Frequency> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl fmt::Display for FrequencySweep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = get_unit(&self.unit, &UnitType::Hz);
+        match self.sweep_type {
+            SweepType::List(_) => write!(
+                f,
+                "{} {} to {} {}, {} points (list)",
+                self.start, unit, self.stop, unit, self.points
+            ),
+            _ => write!(
+                f,
+                "{} {} to {} {}, {} points ({})",
+                self.start, unit, self.stop, unit, self.points, self.sweep_type
+            ),
+        }
+    }
+}
+
+impl Serialize for FrequencySweep {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("FrequencySweep", 8)?;
+        s.serialize_field("start", &self.start)?;
+        s.serialize_field("stop", &self.stop)?;
+        s.serialize_field("center", &self.center())?;
+        s.serialize_field("span", &self.span())?;
+        s.serialize_field("points", &self.points)?;
+        s.serialize_field("unit", &get_unit(&self.unit, &UnitType::Hz))?;
+        s.serialize_field("type", &self.sweep_type.to_string())?;
+        s.serialize_field("freqs", &self.freqs_scaled())?;
+        s.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct FrequencySweepArgs {
+    start: Option<f64>,
+    stop: Option<f64>,
+    center: Option<f64>,
+    span: Option<f64>,
+    points: Option<usize>,
+    unit: Unit,
+    #[serde(rename = "type")]
+    sweep_type: String,
+    freqs: Option<Vec<f64>>,
+}
+
+impl TryFrom<FrequencySweepArgs> for FrequencySweep {
+    type Error = Error;
+
+    fn try_from(args: FrequencySweepArgs) -> Result<Self, Self::Error> {
+        let unit = args.unit;
+        let sweep_type = SweepType::from_str(&args.sweep_type)?;
+
+        if let SweepType::List(_) = sweep_type {
+            return FrequencySweep::list(args.freqs.unwrap_or_default(), unit);
+        }
+
+        let points = args.points.ok_or(Error::InvalidInput(
+            "sweep points not specified".to_string(),
+        ))?;
+        match (args.start, args.stop, args.center, args.span) {
+            (Some(start), Some(stop), _, _) => match sweep_type {
+                SweepType::Log => FrequencySweep::log(start, stop, points, unit),
+                _ => FrequencySweep::linear(start, stop, points, unit),
+            },
+            (_, _, Some(center), Some(span)) => {
+                FrequencySweep::from_center_span(center, span, points, unit, sweep_type)
+            }
+            _ => Err(Error::InvalidInput(
+                "sweep requires either start and stop or center and span".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::comp_f64;
+    use float_cmp::{approx_eq, F64Margin};
+
+    #[test]
+    fn test_frequency() {
+        let val = 280.0;
+        let val_unscale = 280e9;
+        let w = 2.0 * PI * val_unscale;
+        let er = 3.4;
+        let wavelength = 3e8 / (280e9 * 3.4_f64.sqrt());
+        let unit = Unit::Giga;
+        let freq = Frequency::new(val, unit);
+
+        comp_f64(
+            &freq.freq(),
+            &val_unscale,
+            F64Margin::default(),
+            "frequency",
+            "freq()",
+        );
+        comp_f64(
+            &freq.freq_scaled(),
+            &val,
+            F64Margin::default(),
+            "frequency",
+            "freq_scaled()",
+        );
+        comp_f64(&freq.w(), &w, F64Margin::default(), "frequency", "w()");
+        comp_f64(
+            &freq.wavelength(er),
+            &wavelength,
+            F64Margin::default(),
+            "frequency",
+            "wavelength()",
+        );
+    }
+
+    #[test]
+    fn test_frequency_sweep_linear() {
+        let testname = "frequency_sweep_linear";
+        let sweep = FrequencySweep::from_center_span(280.0, 20.0, 5, Unit::Giga, SweepType::Linear)
+            .unwrap();
+        let exemplar = [270.0, 275.0, 280.0, 285.0, 290.0];
+
+        let margin = F64Margin::default();
+
+        comp_f64(&sweep.start(), &270.0, margin, testname, "start()");
+        comp_f64(&sweep.stop(), &290.0, margin, testname, "stop()");
+        comp_f64(&sweep.center(), &280.0, margin, testname, "center()");
+        comp_f64(&sweep.span(), &20.0, margin, testname, "span()");
+        assert_eq!(sweep.points(), 5);
+        assert_eq!(sweep.unit(), Unit::Giga);
+        for (i, freq) in sweep.iter().enumerate() {
+            comp_f64(
+                &freq.freq(),
+                &(exemplar[i] * 1e9),
+                margin,
+                testname,
+                &format!("freq[{}]", i),
+            );
+        }
+        assert_eq!(sweep.to_string(), "270 GHz to 290 GHz, 5 points (linear)");
+
+        // Reversed ranges, negative frequencies and empty sweeps are rejected, directly or through
+        // the center and span
+        let codes = [
+            FrequencySweep::linear(290.0, 270.0, 5, Unit::Giga),
+            FrequencySweep::linear(-10.0, 270.0, 5, Unit::Giga),
+            FrequencySweep::linear(270.0, 290.0, 0, Unit::Giga),
+            FrequencySweep::from_center_span(280.0, -20.0, 5, Unit::Giga, SweepType::Linear),
+            FrequencySweep::from_center_span(5.0, 20.0, 5, Unit::Giga, SweepType::Linear),
+            FrequencySweep::from_center_span(280.0, 20.0, 0, Unit::Giga, SweepType::Linear),
+        ]
+        .map(|val| val.unwrap_err().code().to_string());
+        assert_eq!(codes.to_vec(), vec!["invalid_input"; 6]);
+        assert_eq!(
+            FrequencySweep::linear(290.0, 270.0, 5, Unit::Giga)
+                .unwrap_err()
+                .message(),
+            "sweep starts at 290 GHz, above its stop at 270 GHz"
+        );
+        assert!(FrequencySweep::linear(0.0, 0.0, 1, Unit::Giga).is_ok());
+    }
+
+    #[test]
+    fn test_frequency_sweep_log() {
+        let testname = "frequency_sweep_log";
+        let sweep = FrequencySweep::log(1.0, 1000.0, 4, Unit::Mega).unwrap();
+        let exemplar = [1.0, 10.0, 100.0, 1000.0];
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        assert_eq!(sweep.freqs_scaled().len(), 4);
+        for (i, freq) in (&sweep).into_iter().enumerate() {
+            comp_f64(
+                &freq.freq_scaled(),
+                &exemplar[i],
+                margin,
+                testname,
+                &format!("freq[{}]", i),
+            );
+        }
+        assert!(FrequencySweep::log(0.0, 1000.0, 4, Unit::Mega).is_err());
+        assert!(FrequencySweep::linear(1.0, 1000.0, 0, Unit::Mega).is_err());
+    }
+
+    #[test]
+    fn test_frequency_sweep_serde() {
+        let sweep: FrequencySweep = serde_json::from_str(
+            r#"{"start": 270.0, "stop": 290.0, "points": 3, "unit": "GHz", "type": "linear"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sweep,
+            FrequencySweep::linear(270.0, 290.0, 3, Unit::Giga).unwrap()
+        );
+
+        let sweep: FrequencySweep =
+            serde_json::from_str(r#"{"unit": "MHz", "type": "list", "freqs": [5.0, 1.0, 3.0]}"#)
+                .unwrap();
+        assert_eq!(sweep.start(), 1.0);
+        assert_eq!(sweep.stop(), 5.0);
+        assert_eq!(sweep.freqs_scaled(), vec![5.0, 1.0, 3.0]);
+        assert_eq!(
+            serde_json::to_string(&sweep).unwrap(),
+            r#"{"start":1.0,"stop":5.0,"center":3.0,"span":4.0,"points":3,"unit":"MHz","type":"list","freqs":[5.0,1.0,3.0]}"#
+        );
+
+        assert!(serde_json::from_str::<FrequencySweep>(
+            r#"{"start": 270.0, "points": 3, "unit": "GHz", "type": "linear"}"#
+        )
+        .is_err());
+    }
+}
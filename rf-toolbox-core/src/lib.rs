@@ -0,0 +1,21 @@
+// Calculator entry points mirror the argument lists of the frontend forms
+#![allow(clippy::too_many_arguments)]
+
+pub mod conjugate;
+pub mod differential;
+pub mod element;
+pub mod error;
+pub mod freq_data;
+pub mod frequency;
+pub mod matching;
+pub mod mna;
+pub mod project;
+pub mod response;
+pub mod rf_utils;
+pub mod richards;
+pub mod schematic;
+pub mod smith;
+pub mod spice;
+pub mod touchstone;
+pub mod unit;
+pub mod vector_fit;
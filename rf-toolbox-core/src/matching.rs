@@ -0,0 +1,1271 @@
+#![allow(unused)]
+use crate::differential::{balance, BalancedElement};
+use crate::error::Error;
+use crate::matching::bp::{calc_bp1, calc_bp2, calc_bp3, calc_bp4};
+use crate::matching::ell::{calc_hp_ell_cl, calc_hp_ell_lc, calc_lp_ell_cl, calc_lp_ell_lc};
+use crate::matching::ell_w_q::{
+    calc_hp_ell_cl_w_q, calc_hp_ell_lc_w_q, calc_lp_ell_cl_w_q, calc_lp_ell_lc_w_q,
+};
+use crate::matching::hp::{calc_hp1, calc_hp2};
+use crate::matching::ladder::Topology;
+use crate::matching::lp::{calc_lp1, calc_lp2};
+use crate::matching::pi::calc_pi;
+use crate::matching::tee::calc_tee;
+use crate::rf_utils::{
+    calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn, ZScale,
+};
+use crate::unit::{get_unit, Unit, UnitType};
+use float_cmp::F64Margin;
+use num_complex::Complex;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod bp;
+pub mod ell;
+pub mod ell_w_q;
+pub mod hp;
+pub mod ladder;
+pub mod lp;
+pub mod pi;
+pub mod tee;
+pub mod xfmr;
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct MatchingReturn {
+    pub zs: ComplexReturn,
+    pub zl: ComplexReturn,
+    pub hp1: Vec<CCLL>,
+    pub hp2: Vec<CCLL>,
+    pub lp1: Vec<CCLL>,
+    pub lp2: Vec<CCLL>,
+    pub bp1: Vec<CCLL>,
+    pub bp2: Vec<CCLL>,
+    pub bp3: Vec<CCLL>,
+    pub bp4: Vec<CCLL>,
+    pub pi: Vec<PiTee>,
+    pub tee: Vec<PiTee>,
+    pub hp_ell_cl: Vec<CL>,
+    pub hp_ell_cl_w_q: Vec<CLQ>,
+    pub hp_ell_lc: Vec<CL>,
+    pub hp_ell_lc_w_q: Vec<CLQ>,
+    pub lp_ell_cl: Vec<CL>,
+    pub lp_ell_cl_w_q: Vec<CLQ>,
+    pub lp_ell_lc: Vec<CL>,
+    pub lp_ell_lc_w_q: Vec<CLQ>,
+}
+
+impl MatchingReturn {
+    // Balanced form of every realizable solution, each network written from the source as a ladder
+    fn balance(&mut self) {
+        let ccll = [
+            (&mut self.hp1, "pL-sC-pL-sC"),
+            (&mut self.hp2, "sC-pL-sC-pL"),
+            (&mut self.lp1, "pC-sL-pC-sL"),
+            (&mut self.lp2, "sL-pC-sL-pC"),
+            (&mut self.bp1, "pL-sC-pC-sL"),
+            (&mut self.bp2, "sL-pC-sC-pL"),
+            (&mut self.bp3, "pC-sL-pL-sC"),
+            (&mut self.bp4, "sC-pL-sL-pC"),
+        ];
+        for (nets, topology) in ccll {
+            // The source section holds cs and ls, the load section cl and ll
+            let (source, load) = (&topology[..5], &topology[6..]);
+            for val in nets.iter_mut().filter(|val| val.reason.is_none()) {
+                let vals = [
+                    ell_order(source, val.cs, val.ls),
+                    ell_order(load, val.cl, val.ll),
+                ]
+                .concat();
+                val.balanced = balanced(topology, &vals);
+            }
+        }
+
+        let pi_tee = [
+            (&mut self.pi, "pC-sL-pC", "pL-sC-pL"),
+            (&mut self.tee, "sC-pL-sC", "sL-pC-sL"),
+        ];
+        for (nets, l_topology, c_topology) in pi_tee {
+            for val in nets.iter_mut() {
+                if val.l_reason.is_none() {
+                    val.l_balanced = balanced(l_topology, &[val.cs, val.l, val.cl]);
+                }
+                if val.c_reason.is_none() {
+                    val.c_balanced = balanced(c_topology, &[val.ls, val.c, val.ll]);
+                }
+            }
+        }
+
+        let ell = [
+            (&mut self.hp_ell_cl, "sC-pL"),
+            (&mut self.hp_ell_lc, "pL-sC"),
+            (&mut self.lp_ell_cl, "pC-sL"),
+            (&mut self.lp_ell_lc, "sL-pC"),
+        ];
+        for (nets, topology) in ell {
+            for val in nets.iter_mut().filter(|val| val.reason.is_none()) {
+                val.balanced = balanced(topology, &ell_order(topology, val.c, val.l));
+            }
+        }
+        let ell_w_q = [
+            (&mut self.hp_ell_cl_w_q, "sC-pL"),
+            (&mut self.hp_ell_lc_w_q, "pL-sC"),
+            (&mut self.lp_ell_cl_w_q, "pC-sL"),
+            (&mut self.lp_ell_lc_w_q, "sL-pC"),
+        ];
+        for (nets, topology) in ell_w_q {
+            for val in nets.iter_mut().filter(|val| val.reason.is_none()) {
+                val.balanced = balanced(topology, &ell_order(topology, val.c, val.l));
+            }
+        }
+    }
+}
+
+// Values of an L-section in the order of its topology
+fn ell_order(topology: &str, c: f64, l: f64) -> [f64; 2] {
+    match topology.find('C') < topology.find('L') {
+        true => [c, l],
+        false => [l, c],
+    }
+}
+
+fn balanced(topology: &str, vals: &[f64]) -> Option<Vec<BalancedElement>> {
+    let topology = topology.parse::<Topology>().ok()?;
+    Some(balance(&topology, vals))
+}
+
+// serde_json writes the NaN values of an unrealizable network as null, so a solution sent back
+// reads them as NaN again
+fn null_as_nan<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct CCLL {
+    #[serde(deserialize_with = "null_as_nan")]
+    pub cs: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub cl: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub ls: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub ll: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub q: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub bw: f64,
+    pub c_scale: String,
+    pub l_scale: String,
+    pub reason: Option<Unrealizable>,
+    // balanced form for differential terminations, not read back as its series elements hold a
+    // NaN floating value
+    #[serde(skip_deserializing)]
+    pub balanced: Option<Vec<BalancedElement>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct PiTee {
+    #[serde(deserialize_with = "null_as_nan")]
+    pub c: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub cs: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub cl: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub l: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub ls: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub ll: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub q: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub bw: f64,
+    pub c_scale: String,
+    pub l_scale: String,
+    // ls-c-ll and cs-l-cl networks fail independently
+    pub c_reason: Option<Unrealizable>,
+    pub l_reason: Option<Unrealizable>,
+    #[serde(skip_deserializing)]
+    pub c_balanced: Option<Vec<BalancedElement>>,
+    #[serde(skip_deserializing)]
+    pub l_balanced: Option<Vec<BalancedElement>>,
+}
+
+impl PiTee {
+    // Both networks fail for the same reason
+    fn unrealizable(reason: Unrealizable, c_scale: &Unit, l_scale: &Unit) -> Self {
+        PiTee {
+            c: f64::NAN,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            l: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
+            c_scale: get_unit(c_scale, &UnitType::Farad),
+            l_scale: get_unit(l_scale, &UnitType::Henry),
+            c_reason: Some(reason.clone()),
+            l_reason: Some(reason),
+            c_balanced: None,
+            l_balanced: None,
+        }
+    }
+
+    // Equal terminations with no Q target need no elements
+    fn through(c_scale: &Unit, l_scale: &Unit) -> Self {
+        PiTee {
+            bw: bandwidth(0.0),
+            c_scale: get_unit(c_scale, &UnitType::Farad),
+            l_scale: get_unit(l_scale, &UnitType::Henry),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct CL {
+    #[serde(deserialize_with = "null_as_nan")]
+    pub c: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub l: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub q: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub bw: f64,
+    pub c_scale: String,
+    pub l_scale: String,
+    pub reason: Option<Unrealizable>,
+    #[serde(skip_deserializing)]
+    pub balanced: Option<Vec<BalancedElement>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct CLQ {
+    #[serde(deserialize_with = "null_as_nan")]
+    pub c: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub l: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub q: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub q_net: f64,
+    #[serde(deserialize_with = "null_as_nan")]
+    pub bw: f64,
+    pub sol: usize,
+    pub c_scale: String,
+    pub l_scale: String,
+    pub reason: Option<Unrealizable>,
+    #[serde(skip_deserializing)]
+    pub balanced: Option<Vec<BalancedElement>>,
+}
+
+// Why a topology has no solution. Sent next to the NaN values as {code, message} so the UI can
+// tell the user what to change, with the values the message quotes so that it reads back.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum Unrealizable {
+    NegativeCapacitance,
+    NegativeInductance,
+    NegativeQ { q: f64 },
+    InductorQ { q: f64 },
+    QBelowMinimum { q: f64, q_min: f64 },
+    SourceExceedsLoadParallel { rs: f64, rp: f64 },
+    LoadExceedsSourceParallel { rl: f64, rp: f64 },
+}
+
+impl Unrealizable {
+    // Pick the reason for a solution with a negative element; capacitors are checked first
+    pub fn negative(c: f64, l: f64) -> Self {
+        if c < 0.0 {
+            Unrealizable::NegativeCapacitance
+        } else {
+            Unrealizable::NegativeInductance
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Unrealizable::NegativeCapacitance => "negative_capacitance",
+            Unrealizable::NegativeInductance => "negative_inductance",
+            Unrealizable::NegativeQ { .. } => "negative_q",
+            Unrealizable::InductorQ { .. } => "inductor_q",
+            Unrealizable::QBelowMinimum { .. } => "q_below_minimum",
+            Unrealizable::SourceExceedsLoadParallel { .. } => "source_exceeds_load_parallel",
+            Unrealizable::LoadExceedsSourceParallel { .. } => "load_exceeds_source_parallel",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Unrealizable::NegativeCapacitance => "requires negative capacitance".to_string(),
+            Unrealizable::NegativeInductance => "requires negative inductance".to_string(),
+            Unrealizable::NegativeQ { q } => format!("Q target {} is negative", q),
+            Unrealizable::InductorQ { q } => format!("no match with an inductor Q of {}", q),
+            Unrealizable::QBelowMinimum { q, q_min } => {
+                format!("Q target {} below minimum of {:.4}", q, q_min)
+            }
+            Unrealizable::SourceExceedsLoadParallel { rs, rp } => format!(
+                "source resistance {:.4} Ω exceeds load parallel resistance {:.4} Ω",
+                rs, rp
+            ),
+            Unrealizable::LoadExceedsSourceParallel { rl, rp } => format!(
+                "load resistance {:.4} Ω exceeds source parallel resistance {:.4} Ω",
+                rl, rp
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Unrealizable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl Serialize for Unrealizable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Unrealizable", 4)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.message())?;
+        match *self {
+            Unrealizable::NegativeCapacitance | Unrealizable::NegativeInductance => {}
+            Unrealizable::NegativeQ { q } | Unrealizable::InductorQ { q } => {
+                s.serialize_field("q", &q)?;
+            }
+            Unrealizable::QBelowMinimum { q, q_min } => {
+                s.serialize_field("q", &q)?;
+                s.serialize_field("q_min", &q_min)?;
+            }
+            Unrealizable::SourceExceedsLoadParallel { rs, rp } => {
+                s.serialize_field("rs", &rs)?;
+                s.serialize_field("rp", &rp)?;
+            }
+            Unrealizable::LoadExceedsSourceParallel { rl, rp } => {
+                s.serialize_field("rl", &rl)?;
+                s.serialize_field("rp", &rp)?;
+            }
+        }
+        s.end()
+    }
+}
+
+// Fractional 3 dB bandwidth of a doubly terminated network with node Q q. Its loaded Q is half
+// the node Q.
+pub fn bandwidth(q: f64) -> f64 {
+    2.0 / q
+}
+
+// Element types of an L-section, seen from the terminal its shunt element sits across
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Section {
+    // shunt capacitor, series inductor
+    LowPass,
+    // shunt inductor, series capacitor
+    HighPass,
+}
+
+// Terminal a section's shunt element sits across
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Side {
+    Source,
+    Load,
+}
+
+impl Side {
+    // Orders (zs, zl) as (shunt terminal, series terminal)
+    pub fn order(self, zs: Complex<f64>, zl: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        match self {
+            Side::Source => (zs, zl),
+            Side::Load => (zl, zs),
+        }
+    }
+
+    // Reason for a shunt terminal whose parallel resistance rp is too low
+    pub fn rp_reason(self, zs: Complex<f64>, zl: Complex<f64>, rp: f64) -> Unrealizable {
+        match self {
+            Side::Source => Unrealizable::LoadExceedsSourceParallel { rl: zl.re, rp },
+            Side::Load => Unrealizable::SourceExceedsLoadParallel { rs: zs.re, rp },
+        }
+    }
+}
+
+// One root of an L-section as shunt susceptance b and series reactance x, in farads and henries,
+// with the Q of its center node
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EllRoot {
+    pub b: f64,
+    pub x: f64,
+    pub c: f64,
+    pub l: f64,
+    pub q: f64,
+}
+
+impl EllRoot {
+    pub fn valid(&self) -> bool {
+        (self.c > 0.0) && (self.l > 0.0)
+    }
+}
+
+// Both roots of the L-section that conjugately matches za to zb, with its shunt element across za
+// and its series element towards zb. Roots with negative elements are kept so callers can tell
+// why a topology failed. Empty when the parallel resistance of za is below zb.re.
+//
+//  zb ---SER------- za
+//             |
+//            SHN
+//             |
+//            GND
+pub fn ell_roots(za: Complex<f64>, zb: Complex<f64>, w: f64, section: Section) -> Vec<EllRoot> {
+    let mag2 = za.norm_sqr();
+    let d = mag2 - zb.re * za.re;
+    if d < 0.0 {
+        return vec![];
+    }
+
+    [1.0, -1.0]
+        .iter()
+        .map(|sign| {
+            let b = (za.im + sign * (za.re / zb.re).sqrt() * d.sqrt()) / mag2;
+            let zp = 1.0 / (Complex::new(0.0, b) + 1.0 / za);
+            let x = -(zb.im + zp.im);
+            let (c, l) = match section {
+                Section::LowPass => (b / w, x / w),
+                Section::HighPass => (-1.0 / (w * x), -1.0 / (w * b)),
+            };
+            EllRoot {
+                b,
+                x,
+                c,
+                l,
+                q: zp.im.abs() / zp.re,
+            }
+        })
+        .collect()
+}
+
+// Every two-section ladder that matches zs to zl through a virtual resistance, with the first
+// section's shunt element across the terminal at side. Element values are named after the section
+// they sit in: cs and ls in the first, cl and ll in the second.
+pub fn calc_ccll(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    side: Side,
+    first: Section,
+    second: Section,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Vec<CCLL> {
+    let (za, zb) = side.order(zs, zl);
+    let rp = za.norm_sqr() / za.re;
+    let rv = (rp * zb.re).sqrt();
+    let mut out = vec![];
+    let mut reason = None;
+
+    if rp <= rv {
+        reason = Some(side.rp_reason(zs, zl, rp));
+    } else {
+        let rv = Complex::new(rv, 0.0);
+        for s in ell_roots(za, rv, w, first) {
+            for l in ell_roots(rv, zb, w, second) {
+                if s.valid() && l.valid() {
+                    let q = s.q.max(l.q);
+                    out.push(CCLL {
+                        cs: scale(s.c, c_scale),
+                        cl: scale(l.c, c_scale),
+                        ls: scale(s.l, l_scale),
+                        ll: scale(l.l, l_scale),
+                        q,
+                        bw: bandwidth(q),
+                        c_scale: get_unit(c_scale, &UnitType::Farad),
+                        l_scale: get_unit(l_scale, &UnitType::Henry),
+                        reason: None,
+                        balanced: None,
+                    });
+                } else if reason.is_none() {
+                    reason = Some(Unrealizable::negative(s.c.min(l.c), s.l.min(l.l)));
+                }
+            }
+        }
+    }
+
+    if out.is_empty() {
+        out.push(CCLL {
+            cs: f64::NAN,
+            cl: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
+            c_scale: get_unit(c_scale, &UnitType::Farad),
+            l_scale: get_unit(l_scale, &UnitType::Henry),
+            reason,
+            balanced: None,
+        });
+    }
+    out
+}
+
+// Formats accepted by the matching calculator for the source and load entries
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum ImpedanceType {
+    #[serde(rename = "zri")]
+    ZReIm,
+    #[serde(rename = "yri")]
+    YReIm,
+    #[serde(rename = "gma")]
+    GammaMagAng,
+    #[serde(rename = "gri")]
+    GammaReIm,
+    #[serde(rename = "rc")]
+    Rc,
+}
+
+impl FromStr for ImpedanceType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zri" => Ok(ImpedanceType::ZReIm),
+            "yri" => Ok(ImpedanceType::YReIm),
+            "gma" => Ok(ImpedanceType::GammaMagAng),
+            "gri" => Ok(ImpedanceType::GammaReIm),
+            "rc" => Ok(ImpedanceType::Rc),
+            _ => Err(Error::Parse(format!(
+                "impedance type {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+// Source and load impedances from the calculator entries. Differential entries are matched as
+// entered, between the two legs, and the networks given in balanced form as well.
+fn terminations(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    z0: f64,
+    w: f64,
+    cap_unit: &Unit,
+) -> Result<(Complex<f64>, Complex<f64>), Error> {
+    let (zs, zl) = match imp {
+        ImpedanceType::ZReIm => (Complex::new(rs, xs), Complex::new(rl, xl)),
+        ImpedanceType::YReIm => (1.0 / Complex::new(rs, xs), 1.0 / Complex::new(rl, xl)),
+        ImpedanceType::GammaMagAng => (
+            calc_z(Complex::from_polar(rs, xs * PI / 180.0), z0),
+            calc_z(Complex::from_polar(rl, xl * PI / 180.0), z0),
+        ),
+        ImpedanceType::GammaReIm => (
+            calc_z(Complex::new(rs, xs), z0),
+            calc_z(Complex::new(rl, xl), z0),
+        ),
+        ImpedanceType::Rc => (
+            1.0 / Complex::new(1.0 / rs, unscale(xs, cap_unit) * w),
+            1.0 / Complex::new(1.0 / rl, unscale(xl, cap_unit) * w),
+        ),
+    };
+
+    // Lossless networks can only match passive terminations
+    if !(zs.re > 0.0 && zl.re > 0.0) {
+        return Err(Error::NonPhysical(format!(
+            "source and load resistance must be positive, got {} Ω and {} Ω",
+            zs.re, zl.re
+        )));
+    }
+
+    Ok((zs, zl))
+}
+
+pub fn calc_networks(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    q_net: f64,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+    ind_unit: Unit,
+    z_scale: ZScale,
+) -> Result<MatchingReturn, Error> {
+    let mut out = MatchingReturn::default();
+
+    let w = 2.0 * PI * unscale(freq, &freq_unit);
+    let (zs, zl) = terminations(rs, xs, rl, xl, imp, z0, w, &cap_unit)?;
+
+    out.hp_ell_cl = calc_hp_ell_cl(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.hp_ell_cl_w_q = calc_hp_ell_cl_w_q(zs, zl, q, w, &cap_unit, &ind_unit)?;
+    out.hp_ell_lc = calc_hp_ell_lc(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.hp_ell_lc_w_q = calc_hp_ell_lc_w_q(zs, zl, q, w, &cap_unit, &ind_unit)?;
+    out.lp_ell_cl = calc_lp_ell_cl(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.lp_ell_cl_w_q = calc_lp_ell_cl_w_q(zs, zl, q, w, &cap_unit, &ind_unit)?;
+    out.lp_ell_lc = calc_lp_ell_lc(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.lp_ell_lc_w_q = calc_lp_ell_lc_w_q(zs, zl, q, w, &cap_unit, &ind_unit)?;
+    out.tee = calc_tee(zs, zl, w, q_net, &cap_unit, &ind_unit)?;
+    out.pi = calc_pi(zs, zl, w, q_net, &cap_unit, &ind_unit)?;
+    out.lp1 = calc_lp1(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.lp2 = calc_lp2(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.hp1 = calc_hp1(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.hp2 = calc_hp2(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.bp1 = calc_bp1(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.bp2 = calc_bp2(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.bp3 = calc_bp3(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.bp4 = calc_bp4(zs, zl, w, &cap_unit, &ind_unit)?;
+    out.zs = ComplexReturn {
+        re: zs.re,
+        im: zs.im,
+    };
+    out.zl = ComplexReturn {
+        re: zl.re,
+        im: zl.im,
+    };
+    if z_scale == ZScale::Diff {
+        out.balance();
+    }
+
+    Ok(out)
+}
+
+pub fn change_impedance(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp_in: ImpedanceType,
+    imp_out: ImpedanceType,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+) -> Result<Complex2Return, Error> {
+    if imp_in == imp_out {
+        return Ok(Complex2Return {
+            src: ComplexReturn { re: rs, im: xs },
+            load: ComplexReturn { re: rl, im: xl },
+        });
+    }
+
+    match imp_in {
+        ImpedanceType::ZReIm => match imp_out {
+            ImpedanceType::YReIm => {
+                let ys = Complex::new(rs, xs).inv();
+                let yl = Complex::new(rl, xl).inv();
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: ys.re,
+                        im: ys.im,
+                    },
+                    load: ComplexReturn {
+                        re: yl.re,
+                        im: yl.im,
+                    },
+                })
+            }
+            ImpedanceType::GammaMagAng => {
+                let gs = calc_gamma(Complex::new(rs, xs), z0);
+                let gl = calc_gamma(Complex::new(rl, xl), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.norm(),
+                        im: gs.arg() * 180.0 / PI,
+                    },
+                    load: ComplexReturn {
+                        re: gl.norm(),
+                        im: gl.arg() * 180.0 / PI,
+                    },
+                })
+            }
+            ImpedanceType::GammaReIm => {
+                let gs = calc_gamma(Complex::new(rs, xs), z0);
+                let gl = calc_gamma(Complex::new(rl, xl), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.re,
+                        im: gs.im,
+                    },
+                    load: ComplexReturn {
+                        re: gl.re,
+                        im: gl.im,
+                    },
+                })
+            }
+            ImpedanceType::Rc => {
+                let (src_r, src_c) = calc_rc(
+                    Complex::new(rs, xs),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                let (load_r, load_c) = calc_rc(
+                    Complex::new(rl, xl),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: src_r,
+                        im: src_c,
+                    },
+                    load: ComplexReturn {
+                        re: load_r,
+                        im: load_c,
+                    },
+                })
+            }
+            _ => unreachable!(),
+        },
+        ImpedanceType::YReIm => match imp_out {
+            ImpedanceType::ZReIm => {
+                let zs = Complex::new(rs, xs).inv();
+                let zl = Complex::new(rl, xl).inv();
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: zs.re,
+                        im: zs.im,
+                    },
+                    load: ComplexReturn {
+                        re: zl.re,
+                        im: zl.im,
+                    },
+                })
+            }
+            ImpedanceType::GammaMagAng => {
+                let gs = calc_gamma(Complex::new(rs, xs).inv(), z0);
+                let gl = calc_gamma(Complex::new(rl, xl).inv(), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.norm(),
+                        im: gs.arg() * 180.0 / PI,
+                    },
+                    load: ComplexReturn {
+                        re: gl.norm(),
+                        im: gl.arg() * 180.0 / PI,
+                    },
+                })
+            }
+            ImpedanceType::GammaReIm => {
+                let gs = calc_gamma(Complex::new(rs, xs).inv(), z0);
+                let gl = calc_gamma(Complex::new(rl, xl).inv(), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.re,
+                        im: gs.im,
+                    },
+                    load: ComplexReturn {
+                        re: gl.re,
+                        im: gl.im,
+                    },
+                })
+            }
+            ImpedanceType::Rc => {
+                let (src_r, src_c) = calc_rc(
+                    Complex::new(rs, xs).inv(),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                let (load_r, load_c) = calc_rc(
+                    Complex::new(rl, xl).inv(),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: src_r,
+                        im: src_c,
+                    },
+                    load: ComplexReturn {
+                        re: load_r,
+                        im: load_c,
+                    },
+                })
+            }
+            _ => unreachable!(),
+        },
+        ImpedanceType::GammaMagAng => match imp_out {
+            ImpedanceType::ZReIm => {
+                let zs = calc_z(Complex::new(rs, xs), z0);
+                let zl = calc_z(Complex::new(rl, xl), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: zs.re,
+                        im: zs.im,
+                    },
+                    load: ComplexReturn {
+                        re: zl.re,
+                        im: zl.im,
+                    },
+                })
+            }
+            ImpedanceType::YReIm => {
+                let ys = calc_z(Complex::from_polar(rs, xs * PI / 180.0), z0).inv();
+                let yl = calc_z(Complex::from_polar(rl, xl * PI / 180.0), z0).inv();
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: ys.re,
+                        im: ys.im,
+                    },
+                    load: ComplexReturn {
+                        re: yl.re,
+                        im: yl.im,
+                    },
+                })
+            }
+            ImpedanceType::GammaReIm => {
+                let gs = Complex::from_polar(rs, xs * PI / 180.0);
+                let gl = Complex::from_polar(rl, xl * PI / 180.0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.re,
+                        im: gs.im,
+                    },
+                    load: ComplexReturn {
+                        re: gl.re,
+                        im: gl.im,
+                    },
+                })
+            }
+            ImpedanceType::Rc => {
+                let (src_r, src_c) = calc_rc(
+                    calc_z(Complex::new(rs, xs), z0),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                let (load_r, load_c) = calc_rc(
+                    calc_z(Complex::new(rl, xl), z0),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: src_r,
+                        im: src_c,
+                    },
+                    load: ComplexReturn {
+                        re: load_r,
+                        im: load_c,
+                    },
+                })
+            }
+            _ => unreachable!(),
+        },
+        ImpedanceType::GammaReIm => match imp_out {
+            ImpedanceType::ZReIm => {
+                let zs = calc_z(Complex::new(rs, xs), z0);
+                let zl = calc_z(Complex::new(rl, xl), z0);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: zs.re,
+                        im: zs.im,
+                    },
+                    load: ComplexReturn {
+                        re: zl.re,
+                        im: zl.im,
+                    },
+                })
+            }
+            ImpedanceType::YReIm => {
+                let ys = calc_z(Complex::new(rs, xs), z0).inv();
+                let yl = calc_z(Complex::new(rl, xl), z0).inv();
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: ys.re,
+                        im: ys.im,
+                    },
+                    load: ComplexReturn {
+                        re: yl.re,
+                        im: yl.im,
+                    },
+                })
+            }
+            ImpedanceType::GammaMagAng => {
+                let gs = Complex::new(rs, xs);
+                let gl = Complex::new(rl, xl);
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.norm(),
+                        im: gs.arg() * 180.0 / PI,
+                    },
+                    load: ComplexReturn {
+                        re: gl.norm(),
+                        im: gl.arg() * 180.0 / PI,
+                    },
+                })
+            }
+            ImpedanceType::Rc => {
+                let (src_r, src_c) = calc_rc(
+                    calc_z(Complex::new(rs, xs), z0),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                let (load_r, load_c) = calc_rc(
+                    calc_z(Complex::new(rl, xl), z0),
+                    freq,
+                    &freq_unit,
+                    &Unit::Base,
+                    &cap_unit,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: src_r,
+                        im: src_c,
+                    },
+                    load: ComplexReturn {
+                        re: load_r,
+                        im: load_c,
+                    },
+                })
+            }
+            _ => unreachable!(),
+        },
+        ImpedanceType::Rc => match imp_out {
+            ImpedanceType::ZReIm => {
+                let zs = Complex::new(
+                    1.0 / rs,
+                    unscale(xs, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                )
+                .inv();
+                let zl = Complex::new(
+                    1.0 / rl,
+                    unscale(xl, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                )
+                .inv();
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: zs.re,
+                        im: zs.im,
+                    },
+                    load: ComplexReturn {
+                        re: zl.re,
+                        im: zl.im,
+                    },
+                })
+            }
+            ImpedanceType::YReIm => {
+                let ys = Complex::new(
+                    1.0 / rs,
+                    unscale(xs, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                );
+                let yl = Complex::new(
+                    1.0 / rl,
+                    unscale(xl, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: ys.re,
+                        im: ys.im,
+                    },
+                    load: ComplexReturn {
+                        re: yl.re,
+                        im: yl.im,
+                    },
+                })
+            }
+            ImpedanceType::GammaMagAng => {
+                let gs = calc_gamma(
+                    Complex::new(
+                        1.0 / rs,
+                        unscale(xs, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                    )
+                    .inv(),
+                    z0,
+                );
+                let gl = calc_gamma(
+                    Complex::new(
+                        1.0 / rl,
+                        unscale(xl, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                    )
+                    .inv(),
+                    z0,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.norm(),
+                        im: gs.arg() * 180.0 / PI,
+                    },
+                    load: ComplexReturn {
+                        re: gl.norm(),
+                        im: gl.arg() * 180.0 / PI,
+                    },
+                })
+            }
+            ImpedanceType::GammaReIm => {
+                let gs = calc_gamma(
+                    Complex::new(
+                        1.0 / rs,
+                        unscale(xs, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                    )
+                    .inv(),
+                    z0,
+                );
+                let gl = calc_gamma(
+                    Complex::new(
+                        1.0 / rl,
+                        unscale(xl, &cap_unit) * 2.0 * PI * unscale(freq, &freq_unit),
+                    )
+                    .inv(),
+                    z0,
+                );
+                Ok(Complex2Return {
+                    src: ComplexReturn {
+                        re: gs.re,
+                        im: gs.im,
+                    },
+                    load: ComplexReturn {
+                        re: gl.re,
+                        im: gl.im,
+                    },
+                })
+            }
+            _ => unreachable!(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::comp_f64;
+
+    #[test]
+    fn test_calc_networks_non_physical() {
+        let calc = |rs: f64, rl: f64| {
+            calc_networks(
+                rs,
+                0.0,
+                rl,
+                0.0,
+                ImpedanceType::ZReIm,
+                0.0,
+                0.0,
+                50.0,
+                275.0,
+                Unit::Giga,
+                Unit::Femto,
+                Unit::Pico,
+                ZScale::SingleEnded,
+            )
+        };
+
+        assert!(calc(25.0, 50.0).is_ok());
+        assert_eq!(calc(-25.0, 50.0).err().unwrap().code(), "non_physical");
+        assert_eq!(calc(25.0, f64::NAN).err().unwrap().code(), "non_physical");
+    }
+
+    #[test]
+    fn test_calc_networks_diff() {
+        let calc = |rs: f64, rl: f64, xl: f64, z_scale: ZScale| {
+            calc_networks(
+                rs,
+                0.0,
+                rl,
+                xl,
+                ImpedanceType::ZReIm,
+                3.0,
+                10.0,
+                50.0,
+                2.0,
+                Unit::Giga,
+                Unit::Pico,
+                Unit::Nano,
+                z_scale,
+            )
+            .unwrap()
+        };
+        // Matched between the legs as entered, the legs of the balanced form are the half circuit
+        let diff = calc(100.0, 400.0, -20.0, ZScale::Diff);
+        let se = calc(100.0, 400.0, -20.0, ZScale::SingleEnded);
+        let half = calc(50.0, 200.0, -10.0, ZScale::SingleEnded);
+        let margin = F64Margin::from((1e-12, 4));
+        let name = "calc_networks_diff";
+        comp_f64(&diff.pi[0].cs, &se.pi[0].cs, margin, name, "pi.cs");
+        assert!(se.pi[0].l_balanced.is_none());
+
+        let legs = |val: &Option<Vec<BalancedElement>>| {
+            val.as_ref()
+                .unwrap()
+                .iter()
+                .map(|val| val.leg)
+                .collect::<Vec<f64>>()
+        };
+        let check = |test: Vec<f64>, exemplar: Vec<f64>, network: &str| {
+            assert_eq!(test.len(), exemplar.len());
+            for (test, exemplar) in test.iter().zip(exemplar.iter()) {
+                comp_f64(test, exemplar, margin, name, network);
+            }
+        };
+        let pi = &half.pi[0];
+        check(
+            legs(&diff.pi[0].l_balanced),
+            vec![pi.cs, pi.l, pi.cl],
+            "pi.l",
+        );
+        assert!(diff.pi[0].c_balanced.is_none());
+        let tee = &half.tee[1];
+        check(
+            legs(&diff.tee[1].l_balanced),
+            vec![tee.cs, tee.l, tee.cl],
+            "tee.l",
+        );
+        check(
+            legs(&diff.tee[1].c_balanced),
+            vec![tee.ls, tee.c, tee.ll],
+            "tee.c",
+        );
+        let bp2 = &half.bp2[0];
+        check(
+            legs(&diff.bp2[0].balanced),
+            vec![bp2.ls, bp2.cs, bp2.cl, bp2.ll],
+            "bp2",
+        );
+        let hp2 = &half.hp2[0];
+        check(
+            legs(&diff.hp2[0].balanced),
+            vec![hp2.cs, hp2.ls, hp2.cl, hp2.ll],
+            "hp2",
+        );
+        let hp = &half.hp_ell_cl[0];
+        check(
+            legs(&diff.hp_ell_cl[0].balanced),
+            vec![hp.c, hp.l],
+            "hp_ell_cl",
+        );
+        let hp = &half.hp_ell_cl_w_q[0];
+        check(
+            legs(&diff.hp_ell_cl_w_q[0].balanced),
+            vec![hp.c, hp.l],
+            "hp_ell_cl_w_q",
+        );
+
+        // A shunt element can instead float across the pair unchanged
+        let floating = diff.tee[0].c_balanced.as_ref().unwrap();
+        assert!(floating[0].floating.is_nan());
+        comp_f64(&floating[1].floating, &diff.tee[0].c, margin, name, "tee.c");
+    }
+
+    #[test]
+    fn test_unrealizable_reasons() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+
+        let test = calc_lp1(zs, zl, w, &Unit::Femto, &Unit::Pico)
+            .unwrap()
+            .remove(0);
+        assert!(matches!(
+            test.reason,
+            Some(Unrealizable::LoadExceedsSourceParallel { .. })
+        ));
+        let test = calc_lp2(zs, zl, w, &Unit::Femto, &Unit::Pico)
+            .unwrap()
+            .remove(0);
+        assert_eq!(test.reason, None);
+
+        let test = calc_pi(zs, zl, w, 4.32, &Unit::Femto, &Unit::Pico)
+            .unwrap()
+            .remove(0);
+        assert_eq!(test.c_reason, Some(Unrealizable::NegativeInductance));
+        assert_eq!(test.l_reason, None);
+
+        let test = calc_pi(zl, zs, w, 1.99, &Unit::Femto, &Unit::Pico)
+            .unwrap()
+            .remove(0);
+        let reason = test.c_reason.unwrap();
+        let Unrealizable::QBelowMinimum { q_min, .. } = reason else {
+            panic!("expected QBelowMinimum, got {:?}", reason);
+        };
+        assert_eq!(reason.code(), "q_below_minimum");
+        assert_eq!(reason.message(), "Q target 1.99 below minimum of 2.0018");
+        assert_eq!(
+            serde_json::to_value(&reason).unwrap(),
+            serde_json::json!({
+                "code": "q_below_minimum",
+                "message": "Q target 1.99 below minimum of 2.0018",
+                "q": 1.99,
+                "q_min": q_min,
+            })
+        );
+        let json = serde_json::to_value(&reason).unwrap();
+        assert_eq!(
+            serde_json::from_value::<Unrealizable>(json).unwrap(),
+            reason
+        );
+        let json = serde_json::to_value(Unrealizable::NegativeCapacitance).unwrap();
+        assert_eq!(
+            serde_json::from_value::<Unrealizable>(json).unwrap(),
+            Unrealizable::NegativeCapacitance
+        );
+
+        let mut test = calc_tee(zs, zl, w, -1.0, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 1);
+        let test = test.remove(0);
+        assert_eq!(test.l_reason, Some(Unrealizable::NegativeQ { q: -1.0 }));
+    }
+
+    #[test]
+    fn test_calc_ell_w_q_second_root() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 20.0;
+
+        // ---RES--IND---------
+        //          |
+        //         CAP
+        let test = calc_lp_ell_lc_w_q(zs, zl, q, w, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].sol, 2);
+        let xl = w * unscale(test[0].l, &Unit::Pico);
+        let zind = Complex::new(xl / q, xl);
+        let zcap = Complex::new(0.0, -1.0 / (w * unscale(test[0].c, &Unit::Femto)));
+        let zin = zind + zcap * zl / (zcap + zl);
+        comp_f64(
+            &zin.re,
+            &zs.re,
+            F64Margin {
+                epsilon: 1e-9,
+                ulps: 4,
+            },
+            "calc_lp_ell_lc_w_q()",
+            "re",
+        );
+        comp_f64(
+            &zin.im,
+            &(-zs.im),
+            F64Margin {
+                epsilon: 1e-9,
+                ulps: 4,
+            },
+            "calc_lp_ell_lc_w_q()",
+            "im",
+        );
+    }
+}
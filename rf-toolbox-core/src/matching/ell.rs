@@ -1,11 +1,11 @@
 #![allow(unused)]
-use crate::matching::CL;
+use crate::error::Error;
+use crate::matching::{bandwidth, ell_roots, Section, Side, Unrealizable, CL};
 use crate::rf_utils::{calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn};
 use crate::unit::{get_unit, Unit, UnitType};
 use float_cmp::F64Margin;
 use num_complex::Complex;
 use std::f64::consts::PI;
-use std::f64::{INFINITY, NAN};
 use std::str::FromStr;
 
 // ---CAP---------
@@ -19,64 +19,16 @@ pub fn calc_hp_ell_cl(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CL, String> {
-    let mut c: f64;
-    let mut l: f64;
-    let mut q: f64;
-
-    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
-        c = 0.0;
-        l = 0.0;
-        q = zs.im / zs.re;
-    } else {
-        let qs = zl.im / zl.re;
-        let c1 = -1.0 / (w * zs.im);
-        let l1 = (1.0 + qs.powi(2)) * zl.im / (w * qs.powi(2));
-        let rp = (1.0 + qs.powi(2)) * zl.re;
-
-        if zs.re > rp {
-            c = NAN;
-            l = NAN;
-            q = NAN;
-        } else {
-            q = (rp / zs.re - 1.0).sqrt();
-            l = rp / (w * q);
-            c = 1.0 / (q * w * zs.re);
-
-            if zs.im != 0.0 {
-                if c1 == c {
-                    c = INFINITY;
-                } else {
-                    c *= c1 / (c1 - c);
-                }
-            }
-
-            if zl.im != 0.0 {
-                if l1 == l {
-                    l = INFINITY;
-                } else {
-                    l *= l1 / (l1 - l);
-                }
-            }
-
-            c = scale(c, c_scale);
-            l = scale(l, l_scale);
-
-            if (c < 0.0) || (l < 0.0) {
-                c = NAN;
-                l = NAN;
-                q = NAN;
-            }
-        }
-    }
-
-    Ok(CL {
-        c: c,
-        l: l,
-        q: q,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+) -> Result<Vec<CL>, Error> {
+    Ok(calc_ell(
+        zs,
+        zl,
+        w,
+        Side::Load,
+        Section::HighPass,
+        c_scale,
+        l_scale,
+    ))
 }
 
 // --------CAP----
@@ -90,65 +42,16 @@ pub fn calc_hp_ell_lc(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CL, String> {
-    let mut c: f64;
-    let mut l: f64;
-    let mut q: f64;
-
-    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
-        c = 0.0;
-        l = 0.0;
-        q = zs.im / zs.re;
-    } else {
-        let qs = zs.im / zs.re;
-        let c1 = -1.0 / (w * zl.im);
-        let l1 = (1.0 + qs.powi(2)) * zs.im / (w * qs.powi(2));
-        let rp = (1.0 + qs.powi(2)) * zs.re;
-        let rs = zl.re + 0.0;
-
-        if rs > rp {
-            c = NAN;
-            l = NAN;
-            q = NAN;
-        } else {
-            q = (rp / rs - 1.0).sqrt();
-            l = rp / (w * q);
-            c = 1.0 / (q * w * rs);
-
-            if zl.im != 0.0 {
-                if c1 == c {
-                    c = INFINITY;
-                } else {
-                    c *= c1 / (c1 - c);
-                }
-            }
-
-            if zs.im != 0.0 {
-                if l1 == l {
-                    l = INFINITY;
-                } else {
-                    l *= l1 / (l1 - l);
-                }
-            }
-
-            c = scale(c, c_scale);
-            l = scale(l, l_scale);
-
-            if (c < 0.0) || (l < 0.0) {
-                c = NAN;
-                l = NAN;
-                q = NAN;
-            }
-        }
-    }
-
-    Ok(CL {
-        c: c,
-        l: l,
-        q: q,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+) -> Result<Vec<CL>, Error> {
+    Ok(calc_ell(
+        zs,
+        zl,
+        w,
+        Side::Source,
+        Section::HighPass,
+        c_scale,
+        l_scale,
+    ))
 }
 
 // --------IND----
@@ -162,50 +65,16 @@ pub fn calc_lp_ell_cl(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CL, String> {
-    let mut c: f64;
-    let mut l: f64;
-    let mut q: f64;
-
-    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
-        c = 0.0;
-        l = 0.0;
-        q = zs.im / zs.re;
-    } else {
-        let qs = -zs.im / zs.re;
-        let rp = zs.re * (1.0 + qs.powi(2));
-
-        if zl.re > rp {
-            c = NAN;
-            l = NAN;
-            q = NAN;
-        } else {
-            q = (rp / zl.re - 1.0).sqrt();
-            let cp = q / (rp * w);
-            let c1 = qs / (rp * w);
-            c = cp - c1;
-            let ls = q * zl.re / w;
-            let l1 = zl.im / w;
-            l = ls - l1;
-
-            c = scale(c, c_scale);
-            l = scale(l, l_scale);
-
-            if (c < 0.0) || (l < 0.0) {
-                c = NAN;
-                l = NAN;
-                q = NAN;
-            }
-        }
-    }
-
-    Ok(CL {
-        c: c,
-        l: l,
-        q: q,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+) -> Result<Vec<CL>, Error> {
+    Ok(calc_ell(
+        zs,
+        zl,
+        w,
+        Side::Source,
+        Section::LowPass,
+        c_scale,
+        l_scale,
+    ))
 }
 
 // ---IND---------
@@ -219,50 +88,60 @@ pub fn calc_lp_ell_lc(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CL, String> {
-    let mut c: f64;
-    let mut l: f64;
-    let mut q: f64;
-
-    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
-        c = 0.0;
-        l = 0.0;
-        q = zs.im / zs.re;
-    } else {
-        let qs = -zl.im / zl.re;
-        let rp = zl.re * (1.0 + qs.powi(2));
+) -> Result<Vec<CL>, Error> {
+    Ok(calc_ell(
+        zs,
+        zl,
+        w,
+        Side::Load,
+        Section::LowPass,
+        c_scale,
+        l_scale,
+    ))
+}
 
-        if zs.re > rp {
-            c = NAN;
-            l = NAN;
-            q = NAN;
-        } else {
-            q = (rp / zs.re - 1.0).sqrt();
-            let cp = q / (rp * w);
-            let c1 = qs / (rp * w);
-            c = cp - c1;
-            let ls = q * zs.re / w;
-            let l1 = zs.im / w;
-            l = ls - l1;
+// Every root of the L-section whose element types match section, or a single NaN entry with the
+// reason none of them do
+fn calc_ell(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    side: Side,
+    section: Section,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Vec<CL> {
+    let cl = |c: f64, l: f64, q: f64, reason: Option<Unrealizable>| CL {
+        c,
+        l,
+        q,
+        bw: bandwidth(q),
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        reason,
+        balanced: None,
+    };
 
-            c = scale(c, c_scale);
-            l = scale(l, l_scale);
+    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
+        return vec![cl(0.0, 0.0, zs.im / zs.re, None)];
+    }
 
-            if (c < 0.0) || (l < 0.0) {
-                c = NAN;
-                l = NAN;
-                q = NAN;
-            }
-        }
+    let (za, zb) = side.order(zs, zl);
+    let roots = ell_roots(za, zb, w, section);
+    let out: Vec<CL> = roots
+        .iter()
+        .filter(|root| root.valid())
+        .map(|root| cl(scale(root.c, c_scale), scale(root.l, l_scale), root.q, None))
+        .collect();
+    if !out.is_empty() {
+        return out;
     }
 
-    Ok(CL {
-        c: c,
-        l: l,
-        q: q,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+    let reason = match roots.first() {
+        Some(root) => Unrealizable::negative(root.c, root.l),
+        None => side.rp_reason(zs, zl, za.norm_sqr() / za.re),
+    };
+    vec![cl(f64::NAN, f64::NAN, f64::NAN, Some(reason))]
 }
 
 #[cfg(test)]
@@ -282,10 +161,15 @@ mod tests {
             c: 8.58125245724517,
             l: 69.18681390709257,
             q: 2.0529004985170953,
+            bw: 0.9742313382673404,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -307,6 +191,13 @@ mod tests {
             "calc_hp_ell_cl()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp_ell_cl()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -319,10 +210,15 @@ mod tests {
             c: 11.408503434826747,
             l: 133.4483264614267,
             q: 1.5114976179652644,
+            bw: 1.3231909704841902,
             c_scale: "pF".to_string(),
             l_scale: "nH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -344,6 +240,13 @@ mod tests {
             "calc_hp_ell_cl()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp_ell_cl()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -353,13 +256,18 @@ mod tests {
         let c_scale = Unit::Pico;
         let l_scale = Unit::Nano;
         let exemplar = CL {
-            c: NAN,
-            l: NAN,
-            q: NAN,
+            c: f64::NAN,
+            l: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "pF".to_string(),
             l_scale: "nH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         assert!(test.c.is_nan());
         assert!(test.l.is_nan());
         assert!(test.q.is_nan());
@@ -378,10 +286,15 @@ mod tests {
             c: 8.58125245724517,
             l: 69.18681390709257,
             q: 2.0529004985170953,
+            bw: 0.9742313382673404,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -403,6 +316,13 @@ mod tests {
             "calc_hp_ell_lc()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp_ell_lc()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -415,10 +335,15 @@ mod tests {
             c: 11.408503434826747,
             l: 133.4483264614267,
             q: 1.5114976179652644,
+            bw: 1.3231909704841902,
             c_scale: "pF".to_string(),
             l_scale: "nH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -440,6 +365,13 @@ mod tests {
             "calc_hp_ell_lc()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp_ell_lc()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -449,13 +381,18 @@ mod tests {
         let c_scale = Unit::Femto;
         let l_scale = Unit::Pico;
         let exemplar = CL {
-            c: NAN,
-            l: NAN,
-            q: NAN,
+            c: f64::NAN,
+            l: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         assert!(test.c.is_nan());
         assert!(test.l.is_nan());
         assert!(test.q.is_nan());
@@ -474,10 +411,15 @@ mod tests {
             c: 5.906505625073422,
             l: 61.719118523742445,
             q: 2.0529004985170953,
+            bw: 0.9742313382673404,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -499,6 +441,13 @@ mod tests {
             "calc_lp_ell_cl()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_lp_ell_cl()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -511,10 +460,15 @@ mod tests {
             c: 7.2157251698188345,
             l: 99.0557187033109,
             q: 1.5114976179652644,
+            bw: 1.3231909704841902,
             c_scale: "pF".to_string(),
             l_scale: "nH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -536,6 +490,13 @@ mod tests {
             "calc_lp_ell_cl()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_lp_ell_cl()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -545,13 +506,18 @@ mod tests {
         let c_scale = Unit::Femto;
         let l_scale = Unit::Pico;
         let exemplar = CL {
-            c: NAN,
-            l: NAN,
-            q: NAN,
+            c: f64::NAN,
+            l: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_cl(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         assert!(test.c.is_nan());
         assert!(test.l.is_nan());
         assert!(test.q.is_nan());
@@ -570,10 +536,15 @@ mod tests {
             c: 5.906505625073422,
             l: 61.719118523742445,
             q: 2.0529004985170953,
+            bw: 0.9742313382673404,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -595,6 +566,13 @@ mod tests {
             "calc_lp_ell_lc()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_lp_ell_lc()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -607,10 +585,15 @@ mod tests {
             c: 7.2157251698188345,
             l: 99.0557187033109,
             q: 1.5114976179652644,
+            bw: 1.3231909704841902,
             c_scale: "pF".to_string(),
             l_scale: "nH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         comp_f64(
             &test.c,
             &exemplar.c,
@@ -632,6 +615,13 @@ mod tests {
             "calc_lp_ell_lc()",
             "q",
         );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_lp_ell_lc()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -641,17 +631,63 @@ mod tests {
         let c_scale = Unit::Femto;
         let l_scale = Unit::Pico;
         let exemplar = CL {
-            c: NAN,
-            l: NAN,
-            q: NAN,
+            c: f64::NAN,
+            l: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_lp_ell_lc(zs, zl, w, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
         assert!(test.c.is_nan());
         assert!(test.l.is_nan());
         assert!(test.q.is_nan());
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
     }
+
+    #[test]
+    fn test_calc_hp_ell_cl_both_solutions() {
+        let zs = Complex::new(10.0, 100.0);
+        let zl = Complex::new(5.0, -30.0);
+        let w = 2.0 * PI * 1.0e9;
+        let test = calc_hp_ell_cl(zs, zl, w, &Unit::Pico, &Unit::Nano).unwrap();
+        assert_eq!(test.len(), 2);
+        for sol in test.iter() {
+            let zsh = Complex::new(0.0, w * unscale(sol.l, &Unit::Nano));
+            let zser = Complex::new(0.0, -1.0 / (w * unscale(sol.c, &Unit::Pico)));
+            let zin = zser + zsh * zl / (zsh + zl);
+            comp_f64(
+                &zin.re,
+                &zs.re,
+                F64Margin {
+                    epsilon: 1e-9,
+                    ulps: 4,
+                },
+                "calc_hp_ell_cl()",
+                "re",
+            );
+            comp_f64(
+                &zin.im,
+                &(-zs.im),
+                F64Margin {
+                    epsilon: 1e-9,
+                    ulps: 4,
+                },
+                "calc_hp_ell_cl()",
+                "im",
+            );
+            comp_f64(
+                &sol.bw,
+                &(2.0 / sol.q),
+                F64Margin::default(),
+                "calc_hp_ell_cl()",
+                "bw",
+            );
+        }
+    }
 }
@@ -0,0 +1,223 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::matching::{bandwidth, Unrealizable, CLQ};
+use crate::rf_utils::{calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn};
+use crate::unit::{get_unit, Unit, UnitType};
+use float_cmp::F64Margin;
+use num_complex::Complex;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+// ---CAP---------
+//          |
+//         RES
+//          |
+//         IND
+//          |
+//         GND
+pub fn calc_hp_ell_cl_w_q(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    q: f64,
+    w: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<CLQ>, Error> {
+    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
+        return Ok(vec![matched(zs, q, c_scale, l_scale)]);
+    }
+
+    let rs = zs.re;
+    let xs = zs.im;
+    let rl = zl.re;
+    let xl = zl.im;
+    let d = xl.powi(4) - 4.0 * q * rs * xl.powi(3)
+        + (-(4.0 * rs.powi(2)) + 4.0 * q.powi(2) * rl * rs + 2.0 * rl.powi(2)) * xl.powi(2)
+        + (8.0 * q * rl * rs.powi(2) - 4.0 * q * rl.powi(2) * rs) * xl
+        - 4.0 * q.powi(2) * rl.powi(2) * rs.powi(2)
+        + 4.0 * q.powi(2) * rl.powi(3) * rs
+        + rl.powi(4);
+    let roots = [d.sqrt(), -d.sqrt()].map(|r| {
+        let xp = -((q * r - q * xl.powi(2) + 2.0 * q.powi(2) * rs * xl + 2.0 * q * rl * rs
+            - q * rl.powi(2))
+            / ((2.0 * q.powi(2) + 2.0) * rs + (-(2.0 * q.powi(2)) - 2.0) * rl));
+        let xc = ((2.0 * q * rl - 2.0 * xl) * xs + r - xl.powi(2) - rl.powi(2))
+            / (2.0 * xl - 2.0 * q * rl);
+        (xp / w, -1.0 / (w * xc), (xs + xc).abs() / rs)
+    });
+
+    Ok(solutions(roots, q, c_scale, l_scale))
+}
+
+// --------CAP----
+//     |
+//    RES
+//     |
+//    IND
+//     |
+//    GND
+pub fn calc_hp_ell_lc_w_q(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    q: f64,
+    w: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<CLQ>, Error> {
+    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
+        return Ok(vec![matched(zs, q, c_scale, l_scale)]);
+    }
+
+    let rs = zs.re;
+    let xs = zs.im;
+    let rl = zl.re;
+    let xl = zl.im;
+    let d = xs.powi(4) - 4.0 * q * rl * xs.powi(3)
+        + (2.0 * rs.powi(2) + 4.0 * q.powi(2) * rl * rs - 4.0 * rl.powi(2)) * xs.powi(2)
+        + (8.0 * q * rl.powi(2) * rs - 4.0 * q * rl * rs.powi(2)) * xs
+        + rs.powi(4)
+        + 4.0 * q.powi(2) * rl * rs.powi(3)
+        - 4.0 * q.powi(2) * rl.powi(2) * rs.powi(2);
+    let roots = [d.sqrt(), -d.sqrt()].map(|r| {
+        let xp = (q * r - q * xs.powi(2) + 2.0 * q.powi(2) * rl * xs - q * rs.powi(2)
+            + 2.0 * q * rl * rs)
+            / ((2.0 * q.powi(2) + 2.0) * rs + (-(2.0 * q.powi(2)) - 2.0) * rl);
+        let xc = (r - xs.powi(2) - 2.0 * xl * xs + 2.0 * q * rs * xl - rs.powi(2))
+            / (2.0 * xs - 2.0 * q * rs);
+        (xp / w, -1.0 / (w * xc), (xl + xc).abs() / rl)
+    });
+
+    Ok(solutions(roots, q, c_scale, l_scale))
+}
+
+// --------RES--IND----
+//     |
+//    CAP
+//     |
+//    GND
+pub fn calc_lp_ell_cl_w_q(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    q: f64,
+    w: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<CLQ>, Error> {
+    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
+        return Ok(vec![matched(zs, q, c_scale, l_scale)]);
+    }
+
+    let rs = zs.re;
+    let xs = zs.im;
+    let rl = zl.re;
+    let xl = zl.im;
+    let d = xs.powi(4)
+        + (4.0 * q * rs * xl + 2.0 * rs.powi(2) + 4.0 * q.powi(2) * rl * rs) * xs.powi(2)
+        - 4.0 * rs.powi(2) * xl.powi(2)
+        + (4.0 * q * rs.powi(3) - 8.0 * q * rl * rs.powi(2)) * xl
+        + rs.powi(4)
+        + 4.0 * q.powi(2) * rl * rs.powi(3)
+        - 4.0 * q.powi(2) * rl.powi(2) * rs.powi(2);
+    let roots = [d.sqrt(), -d.sqrt()].map(|r| {
+        let xp = (q * r - q * xs.powi(2) - 2.0 * q.powi(2) * rs * xl - q * rs.powi(2)
+            + 2.0 * q * rl * rs)
+            / ((2.0 * q.powi(2) + 2.0) * rs);
+        let xc = (r - xs.powi(2) + (-(2.0 * xl) - 2.0 * q * rl) * xs - rs.powi(2))
+            / (2.0 * xs + 2.0 * xl - 2.0 * q * rs + 2.0 * q * rl);
+        (xp / w, -1.0 / (w * xc), (xl + xp).abs() / (rl - xp / q))
+    });
+
+    Ok(solutions(roots, q, c_scale, l_scale))
+}
+
+// ---RES--IND---------
+//          |
+//         CAP
+//          |
+//         GND
+pub fn calc_lp_ell_lc_w_q(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    q: f64,
+    w: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<CLQ>, Error> {
+    if (zs.re == zl.re) && (zs.im == (-zl.im)) {
+        return Ok(vec![matched(zs, q, c_scale, l_scale)]);
+    }
+
+    let rs = zs.re;
+    let xs = zs.im;
+    let rl = zl.re;
+    let xl = zl.im;
+    let d = -(4.0 * rl.powi(2) * xs.powi(2))
+        + (4.0 * q * rl * xl.powi(2) - 8.0 * q * rl.powi(2) * rs + 4.0 * q * rl.powi(3)) * xs
+        + xl.powi(4)
+        + (4.0 * q.powi(2) * rl * rs + 2.0 * rl.powi(2)) * xl.powi(2)
+        - 4.0 * q.powi(2) * rl.powi(2) * rs.powi(2)
+        + 4.0 * q.powi(2) * rl.powi(3) * rs
+        + rl.powi(4);
+    let roots = [d.sqrt(), -d.sqrt()].map(|r| {
+        let xp = -((q * r + 2.0 * q.powi(2) * rl * xs + q * xl.powi(2) - 2.0 * q * rl * rs
+            + q * rl.powi(2))
+            / ((2.0 * q.powi(2) + 2.0) * rl));
+        let xc = -((r + 2.0 * xl * xs + xl.powi(2) + 2.0 * q * rs * xl + rl.powi(2))
+            / (2.0 * xs + 2.0 * xl + 2.0 * q * rs - 2.0 * q * rl));
+        (xp / w, -1.0 / (w * xc), (xs + xp).abs() / (rs - xp / q))
+    });
+
+    Ok(solutions(roots, q, c_scale, l_scale))
+}
+
+// Zero-element network for terminations that are already conjugate matched
+fn matched(zs: Complex<f64>, q: f64, c_scale: &Unit, l_scale: &Unit) -> CLQ {
+    let q_net = zs.im / zs.re;
+    CLQ {
+        c: 0.0,
+        l: 0.0,
+        q,
+        q_net,
+        bw: bandwidth(q_net),
+        sol: 0,
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        reason: None,
+        balanced: None,
+    }
+}
+
+// Roots given as (inductance, capacitance, node Q) whose elements are both positive, numbered from 1
+// in sol, or a single NaN entry with the reason neither root is realizable
+fn solutions(roots: [(f64, f64, f64); 2], q: f64, c_scale: &Unit, l_scale: &Unit) -> Vec<CLQ> {
+    let clq = |c: f64, l: f64, q_net: f64, sol: usize, reason: Option<Unrealizable>| CLQ {
+        c,
+        l,
+        q,
+        q_net,
+        bw: bandwidth(q_net),
+        sol,
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        reason,
+        balanced: None,
+    };
+
+    let out: Vec<CLQ> = roots
+        .iter()
+        .enumerate()
+        .filter(|(_, (l, c, _))| (*l > 0.0) && (*c > 0.0))
+        .map(|(i, (l, c, q_net))| clq(scale(*c, c_scale), scale(*l, l_scale), *q_net, i + 1, None))
+        .collect();
+    if !out.is_empty() {
+        return out;
+    }
+
+    let (l, c, _) = roots[0];
+    let reason = if l.is_nan() || c.is_nan() {
+        Unrealizable::InductorQ { q }
+    } else {
+        Unrealizable::negative(c, l)
+    };
+    vec![clq(f64::NAN, f64::NAN, f64::NAN, 0, Some(reason))]
+}
@@ -1,11 +1,11 @@
 #![allow(unused)]
-use crate::matching::CCLL;
+use crate::error::Error;
+use crate::matching::{calc_ccll, Section, Side, CCLL};
 use crate::rf_utils::{calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn};
 use crate::unit::{get_unit, Unit, UnitType};
 use float_cmp::F64Margin;
 use num_complex::Complex;
 use std::f64::consts::PI;
-use std::f64::{INFINITY, NAN};
 use std::str::FromStr;
 
 // --------CAP-------CAP--
@@ -19,66 +19,17 @@ pub fn calc_hp1(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CCLL, String> {
-    let mut cs: f64;
-    let mut cl: f64;
-    let mut ls: f64;
-    let mut ll: f64;
-
-    let q = zs.im / zs.re;
-    let rp = (1.0 + q.powi(2)) * zs.re;
-    let rv = (rp * zl.re).sqrt();
-    if rp <= rv {
-        ls = NAN;
-        ll = NAN;
-        cs = NAN;
-        cl = NAN;
-    } else {
-        let qs = (rp / rv - 1.0).sqrt();
-        let ql = (rv / zl.re - 1.0).sqrt();
-        let lp = rp / (w * q);
-        cs = 1.0 / (w * rv * qs);
-        ls = rp / (w * qs);
-        if zs.im != 0.0 {
-            if lp == ls {
-                ls = INFINITY;
-            } else {
-                ls *= lp / (lp - ls);
-            }
-        }
-
-        let c5 = -1.0 / (w * zl.im);
-        ll = rv / (w * ql);
-        cl = 1.0 / (w * zl.re * ql);
-        if zl.im != 0.0 {
-            if c5 == cl {
-                cl = INFINITY;
-            } else {
-                cl *= c5 / (c5 - cl);
-            }
-        }
-
-        cs = scale(cs, c_scale);
-        cl = scale(cl, c_scale);
-        ls = scale(ls, l_scale);
-        ll = scale(ll, l_scale);
-
-        if (cs < 0.0) || (cl < 0.0) || (ls < 0.0) || (ll < 0.0) {
-            cs = NAN;
-            cl = NAN;
-            ls = NAN;
-            ll = NAN;
-        }
-    }
-
-    Ok(CCLL {
-        cs: cs,
-        cl: cl,
-        ls: ls,
-        ll: ll,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+) -> Result<Vec<CCLL>, Error> {
+    Ok(calc_ccll(
+        zs,
+        zl,
+        w,
+        Side::Source,
+        Section::HighPass,
+        Section::HighPass,
+        c_scale,
+        l_scale,
+    ))
 }
 
 // --CAP--------CAP-------
@@ -92,66 +43,17 @@ pub fn calc_hp2(
     w: f64,
     c_scale: &Unit,
     l_scale: &Unit,
-) -> Result<CCLL, String> {
-    let mut cs: f64;
-    let mut cl: f64;
-    let mut ls: f64;
-    let mut ll: f64;
-
-    let q = zl.im / zl.re;
-    let rp = (1.0 + q.powi(2)) * zl.re;
-    let rv = (rp * zs.re).sqrt();
-    if rp <= rv {
-        cs = NAN;
-        cl = NAN;
-        ls = NAN;
-        ll = NAN;
-    } else {
-        let qs = (rp / rv - 1.0).sqrt();
-        let ql = (rv / zs.re - 1.0).sqrt();
-        let lp = rp / (w * q);
-        cs = 1.0 / (w * rv * qs);
-        ls = rp / (w * qs);
-        if zl.im != 0.0 {
-            if lp == ls {
-                ls = INFINITY;
-            } else {
-                ls *= lp / (lp - ls);
-            }
-        }
-
-        let c5 = -1.0 / (w * zs.im);
-        ll = rv / (w * ql);
-        cl = 1.0 / (w * zs.re * ql);
-        if zs.im != 0.0 {
-            if c5 == cl {
-                cl = INFINITY;
-            } else {
-                cl *= c5 / (c5 - cl);
-            }
-        }
-
-        cs = scale(cs, c_scale);
-        cl = scale(cl, c_scale);
-        ls = scale(ls, l_scale);
-        ll = scale(ll, l_scale);
-
-        if (cs < 0.0) || (cl < 0.0) || (ls < 0.0) || (ll < 0.0) {
-            cs = NAN;
-            cl = NAN;
-            ls = NAN;
-            ll = NAN;
-        }
-    }
-
-    Ok(CCLL {
-        cs: cs,
-        cl: cl,
-        ls: ls,
-        ll: ll,
-        c_scale: get_unit(c_scale, &UnitType::Farad),
-        l_scale: get_unit(l_scale, &UnitType::Henry),
-    })
+) -> Result<Vec<CCLL>, Error> {
+    Ok(calc_ccll(
+        zs,
+        zl,
+        w,
+        Side::Load,
+        Section::HighPass,
+        Section::HighPass,
+        c_scale,
+        l_scale,
+    ))
 }
 
 #[cfg(test)]
@@ -168,14 +70,18 @@ mod tests {
         let c_scale = Unit::Femto;
         let l_scale = Unit::Pico;
         let exemplar = CCLL {
-            cs: NAN,
-            cl: NAN,
-            ls: NAN,
-            ll: NAN,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp1(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp1(zs, zl, w, &c_scale, &l_scale).unwrap().remove(0);
         assert!(test.cs.is_nan());
         assert!(test.cl.is_nan());
         assert!(test.ls.is_nan());
@@ -193,10 +99,14 @@ mod tests {
             cl: 20.352712959723295,
             ls: 137.66998607438342,
             ll: 49.4602723641384,
+            q: 1.1329193158451012,
+            bw: 1.7653507818498972,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp1(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp1(zs, zl, w, &c_scale, &l_scale).unwrap().remove(0);
         comp_f64(
             &test.cs,
             &exemplar.cs,
@@ -225,6 +135,20 @@ mod tests {
             "calc_hp1()",
             "ll",
         );
+        comp_f64(
+            &test.q,
+            &exemplar.q,
+            F64Margin::default(),
+            "calc_hp1()",
+            "q",
+        );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp1()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
     }
@@ -241,10 +165,14 @@ mod tests {
             cl: 20.352712959723295,
             ls: 137.66998607438342,
             ll: 49.4602723641384,
+            q: 1.1329193158451012,
+            bw: 1.7653507818498972,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp2(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp2(zs, zl, w, &c_scale, &l_scale).unwrap().remove(0);
         comp_f64(
             &test.cs,
             &exemplar.cs,
@@ -273,6 +201,20 @@ mod tests {
             "calc_hp2()",
             "ll",
         );
+        comp_f64(
+            &test.q,
+            &exemplar.q,
+            F64Margin::default(),
+            "calc_hp2()",
+            "q",
+        );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_hp2()",
+            "bw",
+        );
         assert_eq!(test.c_scale, exemplar.c_scale);
         assert_eq!(test.l_scale, exemplar.l_scale);
 
@@ -282,14 +224,18 @@ mod tests {
         let c_scale = Unit::Femto;
         let l_scale = Unit::Pico;
         let exemplar = CCLL {
-            cs: NAN,
-            cl: NAN,
-            ls: NAN,
-            ll: NAN,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
             c_scale: "fF".to_string(),
             l_scale: "pH".to_string(),
+            reason: None,
+            balanced: None,
         };
-        let test = calc_hp2(zs, zl, w, &c_scale, &l_scale).unwrap();
+        let test = calc_hp2(zs, zl, w, &c_scale, &l_scale).unwrap().remove(0);
         assert!(test.cs.is_nan());
         assert!(test.cl.is_nan());
         assert!(test.ls.is_nan());
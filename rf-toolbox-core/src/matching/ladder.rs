@@ -0,0 +1,583 @@
+use crate::differential::{balance, BalancedElement};
+use crate::error::Error;
+use crate::matching::{
+    bandwidth, ell_roots, terminations, EllRoot, ImpedanceType, Section, Side, Unrealizable,
+};
+use crate::rf_utils::{scale, unscale, ComplexReturn, ZScale};
+use crate::unit::{get_unit, Unit, UnitType};
+use num_complex::Complex;
+use serde::Deserialize;
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Placement {
+    Series,
+    Shunt,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Kind {
+    Capacitor,
+    Inductor,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LadderElement {
+    pub placement: Placement,
+    pub kind: Kind,
+}
+
+impl FromStr for LadderElement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (placement, kind) = match s.to_lowercase().as_str() {
+            "sc" => (Placement::Series, Kind::Capacitor),
+            "sl" => (Placement::Series, Kind::Inductor),
+            "pc" => (Placement::Shunt, Kind::Capacitor),
+            "pl" => (Placement::Shunt, Kind::Inductor),
+            _ => {
+                return Err(Error::Parse(format!(
+                    "ladder element {:?} not recognized",
+                    s
+                )))
+            }
+        };
+        Ok(LadderElement { placement, kind })
+    }
+}
+
+impl fmt::Display for LadderElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let placement = match self.placement {
+            Placement::Series => "s",
+            Placement::Shunt => "p",
+        };
+        let kind = match self.kind {
+            Kind::Capacitor => "C",
+            Kind::Inductor => "L",
+        };
+        write!(f, "{}{}", placement, kind)
+    }
+}
+
+// Ladder of series (s) and shunt (p) capacitors and inductors written from source to load,
+// e.g. "sC-pL-sL-pC". Series and shunt elements must alternate.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Topology {
+    pub elements: Vec<LadderElement>,
+}
+
+impl FromStr for Topology {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s
+            .split('-')
+            .map(|val| val.trim().parse::<LadderElement>())
+            .collect::<Result<Vec<LadderElement>, Error>>()?;
+
+        if elements.len() < 2 {
+            return Err(Error::InvalidInput(format!(
+                "ladder {:?} needs at least two elements",
+                s
+            )));
+        }
+        if let Some(pair) = elements
+            .windows(2)
+            .find(|pair| pair[0].placement == pair[1].placement)
+        {
+            return Err(Error::InvalidInput(format!(
+                "ladder {:?} has adjacent {} and {}, which combine into one element",
+                s, pair[0], pair[1]
+            )));
+        }
+
+        Ok(Topology { elements })
+    }
+}
+
+impl TryFrom<String> for Topology {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let vals = self
+            .elements
+            .iter()
+            .map(|val| val.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", vals.join("-"))
+    }
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct LadderSolution {
+    // element values in topology order, capacitors in c_scale and inductors in l_scale
+    pub vals: Vec<f64>,
+    pub q: f64,
+    pub bw: f64,
+    // balanced form for differential terminations
+    pub balanced: Option<Vec<BalancedElement>>,
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct LadderReturn {
+    pub topology: String,
+    pub zs: ComplexReturn,
+    pub zl: ComplexReturn,
+    pub solutions: Vec<LadderSolution>,
+    pub c_scale: String,
+    pub l_scale: String,
+    pub reason: Option<Unrealizable>,
+}
+
+// Every realizable set of element values for a ladder between zs and zl. The ladder is built as a
+// chain of L-sections on the Smith chart, each matching between two real virtual resistances
+// spaced geometrically between the terminals. An odd ladder is made even by splitting one of its
+// inner elements in two: a split shunt element is the peak of the chain, a split series element
+// its valley, and q_tgt sets its resistance as in the Pi and Tee networks. Every element that can
+// be split is tried and the halves are recombined, so an element only has to be realizable as a
+// whole.
+//
+//  zs ---sC---+---sL---+--- zl      "sC-pL-sL-pC"
+//             |        |
+//             pL       pC
+//             |        |
+//            GND      GND
+pub fn solve_ladder(
+    topology: &Topology,
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    q_tgt: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> LadderReturn {
+    let elements = &topology.elements;
+    let mut out = LadderReturn {
+        topology: topology.to_string(),
+        zs: ComplexReturn {
+            re: zs.re,
+            im: zs.im,
+        },
+        zl: ComplexReturn {
+            re: zl.re,
+            im: zl.im,
+        },
+        solutions: vec![],
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        reason: None,
+    };
+
+    // Element indexes to split; the elements at odd indexes are the ones between two others of the
+    // other placement
+    let splits = if elements.len().is_multiple_of(2) {
+        vec![None]
+    } else {
+        (1..elements.len()).step_by(2).map(Some).collect()
+    };
+
+    for split in splits {
+        // (element index, placement) of each section element, paired source to load
+        let parts = elements
+            .iter()
+            .enumerate()
+            .flat_map(|(i, val)| {
+                let n = if split == Some(i) { 2 } else { 1 };
+                std::iter::repeat_n((i, val.placement), n)
+            })
+            .collect::<Vec<(usize, Placement)>>();
+        let sections = parts.chunks(2).collect::<Vec<_>>();
+        let shunt_first = |j: usize| sections[j][0].1 == Placement::Shunt;
+        let m = sections.len();
+
+        // Real part seen at each terminal, as a parallel resistance where a shunt element sits
+        // across it
+        let r0 = if shunt_first(0) {
+            zs.norm_sqr() / zs.re
+        } else {
+            zs.re
+        };
+        let rm = if shunt_first(m - 1) {
+            zl.re
+        } else {
+            zl.norm_sqr() / zl.re
+        };
+
+        // Section boundary at the split and its resistance
+        let (t, rt) = match split {
+            None => (m, rm),
+            Some(i) => {
+                if q_tgt < 0.0 {
+                    out.reason = Some(Unrealizable::NegativeQ { q: q_tgt });
+                    return out;
+                }
+                let q_min = (r0.max(rm) / r0.min(rm) - 1.0).sqrt();
+                if q_tgt < q_min {
+                    out.reason = Some(Unrealizable::QBelowMinimum { q: q_tgt, q_min });
+                    return out;
+                }
+                let rt = match elements[i].placement {
+                    Placement::Shunt => r0.min(rm) * (q_tgt.powi(2) + 1.0),
+                    Placement::Series => r0.max(rm) / (q_tgt.powi(2) + 1.0),
+                };
+                (i.div_ceil(2), rt)
+            }
+        };
+        let node = |k: usize| -> Complex<f64> {
+            if k == 0 {
+                zs
+            } else if k == m {
+                zl
+            } else if k <= t {
+                Complex::new(r0 * (rt / r0).powf(k as f64 / t as f64), 0.0)
+            } else {
+                Complex::new(rt * (rm / rt).powf((k - t) as f64 / (m - t) as f64), 0.0)
+            }
+        };
+
+        // Both roots of each section, with the shunt element across the node it sits on
+        let mut roots = vec![];
+        for j in 0..m {
+            let (za, zb, side) = if shunt_first(j) {
+                (node(j), node(j + 1), Side::Source)
+            } else {
+                (node(j + 1), node(j), Side::Load)
+            };
+            let val = ell_roots(za, zb, w, Section::LowPass);
+            if val.is_empty() {
+                if out.reason.is_none() {
+                    let rp = za.norm_sqr() / za.re;
+                    out.reason = Some(side.rp_reason(zs, zl, rp));
+                }
+                break;
+            }
+            roots.push(val);
+        }
+        if roots.len() < m {
+            continue;
+        }
+
+        for combo in 0..(1usize << m) {
+            let chosen = (0..m)
+                .map(|j| roots[j][(combo >> j) & 1])
+                .collect::<Vec<EllRoot>>();
+
+            // Split halves recombine as series reactances or shunt susceptances
+            let mut sums = vec![0.0; elements.len()];
+            for (j, section) in sections.iter().enumerate() {
+                for (i, placement) in section.iter() {
+                    sums[*i] += match placement {
+                        Placement::Series => chosen[j].x,
+                        Placement::Shunt => chosen[j].b,
+                    };
+                }
+            }
+            let vals = elements
+                .iter()
+                .zip(sums.iter())
+                .map(|(val, sum)| match (val.placement, val.kind) {
+                    (Placement::Series, Kind::Inductor) => sum / w,
+                    (Placement::Series, Kind::Capacitor) => -1.0 / (w * sum),
+                    (Placement::Shunt, Kind::Capacitor) => sum / w,
+                    (Placement::Shunt, Kind::Inductor) => -1.0 / (w * sum),
+                })
+                .collect::<Vec<f64>>();
+
+            if vals.iter().all(|val| (*val > 0.0) && val.is_finite()) {
+                let q = chosen.iter().fold(0.0, |acc: f64, val| acc.max(val.q));
+                out.solutions.push(LadderSolution {
+                    vals: elements
+                        .iter()
+                        .zip(vals.iter())
+                        .map(|(val, x)| match val.kind {
+                            Kind::Capacitor => scale(*x, c_scale),
+                            Kind::Inductor => scale(*x, l_scale),
+                        })
+                        .collect(),
+                    q,
+                    bw: bandwidth(q),
+                    balanced: None,
+                });
+            } else if out.reason.is_none() {
+                let min = |kind: Kind| {
+                    elements
+                        .iter()
+                        .zip(vals.iter())
+                        .filter(|(val, _)| val.kind == kind)
+                        .fold(f64::INFINITY, |acc, (_, x)| acc.min(*x))
+                };
+                out.reason = Some(Unrealizable::negative(
+                    min(Kind::Capacitor),
+                    min(Kind::Inductor),
+                ));
+            }
+        }
+    }
+
+    if !out.solutions.is_empty() {
+        out.reason = None;
+    }
+    out
+}
+
+pub fn calc_ladder(
+    topology: &Topology,
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+    ind_unit: Unit,
+    z_scale: ZScale,
+) -> Result<LadderReturn, Error> {
+    let w = 2.0 * PI * unscale(freq, &freq_unit);
+    // Differential terminations are matched as entered, between the two legs, as in calc_networks.
+    // The leg values of the balanced form are the half circuit.
+    let (zs, zl) = terminations(rs, xs, rl, xl, imp, z0, w, &cap_unit)?;
+
+    let mut out = solve_ladder(topology, zs, zl, w, q, &cap_unit, &ind_unit);
+    if z_scale == ZScale::Diff {
+        for val in out.solutions.iter_mut() {
+            val.balanced = Some(balance(topology, &val.vals));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::lp::calc_lp2;
+    use crate::matching::pi::calc_pi;
+    use crate::rf_utils::comp_f64;
+    use float_cmp::F64Margin;
+
+    // Impedance looking into the ladder from the source side
+    fn z_in(topology: &Topology, vals: &[f64], zl: Complex<f64>, w: f64) -> Complex<f64> {
+        let mut z = zl;
+        for (val, x) in topology.elements.iter().zip(vals.iter()).rev() {
+            let x = match val.kind {
+                Kind::Capacitor => -1.0 / (w * unscale(*x, &Unit::Femto)),
+                Kind::Inductor => w * unscale(*x, &Unit::Pico),
+            };
+            z = match val.placement {
+                Placement::Series => z + Complex::new(0.0, x),
+                Placement::Shunt => 1.0 / (1.0 / z + 1.0 / Complex::new(0.0, x)),
+            };
+        }
+        z
+    }
+
+    #[test]
+    fn test_topology() {
+        let test = "sC-pL-sl-PC".parse::<Topology>().unwrap();
+        assert_eq!(test.to_string(), "sC-pL-sL-pC");
+        assert_eq!(
+            test.elements[1],
+            LadderElement {
+                placement: Placement::Shunt,
+                kind: Kind::Inductor,
+            }
+        );
+
+        assert_eq!(
+            "sC-sL".parse::<Topology>().unwrap_err().code(),
+            "invalid_input"
+        );
+        assert_eq!(
+            "pC".parse::<Topology>().unwrap_err().code(),
+            "invalid_input"
+        );
+        assert_eq!("sC-pR".parse::<Topology>().unwrap_err().code(), "parse");
+        let test: Topology = serde_json::from_str("\"pC-sL-pC\"").unwrap();
+        assert_eq!(test.elements.len(), 3);
+    }
+
+    #[test]
+    fn test_solve_ladder_pi() {
+        let zs = Complex::new(50.0, 0.0);
+        let zl = Complex::new(200.0, 0.0);
+        let w = 2.0 * PI * 1.0e9;
+        let q = 3.0;
+        let c_scale = Unit::Pico;
+        let l_scale = Unit::Nano;
+        let exemplar = calc_pi(zs, zl, w, q, &c_scale, &l_scale).unwrap().remove(0);
+        let topology = "pC-sL-pC".parse::<Topology>().unwrap();
+        let test = solve_ladder(&topology, zs, zl, w, q, &c_scale, &l_scale);
+        assert_eq!(test.solutions.len(), 1);
+        assert_eq!(test.c_scale, "pF");
+        assert_eq!(test.l_scale, "nH");
+        let vals = &test.solutions[0].vals;
+        comp_f64(
+            &vals[0],
+            &exemplar.cs,
+            F64Margin::default(),
+            "solve_ladder()",
+            "cs",
+        );
+        comp_f64(
+            &vals[1],
+            &exemplar.l,
+            F64Margin::default(),
+            "solve_ladder()",
+            "l",
+        );
+        comp_f64(
+            &vals[2],
+            &exemplar.cl,
+            F64Margin::default(),
+            "solve_ladder()",
+            "cl",
+        );
+        comp_f64(
+            &test.solutions[0].q,
+            &exemplar.q,
+            F64Margin::default(),
+            "solve_ladder()",
+            "q",
+        );
+
+        let test = solve_ladder(&topology, zs, zl, w, 1.5, &c_scale, &l_scale);
+        assert!(test.solutions.is_empty());
+        assert_eq!(test.reason.unwrap().code(), "q_below_minimum");
+    }
+
+    #[test]
+    fn test_solve_ladder_lp2() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = calc_lp2(zs, zl, w, &c_scale, &l_scale).unwrap().remove(0);
+        let topology = "sL-pC-sL-pC".parse::<Topology>().unwrap();
+        let test = solve_ladder(&topology, zs, zl, w, 0.0, &c_scale, &l_scale);
+        assert_eq!(test.solutions.len(), 1);
+        let vals = &test.solutions[0].vals;
+        comp_f64(
+            &vals[0],
+            &exemplar.ll,
+            F64Margin::default(),
+            "solve_ladder()",
+            "ll",
+        );
+        comp_f64(
+            &vals[1],
+            &exemplar.cl,
+            F64Margin::default(),
+            "solve_ladder()",
+            "cl",
+        );
+        comp_f64(
+            &vals[2],
+            &exemplar.ls,
+            F64Margin::default(),
+            "solve_ladder()",
+            "ls",
+        );
+        comp_f64(
+            &vals[3],
+            &exemplar.cs,
+            F64Margin::default(),
+            "solve_ladder()",
+            "cs",
+        );
+        comp_f64(
+            &test.solutions[0].bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "solve_ladder()",
+            "bw",
+        );
+
+        let test = solve_ladder(&topology, zl, zs, w, 0.0, &c_scale, &l_scale);
+        assert!(test.solutions.is_empty());
+        assert_eq!(test.reason.unwrap().code(), "source_exceeds_load_parallel");
+    }
+
+    #[test]
+    fn test_solve_ladder_conjugate_match() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        for topology in ["sC-pL-sL-pC-sC", "sC-pL-sC-pL-sC-pL", "sL-pC-sC-pL-sL"] {
+            let topology = topology.parse::<Topology>().unwrap();
+            let test = solve_ladder(&topology, zs, zl, w, 3.0, &c_scale, &l_scale);
+            assert!(!test.solutions.is_empty(), "{}", topology);
+            for val in test.solutions.iter() {
+                let z = z_in(&topology, &val.vals, zl, w);
+                comp_f64(&z.re, &zs.re, margin, "solve_ladder()", "re");
+                comp_f64(&z.im, &(-zs.im), margin, "solve_ladder()", "im");
+            }
+        }
+    }
+
+    #[test]
+    fn test_calc_ladder_diff() {
+        // The legs of a balanced ladder between differential impedances are the single-ended
+        // ladder between half of them
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let topology = "sL-pC-sL-pC".parse::<Topology>().unwrap();
+        let exemplar = solve_ladder(&topology, zs, zl, w, 0.0, &Unit::Femto, &Unit::Pico);
+        let test = calc_ladder(
+            &topology,
+            2.0 * zs.re,
+            2.0 * zs.im,
+            2.0 * zl.re,
+            2.0 * zl.im,
+            ImpedanceType::ZReIm,
+            0.0,
+            50.0,
+            275.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+            ZScale::Diff,
+        )
+        .unwrap();
+        assert_eq!(test.solutions.len(), exemplar.solutions.len());
+        let balanced = test.solutions[0].balanced.as_ref().unwrap();
+        for (i, val) in balanced.iter().enumerate() {
+            comp_f64(
+                &val.leg,
+                &exemplar.solutions[0].vals[i],
+                F64Margin::default(),
+                "calc_ladder()",
+                &val.element,
+            );
+        }
+        comp_f64(
+            &balanced[1].floating,
+            &test.solutions[0].vals[1],
+            F64Margin::default(),
+            "calc_ladder()",
+            "floating",
+        );
+        assert!(balanced[0].floating.is_nan());
+        assert!(exemplar.solutions[0].balanced.is_none());
+    }
+}
@@ -0,0 +1,326 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::matching::{bandwidth, PiTee, Unrealizable};
+use crate::rf_utils::{calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn};
+use crate::unit::{get_unit, Unit, UnitType};
+use float_cmp::F64Margin;
+use num_complex::Complex;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+pub fn calc_pi(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    q_tgt: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<PiTee>, Error> {
+    if q_tgt < 0.0 {
+        return Ok(vec![PiTee::unrealizable(
+            Unrealizable::NegativeQ { q: q_tgt },
+            c_scale,
+            l_scale,
+        )]);
+    }
+    if (q_tgt == 0.0) && (zs.re == zl.re) {
+        return Ok(vec![PiTee::through(c_scale, l_scale)]);
+    }
+
+    let r_min = zs.re.min(zl.re);
+    let r_max = zs.re.max(zl.re);
+    let q_min = (r_max / r_min - 1.0).sqrt();
+    let mut out = vec![];
+    if q_tgt < q_min {
+        out.push(PiTee::unrealizable(
+            Unrealizable::QBelowMinimum { q: q_tgt, q_min },
+            c_scale,
+            l_scale,
+        ));
+    } else {
+        out.push(pi_network(zs, zl, w, q_tgt, c_scale, l_scale));
+    }
+    // The Q target sets the virtual resistance from the larger termination. Setting it from the
+    // smaller one instead gives a second network with a higher Q.
+    if (q_tgt > 0.0) && (r_min < r_max) {
+        let q = (r_max * (q_tgt.powi(2) + 1.0) / r_min - 1.0).sqrt();
+        out.push(pi_network(zs, zl, w, q, c_scale, l_scale));
+    }
+
+    Ok(out)
+}
+
+// Pi networks with node Q q
+fn pi_network(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    q: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> PiTee {
+    let mut c_reason = None;
+    let mut l_reason = None;
+    let rv = zs.re.max(zl.re) / (q.powi(2) + 1.0);
+
+    let qs = -zs.im / zs.re;
+    let ql = -zl.im / zl.re;
+    let rps = zs.re * (1.0 + qs.powi(2));
+    let rpl = zl.re * (1.0 + ql.powi(2));
+
+    //cs-l-cl pi network matching
+    let cps = qs / (rps * w);
+    let cpl = ql / (rpl * w);
+    let mut qx = (rps / rv - 1.0).sqrt();
+    let mut cs = qx / (w * rps) - cps;
+    let l5 = qx * rv / w;
+    qx = (rpl / rv - 1.0).sqrt();
+    let mut cl = qx / (w * rpl) - cpl;
+    let mut l = l5 + (qx * rv / w);
+
+    //ls-c-ll pi network matching
+    qx = (rps / rv - 1.0).sqrt();
+    let mut ls = rps / (w * qx);
+    if qs != 0.0 {
+        let lps = rps / (qs * w);
+        ls *= lps / (ls - lps);
+    }
+    let c5 = 1.0 / (w * qx * rv);
+    qx = (rpl / rv - 1.0).sqrt();
+    let mut ll = rpl / (w * qx);
+    if ql != 0.0 {
+        let lpl = rpl / (ql * w);
+        ll *= lpl / (ll - lpl);
+    }
+    let c1 = 1.0 / (w * qx * rv);
+    let mut c = c1 * c5 / (c1 + c5);
+
+    c = scale(c, c_scale);
+    cs = scale(cs, c_scale);
+    cl = scale(cl, c_scale);
+    l = scale(l, l_scale);
+    ls = scale(ls, l_scale);
+    ll = scale(ll, l_scale);
+
+    if (c < 0.0) || (ls < 0.0) || (ll < 0.0) {
+        c_reason = Some(Unrealizable::negative(c, ls.min(ll)));
+        c = f64::NAN;
+        ls = f64::NAN;
+        ll = f64::NAN;
+    }
+    if (l < 0.0) || (cs < 0.0) || (cl < 0.0) {
+        l_reason = Some(Unrealizable::negative(cs.min(cl), l));
+        l = f64::NAN;
+        cs = f64::NAN;
+        cl = f64::NAN;
+    }
+
+    PiTee {
+        c,
+        cs,
+        cl,
+        l,
+        ls,
+        ll,
+        q,
+        bw: bandwidth(q),
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        c_reason,
+        l_reason,
+        c_balanced: None,
+        l_balanced: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_calc_pi() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 4.32;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: 8.435997609374349,
+            cs: 16.62637373190316,
+            cl: 12.08508737222243,
+            l: 39.704380813877926,
+            ls: -20.145466896660622,
+            ll: -27.71565081088584,
+            q: 4.32,
+            bw: 0.4629629629629629,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_pi(zs, zl, w, q, &c_scale, &l_scale).unwrap().remove(0);
+        assert!(test.c.is_nan());
+        comp_f64(
+            &test.cs,
+            &exemplar.cs,
+            F64Margin::default(),
+            "calc_pi()",
+            "cs",
+        );
+        comp_f64(
+            &test.cl,
+            &exemplar.cl,
+            F64Margin::default(),
+            "calc_pi()",
+            "cl",
+        );
+        comp_f64(&test.l, &exemplar.l, F64Margin::default(), "calc_pi()", "l");
+        assert!(test.ls.is_nan());
+        assert!(test.ll.is_nan());
+        comp_f64(&test.q, &exemplar.q, F64Margin::default(), "calc_pi()", "q");
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_pi()",
+            "bw",
+        );
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+
+        let zs = Complex::new(212.3, 43.2);
+        let zl = Complex::new(42.4, -19.6);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 1.99;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: f64::NAN,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            l: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_pi(zs, zl, w, q, &c_scale, &l_scale).unwrap().remove(0);
+        assert!(test.c.is_nan());
+        assert!(test.cs.is_nan());
+        assert!(test.cl.is_nan());
+        assert!(test.l.is_nan());
+        assert!(test.ls.is_nan());
+        assert!(test.ll.is_nan());
+        assert!(test.q.is_nan());
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+
+        let zs = Complex::new(42.4, 0.0);
+        let zl = Complex::new(212.3, 0.0);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 3.88;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: 8.157016433395613,
+            cs: 20.27486954435912,
+            cl: 10.57716232084193,
+            l: 41.06232522178837,
+            ls: 16.520257301519933,
+            ll: 31.666911357459586,
+            q: 3.88,
+            bw: 0.5154639175257733,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_pi(zs, zl, w, q, &c_scale, &l_scale).unwrap().remove(0);
+        comp_f64(&test.c, &exemplar.c, F64Margin::default(), "calc_pi()", "c");
+        comp_f64(
+            &test.cs,
+            &exemplar.cs,
+            F64Margin::default(),
+            "calc_pi()",
+            "cs",
+        );
+        comp_f64(
+            &test.cl,
+            &exemplar.cl,
+            F64Margin::default(),
+            "calc_pi()",
+            "cl",
+        );
+        comp_f64(&test.l, &exemplar.l, F64Margin::default(), "calc_pi()", "l");
+        comp_f64(
+            &test.ls,
+            &exemplar.ls,
+            F64Margin::default(),
+            "calc_pi()",
+            "ls",
+        );
+        comp_f64(
+            &test.ll,
+            &exemplar.ll,
+            F64Margin::default(),
+            "calc_pi()",
+            "ll",
+        );
+        comp_f64(&test.q, &exemplar.q, F64Margin::default(), "calc_pi()", "q");
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_pi()",
+            "bw",
+        );
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+    }
+
+    #[test]
+    fn test_calc_pi_second_solution() {
+        let zs = Complex::new(42.4, 0.0);
+        let zl = Complex::new(212.3, 0.0);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 3.88;
+        let test = calc_pi(zs, zl, w, q, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 2);
+        let test = &test[1];
+        let q_alt = (212.3 * (q * q + 1.0) / 42.4 - 1.0).sqrt();
+        comp_f64(&test.q, &q_alt, F64Margin::default(), "calc_pi()", "q");
+        comp_f64(
+            &test.bw,
+            &(2.0 / q_alt),
+            F64Margin::default(),
+            "calc_pi()",
+            "bw",
+        );
+
+        // Both networks still present the conjugate of the source to the load
+        let margin = F64Margin::from((1e-12, 4));
+        let cap = |c: f64| Complex::new(0.0, -1.0 / (w * c * 1e-15));
+        let ind = |l: f64| Complex::new(0.0, w * l * 1e-12);
+        let par = |a: Complex<f64>, b: Complex<f64>| a * b / (a + b);
+        let z = par(par(zl, cap(test.cl)) + ind(test.l), cap(test.cs));
+        comp_c64(&z, &zs.conj(), margin, "calc_pi()", "cs-l-cl");
+        let z = par(par(zl, ind(test.ll)) + cap(test.c), ind(test.ls));
+        comp_c64(&z, &zs.conj(), margin, "calc_pi()", "ls-c-ll");
+
+        let test = calc_pi(zs, zs, w, q, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 1);
+    }
+}
@@ -0,0 +1,409 @@
+#![allow(unused)]
+use crate::error::Error;
+use crate::matching::{bandwidth, PiTee, Unrealizable};
+use crate::rf_utils::{calc_gamma, calc_rc, calc_z, scale, unscale, Complex2Return, ComplexReturn};
+use crate::unit::{get_unit, Unit, UnitType};
+use float_cmp::F64Margin;
+use num_complex::Complex;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+pub fn calc_tee(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    q_tgt: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> Result<Vec<PiTee>, Error> {
+    if q_tgt < 0.0 {
+        return Ok(vec![PiTee::unrealizable(
+            Unrealizable::NegativeQ { q: q_tgt },
+            c_scale,
+            l_scale,
+        )]);
+    }
+    if (q_tgt == 0.0) && (zs.re == zl.re) {
+        return Ok(vec![PiTee::through(c_scale, l_scale)]);
+    }
+
+    let r_min = zs.re.min(zl.re);
+    let r_max = zs.re.max(zl.re);
+    let q_min = (r_max / r_min - 1.0).sqrt();
+    let mut out = vec![];
+    if q_tgt < q_min {
+        out.push(PiTee::unrealizable(
+            Unrealizable::QBelowMinimum { q: q_tgt, q_min },
+            c_scale,
+            l_scale,
+        ));
+    } else {
+        out.push(tee_network(zs, zl, w, q_tgt, c_scale, l_scale));
+    }
+    // The Q target sets the virtual resistance from the smaller termination. Setting it from the
+    // larger one instead gives a second network with a higher Q.
+    if (q_tgt > 0.0) && (r_min < r_max) {
+        let q = (r_max * (q_tgt.powi(2) + 1.0) / r_min - 1.0).sqrt();
+        out.push(tee_network(zs, zl, w, q, c_scale, l_scale));
+    }
+
+    Ok(out)
+}
+
+// Tee networks with node Q q
+fn tee_network(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    q: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> PiTee {
+    let mut c_reason = None;
+    let mut l_reason = None;
+    let rv = zs.re.min(zl.re) * (q.powi(2) + 1.0);
+
+    let mut qx = (rv / zs.re - 1.0).sqrt();
+    let mut cs = 1.0 / (w * zs.re * qx);
+    if zs.im != 0.0 {
+        if cs == -1.0 / (w * zs.im) {
+            cs = f64::INFINITY;
+        } else {
+            cs *= -1.0 / (w * zs.im) / (cs + 1.0 / (w * zs.im));
+        }
+    }
+
+    let l5 = rv / (w * qx);
+    qx = (rv / zl.re - 1.0).sqrt();
+    let mut cl = 1.0 / (w * zl.re * qx);
+    if zl.im != 0.0 {
+        if cl == -1.0 / (w * zs.im) {
+            cl = f64::INFINITY;
+        } else {
+            cl *= -1.0 / (w * zs.im) / (cl + 1.0 / (w * zs.im));
+        }
+    }
+
+    let l1 = rv / (w * qx);
+    let mut l = l1 * l5 / (l1 + l5);
+
+    qx = (rv / zs.re - 1.0).sqrt();
+    let mut ls = qx * zs.re / w - zs.im / w;
+    let c5 = qx / (w * rv);
+    qx = (rv / zl.re - 1.0).sqrt();
+    let mut ll = qx * zl.re / w - zl.im / w;
+    let mut c = c5 + (qx / (w * rv));
+
+    c = scale(c, c_scale);
+    cs = scale(cs, c_scale);
+    cl = scale(cl, c_scale);
+    l = scale(l, l_scale);
+    ls = scale(ls, l_scale);
+    ll = scale(ll, l_scale);
+
+    if (c < 0.0) || (ls < 0.0) || (ll < 0.0) {
+        c_reason = Some(Unrealizable::negative(c, ls.min(ll)));
+        c = f64::NAN;
+        ls = f64::NAN;
+        ll = f64::NAN;
+    }
+    if (l < 0.0) || (cs < 0.0) || (cl < 0.0) || (cs == f64::INFINITY) || (cl == f64::INFINITY) {
+        l_reason = Some(Unrealizable::negative(cs.min(cl), l));
+        l = f64::NAN;
+        cs = f64::NAN;
+        cl = f64::NAN;
+    }
+
+    PiTee {
+        c,
+        cs,
+        cl,
+        l,
+        ls,
+        ll,
+        q,
+        bw: bandwidth(q),
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        c_reason,
+        l_reason,
+        c_balanced: None,
+        l_balanced: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_calc_tee() {
+        let zs = Complex::new(42.4, -19.6);
+        let zl = Complex::new(212.3, 43.2);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 4.32;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: 4.186603177852454,
+            cs: -3.5382547173462546,
+            cl: -1.6843173326757916,
+            l: 80.00425342422247,
+            ls: 117.35101636675431,
+            ll: 185.20322518485523,
+            q: 4.32,
+            bw: 0.4629629629629629,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_tee(zs, zl, w, q, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
+        comp_f64(
+            &test.c,
+            &exemplar.c,
+            F64Margin::default(),
+            "calc_tee()",
+            "c",
+        );
+        assert!(test.cs.is_nan());
+        assert!(test.cl.is_nan());
+        assert!(test.l.is_nan());
+        comp_f64(
+            &test.ls,
+            &exemplar.ls,
+            F64Margin::default(),
+            "calc_tee()",
+            "ls",
+        );
+        comp_f64(
+            &test.ll,
+            &exemplar.ll,
+            F64Margin::default(),
+            "calc_tee()",
+            "ll",
+        );
+        comp_f64(
+            &test.q,
+            &exemplar.q,
+            F64Margin::default(),
+            "calc_tee()",
+            "q",
+        );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_tee()",
+            "bw",
+        );
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+
+        let zs = Complex::new(212.3, 43.2);
+        let zl = Complex::new(42.4, -19.6);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 1.99;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: f64::NAN,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            l: f64::NAN,
+            ls: f64::NAN,
+            ll: f64::NAN,
+            q: f64::NAN,
+            bw: f64::NAN,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_tee(zs, zl, w, q, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
+        assert!(test.c.is_nan());
+        assert!(test.cs.is_nan());
+        assert!(test.cl.is_nan());
+        assert!(test.l.is_nan());
+        assert!(test.ls.is_nan());
+        assert!(test.ll.is_nan());
+        assert!(test.q.is_nan());
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+
+        let zs = Complex::new(212.3, -363.20820041403255);
+        let zl = Complex::new(42.4, -19.6);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 4.32;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: 4.186603177852454,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            l: f64::NAN,
+            ls: 420.4100397629459,
+            ll: 117.35101636675431,
+            q: 4.32,
+            bw: 0.4629629629629629,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_tee(zs, zl, w, q, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
+        comp_f64(
+            &test.c,
+            &exemplar.c,
+            F64Margin::default(),
+            "calc_tee()",
+            "c",
+        );
+        assert!(test.cs.is_nan());
+        assert!(test.cl.is_nan());
+        assert!(test.l.is_nan());
+        comp_f64(
+            &test.ls,
+            &exemplar.ls,
+            F64Margin::default(),
+            "calc_tee()",
+            "ls",
+        );
+        comp_f64(
+            &test.ll,
+            &exemplar.ll,
+            F64Margin::default(),
+            "calc_tee()",
+            "ll",
+        );
+        comp_f64(
+            &test.q,
+            &exemplar.q,
+            F64Margin::default(),
+            "calc_tee()",
+            "q",
+        );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_tee()",
+            "bw",
+        );
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+
+        let zs = Complex::new(212.3, -183.168);
+        let zl = Complex::new(42.4, -19.6);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 4.32;
+        let c_scale = Unit::Femto;
+        let l_scale = Unit::Pico;
+        let exemplar = PiTee {
+            c: 4.186603177852454,
+            cs: f64::NAN,
+            cl: f64::NAN,
+            l: f64::NAN,
+            ls: 316.2126293951322,
+            ll: 117.35101636675431,
+            q: 4.32,
+            bw: 0.4629629629629629,
+            c_scale: "fF".to_string(),
+            l_scale: "pH".to_string(),
+            c_reason: None,
+            l_reason: None,
+            c_balanced: None,
+            l_balanced: None,
+        };
+        let test = calc_tee(zs, zl, w, q, &c_scale, &l_scale)
+            .unwrap()
+            .remove(0);
+        comp_f64(
+            &test.c,
+            &exemplar.c,
+            F64Margin::default(),
+            "calc_tee()",
+            "c",
+        );
+        assert!(test.cs.is_nan());
+        assert!(test.cl.is_nan());
+        assert!(test.l.is_nan());
+        comp_f64(
+            &test.ls,
+            &exemplar.ls,
+            F64Margin::default(),
+            "calc_tee()",
+            "ls",
+        );
+        comp_f64(
+            &test.ll,
+            &exemplar.ll,
+            F64Margin::default(),
+            "calc_tee()",
+            "ll",
+        );
+        comp_f64(
+            &test.q,
+            &exemplar.q,
+            F64Margin::default(),
+            "calc_tee()",
+            "q",
+        );
+        comp_f64(
+            &test.bw,
+            &exemplar.bw,
+            F64Margin::default(),
+            "calc_tee()",
+            "bw",
+        );
+        assert_eq!(test.c_scale, exemplar.c_scale);
+        assert_eq!(test.l_scale, exemplar.l_scale);
+    }
+
+    #[test]
+    fn test_calc_tee_second_solution() {
+        let zs = Complex::new(42.4, 0.0);
+        let zl = Complex::new(212.3, 0.0);
+        let w = 2.0 * PI * 275.0e9;
+        let q = 3.88;
+        let test = calc_tee(zs, zl, w, q, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 2);
+        let test = &test[1];
+        let q_alt = (212.3 * (q * q + 1.0) / 42.4 - 1.0).sqrt();
+        comp_f64(&test.q, &q_alt, F64Margin::default(), "calc_tee()", "q");
+        comp_f64(
+            &test.bw,
+            &(2.0 / q_alt),
+            F64Margin::default(),
+            "calc_tee()",
+            "bw",
+        );
+
+        // Both networks still present the conjugate of the source to the load
+        let margin = F64Margin::from((1e-12, 4));
+        let cap = |c: f64| Complex::new(0.0, -1.0 / (w * c * 1e-15));
+        let ind = |l: f64| Complex::new(0.0, w * l * 1e-12);
+        let par = |a: Complex<f64>, b: Complex<f64>| a * b / (a + b);
+        let z = par(zl + cap(test.cl), ind(test.l)) + cap(test.cs);
+        comp_c64(&z, &zs.conj(), margin, "calc_tee()", "cs-l-cl");
+        let z = par(zl + ind(test.ll), cap(test.c)) + ind(test.ls);
+        comp_c64(&z, &zs.conj(), margin, "calc_tee()", "ls-c-ll");
+
+        let test = calc_tee(zs, zs, w, q, &Unit::Femto, &Unit::Pico).unwrap();
+        assert_eq!(test.len(), 1);
+    }
+}
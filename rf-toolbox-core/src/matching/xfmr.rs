@@ -0,0 +1,467 @@
+use crate::element::{capacitor::Capacitor, transformer::Transformer, Element, Orientation};
+use crate::error::Error;
+use crate::frequency::Frequency;
+use crate::matching::{bandwidth, terminations, ImpedanceType, Unrealizable};
+use crate::response::calc_abcd;
+use crate::rf_utils::{scale, unscale, ComplexReturn, ZScale};
+use crate::unit::{get_unit, Unit, UnitType};
+use num_complex::Complex;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct XfmrSolution {
+    // cs across the primary at the source, cl across the secondary at the load, in c_scale
+    pub cs: f64,
+    pub cl: f64,
+    // primary and secondary inductance in l_scale
+    pub lp: f64,
+    pub ls: f64,
+    pub q: f64,
+    pub bw: f64,
+    // power reaching the load over the power into the network
+    pub efficiency: f64,
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct XfmrReturn {
+    pub zs: ComplexReturn,
+    pub zl: ComplexReturn,
+    pub k: f64,
+    pub q_ind: f64,
+    pub solutions: Vec<XfmrSolution>,
+    pub c_scale: String,
+    pub l_scale: String,
+    pub reason: Option<Unrealizable>,
+}
+
+impl XfmrReturn {
+    // Cs, the transformer and Cl from source to load, ready for calc_abcd. The primary is at the
+    // source, so the transformer goes into a Smith chart schematic reversed. A capacitor solved
+    // to 0 is left out, as its open circuit would read as an infinite impedance.
+    pub fn elements(&self, solution: usize) -> Result<Vec<Box<dyn Element>>, Error> {
+        let val = self.solutions.get(solution).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "solution {} out of range, {} found",
+                solution,
+                self.solutions.len()
+            ))
+        })?;
+        let c_scale = Unit::from_str(&self.c_scale)?;
+        let l_scale = Unit::from_str(&self.l_scale)?;
+        let cap = |c: f64| Capacitor::new(0.0, c, Unit::Q, c_scale, 0.0, 0.0, Orientation::Shunt);
+        let xfmr = Transformer::new(
+            self.q_ind,
+            val.lp,
+            val.ls,
+            self.k,
+            Unit::Q,
+            l_scale,
+            l_scale,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        let mut out: Vec<Box<dyn Element>> = Vec::new();
+        if val.cs > 0.0 && val.cs.is_finite() {
+            out.push(Box::new(cap(val.cs)));
+        }
+        out.push(Box::new(xfmr));
+        if val.cl > 0.0 && val.cl.is_finite() {
+            out.push(Box::new(cap(val.cl)));
+        }
+        Ok(out)
+    }
+}
+
+// Admittance into the primary of a transformer with the secondary across yl
+fn y_primary(xfmr: &Transformer, w: f64, yl: Complex<f64>) -> Complex<f64> {
+    xfmr.z_primary(Frequency::new(w / (2.0 * PI), Unit::Base), yl.inv())
+        .inv()
+}
+
+// A transformer of coupling k tuned by a shunt capacitor across each winding. Split into its
+// leakage, magnetizing inductance and ideal transformer, the ratio takes the source parallel
+// resistance to the load parallel resistance and the leakage and shunt susceptances form a CLC
+// Pi network of loaded Q q_net between the two. Lossy windings of Q q_ind keep the inductances
+// and retune the capacitors, as long as the windings still leave enough resistance to match.
+//
+//  zs ---+--+      +--+--- zl
+//        |   )|  |(   |
+//        cs  )|k |(   cl
+//        |   )|  |(   |
+//       GND  GND GND  GND
+//             lp  ls
+pub fn solve_xfmr(
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    w: f64,
+    k: f64,
+    q_net: f64,
+    q_ind: f64,
+    c_scale: &Unit,
+    l_scale: &Unit,
+) -> XfmrReturn {
+    let mut out = XfmrReturn {
+        zs: ComplexReturn {
+            re: zs.re,
+            im: zs.im,
+        },
+        zl: ComplexReturn {
+            re: zl.re,
+            im: zl.im,
+        },
+        k,
+        q_ind,
+        solutions: vec![],
+        c_scale: get_unit(c_scale, &UnitType::Farad),
+        l_scale: get_unit(l_scale, &UnitType::Henry),
+        reason: None,
+    };
+    if q_net < 0.0 {
+        out.reason = Some(Unrealizable::NegativeQ { q: q_net });
+        return out;
+    }
+
+    let (ys, yl) = (zs.inv(), zl.inv());
+    let (rs, rl) = (1.0 / ys.re, 1.0 / yl.re);
+    // Ideal transformer ratio and the virtual resistance in the middle of the Pi
+    let n2 = rl / rs;
+    let rv = rs / (q_net.powi(2) + 1.0);
+    let lp = 2.0 * q_net * rv / (w * (1.0 - k.powi(2)));
+    let ls = n2 * k.powi(2) * lp;
+    let b = q_net / rs;
+    let mut cs = (b - ys.im) / w;
+    let mut cl = ((b + 1.0 / (w * k.powi(2) * lp)) / n2 - yl.im) / w;
+
+    if cs < 0.0 {
+        // The source susceptance alone is more than the Pi allows
+        out.reason = Some(Unrealizable::QBelowMinimum {
+            q: q_net,
+            q_min: ys.im * rs,
+        });
+        return out;
+    }
+    if !(cl >= 0.0 && lp > 0.0 && lp.is_finite()) {
+        out.reason = Some(Unrealizable::negative(cl, lp));
+        return out;
+    }
+
+    let xfmr = Transformer::new(
+        q_ind,
+        lp,
+        ls,
+        k,
+        Unit::Q,
+        Unit::Base,
+        Unit::Base,
+        Unit::K,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+    );
+    if q_ind > 0.0 {
+        // The real part into the primary depends on cl alone, cs then cancels what is left
+        let g = |cl: f64| y_primary(&xfmr, w, yl + Complex::new(0.0, w * cl)).re - ys.re;
+        let mut matched = false;
+        for _ in 0..50 {
+            let step = 1e-6 * cl.abs().max(1e-18);
+            let slope = (g(cl + step) - g(cl - step)) / (2.0 * step);
+            let next = cl - g(cl) / slope;
+            if !next.is_finite() {
+                break;
+            }
+            let done = (next - cl).abs() <= 1e-12 * cl.abs();
+            cl = next;
+            if done {
+                matched = g(cl).abs() <= 1e-9 * ys.re;
+                break;
+            }
+        }
+        if matched {
+            cs = (-ys.im - y_primary(&xfmr, w, yl + Complex::new(0.0, w * cl)).im) / w;
+        }
+        // Starting from the lossless tuning finds the nearer of the two roots, but both can
+        // leave a negative capacitor
+        if !matched || cs < 0.0 || cl < 0.0 {
+            out.reason = Some(Unrealizable::InductorQ { q: q_ind });
+            return out;
+        }
+    }
+
+    out.solutions.push(XfmrSolution {
+        cs: scale(cs, c_scale),
+        cl: scale(cl, c_scale),
+        lp: scale(lp, l_scale),
+        ls: scale(ls, l_scale),
+        q: q_net,
+        bw: bandwidth(q_net),
+        efficiency: 1.0,
+    });
+    if let Ok(elements) = out.elements(0) {
+        let chain = elements.iter().map(|val| val.as_ref()).collect::<Vec<_>>();
+        let [[a, b], [c, d]] = calc_abcd(&chain, Frequency::new(w / (2.0 * PI), Unit::Base));
+        // Unit current into the load
+        let (v2, i2) = (zl, Complex::ONE);
+        let (v1, i1) = (a * v2 + b * i2, c * v2 + d * i2);
+        out.solutions[0].efficiency = zl.re / (v1 * i1.conj()).re;
+    }
+    out
+}
+
+pub fn calc_xfmr(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    k: f64,
+    q_net: f64,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    cap_unit: Unit,
+    ind_unit: Unit,
+) -> Result<XfmrReturn, Error> {
+    if !(k > 0.0 && k < 1.0) {
+        return Err(Error::NonPhysical(format!(
+            "coupling factor must be between 0 and 1, got {}",
+            k
+        )));
+    }
+    if q_net == 0.0 {
+        return Err(Error::InvalidInput(
+            "network Q must be above 0 to absorb the leakage inductance".to_string(),
+        ));
+    }
+    if q < 0.0 {
+        return Err(Error::InvalidInput(format!(
+            "inductor Q must be positive or 0 for lossless windings, got {}",
+            q
+        )));
+    }
+    let w = 2.0 * PI * unscale(freq, &freq_unit);
+    // Each winding can take a differential termination as it is, so none are halved
+    let (zs, zl) = terminations(rs, xs, rl, xl, imp, z0, w, &cap_unit)?;
+    Ok(solve_xfmr(zs, zl, w, k, q_net, q, &cap_unit, &ind_unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    // Impedance into the network from the source with the load in place
+    fn z_in(out: &XfmrReturn, zl: Complex<f64>, freq: Frequency) -> Complex<f64> {
+        let elements = out.elements(0).unwrap();
+        let chain = elements.iter().map(|val| val.as_ref()).collect::<Vec<_>>();
+        let [[a, b], [c, d]] = calc_abcd(&chain, freq);
+        (a * zl + b) / (c * zl + d)
+    }
+
+    #[test]
+    fn test_calc_xfmr() {
+        let freq = Frequency::new(60.0, Unit::Giga);
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+
+        let zs = Complex::new(12.0, 20.0);
+        let zl = Complex::new(50.0, 0.0);
+        let test = calc_xfmr(
+            zs.re,
+            zs.im,
+            zl.re,
+            zl.im,
+            ImpedanceType::ZReIm,
+            0.7,
+            2.0,
+            0.0,
+            50.0,
+            60.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        assert_eq!(test.reason, None);
+        assert_eq!(test.solutions.len(), 1);
+        let val = &test.solutions[0];
+        comp_c64(
+            &z_in(&test, zl, freq),
+            &zs.conj(),
+            margin,
+            "calc_xfmr()",
+            "zin",
+        );
+        comp_f64(&val.efficiency, &1.0, margin, "calc_xfmr()", "efficiency");
+        comp_f64(&val.bw, &1.0, margin, "calc_xfmr()", "bw");
+        // The ratio carries the whole transformation between the parallel resistances
+        let (lp, ls) = (val.lp, val.ls);
+        comp_f64(
+            &(ls / (lp * 0.49)),
+            &(50.0 / (zs.norm_sqr() / zs.re)),
+            margin,
+            "calc_xfmr()",
+            "ratio",
+        );
+
+        // Winding loss retunes the capacitors and costs efficiency
+        let lossy = calc_xfmr(
+            zs.re,
+            zs.im,
+            zl.re,
+            zl.im,
+            ImpedanceType::ZReIm,
+            0.7,
+            2.0,
+            15.0,
+            50.0,
+            60.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        assert_eq!(lossy.reason, None);
+        let lossy_val = &lossy.solutions[0];
+        comp_c64(
+            &z_in(&lossy, zl, freq),
+            &zs.conj(),
+            margin,
+            "calc_xfmr()",
+            "lossy zin",
+        );
+        comp_f64(&lossy_val.lp, &lp, margin, "calc_xfmr()", "lossy lp");
+        assert!(lossy_val.efficiency < 1.0 && lossy_val.efficiency > 0.0);
+
+        // The source reactance takes exactly the Pi's susceptance, leaving no source capacitor
+        let zs = Complex::new(10.0, -20.0);
+        let zl = Complex::new(50.0, 10.0);
+        let edge = calc_xfmr(
+            zs.re,
+            zs.im,
+            zl.re,
+            zl.im,
+            ImpedanceType::ZReIm,
+            0.7,
+            2.0,
+            0.0,
+            50.0,
+            30.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        assert_eq!(edge.reason, None);
+        let edge_val = &edge.solutions[0];
+        comp_f64(&edge_val.cs, &0.0, margin, "calc_xfmr()", "edge cs");
+        assert_eq!(edge.elements(0).unwrap().len(), 2);
+        comp_c64(
+            &z_in(&edge, zl, Frequency::new(30.0, Unit::Giga)),
+            &zs.conj(),
+            margin,
+            "calc_xfmr()",
+            "edge zin",
+        );
+        comp_f64(
+            &edge_val.efficiency,
+            &1.0,
+            margin,
+            "calc_xfmr()",
+            "edge efficiency",
+        );
+
+        // A capacitive source the lossy windings can no longer tune out
+        let test = calc_xfmr(
+            12.0,
+            -20.0,
+            zl.re,
+            zl.im,
+            ImpedanceType::ZReIm,
+            0.7,
+            2.0,
+            15.0,
+            50.0,
+            60.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        assert_eq!(test.reason, Some(Unrealizable::InductorQ { q: 15.0 }));
+
+        // More source susceptance than the Pi takes
+        let test = calc_xfmr(
+            12.0,
+            -20.0,
+            zl.re,
+            zl.im,
+            ImpedanceType::ZReIm,
+            0.7,
+            1.0,
+            0.0,
+            50.0,
+            60.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        match test.reason {
+            Some(Unrealizable::QBelowMinimum { q, q_min }) => {
+                comp_f64(&q, &1.0, margin, "calc_xfmr()", "q");
+                comp_f64(&q_min, &(20.0 / 12.0), margin, "calc_xfmr()", "q_min");
+            }
+            reason => panic!("expected QBelowMinimum, got {:?}", reason),
+        }
+
+        // Too lossy to leave the source resistance
+        let test = calc_xfmr(
+            1.0,
+            0.0,
+            50.0,
+            0.0,
+            ImpedanceType::ZReIm,
+            0.3,
+            0.5,
+            2.0,
+            50.0,
+            60.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+        )
+        .unwrap();
+        assert_eq!(test.reason, Some(Unrealizable::InductorQ { q: 2.0 }));
+        assert!(test.solutions.is_empty());
+
+        assert_eq!(
+            calc_xfmr(
+                10.0,
+                0.0,
+                50.0,
+                0.0,
+                ImpedanceType::ZReIm,
+                1.0,
+                2.0,
+                0.0,
+                50.0,
+                60.0,
+                Unit::Giga,
+                Unit::Femto,
+                Unit::Pico,
+            )
+            .unwrap_err()
+            .code(),
+            "non_physical"
+        );
+    }
+}
@@ -0,0 +1,587 @@
+use crate::element::{Element, ElementType};
+use crate::error::Error;
+use crate::frequency::{Frequency, FrequencySweep};
+use crate::rf_utils::ComplexReturn;
+use crate::smith::gen_element;
+use nalgebra::DMatrix;
+use num_complex::{c64, Complex};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum Param {
+    #[serde(rename = "s")]
+    S,
+    #[serde(rename = "y")]
+    Y,
+    #[serde(rename = "z")]
+    Z,
+}
+
+impl FromStr for Param {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "s" | "S" => Ok(Param::S),
+            "y" | "Y" => Ok(Param::Y),
+            "z" | "Z" => Ok(Param::Z),
+            _ => Err(Error::InvalidInput(format!(
+                "parameter {:?} not recognized, expected s, y or z",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = match self {
+            Param::S => "s",
+            Param::Y => "y",
+            Param::Z => "z",
+        };
+        write!(f, "{}", val)
+    }
+}
+
+// A port from its positive to its negative node, waves referenced to a real z0
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Port {
+    pub nodes: [usize; 2],
+    pub z0: f64,
+}
+
+// Symmetric TEM coupled lines of even and odd mode impedances z0e and z0o, a quarter wave long
+// at f0 (Hz). Terminals 1 and 2 are the near ends of the two lines and 3 and 4 their far ends, each
+// to ground.
+//
+//     1 o==========o 3
+//     2 o==========o 4
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CoupledLine {
+    pub z0e: f64,
+    pub z0o: f64,
+    pub f0: f64,
+}
+
+impl CoupledLine {
+    // Y-parameters from the even and odd mode lines, each with Y11 = -j cot(θ)/z and
+    // Y12 = j csc(θ)/z
+    pub fn y(&self, freq: Frequency) -> [[Complex<f64>; 4]; 4] {
+        let theta = PI / 2.0 * freq.freq() / self.f0;
+        let (sin, cos) = theta.sin_cos();
+        let ys = |z: f64| c64(0.0, -cos / sin / z);
+        let yt = |z: f64| c64(0.0, 1.0 / sin / z);
+        let (y11, y12) = (
+            (ys(self.z0e) + ys(self.z0o)) / 2.0,
+            (ys(self.z0e) - ys(self.z0o)) / 2.0,
+        );
+        let (y13, y14) = (
+            (yt(self.z0e) + yt(self.z0o)) / 2.0,
+            (yt(self.z0e) - yt(self.z0o)) / 2.0,
+        );
+        [
+            [y11, y12, y13, y14],
+            [y12, y11, y14, y13],
+            [y13, y14, y11, y12],
+            [y14, y13, y12, y11],
+        ]
+    }
+}
+
+// Elements placed between named nodes for modified nodal analysis, so that bridged, lattice and
+// coupled networks that are no ladder can be solved. Two-terminal elements are stamped with the
+// admittance of their z and two-ports (lines, transformers) with the Y-parameters of their ABCD
+// matrix between (p1+, p1-) and (p2+, p2-). Ground is node 0, "0" or "gnd".
+//
+//     p1+ o----+-----+            +-----+----o p2+
+//              | Y11 |---- Y12 ---| Y22 |
+//     p1- o----+-----+            +-----+----o p2-
+#[derive(Default)]
+pub struct Circuit {
+    nodes: Vec<String>,
+    branches: Vec<(String, Box<dyn Element>, [usize; 2])>,
+    two_ports: Vec<(String, Box<dyn Element>, [usize; 4])>,
+    coupled: Vec<(String, CoupledLine, [usize; 4])>,
+    ports: Vec<Port>,
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Circuit::default()
+    }
+
+    // Index of a node, numbered from 1 in the order they are named
+    pub fn node(&mut self, name: &str) -> usize {
+        let name = name.to_ascii_lowercase();
+        if name == "0" || name == "gnd" {
+            return 0;
+        }
+        match self.nodes.iter().position(|val| *val == name) {
+            Some(idx) => idx + 1,
+            None => {
+                self.nodes.push(name);
+                self.nodes.len()
+            }
+        }
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn ports(&self) -> &[Port] {
+        &self.ports
+    }
+
+    pub fn add_branch(&mut self, name: &str, element: Box<dyn Element>, nodes: [&str; 2]) {
+        let nodes = nodes.map(|val| self.node(val));
+        self.branches.push((name.to_string(), element, nodes));
+    }
+
+    pub fn add_two_port(&mut self, name: &str, element: Box<dyn Element>, nodes: [&str; 4]) {
+        let nodes = nodes.map(|val| self.node(val));
+        self.two_ports.push((name.to_string(), element, nodes));
+    }
+
+    pub fn add_coupled_line(&mut self, name: &str, line: CoupledLine, nodes: [&str; 4]) {
+        let nodes = nodes.map(|val| self.node(val));
+        self.coupled.push((name.to_string(), line, nodes));
+    }
+
+    // An element by its Smith chart code. Lines, transformers and baluns are two-ports on four
+    // nodes, or on two with both returns at ground. The series or shunt code of the rest makes no
+    // difference here.
+    pub fn add_element(
+        &mut self,
+        name: &str,
+        element: ElementType,
+        vals: &[f64],
+        units: &[&str],
+        nodes: &[&str],
+    ) -> Result<(), Error> {
+        let part = gen_element(element, vals, units, c64(1.0, 0.0), 50.0)
+            .map_err(|err| err.context(name))?;
+        match (element, nodes) {
+            (ElementType::TLine | ElementType::Transformer | ElementType::Balun, [p1, p2]) => {
+                self.add_two_port(name, part, [p1, "0", p2, "0"])
+            }
+            (
+                ElementType::TLine | ElementType::Transformer | ElementType::Balun,
+                [p1, n1, p2, n2],
+            ) => self.add_two_port(name, part, [p1, n1, p2, n2]),
+            (ElementType::TLine | ElementType::Transformer | ElementType::Balun, _) => {
+                return Err(Error::InvalidInput(format!(
+                    "{} is a two-port and needs 2 or 4 nodes, got {}",
+                    name,
+                    nodes.len()
+                )))
+            }
+            (_, [a, b]) => self.add_branch(name, part, [a, b]),
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "{} needs 2 nodes, got {}",
+                    name,
+                    nodes.len()
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_port(&mut self, nodes: [&str; 2], z0: f64) -> Result<(), Error> {
+        if !(z0.is_finite() && z0 > 0.0) {
+            return Err(Error::InvalidInput(format!(
+                "port {} needs a positive reference impedance, got {} Ω",
+                self.ports.len() + 1,
+                z0
+            )));
+        }
+        let nodes = nodes.map(|val| self.node(val));
+        if nodes[0] == nodes[1] {
+            return Err(Error::InvalidInput(format!(
+                "port {} is shorted, both of its nodes are {}",
+                self.ports.len() + 1,
+                self.node_name(nodes[0])
+            )));
+        }
+        self.ports.push(Port { nodes, z0 });
+        Ok(())
+    }
+
+    fn node_name(&self, idx: usize) -> &str {
+        match idx {
+            0 => "0",
+            _ => &self.nodes[idx - 1],
+        }
+    }
+
+    // Nodal admittance matrix without the ground row and column, each port terminated in its z0
+    fn admittance(&self, freq: Frequency) -> Result<DMatrix<Complex<f64>>, Error> {
+        let mut y = DMatrix::from_element(self.nodes.len(), self.nodes.len(), Complex::ZERO);
+        // Current into terminal a for a unit voltage across terminal b, scaled by the signs of
+        // the terminals within their ports
+        let mut stamp = |a: usize, b: usize, val: Complex<f64>| {
+            if a > 0 && b > 0 {
+                y[(a - 1, b - 1)] += val;
+            }
+        };
+        let mut two_terminal = |[a, b]: [usize; 2], val: Complex<f64>| {
+            stamp(a, a, val);
+            stamp(b, b, val);
+            stamp(a, b, -val);
+            stamp(b, a, -val);
+        };
+
+        for (name, element, nodes) in &self.branches {
+            let z = element.z(freq);
+            if z == Complex::ZERO || !z.is_finite() {
+                return Err(Error::Numerical(format!(
+                    "{} has an impedance of {} Ω, which cannot be stamped as an admittance",
+                    name, z
+                )));
+            }
+            two_terminal(*nodes, z.inv());
+        }
+        for port in &self.ports {
+            two_terminal(port.nodes, c64(1.0 / port.z0, 0.0));
+        }
+
+        for (name, element, nodes) in &self.two_ports {
+            let [[a, b], [c, d]] = element.abcd(freq);
+            if b == Complex::ZERO || !b.is_finite() {
+                return Err(Error::Numerical(format!(
+                    "{} has no Y-parameters at {} Hz",
+                    name,
+                    freq.freq()
+                )));
+            }
+            let y2 = [[d / b, (b * c - a * d) / b], [-b.inv(), a / b]];
+            let terminals = [(nodes[0], 0, 1.0), (nodes[1], 0, -1.0)];
+            let terminals = [
+                terminals[0],
+                terminals[1],
+                (nodes[2], 1, 1.0),
+                (nodes[3], 1, -1.0),
+            ];
+            for (ni, pi, si) in terminals {
+                for (nj, pj, sj) in terminals {
+                    if ni > 0 && nj > 0 {
+                        y[(ni - 1, nj - 1)] += y2[pi][pj] * si * sj;
+                    }
+                }
+            }
+        }
+        for (name, line, nodes) in &self.coupled {
+            if !(line.z0e > 0.0 && line.z0o > 0.0 && line.f0 > 0.0) {
+                return Err(Error::InvalidInput(format!(
+                    "{} needs positive mode impedances and quarter wave frequency",
+                    name
+                )));
+            }
+            let y4 = line.y(freq);
+            if !y4[0][0].is_finite() || !y4[0][2].is_finite() {
+                return Err(Error::Numerical(format!(
+                    "{} has no Y-parameters at {} Hz",
+                    name,
+                    freq.freq()
+                )));
+            }
+            for (i, ni) in nodes.iter().enumerate() {
+                for (j, nj) in nodes.iter().enumerate() {
+                    if *ni > 0 && *nj > 0 {
+                        y[(ni - 1, nj - 1)] += y4[i][j];
+                    }
+                }
+            }
+        }
+        Ok(y)
+    }
+
+    // S-parameters of the ports at freq. With every port terminated in its z0 and driven by a
+    // Norton source of unit current, the port voltages Zt give S = 2 G^½ Zt G^½ - I.
+    pub fn s(&self, freq: Frequency) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        if self.ports.is_empty() {
+            return Err(Error::InvalidInput("circuit has no ports".to_string()));
+        }
+        let n = self.ports.len();
+        let mut inc = DMatrix::from_element(self.nodes.len(), n, Complex::ZERO);
+        for (k, port) in self.ports.iter().enumerate() {
+            for (node, sign) in port.nodes.iter().zip([1.0, -1.0]) {
+                if *node > 0 {
+                    inc[(node - 1, k)] = c64(sign, 0.0);
+                }
+            }
+        }
+        let v = self.admittance(freq)?.lu().solve(&inc).ok_or_else(|| {
+            Error::Numerical(format!(
+                "circuit matrix is singular at {} Hz, check for floating nodes",
+                freq.freq()
+            ))
+        })?;
+        let zt = inc.transpose() * v;
+
+        Ok((0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let delta = if i == j { 1.0 } else { 0.0 };
+                        2.0 * zt[(i, j)] / (self.ports[i].z0 * self.ports[j].z0).sqrt() - delta
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    // S, Y or Z parameters at freq. Y or Z matrices that do not exist, like the Z matrix of a
+    // series element between two ports, come back as NaN.
+    pub fn param(&self, param: Param, freq: Frequency) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        let s = self.s(freq)?;
+        let n = s.len();
+        let s = DMatrix::from_fn(n, n, |i, j| s[i][j]);
+        let eye = DMatrix::<Complex<f64>>::identity(n, n);
+        let root = DMatrix::from_fn(n, n, |i, j| match i == j {
+            true => c64(self.ports[i].z0.sqrt(), 0.0),
+            false => Complex::ZERO,
+        });
+        let out = match param {
+            Param::S => Some(s),
+            // Z = √z0 (I - S)^-1 (I + S) √z0
+            Param::Z => invert(&eye - &s).map(|inv| &root * inv * (&eye + &s) * &root),
+            // Y = 1/√z0 (I + S)^-1 (I - S) 1/√z0
+            Param::Y => invert(&eye + &s).map(|inv| {
+                let root = root.map(|val| match val == Complex::ZERO {
+                    true => val,
+                    false => val.inv(),
+                });
+                &root * inv * (&eye - &s) * &root
+            }),
+        };
+        Ok((0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match &out {
+                        Some(val) => val[(i, j)],
+                        None => c64(f64::NAN, f64::NAN),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+// Inverse of m, or None when it is singular to within rounding of the S-parameters
+fn invert(m: DMatrix<Complex<f64>>) -> Option<DMatrix<Complex<f64>>> {
+    let sv = m.clone().svd(false, false).singular_values;
+    if sv.min() <= 1e-12 * sv.max() {
+        return None;
+    }
+    m.try_inverse()
+}
+
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct NPortReturn {
+    pub freq: f64,
+    pub param: Vec<Vec<ComplexReturn>>,
+}
+
+pub fn calc_nport(
+    circuit: &Circuit,
+    param: Param,
+    sweep: &FrequencySweep,
+) -> Result<Vec<NPortReturn>, Error> {
+    sweep
+        .iter()
+        .map(|freq| {
+            let val = circuit.param(param, freq)?;
+            Ok(NPortReturn {
+                freq: freq.freq(),
+                param: val
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|x| ComplexReturn { re: x.re, im: x.im })
+                            .collect()
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::{
+        capacitor::Capacitor, inductor::Inductor, resistor::Resistor, transformer::Transformer,
+        Orientation,
+    };
+    use crate::response::calc_s;
+    use crate::rf_utils::comp_c64;
+    use crate::unit::Unit;
+    use float_cmp::F64Margin;
+
+    fn comp_matrix(
+        calc: &[Vec<Complex<f64>>],
+        exemplar: &[Vec<Complex<f64>>],
+        margin: F64Margin,
+        test: &str,
+    ) {
+        for (i, (row, ex)) in calc.iter().zip(exemplar).enumerate() {
+            for (j, (val, x)) in row.iter().zip(ex).enumerate() {
+                comp_c64(val, x, margin, test, &format!("{}{}", i + 1, j + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ladder() {
+        let freq = Frequency::new(2.0, Unit::Giga);
+        let margin = F64Margin {
+            epsilon: 1e-12,
+            ulps: 4,
+        };
+        let ind = Inductor::new(
+            20.0,
+            2.0,
+            Unit::Q,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        let cap = Capacitor::new(0.0, 1.5, Unit::Q, Unit::Pico, 0.0, 0.0, Orientation::Shunt);
+        let xfmr = Transformer::new(
+            0.0,
+            3.0,
+            5.0,
+            0.7,
+            Unit::Base,
+            Unit::Nano,
+            Unit::Nano,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+
+        // in --L-- a --xfmr-- out, C from a to ground
+        let mut circuit = Circuit::new();
+        circuit.add_branch("L1", Box::new(ind), ["in", "a"]);
+        circuit.add_branch("C1", Box::new(cap), ["a", "0"]);
+        circuit.add_two_port("X1", Box::new(xfmr), ["a", "0", "out", "0"]);
+        circuit.add_port(["in", "0"], 50.0).unwrap();
+        circuit.add_port(["out", "gnd"], 50.0).unwrap();
+        assert_eq!(circuit.nodes(), 3);
+
+        let s = calc_s(&[&ind, &cap, &xfmr], freq, 50.0);
+        let s = s.iter().map(|row| row.to_vec()).collect::<Vec<_>>();
+        comp_matrix(&circuit.s(freq).unwrap(), &s, margin, "ladder s");
+
+        // Z from S round trips to the same S through the Z of the cascade
+        let z = circuit.param(Param::Z, freq).unwrap();
+        let y = circuit.param(Param::Y, freq).unwrap();
+        let det = z[0][0] * z[1][1] - z[0][1] * z[1][0];
+        comp_c64(&y[0][0], &(z[1][1] / det), margin, "ladder y", "11");
+        comp_c64(&y[0][1], &(-z[0][1] / det), margin, "ladder y", "12");
+    }
+
+    #[test]
+    fn test_bridged_tee() {
+        let freq = Frequency::new(1.0, Unit::Giga);
+        let margin = F64Margin {
+            epsilon: 1e-12,
+            ulps: 4,
+        };
+        let r = |val: f64| Box::new(Resistor::new(val, Unit::Base, 0.0, Orientation::Series));
+
+        // Bridged tee attenuator, matched when r_bridge * r_shunt = z0^2, S21 = z0 / (z0 + r_bridge)
+        //
+        //              +---- 100 ----+
+        //              |             |
+        //     in o-----+-- 50 --+-- 50 --+-----o out
+        //                       |
+        //                       25
+        //                       |
+        //                      GND
+        let mut circuit = Circuit::new();
+        circuit.add_branch("R1", r(50.0), ["in", "mid"]);
+        circuit.add_branch("R2", r(50.0), ["mid", "out"]);
+        circuit.add_branch("R3", r(100.0), ["in", "out"]);
+        circuit.add_branch("R4", r(25.0), ["mid", "0"]);
+        circuit.add_port(["in", "0"], 50.0).unwrap();
+        circuit.add_port(["out", "0"], 50.0).unwrap();
+        let third = c64(1.0 / 3.0, 0.0);
+        comp_matrix(
+            &circuit.s(freq).unwrap(),
+            &[vec![Complex::ZERO, third], vec![third, Complex::ZERO]],
+            margin,
+            "bridged tee s",
+        );
+
+        // Resistive 3-way star splitter of z0/3 arms, matched with S21 = 1/2
+        let mut circuit = Circuit::new();
+        for (name, node) in [("R1", "p1"), ("R2", "p2"), ("R3", "p3")] {
+            circuit
+                .add_element(
+                    name,
+                    ElementType::SeriesRes,
+                    &[50.0 / 3.0],
+                    &["Ω"],
+                    &[node, "c"],
+                )
+                .unwrap();
+            circuit.add_port([node, "0"], 50.0).unwrap();
+        }
+        let s = circuit.s(freq).unwrap();
+        comp_c64(&s[0][0], &Complex::ZERO, margin, "splitter s", "11");
+        comp_c64(&s[2][1], &c64(0.5, 0.0), margin, "splitter s", "32");
+
+        // A series resistor between two ports has Y but no Z
+        let mut circuit = Circuit::new();
+        circuit.add_branch("R1", r(25.0), ["a", "b"]);
+        circuit.add_port(["a", "0"], 50.0).unwrap();
+        circuit.add_port(["b", "0"], 75.0).unwrap();
+        let y = circuit.param(Param::Y, freq).unwrap();
+        comp_c64(&y[0][0], &c64(0.04, 0.0), margin, "series y", "11");
+        comp_c64(&y[1][0], &c64(-0.04, 0.0), margin, "series y", "21");
+        assert!(circuit.param(Param::Z, freq).unwrap()[0][0].is_nan());
+    }
+
+    #[test]
+    fn test_circuit_errors() {
+        let freq = Frequency::new(1e9, Unit::Base);
+        let mut circuit = Circuit::new();
+        assert_eq!(
+            circuit.s(freq).unwrap_err().message(),
+            "circuit has no ports"
+        );
+        assert!(circuit.add_port(["a", "a"], 50.0).is_err());
+        assert!(circuit.add_port(["a", "0"], 0.0).is_err());
+
+        // The secondary floats, so its potential is undefined
+        circuit
+            .add_element(
+                "X1",
+                ElementType::Transformer,
+                &[0.0, 1.0, 1.0, 0.5],
+                &["Ω", "nH", "nH", "K"],
+                &["a", "0", "b", "c"],
+            )
+            .unwrap();
+        circuit.add_port(["a", "0"], 50.0).unwrap();
+        assert_eq!(
+            circuit.s(freq).unwrap_err().message(),
+            "circuit matrix is singular at 1000000000 Hz, check for floating nodes"
+        );
+        assert!(circuit
+            .add_element("T1", ElementType::TLine, &[50.0, 1.0, 1.0], &["mm"], &["a"])
+            .is_err());
+        assert_eq!("Y".parse::<Param>().unwrap(), Param::Y);
+        assert!("h".parse::<Param>().is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+// Every change to the layout of Project appends a step to MIGRATIONS, which bumps the version
+pub const PROJECT_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+pub const MAX_RECENT: usize = 10;
+
+// Calculator windows spawned by the start_* commands, labelled "<prefix>-<index>"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Calculator {
+    Impedance,
+    Matching,
+    Conjugate,
+    Smith,
+}
+
+impl Calculator {
+    pub const ALL: [Calculator; 4] = [
+        Calculator::Impedance,
+        Calculator::Matching,
+        Calculator::Conjugate,
+        Calculator::Smith,
+    ];
+
+    pub fn label_prefix(&self) -> &'static str {
+        match self {
+            Calculator::Impedance => "Impedance-Calculator",
+            Calculator::Matching => "Matching-Network-Calculator",
+            Calculator::Conjugate => "Conjugate-Match-Calculator",
+            Calculator::Smith => "Smith-Chart-Tool",
+        }
+    }
+
+    pub fn page(&self) -> &'static str {
+        match self {
+            Calculator::Impedance => "impCalc.html",
+            Calculator::Matching => "matchCalc.html",
+            Calculator::Conjugate => "conjCalc.html",
+            Calculator::Smith => "smithChart.html",
+        }
+    }
+
+    // Inner window size, width by height
+    pub fn size(&self) -> (f64, f64) {
+        match self {
+            Calculator::Impedance => (500.0, 600.0),
+            Calculator::Matching => (1200.0, 1150.0),
+            Calculator::Conjugate => (750.0, 750.0),
+            Calculator::Smith => (1800.0, 1600.0),
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Calculator> {
+        Calculator::parse_label(label).map(|(calculator, _)| calculator)
+    }
+
+    // Calculator and index of a window label, which sort windows by kind and then numerically
+    pub fn parse_label(label: &str) -> Option<(Calculator, usize)> {
+        Calculator::ALL.into_iter().find_map(|val| {
+            let idx = label
+                .strip_prefix(val.label_prefix())?
+                .strip_prefix('-')?
+                .parse::<usize>()
+                .ok()?;
+            Some((val, idx))
+        })
+    }
+}
+
+// Whatever the frontend of a window reports as its state, kept opaque on the Rust side
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub calculator: Calculator,
+    pub state: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub windows: Vec<WindowState>,
+}
+
+impl Project {
+    pub fn new(windows: Vec<WindowState>) -> Self {
+        Project {
+            version: PROJECT_VERSION,
+            windows,
+        }
+    }
+}
+
+type Migration = fn(Value) -> Result<Value, Error>;
+
+// MIGRATIONS[i] upgrades a version i + 1 project to version i + 2
+const MIGRATIONS: &[Migration] = &[];
+
+fn migrate_with(mut value: Value, steps: &[Migration]) -> Result<Value, Error> {
+    let latest = steps.len() as u64 + 1;
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .filter(|val| *val > 0)
+        .ok_or_else(|| Error::Parse("project has no version".to_string()))?;
+    if version > latest {
+        return Err(Error::Parse(format!(
+            "project version {} is newer than the supported version {}",
+            version, latest
+        )));
+    }
+
+    for step in &steps[(version - 1) as usize..] {
+        value = step(value)?;
+    }
+    value["version"] = Value::from(latest);
+
+    Ok(value)
+}
+
+pub fn parse_project(text: &str) -> Result<Project, Error> {
+    let value = serde_json::from_str(text).map_err(|err| Error::Parse(err.to_string()))?;
+    serde_json::from_value(migrate_with(value, MIGRATIONS)?)
+        .map_err(|err| Error::Parse(err.to_string()))
+}
+
+pub fn save_project(path: &Path, project: &Project) -> Result<(), Error> {
+    let text =
+        serde_json::to_string_pretty(project).map_err(|err| Error::Parse(err.to_string()))?;
+    std::fs::write(path, text).map_err(|err| Error::from(err).context(&path.display().to_string()))
+}
+
+pub fn load_project(path: &Path) -> Result<Project, Error> {
+    let ctx = path.display().to_string();
+    let text = std::fs::read_to_string(path).map_err(|err| Error::from(err).context(&ctx))?;
+    parse_project(&text).map_err(|err| err.context(&ctx))
+}
+
+// Most recent first, without duplicates
+pub fn push_recent(recent: &mut Vec<String>, path: &str, max: usize) {
+    recent.retain(|val| val != path);
+    recent.insert(0, path.to_string());
+    recent.truncate(max);
+}
+
+// A missing list is an empty one
+pub fn read_recent(path: &Path) -> Result<Vec<String>, Error> {
+    let ctx = path.display().to_string();
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            serde_json::from_str(&text).map_err(|err| Error::Parse(err.to_string()).context(&ctx))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(Error::from(err).context(&ctx)),
+    }
+}
+
+pub fn write_recent(path: &Path, recent: &[String]) -> Result<(), Error> {
+    let ctx = path.display().to_string();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| Error::from(err).context(&ctx))?;
+    }
+    let text = serde_json::to_string_pretty(recent).map_err(|err| Error::Parse(err.to_string()))?;
+    std::fs::write(path, text).map_err(|err| Error::from(err).context(&ctx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_calculator() {
+        assert_eq!(
+            Calculator::from_label("Matching-Network-Calculator-3"),
+            Some(Calculator::Matching)
+        );
+        assert_eq!(
+            Calculator::from_label("Smith-Chart-Tool-1"),
+            Some(Calculator::Smith)
+        );
+        assert_eq!(Calculator::from_label("Smith-Chart-Tool"), None);
+        assert_eq!(Calculator::from_label("main"), None);
+
+        let mut labels = [
+            "Smith-Chart-Tool-10",
+            "Smith-Chart-Tool-2",
+            "Conjugate-Match-Calculator-1",
+            "Impedance-Calculator-3",
+        ]
+        .map(|val| Calculator::parse_label(val).unwrap());
+        labels.sort();
+        assert_eq!(
+            labels,
+            [
+                (Calculator::Impedance, 3),
+                (Calculator::Conjugate, 1),
+                (Calculator::Smith, 2),
+                (Calculator::Smith, 10),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(Calculator::Conjugate).unwrap(),
+            json!("conjugate")
+        );
+    }
+
+    #[test]
+    fn test_parse_project() {
+        let project = Project::new(vec![
+            WindowState {
+                calculator: Calculator::Impedance,
+                state: json!({"inputs": {"freq": "1"}}),
+            },
+            WindowState {
+                calculator: Calculator::Smith,
+                state: json!({"schematic": [{"type": "raw", "z0": 50}]}),
+            },
+        ]);
+        let text = serde_json::to_string(&project).unwrap();
+        assert_eq!(parse_project(&text).unwrap(), project);
+
+        assert_eq!(
+            parse_project(r#"{"windows": []}"#).unwrap_err().code(),
+            "parse"
+        );
+        assert_eq!(
+            parse_project(r#"{"version": 99, "windows": []}"#)
+                .unwrap_err()
+                .code(),
+            "parse"
+        );
+        assert_eq!(
+            parse_project(r#"{"version": 1, "windows": [{"calculator": "x", "state": {}}]}"#)
+                .unwrap_err()
+                .code(),
+            "parse"
+        );
+    }
+
+    #[test]
+    fn test_migrate() {
+        // A version 1 layout that named the calculator "kind"
+        fn rename_kind(mut value: Value) -> Result<Value, Error> {
+            for window in value["windows"].as_array_mut().into_iter().flatten() {
+                let kind = window["kind"].take();
+                window["calculator"] = kind;
+            }
+            Ok(value)
+        }
+
+        let old = json!({"version": 1, "windows": [{"kind": "matching", "state": {}}]});
+        let test = migrate_with(old, &[rename_kind]).unwrap();
+        assert_eq!(test["version"], json!(2));
+        assert_eq!(test["windows"][0]["calculator"], json!("matching"));
+
+        let new = json!({"version": 2, "windows": []});
+        assert_eq!(migrate_with(new.clone(), &[rename_kind]).unwrap(), new);
+
+        // Saved projects are written at the version the shipped migrations end at
+        let old = json!({"version": 1, "windows": []});
+        assert_eq!(
+            migrate_with(old, MIGRATIONS).unwrap()["version"],
+            json!(PROJECT_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_recent() {
+        let mut recent = vec!["a.json".to_string(), "b.json".to_string()];
+        push_recent(&mut recent, "b.json", 3);
+        assert_eq!(recent, vec!["b.json", "a.json"]);
+        push_recent(&mut recent, "c.json", 3);
+        push_recent(&mut recent, "d.json", 3);
+        assert_eq!(recent, vec!["d.json", "c.json", "b.json"]);
+
+        let dir = std::env::temp_dir().join(format!("rf-toolbox-recent-{}", std::process::id()));
+        let path = dir.join("recent.json");
+        assert!(read_recent(&path).unwrap().is_empty());
+        write_recent(&path, &recent).unwrap();
+        assert_eq!(read_recent(&path).unwrap(), recent);
+
+        let project = Project::new(vec![]);
+        let file = dir.join("session.json");
+        save_project(&file, &project).unwrap();
+        assert_eq!(load_project(&file).unwrap(), project);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
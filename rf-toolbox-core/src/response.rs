@@ -0,0 +1,227 @@
+#![allow(unused)]
+use crate::element::measured::PartFile;
+use crate::element::{Element, ElementType};
+use crate::error::Error;
+use crate::frequency::{Frequency, FrequencySweep};
+use crate::rf_utils::{abcd2s, abcd_mult};
+use crate::smith::gen_part;
+use crate::unit::Unit;
+use num_complex::{c64, Complex};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+// Relative frequency step used for the numerical derivative of the S21 phase
+const GROUP_DELAY_STEP: f64 = 1e-6;
+
+#[derive(Default, Debug, PartialEq)]
+pub struct FreqResponseReturn {
+    pub freq: f64,
+    pub s11: Complex<f64>,
+    pub s21: Complex<f64>,
+    pub vswr: f64,
+    pub group_delay: f64,
+}
+
+impl Serialize for FreqResponseReturn {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("FreqResponseReturn", 12)?;
+        s.serialize_field("freq", &self.freq)?;
+        s.serialize_field("s11_re", &self.s11.re)?;
+        s.serialize_field("s11_im", &self.s11.im)?;
+        s.serialize_field("s11_db", &(20.0 * self.s11.norm().log10()))?;
+        s.serialize_field("s11_ang", &(self.s11.arg() * 180.0 / PI))?;
+        s.serialize_field("s21_re", &self.s21.re)?;
+        s.serialize_field("s21_im", &self.s21.im)?;
+        s.serialize_field("s21_db", &(20.0 * self.s21.norm().log10()))?;
+        s.serialize_field("s21_ang", &(self.s21.arg() * 180.0 / PI))?;
+        s.serialize_field("vswr", &self.vswr)?;
+        s.serialize_field("group_delay", &self.group_delay)?;
+        s.end()
+    }
+}
+
+pub fn calc_abcd(elements: &[&dyn Element], freq: Frequency) -> [[Complex<f64>; 2]; 2] {
+    elements.iter().fold(
+        [[Complex::ONE, Complex::ZERO], [Complex::ZERO, Complex::ONE]],
+        |acc, val| abcd_mult(&acc, &val.abcd(freq)),
+    )
+}
+
+pub fn calc_s(elements: &[&dyn Element], freq: Frequency, z0: f64) -> [[Complex<f64>; 2]; 2] {
+    abcd2s(&calc_abcd(elements, freq), z0)
+}
+
+pub fn calc_freq_response(
+    elements: &[&dyn Element],
+    freq: Frequency,
+    z0: f64,
+) -> FreqResponseReturn {
+    let s = calc_s(elements, freq, z0);
+    let s11 = s[0][0];
+    let s21 = s[1][0];
+
+    let mut freq_lo = freq;
+    let mut freq_hi = freq;
+    freq_lo.set_freq(freq.freq_scaled() * (1.0 - GROUP_DELAY_STEP));
+    freq_hi.set_freq(freq.freq_scaled() * (1.0 + GROUP_DELAY_STEP));
+    let s21_lo = calc_s(elements, freq_lo, z0)[1][0];
+    let s21_hi = calc_s(elements, freq_hi, z0)[1][0];
+
+    // The phase difference is taken from the ratio so that it never wraps
+    let group_delay = -(s21_hi / s21_lo).arg() / (freq_hi.w() - freq_lo.w());
+
+    FreqResponseReturn {
+        freq: freq.freq_scaled(),
+        s11,
+        s21,
+        vswr: (1.0 + s11.norm()) / (1.0 - s11.norm()),
+        group_delay,
+    }
+}
+
+pub fn calc_response(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    parts: Vec<Option<PartFile>>,
+    z0: f64,
+    sweep: FrequencySweep,
+) -> Result<Vec<FreqResponseReturn>, Error> {
+    if elements.len() != vals.len() || elements.len() != units.len() {
+        return Err(Error::InvalidInput(
+            "elements, vals and units must have the same length".to_string(),
+        ));
+    }
+
+    let chain = elements
+        .iter()
+        .zip(vals.iter().zip(units.iter()))
+        .enumerate()
+        .map(|(i, (element, (val, unit)))| {
+            let part = parts.get(i).and_then(Option::as_ref);
+            gen_part(*element, val, unit, part, c64(1.0, 0.0), z0)
+        })
+        .collect::<Result<Vec<Box<dyn Element>>, Error>>()?;
+    let chain = chain.iter().map(|val| val.as_ref()).collect::<Vec<_>>();
+
+    Ok(sweep
+        .iter()
+        .map(|freq| calc_freq_response(&chain, freq, z0))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::{capacitor::Capacitor, tline::TLine, Orientation};
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    #[test]
+    fn test_calc_freq_response_series_cap() {
+        let testname = "calc_freq_response_series_cap";
+        let freq = Frequency::new(280.0, Unit::Giga);
+        let z0 = 50.0;
+        let cap = Capacitor::new(
+            0.0,
+            20.0,
+            Unit::Q,
+            Unit::Femto,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        let z = c64(0.0, -28.420525552124168);
+        let s11 = z / (z + 2.0 * z0);
+        let s21 = 2.0 * z0 / (z + 2.0 * z0);
+        let test = calc_freq_response(&[&cap], freq, z0);
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_f64(&test.freq, &280.0, margin, testname, "freq");
+        comp_c64(&test.s11, &s11, margin, testname, "s11");
+        comp_c64(&test.s21, &s21, margin, testname, "s21");
+        comp_f64(
+            &test.vswr,
+            &((1.0 + s11.norm()) / (1.0 - s11.norm())),
+            margin,
+            testname,
+            "vswr",
+        );
+        // -d/dw of the S21 phase, atan(1 / (2 w C z0)), for the series capacitor
+        let w = freq.w();
+        let wcz = 2.0 * w * 20e-15 * z0;
+        comp_f64(
+            &test.group_delay,
+            &(2.0 * 20e-15 * z0 / (1.0 + wcz.powi(2))),
+            F64Margin::from((1e-18, 4)),
+            testname,
+            "group_delay",
+        );
+    }
+
+    #[test]
+    fn test_calc_freq_response_tline() {
+        let testname = "calc_freq_response_tline";
+        let freq = Frequency::new(28.0, Unit::Giga);
+        let z0 = 50.0;
+        let er = 3.4;
+        let line = TLine::new(z0, c64(z0, 0.0), er, 1.5, Unit::Milli);
+        let test = calc_freq_response(&[&line], freq, z0);
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_c64(&test.s11, &Complex::ZERO, margin, testname, "s11");
+        comp_c64(
+            &test.s21,
+            &Complex::from_polar(1.0, -line.betal(freq)),
+            margin,
+            testname,
+            "s21",
+        );
+        comp_f64(&test.vswr, &1.0, margin, testname, "vswr");
+        comp_f64(
+            &test.group_delay,
+            &(1.5e-3 * er.sqrt() / 3e8),
+            F64Margin::from((1e-18, 4)),
+            testname,
+            "group_delay",
+        );
+    }
+
+    #[test]
+    fn test_calc_response() {
+        let test = calc_response(
+            vec![ElementType::SeriesCap, ElementType::TLine],
+            vec![vec![0.0, 20.0], vec![50.0, 3.4, 1500.0]],
+            vec![vec!["Q", "fF"], vec!["um"]],
+            vec![],
+            50.0,
+            FrequencySweep::linear(270.0, 290.0, 3, Unit::Giga).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(test.len(), 3);
+        comp_f64(
+            &test[1].s21.norm(),
+            &(100.0 / c64(100.0, -28.420525552124168).norm()),
+            F64Margin::from((1e-12, 4)),
+            "calc_response()",
+            "s21",
+        );
+        assert!(calc_response(
+            vec![ElementType::SeriesCap],
+            vec![],
+            vec![vec!["Q", "fF"]],
+            vec![],
+            50.0,
+            FrequencySweep::single(Frequency::new(280.0, Unit::Giga))
+        )
+        .is_err());
+    }
+}
@@ -1,15 +1,14 @@
 #![allow(unused)]
+use crate::error::Error;
 use crate::unit::{get_unit, Unit, UnitType};
 use float_cmp::{approx_eq, F64Margin};
 use num_complex::Complex;
 use serde::ser::{SerializeStruct, Serializer};
-use serde::Serialize;
-use std::error::Error;
+use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
 use std::fmt;
 use std::str::FromStr;
 use std::string::ToString;
-use tauri::ipc::Response;
 
 #[derive(Default, Debug, PartialEq)]
 pub struct ComplexReturn {
@@ -87,36 +86,108 @@ impl Serialize for ResponseReturn {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
 pub enum ComplexType {
+    #[serde(rename = "ri", alias = "reim")]
     ReIm,
+    #[serde(rename = "ma", alias = "magang")]
     MagAng,
+    #[serde(rename = "db", alias = "dbang")]
     Db,
 }
 
 impl FromStr for ComplexType {
-    type Err = Box<dyn Error>;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "ri" | "reim" => Ok(ComplexType::ReIm),
             "ma" | "magang" => Ok(ComplexType::MagAng),
             "db" | "dbang" => Ok(ComplexType::Db),
-            _ => Err("ComplexType not recognized".to_string().into()),
+            _ => Err(Error::Parse(format!(
+                "complex format {:?} not recognized",
+                s
+            ))),
         }
     }
 }
 
-pub fn gen_complex(re: f64, im: f64, imp: &str) -> Result<Complex<f64>, String> {
-    match ComplexType::from_str(imp) {
-        Ok(ComplexType::ReIm) => Ok(Complex::new(re, im)),
-        Ok(ComplexType::MagAng) => Ok(Complex::from_polar(re, im * PI / 180.0)),
-        Ok(ComplexType::Db) => Ok(Complex::from_polar(10_f64.powf(re / 20.0), im * PI / 180.0)),
-        Err(_) => Err("ComplexType not recognized".to_string()),
+// Input format of the impedance calculator: Z directly, Γ in one of the ComplexType forms, or parallel RC
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum ImpedanceFormat {
+    #[serde(rename = "z")]
+    Z,
+    #[serde(rename = "ri")]
+    GammaReIm,
+    #[serde(rename = "ma")]
+    GammaMagAng,
+    #[serde(rename = "db")]
+    GammaDb,
+    #[serde(rename = "rc")]
+    Rc,
+}
+
+impl FromStr for ImpedanceFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "z" => Ok(ImpedanceFormat::Z),
+            "ri" => Ok(ImpedanceFormat::GammaReIm),
+            "ma" => Ok(ImpedanceFormat::GammaMagAng),
+            "db" => Ok(ImpedanceFormat::GammaDb),
+            "rc" => Ok(ImpedanceFormat::Rc),
+            _ => Err(Error::Parse(format!(
+                "impedance format {:?} not recognized",
+                s
+            ))),
+        }
     }
 }
 
-#[tauri::command(rename_all = "snake_case")]
-pub fn get_c64_inv(re: f64, im: f64) -> Result<ComplexReturn, String> {
+// Whether the entered impedances are differential or single-ended
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum ZScale {
+    #[serde(rename = "diff")]
+    Diff,
+    #[serde(rename = "se")]
+    SingleEnded,
+}
+
+impl ZScale {
+    // Mixed-mode reference of an entered impedance, differential waves are referenced to 2*z0
+    pub fn reference(&self, z0: f64) -> f64 {
+        match self {
+            ZScale::Diff => 2.0 * z0,
+            ZScale::SingleEnded => z0,
+        }
+    }
+}
+
+impl FromStr for ZScale {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "diff" => Ok(ZScale::Diff),
+            "se" => Ok(ZScale::SingleEnded),
+            _ => Err(Error::Parse(format!(
+                "impedance scale {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+pub fn gen_complex(re: f64, im: f64, imp: ComplexType) -> Complex<f64> {
+    match imp {
+        ComplexType::ReIm => Complex::new(re, im),
+        ComplexType::MagAng => Complex::from_polar(re, im * PI / 180.0),
+        ComplexType::Db => Complex::from_polar(10_f64.powf(re / 20.0), im * PI / 180.0),
+    }
+}
+
+pub fn get_c64_inv(re: f64, im: f64) -> Result<ComplexReturn, Error> {
     let mut val = Complex::<f64>::new(re, im);
     val = val.inv();
 
@@ -152,7 +223,7 @@ pub fn calc_gamma_from_rc(
     let z = 1.0
         / Complex::new(
             1.0 / r,
-            2.0 * std::f64::consts::PI * unscale(freq, &fscale) * unscale(c, &cscale),
+            2.0 * std::f64::consts::PI * unscale(freq, fscale) * unscale(c, cscale),
         );
 
     (z - z0) / (z + z0)
@@ -166,6 +237,45 @@ pub fn calc_z_norm(gamma: Complex<f64>, z0: f64) -> Complex<f64> {
     calc_z(gamma, z0) / z0
 }
 
+pub fn abcd_mult(a: &[[Complex<f64>; 2]; 2], b: &[[Complex<f64>; 2]; 2]) -> [[Complex<f64>; 2]; 2] {
+    [
+        [
+            a[0][0] * b[0][0] + a[0][1] * b[1][0],
+            a[0][0] * b[0][1] + a[0][1] * b[1][1],
+        ],
+        [
+            a[1][0] * b[0][0] + a[1][1] * b[1][0],
+            a[1][0] * b[0][1] + a[1][1] * b[1][1],
+        ],
+    ]
+}
+
+pub fn abcd2s(abcd: &[[Complex<f64>; 2]; 2], z0: f64) -> [[Complex<f64>; 2]; 2] {
+    let [[a, b], [c, d]] = *abcd;
+    let den = a + b / z0 + c * z0 + d;
+
+    [
+        [(a + b / z0 - c * z0 - d) / den, 2.0 * (a * d - b * c) / den],
+        [2.0 / den, (-a + b / z0 - c * z0 + d) / den],
+    ]
+}
+
+pub fn s2abcd(s: &[[Complex<f64>; 2]; 2], z0: f64) -> [[Complex<f64>; 2]; 2] {
+    let [[s11, s12], [s21, s22]] = *s;
+    let den = 2.0 * s21;
+
+    [
+        [
+            ((1.0 + s11) * (1.0 - s22) + s12 * s21) / den,
+            z0 * ((1.0 + s11) * (1.0 + s22) - s12 * s21) / den,
+        ],
+        [
+            ((1.0 - s11) * (1.0 - s22) - s12 * s21) / (z0 * den),
+            ((1.0 - s11) * (1.0 + s22) + s12 * s21) / den,
+        ],
+    ]
+}
+
 pub fn calc_z_from_rc(
     r: f64,
     c: f64,
@@ -176,7 +286,7 @@ pub fn calc_z_from_rc(
 ) -> Complex<f64> {
     1.0 / Complex::new(
         1.0 / r,
-        2.0 * std::f64::consts::PI * unscale(freq, &fscale) * unscale(c, &cscale),
+        2.0 * std::f64::consts::PI * unscale(freq, fscale) * unscale(c, cscale),
     )
 }
 
@@ -190,61 +300,53 @@ pub fn calc_rc(
     let y = 1.0 / z;
 
     (
-        1.0 / scale(y.re, &rscale),
+        1.0 / scale(y.re, rscale),
         scale(
-            y.im / (2.0 * std::f64::consts::PI * unscale(freq, &fscale)),
-            &cscale,
+            y.im / (2.0 * std::f64::consts::PI * unscale(freq, fscale)),
+            cscale,
         ),
     )
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn calc_impedance(
     re: f64,
     im: f64,
-    imp: &str,
+    imp: ImpedanceFormat,
     z0: f64,
     freq: f64,
-    f_scale: &str,
-    _r_scale: &str,
-    c_scale: &str,
-) -> Response {
-    let freq_unit = Unit::from_str(f_scale).unwrap();
-    let cap_unit = Unit::from_str(c_scale).unwrap();
-
+    freq_unit: Unit,
+    cap_unit: Unit,
+) -> ResponseReturn {
     let (z, g) = match imp {
-        "z" => (Complex::new(re, im), calc_gamma(Complex::new(re, im), z0)),
-        "ri" => (calc_z(Complex::new(re, im), z0), Complex::new(re, im)),
-        "ma" => (
+        ImpedanceFormat::Z => (Complex::new(re, im), calc_gamma(Complex::new(re, im), z0)),
+        ImpedanceFormat::GammaReIm => (calc_z(Complex::new(re, im), z0), Complex::new(re, im)),
+        ImpedanceFormat::GammaMagAng => (
             calc_z(Complex::from_polar(re, im * PI / 180.0), z0),
             Complex::from_polar(re, im * PI / 180.0),
         ),
-        "db" => (
+        ImpedanceFormat::GammaDb => (
             calc_z(
                 Complex::from_polar(10_f64.powf(re / 20.0), im * PI / 180.0),
                 z0,
             ),
             Complex::from_polar(10_f64.powf(re / 20.0), im * PI / 180.0),
         ),
-        "rc" => (
+        ImpedanceFormat::Rc => (
             calc_z_from_rc(re, im, freq, &freq_unit, &Unit::Base, &cap_unit),
             calc_gamma_from_rc(re, im, z0, freq, &freq_unit, &Unit::Base, &cap_unit),
         ),
-        _ => (Complex::ONE, Complex::ONE),
     };
 
     let (r, c) = calc_rc(z, freq, &freq_unit, &Unit::Base, &cap_unit);
 
-    let out = ResponseReturn {
-        z: z,
-        g: g,
+    ResponseReturn {
+        z,
+        g,
         g_mag: g.norm(),
         g_ang: g.arg() * 180.0 / PI,
-        r: r,
-        c: c,
-    };
-
-    Response::new(serde_json::to_string(&out).unwrap())
+        r,
+        c,
+    }
 }
 
 pub fn comp_c64(
@@ -255,15 +357,15 @@ pub fn comp_c64(
     idx: &str,
 ) {
     comp_f64(
-        &(*calc).re,
-        &(*exemplar).re,
+        &calc.re,
+        &exemplar.re,
         precision,
         test,
         &(idx.to_owned() + ".re"),
     );
     comp_f64(
-        &(*calc).im,
-        &(*exemplar).im,
+        &calc.im,
+        &exemplar.im,
         precision,
         test,
         &(idx.to_owned() + ".im"),
@@ -309,14 +411,15 @@ mod tests {
         let tera = ["tera", "T", "THz", "thz"];
         let giga = ["giga", "G", "GHz", "ghz", "GΩ"];
         let mega = ["mega", "M", "MHz", "mhz", "MΩ"];
-        let kilo = ["kilo", "k", "kHz", "khz", "kΩ"];
+        let kilo = ["kilo", "k", "kHz", "khz", "KHz", "kΩ", "kF", "kH"];
         let milli = ["milli", "m", "mΩ", "mF", "mH"];
-        let micro = ["micro", "u", "μΩ", "μF", "μH"];
+        let micro = ["micro", "u", "μ", "μΩ", "μF", "μH", "μHz"];
         let nano = ["nano", "n", "nΩ", "nF", "nH"];
         let pico = ["pico", "p", "pΩ", "pF", "pH"];
         let femto = ["femto", "f", "fΩ", "fF", "fH"];
         let lambda = ["lambda", "λ"];
-        let nada = ["", "google", ".sfwe"];
+        let nada = ["", "base", "Ω", "Hz"];
+        let bad = ["google", ".sfwe", "xF", "KH"];
 
         for mult in tera.iter() {
             assert_eq!(Unit::from_str(mult).unwrap(), Unit::Tera);
@@ -361,6 +464,10 @@ mod tests {
         for mult in nada.iter() {
             assert_eq!(Unit::from_str(mult).unwrap(), Unit::Base);
         }
+
+        for mult in bad.iter() {
+            assert!(Unit::from_str(mult).is_err());
+        }
     }
 
     #[test]
@@ -376,9 +483,9 @@ mod tests {
             Complex::new(0.15376155704397684, 0.40691815341099224),
             Complex::new(-3.65144119629969, -4.793201713570547),
         ];
-        let test_ri = gen_complex(re, im, "ri").unwrap();
-        let test_ma = gen_complex(mag, ang, "ma").unwrap();
-        let test_db = gen_complex(db, angdb, "db").unwrap();
+        let test_ri = gen_complex(re, im, ComplexType::ReIm);
+        let test_ma = gen_complex(mag, ang, ComplexType::MagAng);
+        let test_db = gen_complex(db, angdb, ComplexType::Db);
 
         comp_c64(
             &test_ri,
@@ -526,6 +633,77 @@ mod tests {
         comp_c64(&test, &z, F64Margin::default(), "calc_z()", "z");
     }
 
+    #[test]
+    fn test_abcd2s() {
+        let z0 = 50.0;
+        let z = Complex::new(12.5, -37.0);
+        let series = [[Complex::ONE, z], [Complex::ZERO, Complex::ONE]];
+        let shunt = [[Complex::ONE, Complex::ZERO], [z.inv(), Complex::ONE]];
+        let test = abcd2s(&series, z0);
+
+        comp_c64(
+            &test[0][0],
+            &(z / (z + 2.0 * z0)),
+            F64Margin::default(),
+            "abcd2s()",
+            "s11",
+        );
+        comp_c64(
+            &test[1][0],
+            &(2.0 * z0 / (z + 2.0 * z0)),
+            F64Margin::default(),
+            "abcd2s()",
+            "s21",
+        );
+        comp_c64(
+            &test[0][1],
+            &test[1][0],
+            F64Margin::default(),
+            "abcd2s()",
+            "s12",
+        );
+
+        let test = abcd_mult(&series, &shunt);
+        comp_c64(
+            &test[0][0],
+            &(1.0 + z / z),
+            F64Margin::default(),
+            "abcd_mult()",
+            "a",
+        );
+        comp_c64(&test[0][1], &z, F64Margin::default(), "abcd_mult()", "b");
+        comp_c64(
+            &test[1][0],
+            &z.inv(),
+            F64Margin::default(),
+            "abcd_mult()",
+            "c",
+        );
+        comp_c64(
+            &test[1][1],
+            &Complex::ONE,
+            F64Margin::default(),
+            "abcd_mult()",
+            "d",
+        );
+    }
+
+    #[test]
+    fn test_s2abcd() {
+        let z0 = 50.0;
+        let abcd = [
+            [Complex::new(0.8, 0.1), Complex::new(12.5, -37.0)],
+            [Complex::new(0.002, 0.011), Complex::new(1.1, -0.3)],
+        ];
+        let test = s2abcd(&abcd2s(&abcd, z0), z0);
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_c64(&test[0][0], &abcd[0][0], margin, "s2abcd()", "a");
+        comp_c64(&test[0][1], &abcd[0][1], margin, "s2abcd()", "b");
+        comp_c64(&test[1][0], &abcd[1][0], margin, "s2abcd()", "c");
+        comp_c64(&test[1][1], &abcd[1][1], margin, "s2abcd()", "d");
+    }
+
     #[test]
     fn test_calc_rc() {
         let z = Complex::new(42.4, -19.6);
@@ -0,0 +1,781 @@
+#![allow(unused)]
+use crate::element::{
+    capacitor::Capacitor, inductor::Inductor, openstub::OpenStub, shortedstub::ShortedStub,
+    tline::TLine, Element, ElementType, Orientation,
+};
+use crate::error::Error;
+use crate::frequency::Frequency;
+use crate::rf_utils::{scale, unscale};
+use crate::smith::element_trailing;
+use crate::unit::Unit;
+use num_complex::{c64, Complex};
+use serde::Serialize;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Lumped {
+    Capacitor(Capacitor),
+    Inductor(Inductor),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Distributed {
+    OpenStub(OpenStub),
+    ShortedStub(ShortedStub),
+    TLine(TLine),
+}
+
+impl Distributed {
+    pub fn element(&self) -> &dyn Element {
+        match self {
+            Distributed::OpenStub(val) => val,
+            Distributed::ShortedStub(val) => val,
+            Distributed::TLine(val) => val,
+        }
+    }
+}
+
+// Commensurate sections, characterized only by their characteristic impedance
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Section {
+    SeriesShort(f64),
+    SeriesOpen(f64),
+    ShuntShort(f64),
+    ShuntOpen(f64),
+    UnitElement(f64),
+}
+
+// All stubs and unit elements are λ/8 long at the design frequency so that tan(βl) = 1
+fn commensurate_length(freq: Frequency, er: f64, length_unit: &Unit) -> f64 {
+    scale(freq.wavelength(er) / 8.0, length_unit)
+}
+
+// --IND--   =>   --SHORTED STUB--
+//                  z0 = wL
+pub fn richards_inductor(
+    ind: &Inductor,
+    freq: Frequency,
+    er: f64,
+    length_unit: Unit,
+) -> ShortedStub {
+    let z0 = freq.w() * unscale(ind.ind(), &ind.ind_unit());
+    let mut out = ShortedStub::new(
+        z0,
+        er,
+        commensurate_length(freq, er, &length_unit),
+        length_unit,
+    );
+    out.set_orientation(ind.orientation());
+    out
+}
+
+// --CAP--   =>   --OPEN STUB--
+//                  z0 = 1 / wC
+pub fn richards_capacitor(
+    cap: &Capacitor,
+    freq: Frequency,
+    er: f64,
+    length_unit: Unit,
+) -> OpenStub {
+    let z0 = 1.0 / (freq.w() * unscale(cap.cap(), &cap.cap_unit()));
+    let mut out = OpenStub::new(
+        z0,
+        c64(z0, 0.0),
+        er,
+        commensurate_length(freq, er, &length_unit),
+        length_unit,
+    );
+    out.set_orientation(cap.orientation());
+    out
+}
+
+// --SHORTED STUB (zs)----UE (zu)--   <=>   --UE (zu + zs)----OPEN STUB (zu (zu + zs) / zs)--
+//                                                                    |
+//                                                                   GND
+// The identity holds in either order, returns (ue z0, open stub z0)
+pub fn kuroda_series_short(zs: f64, zu: f64) -> (f64, f64) {
+    (zu + zs, zu * (zu + zs) / zs)
+}
+
+// --OPEN STUB (zp)----UE (zu)--   <=>   --UE (zu zp / (zu + zp))----SHORTED STUB (zu^2 / (zu + zp))--
+//        |
+//       GND
+// The identity holds in either order, returns (ue z0, shorted stub z0)
+pub fn kuroda_shunt_open(zp: f64, zu: f64) -> (f64, f64) {
+    (zu * zp / (zu + zp), zu.powi(2) / (zu + zp))
+}
+
+// Fewest unit elements each stub has to be crossed by, walking away from the point the unit
+// elements enter at. A series short becomes a shunt open after an odd number of crossings, a
+// shunt open stays one after an even number and a shunt short has no identity to cross with
+fn min_crossings<'a>(stubs: impl Iterator<Item = &'a Section>) -> Option<Vec<usize>> {
+    let mut prev = 0;
+    let mut out = vec![];
+    for stub in stubs {
+        prev = match stub {
+            Section::SeriesShort(_) => prev + 1 - prev % 2,
+            Section::ShuntOpen(_) => prev + prev % 2,
+            Section::ShuntShort(_) if prev == 0 => 0,
+            _ => return None,
+        };
+        out.push(prev);
+    }
+    Some(out)
+}
+
+// Moves the unit element at index ue across its neighbouring stub at index stub
+fn exchange(sections: &mut [Section], ue: usize, stub: usize) {
+    let Section::UnitElement(zu) = sections[ue] else {
+        unreachable!()
+    };
+    let (zu, section) = match sections[stub] {
+        Section::SeriesShort(zs) => {
+            let (zu, zp) = kuroda_series_short(zs, zu);
+            (zu, Section::ShuntOpen(zp))
+        }
+        Section::ShuntOpen(zp) => {
+            let (zu, zs) = kuroda_shunt_open(zp, zu);
+            (zu, Section::SeriesShort(zs))
+        }
+        _ => unreachable!(),
+    };
+    sections[ue] = section;
+    sections[stub] = Section::UnitElement(zu);
+}
+
+pub fn richards_kuroda(
+    ladder: &[Lumped],
+    z0: f64,
+    freq: Frequency,
+    er: f64,
+    length_unit: Unit,
+) -> Result<Vec<Distributed>, Error> {
+    if ladder.is_empty() {
+        return Err(Error::InvalidInput(
+            "ladder contains no elements".to_string(),
+        ));
+    }
+
+    let mut sections: Vec<Section> = ladder
+        .iter()
+        .map(|val| match val {
+            Lumped::Inductor(ind) => {
+                let stub = richards_inductor(ind, freq, er, length_unit);
+                match ind.orientation() {
+                    Orientation::Series => Section::SeriesShort(stub.z0()),
+                    Orientation::Shunt => Section::ShuntShort(stub.z0()),
+                }
+            }
+            Lumped::Capacitor(cap) => {
+                let stub = richards_capacitor(cap, freq, er, length_unit);
+                match cap.orientation() {
+                    Orientation::Series => Section::SeriesOpen(stub.z0()),
+                    Orientation::Shunt => Section::ShuntOpen(stub.z0()),
+                }
+            }
+        })
+        .collect();
+
+    if let Some(i) = sections
+        .iter()
+        .position(|val| matches!(val, Section::SeriesOpen(_)))
+    {
+        return Err(Error::Unrealizable(format!(
+            "series capacitor in section {} cannot be converted to a shunt stub without an ideal transformer",
+            i
+        )));
+    }
+
+    // Unit elements matched to the terminations are redundant, so any number can be added at
+    // either end of the ladder and moved inwards with Kuroda identities. Every series stub has
+    // to be crossed an odd number of times, so pick the split between the unit elements
+    // entering from the left and from the right that needs the fewest
+    let (left, right) = (0..=sections.len())
+        .filter_map(|k| {
+            Some((
+                min_crossings(sections[..k].iter().rev())?,
+                min_crossings(sections[k..].iter())?,
+            ))
+        })
+        .min_by_key(|(left, right)| left.last().unwrap_or(&0) + right.last().unwrap_or(&0))
+        .ok_or_else(|| {
+            Error::Unrealizable(
+                "every series inductor must exchange with a unit element, but a shunt inductor blocks them from reaching it"
+                    .to_string(),
+            )
+        })?;
+
+    let n_left = *left.last().unwrap_or(&0);
+    let n_right = *right.last().unwrap_or(&0);
+    let n = sections.len();
+    sections.splice(0..0, vec![Section::UnitElement(z0); n_left]);
+    sections.extend(vec![Section::UnitElement(z0); n_right]);
+
+    // The innermost unit element travels furthest, once it has moved the next one follows
+    for m in 1..=n_left {
+        let pos = n_left - m;
+        for step in 0..left.iter().filter(|&&val| val >= m).count() {
+            exchange(&mut sections, pos + step, pos + step + 1);
+        }
+    }
+    for m in 1..=n_right {
+        let pos = n_left + n + m - 1;
+        for step in 0..right.iter().filter(|&&val| val >= m).count() {
+            exchange(&mut sections, pos - step, pos - step - 1);
+        }
+    }
+
+    let length = commensurate_length(freq, er, &length_unit);
+
+    Ok(sections
+        .iter()
+        .map(|val| match *val {
+            Section::ShuntShort(zc) => {
+                Distributed::ShortedStub(ShortedStub::new(zc, er, length, length_unit))
+            }
+            Section::ShuntOpen(zc) => {
+                Distributed::OpenStub(OpenStub::new(zc, c64(z0, 0.0), er, length, length_unit))
+            }
+            Section::UnitElement(zc) => {
+                Distributed::TLine(TLine::new(zc, c64(z0, 0.0), er, length, length_unit))
+            }
+            Section::SeriesShort(_) | Section::SeriesOpen(_) => unreachable!(),
+        })
+        .collect())
+}
+
+// Distributed element in the form of a Smith chart schematic element, length in length_unit
+#[derive(Serialize, Debug, PartialEq)]
+pub struct DistributedReturn {
+    pub element: ElementType,
+    pub z0: f64,
+    pub er: f64,
+    pub length: f64,
+}
+
+// Lumped L and C elements given as in calc_smith_cascade, [q, val] with their units. Only the
+// values are used, Richards' transformation is of the ideal elements
+fn gen_lumped(element: ElementType, vals: &[f64], units: &[&str]) -> Result<Lumped, Error> {
+    let orientation = match element {
+        ElementType::SeriesInd | ElementType::SeriesCap => Orientation::Series,
+        _ => Orientation::Shunt,
+    };
+    match element {
+        ElementType::SeriesInd | ElementType::ShuntInd => {
+            let (unit, _) = element_trailing::<2>(element, vals, units, 2)?;
+            Ok(Lumped::Inductor(Inductor::new(
+                0.0,
+                vals[1],
+                Unit::Base,
+                unit[1],
+                0.0,
+                0.0,
+                orientation,
+            )))
+        }
+        ElementType::SeriesCap | ElementType::ShuntCap => {
+            let (unit, _) = element_trailing::<2>(element, vals, units, 2)?;
+            Ok(Lumped::Capacitor(Capacitor::new(
+                0.0,
+                vals[1],
+                Unit::Base,
+                unit[1],
+                0.0,
+                0.0,
+                orientation,
+            )))
+        }
+        _ => Err(Error::InvalidInput(format!(
+            "element {} is not an inductor or capacitor",
+            element
+        ))),
+    }
+}
+
+pub fn calc_richards(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    er: f64,
+    length_unit: Unit,
+) -> Result<Vec<DistributedReturn>, Error> {
+    if elements.len() != vals.len() || elements.len() != units.len() {
+        return Err(Error::InvalidInput(format!(
+            "got {} elements, {} value lists and {} unit lists",
+            elements.len(),
+            vals.len(),
+            units.len()
+        )));
+    }
+    if z0 <= 0.0 || freq <= 0.0 || er < 1.0 {
+        return Err(Error::InvalidInput(
+            "z0 and freq must be positive and er at least 1".to_string(),
+        ));
+    }
+
+    let ladder = elements
+        .iter()
+        .zip(vals.iter().zip(units.iter()))
+        .enumerate()
+        .map(|(i, (element, (vals, units)))| {
+            gen_lumped(*element, vals, units).map_err(|err| err.context(&format!("element {}", i)))
+        })
+        .collect::<Result<Vec<Lumped>, Error>>()?;
+
+    let out = richards_kuroda(
+        &ladder,
+        z0,
+        Frequency::new(freq, freq_unit),
+        er,
+        length_unit,
+    )?;
+
+    Ok(out
+        .iter()
+        .map(|val| {
+            let (element, z0, length) = match val {
+                Distributed::OpenStub(stub) => (ElementType::OpenStub, stub.z0(), stub.length()),
+                Distributed::ShortedStub(stub) => {
+                    (ElementType::ShortedStub, stub.z0(), stub.length())
+                }
+                Distributed::TLine(line) => (ElementType::TLine, line.z0(), line.length()),
+            };
+            DistributedReturn {
+                element,
+                z0,
+                er,
+                length,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    type Abcd = [[Complex<f64>; 2]; 2];
+
+    fn mult(a: Abcd, b: Abcd) -> Abcd {
+        [
+            [
+                a[0][0] * b[0][0] + a[0][1] * b[1][0],
+                a[0][0] * b[0][1] + a[0][1] * b[1][1],
+            ],
+            [
+                a[1][0] * b[0][0] + a[1][1] * b[1][0],
+                a[1][0] * b[0][1] + a[1][1] * b[1][1],
+            ],
+        ]
+    }
+
+    fn abcd(section: &Section, t: f64) -> Abcd {
+        let s = c64(0.0, t);
+        let one = Complex::ONE;
+        let zero = Complex::ZERO;
+        match *section {
+            Section::SeriesShort(z) => [[one, s * z], [zero, one]],
+            Section::SeriesOpen(z) => [[one, z / s], [zero, one]],
+            Section::ShuntShort(z) => [[one, zero], [(s * z).inv(), one]],
+            Section::ShuntOpen(z) => [[one, zero], [s / z, one]],
+            Section::UnitElement(z) => {
+                let k = (one - s * s).sqrt().inv();
+                [[k, k * s * z], [k * s / z, k]]
+            }
+        }
+    }
+
+    #[test]
+    fn test_richards_inductor() {
+        let testname = "richards_inductor";
+        let freq = Frequency::new(2.0, Unit::Giga);
+        let ind = Inductor::new(
+            0.0,
+            1.0,
+            Unit::Base,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        let stub = richards_inductor(&ind, freq, 1.0, Unit::Milli);
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_f64(
+            &stub.z0(),
+            &(2.0 * std::f64::consts::PI * 2.0),
+            margin,
+            testname,
+            "z0()",
+        );
+        comp_f64(&stub.length(), &18.75, margin, testname, "length()");
+        assert_eq!(stub.orientation(), Orientation::Series);
+        comp_c64(&stub.z(freq), &ind.z(freq), margin, testname, "z");
+    }
+
+    #[test]
+    fn test_richards_capacitor() {
+        let testname = "richards_capacitor";
+        let freq = Frequency::new(280.0, Unit::Giga);
+        let cap = Capacitor::new(
+            0.0,
+            20.0,
+            Unit::Q,
+            Unit::Femto,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+        let stub = richards_capacitor(&cap, freq, 3.4, Unit::Micro);
+
+        let margin = F64Margin::from((1e-12, 4));
+
+        comp_f64(&stub.z0(), &28.420525552124168, margin, testname, "z0()");
+        comp_f64(
+            &stub.length(),
+            &(3e8 / (280e9 * 3.4_f64.sqrt()) / 8.0 * 1e6),
+            margin,
+            testname,
+            "length()",
+        );
+        assert_eq!(stub.orientation(), Orientation::Shunt);
+        comp_c64(&stub.z(freq), &cap.z(freq), margin, testname, "z");
+    }
+
+    #[test]
+    fn test_kuroda_identities() {
+        let testname = "kuroda_identities";
+        let margin = F64Margin::from((1e-12, 4));
+        let (zs, zp, zu) = (37.0, 23.0, 61.0);
+
+        for t in [0.3, 1.0, 2.7] {
+            let (ue, stub) = kuroda_series_short(zs, zu);
+            let left = mult(
+                abcd(&Section::SeriesShort(zs), t),
+                abcd(&Section::UnitElement(zu), t),
+            );
+            let right = mult(
+                abcd(&Section::UnitElement(ue), t),
+                abcd(&Section::ShuntOpen(stub), t),
+            );
+            for i in 0..2 {
+                for j in 0..2 {
+                    comp_c64(&left[i][j], &right[i][j], margin, testname, "series_short");
+                }
+            }
+
+            let (ue, stub) = kuroda_shunt_open(zp, zu);
+            let left = mult(
+                abcd(&Section::ShuntOpen(zp), t),
+                abcd(&Section::UnitElement(zu), t),
+            );
+            let right = mult(
+                abcd(&Section::UnitElement(ue), t),
+                abcd(&Section::SeriesShort(stub), t),
+            );
+            for i in 0..2 {
+                for j in 0..2 {
+                    comp_c64(&left[i][j], &right[i][j], margin, testname, "shunt_open");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_richards_kuroda() {
+        let testname = "richards_kuroda";
+        let freq = Frequency::new(4.0, Unit::Giga);
+        let z0 = 50.0;
+        let w = freq.w();
+        // 3rd order maximally flat low pass, g = [1, 2, 1]
+        let ladder = vec![
+            Lumped::Inductor(Inductor::new(
+                0.0,
+                z0 / w * 1e9,
+                Unit::Base,
+                Unit::Nano,
+                0.0,
+                0.0,
+                Orientation::Series,
+            )),
+            Lumped::Capacitor(Capacitor::new(
+                0.0,
+                2.0 / (z0 * w) * 1e12,
+                Unit::Base,
+                Unit::Pico,
+                0.0,
+                0.0,
+                Orientation::Shunt,
+            )),
+            Lumped::Inductor(Inductor::new(
+                0.0,
+                z0 / w * 1e9,
+                Unit::Base,
+                Unit::Nano,
+                0.0,
+                0.0,
+                Orientation::Series,
+            )),
+        ];
+        let test = richards_kuroda(&ladder, z0, freq, 1.0, Unit::Milli).unwrap();
+
+        let margin = F64Margin::from((1e-12, 4));
+        let length = 3e8 / 4e9 / 8.0 * 1e3;
+        let exemplar = [
+            ("open", 100.0),
+            ("tline", 100.0),
+            ("open", 25.0),
+            ("tline", 100.0),
+            ("open", 100.0),
+        ];
+
+        assert_eq!(test.len(), exemplar.len());
+        for (i, (val, (kind, zc))) in test.iter().zip(exemplar.iter()).enumerate() {
+            let (z, l, orientation) = match (val, *kind) {
+                (Distributed::OpenStub(stub), "open") => {
+                    (stub.z0(), stub.length(), Orientation::Shunt)
+                }
+                (Distributed::TLine(line), "tline") => {
+                    (line.z0(), line.length(), Orientation::Series)
+                }
+                _ => panic!(" Failed test {} at location [{}]: {:?}", testname, i, val),
+            };
+            comp_f64(&z, zc, margin, testname, &format!("z0[{}]", i));
+            comp_f64(&l, &length, margin, testname, &format!("length[{}]", i));
+            assert_eq!(val.element().orientation(), orientation);
+        }
+    }
+
+    #[test]
+    fn test_richards_kuroda_5th_order() {
+        let testname = "richards_kuroda_5th_order";
+        let freq = Frequency::new(4.0, Unit::Giga);
+        let z0 = 50.0;
+        let w = freq.w();
+        // 5th order maximally flat low pass
+        let g = [0.618034, 1.618034, 2.0, 1.618034, 0.618034];
+        let ladder: Vec<Lumped> = g
+            .iter()
+            .enumerate()
+            .map(|(i, g)| {
+                if i % 2 == 0 {
+                    Lumped::Inductor(Inductor::new(
+                        0.0,
+                        g * z0 / w * 1e9,
+                        Unit::Base,
+                        Unit::Nano,
+                        0.0,
+                        0.0,
+                        Orientation::Series,
+                    ))
+                } else {
+                    Lumped::Capacitor(Capacitor::new(
+                        0.0,
+                        g / (z0 * w) * 1e12,
+                        Unit::Base,
+                        Unit::Pico,
+                        0.0,
+                        0.0,
+                        Orientation::Shunt,
+                    ))
+                }
+            })
+            .collect();
+        let test = richards_kuroda(&ladder, z0, freq, 1.0, Unit::Milli).unwrap();
+
+        // Shunt open stubs separated by unit elements
+        assert_eq!(test.len(), 9);
+        let sections: Vec<Section> = test
+            .iter()
+            .enumerate()
+            .map(|(i, val)| match (i % 2, val) {
+                (0, Distributed::OpenStub(stub)) => Section::ShuntOpen(stub.z0()),
+                (1, Distributed::TLine(line)) => Section::UnitElement(line.z0()),
+                _ => panic!(" Failed test {} at location [{}]: {:?}", testname, i, val),
+            })
+            .collect();
+
+        // The unit elements only add phase, so the transmission magnitude matches the stubs
+        // from Richards' transformation alone
+        let richards: Vec<Section> = g
+            .iter()
+            .enumerate()
+            .map(|(i, g)| {
+                if i % 2 == 0 {
+                    Section::SeriesShort(g * z0)
+                } else {
+                    Section::ShuntOpen(z0 / g)
+                }
+            })
+            .collect();
+
+        let margin = F64Margin::from((1e-9, 4));
+        let s21 = |sections: &[Section], t: f64| {
+            let m = sections.iter().fold(
+                [[Complex::ONE, Complex::ZERO], [Complex::ZERO, Complex::ONE]],
+                |acc, val| mult(acc, abcd(val, t)),
+            );
+            (2.0 / (m[0][0] + m[0][1] / z0 + m[1][0] * z0 + m[1][1])).norm()
+        };
+        for t in [0.3, 1.0, 2.7] {
+            comp_f64(
+                &s21(&sections, t),
+                &s21(&richards, t),
+                margin,
+                testname,
+                &format!("|s21| at tan = {}", t),
+            );
+        }
+        comp_f64(
+            &s21(&sections, 1.0),
+            &0.5_f64.sqrt(),
+            F64Margin::from((1e-6, 4)),
+            testname,
+            "|s21| at cutoff",
+        );
+    }
+
+    #[test]
+    fn test_calc_richards() {
+        let testname = "calc_richards";
+        // The 3rd order low pass of test_richards_kuroda as Smith chart elements
+        let w = 2.0 * std::f64::consts::PI * 4e9;
+        let ind = 50.0 / w * 1e9;
+        let test = calc_richards(
+            vec![
+                ElementType::SeriesInd,
+                ElementType::ShuntCap,
+                ElementType::SeriesInd,
+            ],
+            vec![
+                vec![0.0, ind],
+                vec![0.0, 2.0 / (50.0 * w) * 1e12, 0.1, 0.0],
+                vec![0.0, ind],
+            ],
+            vec![
+                vec!["Q", "nH"],
+                vec!["Q", "pF", "nH", "GHz"],
+                vec!["Q", "nH"],
+            ],
+            50.0,
+            4.0,
+            Unit::Giga,
+            1.0,
+            Unit::Milli,
+        )
+        .unwrap();
+
+        let margin = F64Margin::from((1e-12, 4));
+        let exemplar = [
+            (ElementType::OpenStub, 100.0),
+            (ElementType::TLine, 100.0),
+            (ElementType::OpenStub, 25.0),
+            (ElementType::TLine, 100.0),
+            (ElementType::OpenStub, 100.0),
+        ];
+        assert_eq!(test.len(), exemplar.len());
+        for (i, (val, (element, z0))) in test.iter().zip(exemplar.iter()).enumerate() {
+            assert_eq!(val.element, *element);
+            comp_f64(&val.z0, z0, margin, testname, &format!("z0[{}]", i));
+            comp_f64(&val.er, &1.0, margin, testname, &format!("er[{}]", i));
+            comp_f64(
+                &val.length,
+                &(3e8 / 4e9 / 8.0 * 1e3),
+                margin,
+                testname,
+                &format!("length[{}]", i),
+            );
+        }
+
+        assert!(calc_richards(
+            vec![ElementType::SeriesRes],
+            vec![vec![50.0]],
+            vec![vec![""]],
+            50.0,
+            4.0,
+            Unit::Giga,
+            1.0,
+            Unit::Milli,
+        )
+        .is_err());
+        assert!(calc_richards(
+            vec![ElementType::SeriesInd],
+            vec![],
+            vec![],
+            50.0,
+            4.0,
+            Unit::Giga,
+            1.0,
+            Unit::Milli,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_richards_kuroda_unrealizable() {
+        let freq = Frequency::new(4.0, Unit::Giga);
+        let cap = Capacitor::new(
+            0.0,
+            1.0,
+            Unit::Base,
+            Unit::Pico,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+        let ind = Inductor::new(
+            0.0,
+            1.0,
+            Unit::Base,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+        let series_cap = Capacitor::new(
+            0.0,
+            1.0,
+            Unit::Base,
+            Unit::Pico,
+            0.0,
+            0.0,
+            Orientation::Series,
+        );
+
+        let shunt_ind = Inductor::new(
+            0.0,
+            1.0,
+            Unit::Base,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+
+        // The series inductor can take a unit element from the terminations through the shunt
+        // capacitors, but not through shunt inductors
+        let interior = vec![
+            Lumped::Capacitor(cap),
+            Lumped::Inductor(ind),
+            Lumped::Capacitor(cap),
+        ];
+        assert!(richards_kuroda(&interior, 50.0, freq, 1.0, Unit::Milli).is_ok());
+
+        let blocked = vec![
+            Lumped::Inductor(shunt_ind),
+            Lumped::Inductor(ind),
+            Lumped::Inductor(shunt_ind),
+        ];
+        assert!(richards_kuroda(&blocked, 50.0, freq, 1.0, Unit::Milli).is_err());
+
+        let series = vec![Lumped::Capacitor(series_cap), Lumped::Capacitor(cap)];
+        assert!(richards_kuroda(&series, 50.0, freq, 1.0, Unit::Milli).is_err());
+
+        assert!(richards_kuroda(&[], 50.0, freq, 1.0, Unit::Milli).is_err());
+    }
+}
@@ -0,0 +1,436 @@
+use crate::element::ElementType;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+// Edits kept for undo, the oldest are dropped first
+pub const HISTORY_DEPTH: usize = 100;
+
+// The first two entries of a Smith chart schematic are the source settings and the black box
+// load, neither of which can be added, removed, moved or flipped
+const FIXED: usize = 2;
+
+// One undoable change to the schematic. Applying an edit returns the edit that reverts it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Edit {
+    Add {
+        index: usize,
+        element: Value,
+    },
+    Remove {
+        index: usize,
+    },
+    Move {
+        from: usize,
+        to: usize,
+    },
+    // A null value removes the field
+    Set {
+        index: usize,
+        field: String,
+        value: Value,
+    },
+    // Series <-> shunt
+    Flip {
+        index: usize,
+    },
+    Batch {
+        edits: Vec<Edit>,
+    },
+}
+
+fn check_index(index: usize, min: usize, len: usize) -> Result<(), Error> {
+    if index < min || index >= len {
+        return Err(Error::InvalidInput(format!(
+            "element {} is not an editable element of the schematic",
+            index
+        )));
+    }
+    Ok(())
+}
+
+fn flipped(kind: &str) -> Option<&'static str> {
+    match kind {
+        "si" => Some("pi"),
+        "pi" => Some("si"),
+        "sc" => Some("pc"),
+        "pc" => Some("sc"),
+        "sr" => Some("pr"),
+        "pr" => Some("sr"),
+        "srlc" => Some("prlc"),
+        "prlc" => Some("srlc"),
+        _ => None,
+    }
+}
+
+fn element_type(element: &Value) -> Option<&str> {
+    element.get("type").and_then(Value::as_str)
+}
+
+impl Edit {
+    pub fn apply(self, elements: &mut Vec<Value>) -> Result<Edit, Error> {
+        match self {
+            Edit::Add { index, element } => {
+                check_index(index, FIXED, elements.len() + 1)?;
+                match element_type(&element).map(|val| val.parse::<ElementType>()) {
+                    Some(Ok(ElementType::BlackBox)) => {
+                        return Err(Error::InvalidInput(
+                            "the schematic has a single black box".to_string(),
+                        ))
+                    }
+                    Some(Ok(_)) if element.is_object() => {}
+                    Some(Err(err)) => return Err(err),
+                    _ => return Err(Error::InvalidInput("element has no type".to_string())),
+                }
+                elements.insert(index, element);
+                Ok(Edit::Remove { index })
+            }
+            Edit::Remove { index } => {
+                check_index(index, FIXED, elements.len())?;
+                let element = elements.remove(index);
+                Ok(Edit::Add { index, element })
+            }
+            Edit::Move { from, to } => {
+                check_index(from, FIXED, elements.len())?;
+                check_index(to, FIXED, elements.len())?;
+                let element = elements.remove(from);
+                elements.insert(to, element);
+                Ok(Edit::Move { from: to, to: from })
+            }
+            Edit::Set {
+                index,
+                field,
+                value,
+            } => {
+                check_index(index, 0, elements.len())?;
+                if field == "type" {
+                    return Err(Error::InvalidInput(
+                        "the element type only changes by flipping it".to_string(),
+                    ));
+                }
+                let element = elements[index].as_object_mut().ok_or_else(|| {
+                    Error::InvalidInput(format!("element {} is not an object", index))
+                })?;
+                let old = if value.is_null() {
+                    element.remove(&field)
+                } else {
+                    element.insert(field.clone(), value)
+                };
+                Ok(Edit::Set {
+                    index,
+                    field,
+                    value: old.unwrap_or(Value::Null),
+                })
+            }
+            Edit::Flip { index } => {
+                check_index(index, FIXED, elements.len())?;
+                let kind = element_type(&elements[index]).unwrap_or_default();
+                let new = flipped(kind).ok_or_else(|| {
+                    Error::InvalidInput(format!("{:?} has no series or shunt counterpart", kind))
+                })?;
+                elements[index]["type"] = Value::from(new);
+                Ok(Edit::Flip { index })
+            }
+            Edit::Batch { edits } => {
+                let mut undo = vec![];
+                for edit in edits {
+                    match edit.apply(elements) {
+                        Ok(val) => undo.push(val),
+                        Err(err) => {
+                            // Leave the schematic as it was before the batch
+                            for val in undo.into_iter().rev() {
+                                val.apply(elements)?;
+                            }
+                            return Err(err);
+                        }
+                    }
+                }
+                undo.reverse();
+                Ok(Edit::Batch { edits: undo })
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct SchematicReturn {
+    pub elements: Vec<Value>,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+// A Smith chart schematic with a bounded undo/redo history of its edits
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schematic {
+    elements: Vec<Value>,
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    depth: usize,
+}
+
+impl Schematic {
+    pub fn new(elements: Vec<Value>, depth: usize) -> Self {
+        Schematic {
+            elements,
+            undo: VecDeque::new(),
+            redo: vec![],
+            depth,
+        }
+    }
+
+    pub fn elements(&self) -> &[Value] {
+        &self.elements
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn to_return(&self) -> SchematicReturn {
+        SchematicReturn {
+            elements: self.elements.clone(),
+            can_undo: self.can_undo(),
+            can_redo: self.can_redo(),
+        }
+    }
+
+    // Take changes made outside of the history, like the frequency or z0 of the source. The
+    // recorded edits address elements by index, so they are dropped once the elements shift.
+    pub fn sync(&mut self, elements: Vec<Value>) {
+        if elements.len() != self.elements.len() {
+            self.undo.clear();
+            self.redo.clear();
+        }
+        self.elements = elements;
+    }
+
+    pub fn edit(&mut self, edit: Edit) -> Result<(), Error> {
+        let undo = edit.apply(&mut self.elements)?;
+        if self.undo.len() == self.depth {
+            self.undo.pop_front();
+        }
+        if self.depth > 0 {
+            self.undo.push_back(undo);
+        }
+        self.redo.clear();
+        Ok(())
+    }
+
+    // False when there is nothing to undo
+    pub fn undo(&mut self) -> Result<bool, Error> {
+        let Some(edit) = self.undo.pop_back() else {
+            return Ok(false);
+        };
+        self.redo.push(edit.apply(&mut self.elements)?);
+        Ok(true)
+    }
+
+    // False when there is nothing to redo
+    pub fn redo(&mut self) -> Result<bool, Error> {
+        let Some(edit) = self.redo.pop() else {
+            return Ok(false);
+        };
+        self.undo.push_back(edit.apply(&mut self.elements)?);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn start() -> Vec<Value> {
+        vec![
+            json!({"type": "raw", "z0": 50, "freq": 280}),
+            json!({"type": "bb", "abs": [50, 0], "unit": ["null"], "tol": 0}),
+        ]
+    }
+
+    fn types(schem: &Schematic) -> Vec<&str> {
+        schem
+            .elements()
+            .iter()
+            .map(|val| element_type(val).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut schem = Schematic::new(start(), HISTORY_DEPTH);
+        let cap = json!({"type": "sc", "abs": [0, 20], "unit": ["Q", "fF"], "tol": 0});
+        let ind = json!({"type": "pi", "abs": [20, 10], "unit": ["Q", "pH"], "tol": 0});
+        schem
+            .edit(Edit::Add {
+                index: 2,
+                element: cap,
+            })
+            .unwrap();
+        schem
+            .edit(Edit::Add {
+                index: 3,
+                element: ind,
+            })
+            .unwrap();
+        schem.edit(Edit::Move { from: 3, to: 2 }).unwrap();
+        assert_eq!(types(&schem), vec!["raw", "bb", "pi", "sc"]);
+
+        schem.edit(Edit::Flip { index: 2 }).unwrap();
+        schem
+            .edit(Edit::Set {
+                index: 3,
+                field: "abs".to_string(),
+                value: json!([0, 47]),
+            })
+            .unwrap();
+        assert_eq!(types(&schem), vec!["raw", "bb", "si", "sc"]);
+        assert_eq!(schem.elements()[3]["abs"], json!([0, 47]));
+
+        // Everything unwinds back to the start and replays to the end
+        let end = schem.elements().to_vec();
+        while schem.undo().unwrap() {}
+        assert_eq!(schem.elements(), start().as_slice());
+        assert!(!schem.can_undo());
+        while schem.redo().unwrap() {}
+        assert_eq!(schem.elements(), end.as_slice());
+
+        // A new edit drops what could be redone
+        schem.undo().unwrap();
+        schem.edit(Edit::Remove { index: 3 }).unwrap();
+        assert!(!schem.can_redo());
+        assert_eq!(types(&schem), vec!["raw", "bb", "si"]);
+    }
+
+    #[test]
+    fn test_edit_errors() {
+        let mut schem = Schematic::new(start(), HISTORY_DEPTH);
+        let bb = json!({"type": "bb", "abs": [50, 0]});
+        let codes = [
+            Edit::Remove { index: 1 },
+            Edit::Flip { index: 1 },
+            Edit::Add {
+                index: 1,
+                element: json!({"type": "sc"}),
+            },
+            Edit::Add {
+                index: 2,
+                element: bb,
+            },
+            Edit::Set {
+                index: 1,
+                field: "type".to_string(),
+                value: json!("sc"),
+            },
+        ]
+        .into_iter()
+        .map(|val| schem.edit(val).unwrap_err().code().to_string())
+        .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["invalid_input"; 5]);
+        assert_eq!(
+            schem
+                .edit(Edit::Add {
+                    index: 2,
+                    element: json!({"type": "xx"}),
+                })
+                .unwrap_err()
+                .code(),
+            "parse"
+        );
+        assert!(!schem.can_undo());
+
+        // A failing batch leaves the schematic untouched
+        let batch = Edit::Batch {
+            edits: vec![
+                Edit::Add {
+                    index: 2,
+                    element: json!({"type": "tl", "abs": [1]}),
+                },
+                Edit::Flip { index: 2 },
+            ],
+        };
+        assert!(schem.edit(batch).is_err());
+        assert_eq!(schem.elements(), start().as_slice());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut schem = Schematic::new(start(), 2);
+        for val in [1, 2, 3] {
+            schem
+                .edit(Edit::Set {
+                    index: 1,
+                    field: "tol".to_string(),
+                    value: json!(val),
+                })
+                .unwrap();
+        }
+        // Only the last two edits can be undone
+        while schem.undo().unwrap() {}
+        assert_eq!(schem.elements()[1]["tol"], json!(1));
+
+        // A batch undoes as one and a null value removes the field it added
+        let mut schem = Schematic::new(start(), HISTORY_DEPTH);
+        let batch = Edit::Batch {
+            edits: vec![
+                Edit::Set {
+                    index: 1,
+                    field: "abs_bb_i".to_string(),
+                    value: json!(5),
+                },
+                Edit::Set {
+                    index: 1,
+                    field: "tol".to_string(),
+                    value: json!(10),
+                },
+            ],
+        };
+        schem.edit(batch).unwrap();
+        assert_eq!(schem.elements()[1]["tol"], json!(10));
+        schem.undo().unwrap();
+        assert_eq!(schem.elements(), start().as_slice());
+
+        // Changing the element count outside of the history forgets it
+        schem.redo().unwrap();
+        schem.undo().unwrap();
+        schem.redo().unwrap();
+        let mut elements = schem.elements().to_vec();
+        elements[0]["freq"] = json!(100);
+        schem.sync(elements.clone());
+        assert!(schem.can_undo());
+        elements.pop();
+        schem.sync(elements);
+        assert!(!schem.can_undo());
+
+        let test = serde_json::from_value::<Edit>(json!({"op": "move", "from": 2, "to": 3}));
+        assert_eq!(test.unwrap(), Edit::Move { from: 2, to: 3 });
+    }
+
+    #[test]
+    fn test_parasitics_batch() {
+        // A capacitor saved before it had parasitics gets them filled in with the value the user
+        // typed, as one step of the history
+        let mut elements = start();
+        elements.push(json!({"type": "sc", "abs": [0, 20], "unit": ["Q", "fF"], "tol": 0}));
+        let mut schem = Schematic::new(elements.clone(), HISTORY_DEPTH);
+        let batch = serde_json::from_value::<Edit>(json!({"op": "batch", "edits": [
+            {"op": "set", "index": 2, "field": "unit", "value": ["Q", "fF", "pH", "GHz"]},
+            {"op": "set", "index": 2, "field": "abs", "value": [0, 20, 0.5, 0]},
+        ]}))
+        .unwrap();
+        schem.edit(batch).unwrap();
+        assert_eq!(schem.elements()[2]["abs"], json!([0, 20, 0.5, 0]));
+        assert_eq!(schem.elements()[2]["unit"], json!(["Q", "fF", "pH", "GHz"]));
+
+        assert!(schem.undo().unwrap());
+        assert_eq!(schem.elements(), elements.as_slice());
+        assert!(!schem.can_undo());
+        assert!(schem.redo().unwrap());
+        assert_eq!(schem.elements()[2]["abs"], json!([0, 20, 0.5, 0]));
+    }
+}
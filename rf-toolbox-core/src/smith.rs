@@ -1,18 +1,30 @@
 #![allow(unused)]
 use crate::element::{
-    blackbox::BlackBox, capacitor::Capacitor, inductor::Inductor, openstub::OpenStub,
-    resistor::Resistor, rlc::Rlc, shortedstub::ShortedStub, tline::TLine, transformer::Transformer,
-    Element, Orientation,
+    balun::{Balun, BalunModel, BalunType},
+    blackbox::BlackBox,
+    capacitor::Capacitor,
+    inductor::Inductor,
+    measured::{Measured, PartFile},
+    openstub::OpenStub,
+    resistor::Resistor,
+    rlc::Rlc,
+    shortedstub::ShortedStub,
+    tline::TLine,
+    transformer::Transformer,
+    Element, ElementType, Orientation, SmithArc,
 };
+use crate::error::Error;
+use crate::freq_data::{Extrapolation, FreqData, Interpolation};
 use crate::frequency::Frequency;
-use crate::rf_utils::{calc_z, comp_c64, comp_f64, comp_vec_f64, scale, unscale, ComplexReturn};
+use crate::mna::CoupledLine;
+use crate::rf_utils::{
+    calc_z, comp_c64, comp_f64, comp_vec_f64, scale, unscale, ComplexReturn, ZScale,
+};
 use crate::unit::Unit;
 use float_cmp::{approx_eq, F64Margin};
 use num_complex::{c64, Complex};
-use serde::Serialize;
-use std::error::Error;
+use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
-use std::f64::{EPSILON, NAN};
 use std::str::FromStr;
 use std::string::ToString;
 
@@ -58,7 +70,7 @@ pub fn find_smith_coord(
     im: f64,
     rotate: bool,
     verbose: bool,
-) -> Result<Complex<f64>, String> {
+) -> Result<Complex<f64>, Error> {
     if verbose {
         println!("\nfind_smith_coord({:?}, {:?}, {:?})", re, im, rotate);
     }
@@ -85,7 +97,7 @@ pub fn find_smith_coord_c64(
     val: Complex<f64>,
     rotate: bool,
     verbose: bool,
-) -> Result<Complex<f64>, String> {
+) -> Result<Complex<f64>, Error> {
     if verbose {
         println!("\nfind_smith_coord_c64({:?}, {:?})", val, rotate);
     }
@@ -93,13 +105,12 @@ pub fn find_smith_coord_c64(
     find_smith_coord(val.re, val.im, rotate, verbose)
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn find_smith_coord_js(
     re: f64,
     im: f64,
     rotate: bool,
     verbose: bool,
-) -> Result<Vec<f64>, String> {
+) -> Result<Vec<f64>, Error> {
     if verbose {
         println!("\nfind_smith_coord({:?}, {:?}, {:?})", re, im, rotate);
     }
@@ -122,20 +133,46 @@ pub fn find_smith_coord_js(
     Ok(vec![g.re, g.im])
 }
 
-#[tauri::command(rename_all = "snake_case")]
+// Path traced by arc_smith_points: along a line or stub, or a straight sweep in z or y for lumped elements
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum ArcType {
+    #[serde(rename = "transmission_line", alias = "tl")]
+    TransmissionLine,
+    #[serde(rename = "ss")]
+    ShortedStub,
+    #[serde(rename = "so")]
+    OpenStub,
+    #[serde(
+        rename = "lumped",
+        alias = "bb",
+        alias = "sr",
+        alias = "pr",
+        alias = "sc",
+        alias = "pc",
+        alias = "si",
+        alias = "pi",
+        alias = "xfmr",
+        alias = "rlc",
+        alias = "rl",
+        alias = "rc",
+        alias = "customZ"
+    )]
+    Lumped,
+}
+
 pub fn arc_smith_points(
     x1: f64,
     y1: f64,
     x2: f64,
     y2: f64,
-    type_: &str,
+    type_: ArcType,
     rotate: bool,
     beta: f64,
     start_at_qtr_wl: f64,
     z0: f64,
     resolution: usize,
     verbose: bool,
-) -> Result<ArcReturn, String> {
+) -> Result<ArcReturn, Error> {
     if verbose {
         println!(
             "\narc_smith_points({:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?})",
@@ -163,7 +200,7 @@ pub fn arc_smith_points(
     let zl: Complex<f64> = calc_z(Complex::new(start_x_coord, start_y_coord), z0);
 
     for i in 0..=resolution {
-        if type_ == "transmission_line" {
+        if type_ == ArcType::TransmissionLine {
             let betal = (beta * (i as f64) * line_length) / (resolution as f64);
             let zi = line_zo
                 * ((zl + Complex::<f64>::I * line_zo * betal.tan())
@@ -175,20 +212,19 @@ pub fn arc_smith_points(
             y_coord[i] = temp_array.im;
             real_answer = zi.re;
             imag_answer = zi.im;
-        } else if type_ == "ss" {
+        } else if type_ == ArcType::ShortedStub {
             let tan_beta = match approx_eq!(f64, start_at_qtr_wl, 0_f64, F64Margin::default()) {
                 true => ((beta * (i as f64) * line_length) / (resolution as f64)).tan(),
                 false => (beta
-                    * ((start_at_qtr_wl as f64)
-                        + ((i as f64) * (line_length - (start_at_qtr_wl as f64)))
-                            / (resolution as f64)))
+                    * (start_at_qtr_wl
+                        + ((i as f64) * (line_length - start_at_qtr_wl)) / (resolution as f64)))
                     .tan(),
             };
             stub_admittance_im = -1.0 / ((tan_beta * line_zo) / z0);
             temp_array = find_smith_coord(x1, y1 + stub_admittance_im, rotate, false).unwrap();
             x_coord[i] = temp_array.re;
             y_coord[i] = temp_array.im;
-        } else if type_ == "so" {
+        } else if type_ == ArcType::OpenStub {
             let tan_beta_arg = (beta * (i as f64) * line_length) / (resolution as f64);
             stub_admittance_im = tan_beta_arg.tan() / (line_zo / z0);
             temp_array = find_smith_coord(x1, y1 + stub_admittance_im, rotate, false).unwrap();
@@ -207,11 +243,11 @@ pub fn arc_smith_points(
         }
     }
 
-    if type_ == "transmission_line" {
+    if type_ == ArcType::TransmissionLine {
         temp_array = find_smith_coord(real_answer, imag_answer, rotate, false).unwrap();
         real_old = real_answer;
         imag_old = imag_answer;
-    } else if (type_ == "so") || (type_ == "ss") {
+    } else if (type_ == ArcType::OpenStub) || (type_ == ArcType::ShortedStub) {
         real_old = x1;
         imag_old = y1 + stub_admittance_im;
     }
@@ -231,7 +267,7 @@ pub fn arc_smith_points(
         print!("]\nypts = [");
         for i in 0..y_coord.len() {
             print!("{}", y_coord[i]);
-            if i != x_coord.len() - 1 {
+            if i != y_coord.len() - 1 {
                 print!(", ");
             }
         }
@@ -265,17 +301,19 @@ pub fn arc_smith_points(
     })
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn calc_ri(
     vals: Vec<f64>,
     units: Vec<&str>,
     lut: Vec<[f64; 3]>,
-    type_: &str,
+    interp: Interpolation,
+    extrap: Extrapolation,
+    part: Option<PartFile>,
+    type_: ElementType,
     freq: f64,
     z0: f64,
     diff: bool,
     verbose: bool,
-) -> Result<Vec<f64>, String> {
+) -> Result<Vec<f64>, Error> {
     if verbose {
         print!("\ncalc_ri([");
         for i in 0..vals.len() {
@@ -298,7 +336,10 @@ pub fn calc_ri(
                 print!(", ");
             }
         }
-        println!("], {:?}, {:?}, {:?}, {:?})", type_, freq, z0, diff);
+        println!(
+            "], {:?}, {:?}, {:?}, {:?}, {:?}, {:?})",
+            interp, extrap, type_, freq, z0, diff
+        );
     }
 
     let mut re: f64 = 0.0;
@@ -306,139 +347,70 @@ pub fn calc_ri(
     let mut ln: f64 = 0.0;
 
     match type_ {
-        "bb" => {
-            re = vals[0] / z0;
-            im = vals[1] / z0;
+        ElementType::BlackBox => {
+            element_units(type_, &vals, &units, 2, 0)?;
+            // A differential termination is normalized to its mixed-mode reference
+            let z_scale = match diff {
+                true => ZScale::Diff,
+                false => ZScale::SingleEnded,
+            };
+            re = vals[0] / z_scale.reference(z0);
+            im = vals[1] / z_scale.reference(z0);
         }
-        "sr" | "pr" => {
-            re = unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
+        ElementType::SeriesRes | ElementType::ShuntRes => {
+            let unit = element_units(type_, &vals, &units, 1, 1)?;
+            re = unscale(vals[0], &unit[0]) / z0;
         }
-        "sc" | "pc" => {
-            if approx_eq!(f64, vals[0], 0_f64, F64Margin::default()) {
-                re = 0.0;
-            } else if units[0] == "Q" {
-                re = 1.0
-                    / (2.0
-                        * PI
-                        * freq
-                        * unscale(vals[1], &Unit::from_str(units[1]).unwrap())
-                        * vals[0])
-                    / z0;
-            } else {
-                re = unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
-            }
-            im = -1.0
-                / (2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap()))
+        ElementType::SeriesCap
+        | ElementType::ShuntCap
+        | ElementType::SeriesInd
+        | ElementType::ShuntInd
+        | ElementType::Transformer
+        | ElementType::Balun
+        | ElementType::SeriesMeasured
+        | ElementType::ShuntMeasured => {
+            let z = gen_part(type_, &vals, &units, part.as_ref(), c64(1.0, 0.0), z0)?
+                .z(Frequency::new(freq, Unit::Base))
                 / z0;
-        }
-        "si" | "pi" => {
-            if approx_eq!(f64, vals[0], 0_f64, F64Margin::default()) {
-                re = 0.0;
-            } else if units[0] == "Q" {
-                re = (2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap()))
-                    / vals[0]
-                    / z0;
-            } else {
-                re = unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
-            }
-            im = 2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap()) / z0;
-        }
-        "xfmr" => {
-            let mut l1_tee: f64 = 0.0;
-            let mut l2_tee: f64 = 0.0;
-            let mut ls_tee: f64 = 0.0;
-            // let n: f64 = 0.0;
-            let l1 = unscale(vals[1], &Unit::from_str(units[1]).unwrap());
-            let mut l2 = unscale(vals[2], &Unit::from_str(units[2]).unwrap());
-            let mut k = vals[3];
-            if units[3] == "k" {
-                l1_tee = (1.0 - k) * l1;
-                l2_tee = (1.0 - k) * l2;
-                ls_tee = k * l1;
-            }
-            if units[2] == "n" {
-                let n = vals[2];
-                l2 = n.powi(2) * l1;
-                l2_tee = (1.0 - k) * l2;
-            }
-            if units[3] != "k" {
-                let m = unscale(vals[3], &Unit::from_str(units[3]).unwrap());
-                k = m / l1;
-                l1_tee = (1.0 - k) * l1;
-                l2_tee = (1.0 - k) * l2;
-                ls_tee = m;
-            }
-            let (z1, z2, z3) =
-                match units[0] == "Q" && !approx_eq!(f64, vals[0], 0_f64, F64Margin::default()) {
-                    true => (
-                        c64(
-                            2.0 * PI * freq * l1 / vals[0] / z0,
-                            2.0 * PI * freq * l1_tee,
-                        ),
-                        c64(0.0, 2.0 * PI * freq * ls_tee),
-                        c64(
-                            2.0 * PI * freq * l2 / vals[0] / z0,
-                            2.0 * PI * freq * l2_tee,
-                        ),
-                    ),
-                    false => (
-                        c64(vals[0], 2.0 * PI * freq * l1_tee),
-                        c64(0.0, 2.0 * PI * freq * ls_tee),
-                        c64(vals[0], 2.0 * PI * freq * l2_tee),
-                    ),
-                };
-            let z = (z1.inv() + z2.inv()).inv() + z3;
             re = z.re;
             im = z.im;
         }
-        "rlc" => {
-            re = unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
-            im = (2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap())
-                - 1.0 / (2.0 * PI * freq * unscale(vals[2], &Unit::from_str(units[2]).unwrap())))
+        ElementType::Rlc => {
+            let unit = element_units(type_, &vals, &units, 3, 3)?;
+            re = unscale(vals[0], &unit[0]) / z0;
+            im = (2.0 * PI * freq * unscale(vals[1], &unit[1])
+                - 1.0 / (2.0 * PI * freq * unscale(vals[2], &unit[2])))
                 / z0;
         }
-        "rl" => {
-            re = unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
-            im = 2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap()) / z0;
+        ElementType::Rl => {
+            let unit = element_units(type_, &vals, &units, 2, 2)?;
+            re = unscale(vals[0], &unit[0]) / z0;
+            im = 2.0 * PI * freq * unscale(vals[1], &unit[1]) / z0;
         }
-        "rc" => {
-            re = 1.0 / unscale(vals[0], &Unit::from_str(units[0]).unwrap()) / z0;
-            im = -1.0
-                / (2.0 * PI * freq * unscale(vals[1], &Unit::from_str(units[1]).unwrap()))
-                / z0;
+        ElementType::Rc => {
+            let unit = element_units(type_, &vals, &units, 2, 2)?;
+            re = 1.0 / unscale(vals[0], &unit[0]) / z0;
+            im = -1.0 / (2.0 * PI * freq * unscale(vals[1], &unit[1])) / z0;
         }
-        "tl" | "so" | "ss" => {
-            ln = unscale(vals[0], &Unit::from_str(units[0]).unwrap());
+        ElementType::TLine | ElementType::OpenStub | ElementType::ShortedStub => {
+            let unit = element_units(type_, &vals, &units, 1, 1)?;
+            ln = unscale(vals[0], &unit[0]);
         }
-        "customZ" => {
-            let mut index_res: usize = lut.len() - 1;
-            for i in 0..lut.len() {
-                if lut[i][0] > freq {
-                    index_res = i;
-                    break;
-                }
-            }
-
-            if (index_res == lut.len() - 1) || (index_res == 0) {
-                re = lut[index_res][1];
-                im = lut[index_res][2];
-            } else {
-                let f1 = lut[index_res - 1][0];
-                let f2 = lut[index_res][0];
-                let frac = (freq - f1) / (f2 - f1);
-                re = lut[index_res - 1][1] + frac * (lut[index_res][1] - lut[index_res - 1][1]);
-                im = lut[index_res - 1][2] + frac * (lut[index_res][2] - lut[index_res - 1][2]);
-            }
+        ElementType::CustomZ => {
+            let mut data = FreqData::from_ri(&lut, Unit::Base)?;
+            data.set_interpolation(interp);
+            data.set_extrapolation(extrap);
+            let z = data.interp(Frequency::new(freq, Unit::Base))?;
 
-            re /= z0;
-            im /= z0;
+            re = z.re / z0;
+            im = z.im / z0;
+        }
+        ElementType::SeriesRlc | ElementType::ShuntRlc => {
+            return Err(Error::InvalidInput(format!(
+                "element {} not supported by calc_ri",
+                type_
+            )))
         }
-        _ => return Err("element not recognized".to_string()),
-    }
-
-    if diff && type_ == "bb" {
-        re /= 2.0;
-        im /= 2.0;
     }
 
     if verbose {
@@ -447,7 +419,6 @@ pub fn calc_ri(
     Ok(vec![re, im, ln])
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn calc_const_q(q: f64, npts: usize) -> Vec<(f64, f64)> {
     let mut gpts: Vec<(f64, f64)> = vec![];
     let step = (20_f64).ln() / (npts as f64);
@@ -464,26 +435,25 @@ pub fn calc_const_q(q: f64, npts: usize) -> Vec<(f64, f64)> {
     gpts
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn calc_vswr_circle(vswr: f64, zl: Complex<f64>, _z0: f64) -> (Complex<f64>, f64) {
     let center = find_smith_coord_c64(zl, false, false).unwrap();
     let radius = (vswr - 1.0) / (vswr + 1.0) + center.norm();
     (center, radius)
 }
 
-#[tauri::command(rename_all = "snake_case")]
 pub fn calc_smith_arc(
-    element: &str,
+    element: ElementType,
     vals: Vec<f64>,
     units: Vec<&str>,
+    part: Option<PartFile>,
     rin: f64,
     xin: f64,
     z0: f64,
     freq: f64,
-    freq_unit: &str,
+    freq_unit: Unit,
     npts: usize,
     verbose: bool,
-) -> Result<(Vec<f64>, Vec<f64>, (f64, f64), (f64, f64)), String> {
+) -> Result<SmithArc, Error> {
     if verbose {
         print!("calc_smith_arc(element: {:?}, vals: [", element);
         for val in vals.iter() {
@@ -499,138 +469,331 @@ pub fn calc_smith_arc(
         );
     }
 
-    let freq_int = Frequency::new(freq, Unit::from_str(freq_unit).unwrap());
+    let freq_int = Frequency::new(freq, freq_unit);
     let zin = c64(rin, xin);
 
+    Ok(gen_part(element, &vals, &units, part.as_ref(), zin, z0)?
+        .calc_arc(freq_int, zin, z0, npts, verbose))
+}
+
+#[derive(serde::Serialize, Default, Debug, PartialEq)]
+pub struct SmithCascadeReturn {
+    pub z: ComplexReturn,
+    pub gamma: ComplexReturn,
+}
+
+// Impedance looking into the schematic after each element, starting from the normalized load.
+// parts holds the file of each measured part and may be shorter than elements.
+pub fn calc_smith_cascade(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    parts: Vec<Option<PartFile>>,
+    rin: f64,
+    xin: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+) -> Result<Vec<SmithCascadeReturn>, Error> {
+    if elements.len() != vals.len() || elements.len() != units.len() {
+        return Err(Error::InvalidInput(
+            "elements, vals and units must have the same length".to_string(),
+        ));
+    }
+
+    let freq = Frequency::new(freq, freq_unit);
+    let mut zin = c64(rin, xin);
+    let mut out = vec![];
+    for (i, (element, (val, unit))) in elements
+        .iter()
+        .zip(vals.iter().zip(units.iter()))
+        .enumerate()
+    {
+        let part = parts.get(i).and_then(Option::as_ref);
+        let abcd = gen_part(*element, val, unit, part, zin, z0)?.abcd(freq);
+        let z = zin * z0;
+        let z = (abcd[1][1] * z + abcd[0][1]) / (abcd[1][0] * z + abcd[0][0]);
+        let gamma = (z - z0) / (z + z0);
+        zin = z / z0;
+        out.push(SmithCascadeReturn {
+            z: ComplexReturn { re: z.re, im: z.im },
+            gamma: ComplexReturn {
+                re: gamma.re,
+                im: gamma.im,
+            },
+        });
+    }
+
+    Ok(out)
+}
+
+// Checks the value and unit counts of an element and parses its units
+pub(crate) fn element_units(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+    nvals: usize,
+    nunits: usize,
+) -> Result<Vec<Unit>, Error> {
+    if vals.len() < nvals || units.len() < nunits {
+        return Err(Error::InvalidInput(format!(
+            "element {} expects {} values and {} units, got {} and {}",
+            element,
+            nvals,
+            nunits,
+            vals.len(),
+            units.len()
+        )));
+    }
+    units[..nunits]
+        .iter()
+        .map(|x| Unit::from_str(x).map_err(|err| err.context(&format!("element {}", element))))
+        .collect()
+}
+
+// Optional value with its unit
+type Trailing = (f64, Unit);
+
+// Units of the nvals required values, then up to N optional ones trailing them with a unit each,
+// e.g. the ESL and Q frequency of a capacitor. Missing ones are zero.
+pub(crate) fn element_trailing<const N: usize>(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+    nvals: usize,
+) -> Result<(Vec<Unit>, [Trailing; N]), Error> {
+    let n = vals.len().clamp(nvals, nvals + N);
+    let unit = element_units(element, vals, units, n, n)?;
+    let trailing = std::array::from_fn(|i| match nvals + i < n {
+        true => (vals[nvals + i], unit[nvals + i]),
+        false => (0.0, Unit::Base),
+    });
+    Ok((unit, trailing))
+}
+
+pub fn gen_element(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+    zin: Complex<f64>,
+    z0: f64,
+) -> Result<Box<dyn Element>, Error> {
     match element {
-        "si" => Ok(Inductor::new(
-            vals[0],
-            vals[1],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            0.0,
-            0.0,
-            Orientation::Series,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "pi" => Ok(Inductor::new(
-            vals[0],
-            vals[1],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            0.0,
-            0.0,
-            Orientation::Shunt,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "sc" => Ok(Capacitor::new(
-            vals[0],
-            vals[1],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            0.0,
-            0.0,
-            Orientation::Series,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "pc" => Ok(Capacitor::new(
-            vals[0],
-            vals[1],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            0.0,
-            0.0,
-            Orientation::Shunt,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "sr" => Ok(Resistor::new(
-            vals[0],
-            Unit::from_str(units[0]).unwrap(),
-            0.0,
-            Orientation::Series,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "pr" => Ok(Resistor::new(
-            vals[0],
-            Unit::from_str(units[0]).unwrap(),
-            0.0,
-            Orientation::Shunt,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "prlc" => Ok(Rlc::new(
-            vals[0],
-            vals[1],
-            vals[2],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            Unit::from_str(units[2]).unwrap(),
-            0.0,
-            0.0,
-            0.0,
-            Orientation::Shunt,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "srlc" => Ok(Rlc::new(
-            vals[0],
-            vals[1],
-            vals[2],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            Unit::from_str(units[2]).unwrap(),
-            0.0,
-            0.0,
-            0.0,
-            Orientation::Series,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "bb" => {
-            if units[0] == "diff" {
-                Ok(BlackBox::from_ri(vals[0] / 2.0, vals[1] / 2.0, z0, 0.0)
-                    .calc_arc(freq_int, zin, z0, npts, verbose))
-            } else {
-                Ok(BlackBox::from_ri(vals[0], vals[1], z0, 0.0)
-                    .calc_arc(freq_int, zin, z0, npts, verbose))
-            }
+        ElementType::SeriesInd | ElementType::ShuntInd => {
+            let (unit, [(cap, cap_unit), (q_freq, q_freq_unit)]) =
+                element_trailing(element, vals, units, 2)?;
+            let orientation = match element {
+                ElementType::SeriesInd => Orientation::Series,
+                _ => Orientation::Shunt,
+            };
+            let mut ind = Inductor::new(vals[0], vals[1], unit[0], unit[1], 0.0, 0.0, orientation);
+            ind.set_cap(cap);
+            ind.set_cap_unit(cap_unit);
+            ind.set_q_freq(q_freq);
+            ind.set_q_freq_unit(q_freq_unit);
+            Ok(Box::new(ind))
         }
-        "tl" => Ok(TLine::new(
-            vals[0],
-            zin * z0,
-            vals[1],
-            vals[2],
-            Unit::from_str(units[0]).unwrap(),
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "ss" => Ok(
-            ShortedStub::new(vals[0], vals[1], vals[2], Unit::from_str(units[0]).unwrap())
-                .calc_arc(freq_int, zin, z0, npts, verbose),
+        ElementType::SeriesCap | ElementType::ShuntCap => {
+            let (unit, [(esl, esl_unit), (q_freq, q_freq_unit)]) =
+                element_trailing(element, vals, units, 2)?;
+            let orientation = match element {
+                ElementType::SeriesCap => Orientation::Series,
+                _ => Orientation::Shunt,
+            };
+            let mut cap = Capacitor::new(vals[0], vals[1], unit[0], unit[1], 0.0, 0.0, orientation);
+            cap.set_esl(esl);
+            cap.set_esl_unit(esl_unit);
+            cap.set_q_freq(q_freq);
+            cap.set_q_freq_unit(q_freq_unit);
+            Ok(Box::new(cap))
+        }
+        ElementType::SeriesRes | ElementType::ShuntRes => {
+            let unit = element_units(element, vals, units, 1, 1)?;
+            let orientation = match element {
+                ElementType::SeriesRes => Orientation::Series,
+                _ => Orientation::Shunt,
+            };
+            Ok(Box::new(Resistor::new(vals[0], unit[0], 0.0, orientation)))
+        }
+        ElementType::SeriesRlc | ElementType::ShuntRlc => {
+            let unit = element_units(element, vals, units, 3, 3)?;
+            let orientation = match element {
+                ElementType::SeriesRlc => Orientation::Series,
+                _ => Orientation::Shunt,
+            };
+            Ok(Box::new(Rlc::new(
+                vals[0],
+                vals[1],
+                vals[2],
+                unit[0],
+                unit[1],
+                unit[2],
+                0.0,
+                0.0,
+                0.0,
+                orientation,
+            )))
+        }
+        ElementType::BlackBox => {
+            element_units(element, vals, units, 2, 0)?;
+            // The only unit a black box takes is whether its impedance is differential
+            let z_scale = match units.first() {
+                Some(unit) => ZScale::from_str(unit)
+                    .map_err(|err| err.context(&format!("element {}", element)))?,
+                None => ZScale::SingleEnded,
+            };
+            // Normalized to its mixed-mode reference, then placed on the z0 chart
+            let scale = z0 / z_scale.reference(z0);
+            Ok(Box::new(BlackBox::from_ri(
+                vals[0] * scale,
+                vals[1] * scale,
+                z0,
+                0.0,
+            )))
+        }
+        ElementType::TLine => {
+            let unit = element_units(element, vals, units, 3, 1)?;
+            Ok(Box::new(TLine::new(
+                vals[0],
+                zin * z0,
+                vals[1],
+                vals[2],
+                unit[0],
+            )))
+        }
+        ElementType::ShortedStub => {
+            let unit = element_units(element, vals, units, 3, 1)?;
+            Ok(Box::new(ShortedStub::new(
+                vals[0], vals[1], vals[2], unit[0],
+            )))
+        }
+        ElementType::OpenStub => {
+            let unit = element_units(element, vals, units, 3, 1)?;
+            Ok(Box::new(OpenStub::new(
+                vals[0], zin, vals[1], vals[2], unit[0],
+            )))
+        }
+        ElementType::Transformer => Ok(Box::new(gen_transformer(element, vals, units)?)),
+        // Without its own differential impedance a balun faces the source at 2 z0
+        ElementType::Balun => Ok(Box::new(gen_balun(vals, units, z0, 2.0 * z0)?)),
+        ElementType::SeriesMeasured | ElementType::ShuntMeasured => Err(Error::InvalidInput(
+            format!("element {} has no library or Touchstone file", element),
+        )),
+        ElementType::Rlc | ElementType::Rl | ElementType::Rc | ElementType::CustomZ => Err(
+            Error::InvalidInput(format!("element {} has no two-port model", element)),
         ),
-        "so" => Ok(OpenStub::new(
-            vals[0],
-            zin,
-            vals[1],
-            vals[2],
-            Unit::from_str(units[0]).unwrap(),
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        "xfmr" => Ok(Transformer::new(
-            vals[0],
-            vals[1],
-            vals[2],
-            vals[3],
-            Unit::from_str(units[0]).unwrap(),
-            Unit::from_str(units[1]).unwrap(),
-            Unit::from_str(units[2]).unwrap(),
-            Unit::from_str(units[3]).unwrap(),
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-        )
-        .calc_arc(freq_int, zin, z0, npts, verbose)),
-        _ => return Err("element not recognize".to_string()),
     }
 }
 
+// Element with the file a measured part reads its data from
+pub fn gen_part(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+    part: Option<&PartFile>,
+    zin: Complex<f64>,
+    z0: f64,
+) -> Result<Box<dyn Element>, Error> {
+    match (element, part) {
+        (ElementType::SeriesMeasured | ElementType::ShuntMeasured, Some(part)) => {
+            Ok(Box::new(gen_measured(element, vals, units, part)?))
+        }
+        _ => gen_element(element, vals, units, zin, z0),
+    }
+}
+
+// Measured part of nominal value vals[0] in units[0]
+pub fn gen_measured(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+    part: &PartFile,
+) -> Result<Measured, Error> {
+    let unit = element_units(element, vals, units, 1, 1)?;
+    let orientation = match element {
+        ElementType::SeriesMeasured => Orientation::Series,
+        _ => Orientation::Shunt,
+    };
+    part.part(vals[0], unit[0], orientation)
+        .map_err(|err| err.context(&format!("element {}", element)))
+}
+
+// Transformer from [res, indp, inds, m], optionally followed by the secondary loss res_s, in the
+// same kind of unit as res, and the interwinding capacitance. Without res_s both windings have res.
+pub fn gen_transformer(
+    element: ElementType,
+    vals: &[f64],
+    units: &[&str],
+) -> Result<Transformer, Error> {
+    let (unit, [(res_s, res_s_unit), (cap, cap_unit)]) = element_trailing(element, vals, units, 4)?;
+    let mut out = Transformer::new(
+        vals[0], vals[1], vals[2], vals[3], unit[0], unit[1], unit[2], unit[3], 0.0, 0.0, 0.0, 0.0,
+    );
+    if vals.len() > 4 {
+        let res_s = match (unit[0], res_s_unit) {
+            (Unit::Q, Unit::Q) => res_s,
+            (Unit::Q, _) | (_, Unit::Q) => {
+                return Err(Error::InvalidInput(format!(
+                    "element {}: secondary loss in {} but primary loss in {}",
+                    element, units[4], units[0]
+                )))
+            }
+            _ => scale(unscale(res_s, &res_s_unit), &unit[0]),
+        };
+        out.set_res_s(res_s);
+    }
+    if vals.len() > 5 {
+        out.set_cap(cap);
+        out.set_cap_unit(cap_unit);
+    }
+    Ok(out)
+}
+
+// Balun from its Smith chart values, the model named by the first unit: lattice [l, c],
+// marchand [z0e, z0o, f0] or xfmr [res, indp, inds, m, res_s, cap] with the units of each after
+// the model. A value past those is the differential impedance in Ω, which otherwise is z_diff.
+pub fn gen_balun(vals: &[f64], units: &[&str], z_se: f64, z_diff: f64) -> Result<Balun, Error> {
+    let element = ElementType::Balun;
+    let kind = units
+        .first()
+        .ok_or_else(|| Error::InvalidInput(format!("element {} has no model", element)))?;
+    let kind =
+        BalunType::from_str(kind).map_err(|err| err.context(&format!("element {}", element)))?;
+    let nvals = match kind {
+        BalunType::Lattice => 2,
+        BalunType::Marchand => 3,
+        BalunType::Transformer => 6,
+    };
+    let z_diff = vals.get(nvals).copied().unwrap_or(z_diff);
+    if !(z_diff > 0.0 && z_diff.is_finite()) {
+        return Err(Error::InvalidInput(format!(
+            "element {} differential impedance must be positive, got {} Ω",
+            element, z_diff
+        )));
+    }
+    let model = match kind {
+        BalunType::Lattice => {
+            let unit = element_units(element, vals, &units[1..], 2, 2)?;
+            BalunModel::Lattice {
+                l: unscale(vals[0], &unit[0]),
+                c: unscale(vals[1], &unit[1]),
+            }
+        }
+        BalunType::Marchand => {
+            let unit = element_units(element, vals, &units[1..], 3, 1)?;
+            BalunModel::Marchand(CoupledLine {
+                z0e: vals[0],
+                z0o: vals[1],
+                f0: unscale(vals[2], &unit[0]),
+            })
+        }
+        BalunType::Transformer => {
+            BalunModel::Transformer(gen_transformer(element, vals, &units[1..])?)
+        }
+    };
+    Ok(Balun::new(model, z_se, z_diff))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -668,15 +831,203 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calc_ri_custom_z() {
+        let lut = vec![[1e9, 20.0, -10.0], [2e9, 30.0, -20.0], [3e9, 50.0, 10.0]];
+        let exemplar = [
+            (0.5e9, [0.4, -0.2]),
+            (1.5e9, [0.5, -0.3]),
+            (2.5e9, [0.8, -0.1]),
+            (3.5e9, [1.0, 0.2]),
+        ];
+
+        for (freq, val) in exemplar.iter() {
+            let test = calc_ri(
+                vec![],
+                vec![],
+                lut.clone(),
+                Interpolation::Linear,
+                Extrapolation::Hold,
+                None,
+                ElementType::CustomZ,
+                *freq,
+                50.0,
+                false,
+                false,
+            )
+            .unwrap();
+            comp_vec_f64(
+                test,
+                vec![val[0], val[1], 0.0],
+                F64Margin::from((1e-12, 4)),
+                "calc_ri()",
+                &format!("customZ({})", freq),
+            );
+        }
+
+        // Held from each point to the next, and smooth through the points with a spline
+        let exemplar = [
+            (Interpolation::SampleHold, 2.5e9, [0.6, -0.4]),
+            (Interpolation::SampleHold, 0.5e9, [0.4, -0.2]),
+            (Interpolation::CubicSpline, 2e9, [0.6, -0.4]),
+            (Interpolation::CubicSpline, 1.5e9, [0.48125, -0.375]),
+        ];
+        for (interp, freq, val) in exemplar.iter() {
+            let test = calc_ri(
+                vec![],
+                vec![],
+                lut.clone(),
+                *interp,
+                Extrapolation::Hold,
+                None,
+                ElementType::CustomZ,
+                *freq,
+                50.0,
+                false,
+                false,
+            )
+            .unwrap();
+            comp_vec_f64(
+                test,
+                vec![val[0], val[1], 0.0],
+                F64Margin::from((1e-12, 4)),
+                "calc_ri()",
+                &format!("customZ({:?}, {})", interp, freq),
+            );
+        }
+
+        // Past the end of the table unless asked to hold it
+        assert_eq!(
+            calc_ri(
+                vec![],
+                vec![],
+                lut.clone(),
+                Interpolation::Linear,
+                Extrapolation::default(),
+                None,
+                ElementType::CustomZ,
+                3.5e9,
+                50.0,
+                false,
+                false,
+            )
+            .unwrap_err()
+            .code(),
+            "invalid_input"
+        );
+
+        assert!(calc_ri(
+            vec![],
+            vec![],
+            vec![],
+            Interpolation::Linear,
+            Extrapolation::Error,
+            None,
+            ElementType::CustomZ,
+            1e9,
+            50.0,
+            false,
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_calc_ri_black_box_diff() {
+        // A differential termination sits on the chart at its mixed-mode reference of 2*z0
+        let calc = |diff: bool| {
+            calc_ri(
+                vec![100.0, 20.0],
+                vec![],
+                vec![],
+                Interpolation::Linear,
+                Extrapolation::Error,
+                None,
+                ElementType::BlackBox,
+                1e9,
+                50.0,
+                diff,
+                false,
+            )
+            .unwrap()
+        };
+        let margin = F64Margin::from((1e-12, 4));
+        comp_vec_f64(calc(false), vec![2.0, 0.4, 0.0], margin, "calc_ri()", "se");
+        comp_vec_f64(calc(true), vec![1.0, 0.2, 0.0], margin, "calc_ri()", "diff");
+    }
+
+    #[test]
+    fn test_calc_ri_parasitics() {
+        let w = 2.0 * PI * 1e9;
+        let esr = 1.0 / (w * 1e-12 * 100.0);
+        let test = calc_ri(
+            vec![100.0, 1.0, 0.5, 1.0],
+            vec!["Q", "pF", "nH", "GHz"],
+            vec![],
+            Interpolation::Linear,
+            Extrapolation::Error,
+            None,
+            ElementType::SeriesCap,
+            1e9,
+            50.0,
+            false,
+            false,
+        )
+        .unwrap();
+        comp_vec_f64(
+            test,
+            vec![esr / 50.0, (w * 0.5e-9 - 1.0 / (w * 1e-12)) / 50.0, 0.0],
+            F64Margin::from((1e-12, 4)),
+            "calc_ri()",
+            "esl",
+        );
+
+        // Past its self-resonance a shunt inductor looks capacitive
+        let test = calc_ri(
+            vec![40.0, 10.0, 0.2],
+            vec!["Q", "nH", "pF"],
+            vec![],
+            Interpolation::Linear,
+            Extrapolation::Error,
+            None,
+            ElementType::ShuntInd,
+            5e9,
+            50.0,
+            false,
+            false,
+        )
+        .unwrap();
+        assert!(test[1] < 0.0);
+
+        assert_eq!(
+            calc_ri(
+                vec![40.0, 10.0, 0.2],
+                vec!["Q", "nH"],
+                vec![],
+                Interpolation::Linear,
+                Extrapolation::Error,
+                None,
+                ElementType::ShuntInd,
+                5e9,
+                50.0,
+                false,
+                false,
+            )
+            .err()
+            .unwrap(),
+            Error::InvalidInput("element pi expects 3 values and 3 units, got 3 and 2".to_string())
+        );
+    }
+
     #[test]
     fn test_arc_smith_points() {
         let x1: f64 = 1.0;
         let y1: f64 = 0.0;
         let x2: f64 = 1.1202712484636101;
         let y2: f64 = -2.4054249692722034;
-        let type_ = "pi";
+        let type_ = ArcType::Lumped;
         let rotate = true;
-        let beta: f64 = NAN;
+        let beta: f64 = f64::NAN;
         let start_at_qtr_wl: f64 = 0.0;
         let z0: f64 = 100.0;
         let resolution: usize = 100;
@@ -830,13 +1181,23 @@ mod tests {
 
         let margin = F64Margin::from((1e-15, 1));
 
-        let element = "pi";
+        let element = ElementType::ShuntInd;
         let vals = vec![20.0, 10.0];
         let units = vec!["Q", "pH"];
         let rin = 1.0;
         let xin = 0.0;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.6606893070885922;
@@ -883,14 +1244,24 @@ mod tests {
         comp_vec_f64(test.0, x_coord, margin, testname, "x_coord");
         comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
 
-        let element = "sc";
+        let element = ElementType::SeriesCap;
         let vals = vec![0.0, 20.0];
         let units = vec!["Q", "fF"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.6880997486248435;
@@ -938,6 +1309,169 @@ mod tests {
         comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
     }
 
+    #[test]
+    fn test_gen_element_errors() {
+        let zin = c64(1.0, 0.0);
+        assert_eq!(
+            serde_json::from_str::<ElementType>(r#""pi""#).unwrap(),
+            ElementType::ShuntInd
+        );
+        assert!(serde_json::from_str::<ElementType>(r#""xx""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<ArcType>(r#""sc""#).unwrap(),
+            ArcType::Lumped
+        );
+
+        assert!(gen_element(
+            ElementType::ShuntInd,
+            &[20.0, 10.0],
+            &["Q", "pH"],
+            zin,
+            50.0
+        )
+        .is_ok());
+        assert_eq!(
+            gen_element(ElementType::ShuntInd, &[20.0], &["Q"], zin, 50.0)
+                .err()
+                .unwrap(),
+            Error::InvalidInput("element pi expects 2 values and 2 units, got 1 and 1".to_string())
+        );
+        assert_eq!(
+            gen_element(
+                ElementType::ShuntInd,
+                &[20.0, 10.0],
+                &["Q", "pX"],
+                zin,
+                50.0
+            )
+            .err()
+            .unwrap(),
+            Error::InvalidUnit("element pi: unit \"pX\" not recognized".to_string())
+        );
+        assert!(gen_element(ElementType::BlackBox, &[25.0, 0.0], &[], zin, 50.0).is_ok());
+        assert!(gen_element(ElementType::BlackBox, &[25.0, 0.0], &["both"], zin, 50.0).is_err());
+    }
+
+    #[test]
+    fn test_gen_transformer() {
+        let testname = "gen_transformer";
+        let freq = Frequency::new(2.0, Unit::Giga);
+        let margin = F64Margin::from((1e-12, 4));
+        let mut exemplar = Transformer::new(
+            20.0,
+            2.0,
+            8.0,
+            0.6,
+            Unit::Q,
+            Unit::Nano,
+            Unit::Nano,
+            Unit::K,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+
+        // Both windings share res without the trailing values
+        let test = gen_transformer(
+            ElementType::Transformer,
+            &[20.0, 2.0, 8.0, 0.6],
+            &["Q", "nH", "nH", "K"],
+        )
+        .unwrap();
+        assert_eq!(test, exemplar);
+
+        exemplar.set_res_s(35.0);
+        exemplar.set_cap(150.0);
+        exemplar.set_cap_unit(Unit::Femto);
+        let test = gen_transformer(
+            ElementType::Transformer,
+            &[20.0, 2.0, 8.0, 0.6, 35.0, 150.0],
+            &["Q", "nH", "nH", "K", "Q", "fF"],
+        )
+        .unwrap();
+        assert_eq!(test, exemplar);
+        assert_eq!(test.vals(), vec![20.0, 2.0, 8.0, 0.6, 35.0, 150.0]);
+        let test = gen_element(
+            ElementType::Transformer,
+            &[20.0, 2.0, 8.0, 0.6, 35.0, 150.0],
+            &["Q", "nH", "nH", "K", "Q", "fF"],
+            c64(1.0, 0.0),
+            50.0,
+        )
+        .unwrap();
+        let [[a, b], [c, d]] = test.abcd(freq);
+        let [[a0, b0], [c0, d0]] = exemplar.abcd(freq);
+        for (val, ex) in [(a, a0), (b, b0), (c, c0), (d, d0)] {
+            comp_c64(&val, &ex, margin, testname, "abcd");
+        }
+
+        // A secondary resistance is brought to the unit of the primary one
+        let test = gen_transformer(
+            ElementType::Transformer,
+            &[2.0, 2.0, 8.0, 0.6, 500.0],
+            &["Ω", "nH", "nH", "K", "mΩ"],
+        )
+        .unwrap();
+        comp_f64(&test.res_s(), &0.5, margin, testname, "res_s");
+        assert_eq!(test.cap(), 0.0);
+        assert_eq!(
+            gen_transformer(
+                ElementType::Transformer,
+                &[20.0, 2.0, 8.0, 0.6, 2.0],
+                &["Q", "nH", "nH", "K", "Ω"],
+            )
+            .unwrap_err()
+            .message(),
+            "element xfmr: secondary loss in Ω but primary loss in Q"
+        );
+    }
+
+    #[test]
+    fn test_calc_smith_cascade() {
+        let testname = "calc_smith_cascade";
+        let z0 = 50.0;
+        let margin = F64Margin::from((1e-12, 4));
+
+        // Same schematic as calc_smith_arc_shuntL_seriesC
+        let test = calc_smith_cascade(
+            vec![ElementType::ShuntInd, ElementType::SeriesCap],
+            vec![vec![20.0, 10.0], vec![0.0, 20.0]],
+            vec![vec!["Q", "pH"], vec!["Q", "fF"]],
+            vec![],
+            1.0,
+            0.0,
+            z0,
+            280.0,
+            Unit::Giga,
+        )
+        .unwrap();
+        let z1 = c64(1.1417482571178263, -2.8349651423565256).inv() * z0;
+        let z2 = c64(0.12223478657203007, -0.26490108420099917) * z0;
+
+        assert_eq!(test.len(), 2);
+        comp_f64(&test[0].z.re, &z1.re, margin, testname, "z1.re");
+        comp_f64(&test[0].z.im, &z1.im, margin, testname, "z1.im");
+        comp_f64(&test[1].z.re, &z2.re, margin, testname, "z2.re");
+        comp_f64(&test[1].z.im, &z2.im, margin, testname, "z2.im");
+        let gamma = (z2 - z0) / (z2 + z0);
+        comp_f64(&test[1].gamma.re, &gamma.re, margin, testname, "gamma.re");
+        comp_f64(&test[1].gamma.im, &gamma.im, margin, testname, "gamma.im");
+
+        assert!(calc_smith_cascade(
+            vec![ElementType::SeriesInd],
+            vec![vec![]],
+            vec![vec![]],
+            vec![],
+            1.0,
+            0.0,
+            z0,
+            1.0,
+            Unit::Giga
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_calc_smith_arc_shunt_l_series_tl() {
         let testname = "calc_smith_arc_shuntL_seriesTL";
@@ -947,13 +1481,23 @@ mod tests {
 
         let margin = F64Margin::from((1e-13, 1));
 
-        let element = "pi";
+        let element = ElementType::ShuntInd;
         let vals = vec![20.0, 10.0];
         let units = vec!["Q", "pH"];
         let rin = 1.0;
         let xin = 0.0;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.6606893070885922;
@@ -1000,14 +1544,24 @@ mod tests {
         comp_vec_f64(test.0, x_coord, margin, testname, "x_coord");
         comp_vec_f64(test.1, y_coord, margin, testname, "y_coord");
 
-        let element = "tl";
+        let element = ElementType::TLine;
         let vals = vec![90.0, 9.7, 100.0];
         let units = vec!["um"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = 0.8124103144077774;
@@ -1077,13 +1631,23 @@ mod tests {
         // ss = [88.0, 1.0, 90.0]
         // xfmr = [25.0, 14.0, 34.0, 0.65]
 
-        let element = "pc";
+        let element = ElementType::ShuntCap;
         let vals = vec![0.0, 20.0];
         let units = vec!["Q", "fF"];
         let rin = zstart.re / 100.0;
         let xin = zstart.im / 100.0;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.5990238691961544;
@@ -1165,14 +1729,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "si";
+        let element = ElementType::SeriesInd;
         let vals = vec![20.0, 10.0];
         let units = vec!["Q", "pH"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.7192753998677598;
@@ -1254,14 +1828,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "pi";
+        let element = ElementType::ShuntInd;
         let vals = vec![14.0, 23.0];
         let units = vec!["Q", "pH"];
         let zin = c64(test.3 .0, test.3 .1);
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.7327663256075981;
@@ -1343,14 +1927,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "sc";
+        let element = ElementType::SeriesCap;
         let vals = vec![25.0, 45.0];
         let units = vec!["Q", "fF"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.6610576860592663;
@@ -1432,14 +2026,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "tl";
+        let element = ElementType::TLine;
         let vals = vec![75.0, 1.0, 76.0];
         let units = vec!["um"];
         let zin = c64(test.3 .0, test.3 .1);
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.4776979579337571;
@@ -1521,14 +2125,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "sr";
+        let element = ElementType::SeriesRes;
         let vals = vec![20.0];
         let units = vec!["Ω"];
         let zin = c64(test.3 .0, test.3 .1);
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.1708766715939743;
@@ -1610,14 +2224,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "so";
+        let element = ElementType::OpenStub;
         let vals = vec![30.0, 1.0, 100.0];
         let units = vec!["um"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.046172180141428695;
@@ -1699,14 +2323,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "pr";
+        let element = ElementType::ShuntRes;
         let vals = vec![50.0];
         let units = vec!["Ω"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.3519430749726401;
@@ -1788,14 +2422,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "ss";
+        let element = ElementType::ShortedStub;
         let vals = vec![88.0, 1.0, 90.0];
         let units = vec!["um"];
         let zin = c64(test.3 .0, test.3 .1);
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.38098629626319114;
@@ -1877,14 +2521,24 @@ mod tests {
             &(element.to_string().as_str().to_owned() + "_y_coord"),
         );
 
-        let element = "xfmr";
+        let element = ElementType::Transformer;
         let vals = vec![25.0, 14.0, 34.0, 0.65];
         let units = vec!["Q", "pH", "pH", "K"];
         let zin = c64(test.3 .0, test.3 .1).inv();
         let rin = zin.re;
         let xin = zin.im;
         let test = calc_smith_arc(
-            element, vals, units, rin, xin, z0, 280.0, "ghz", npts, false,
+            element,
+            vals,
+            units,
+            None,
+            rin,
+            xin,
+            z0,
+            280.0,
+            Unit::Giga,
+            npts,
+            false,
         )
         .unwrap();
         let _end_x_coord = -0.028701359002830346;
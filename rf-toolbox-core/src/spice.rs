@@ -0,0 +1,2587 @@
+use crate::element::{
+    capacitor::Capacitor, inductor::Inductor, resistor::Resistor, tline::TLine,
+    transformer::Transformer, Orientation,
+};
+use crate::element::{Element, ElementType};
+use crate::error::Error;
+use crate::frequency::Frequency;
+use crate::matching::ladder::{Kind, Placement, Topology};
+use crate::matching::{PiTee, Unrealizable, CCLL, CL, CLQ};
+use crate::mna::Circuit;
+use crate::rf_utils::{scale, unscale};
+use crate::schematic::Edit;
+use crate::smith::{gen_element, gen_transformer};
+use crate::unit::Unit;
+use num_complex::c64;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+// Ports of an exported subcircuit unless named otherwise, source side first
+pub const DEFAULT_PORTS: [&str; 2] = ["in", "out"];
+
+const C0: f64 = 3e8;
+
+// A two-terminal SPICE primitive in base units. Lossy capacitors and inductors carry the series
+// resistance of their Q in r, written out as a resistor of its own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+    R(f64),
+    L {
+        l: f64,
+        r: f64,
+    },
+    C {
+        c: f64,
+        r: f64,
+    },
+    // Lossy inductor with its winding capacitance across it
+    Tank {
+        l: f64,
+        r: f64,
+        c: f64,
+    },
+    // Lossless line of delay td, referenced to ground
+    Line {
+        z0: f64,
+        td: f64,
+    },
+    // Lossless line with its far end shorted or left open
+    Stub {
+        z0: f64,
+        td: f64,
+        short: bool,
+    },
+    // Coupled windings to ground, the secondary at the source end and the primary at the load
+    // end as in the Smith chart. Their losses are series resistors ahead of the windings, and the
+    // interwinding capacitance c bridges the two ends.
+    Xfmr {
+        lp: f64,
+        ls: f64,
+        k: f64,
+        rp: f64,
+        rs: f64,
+        c: f64,
+    },
+}
+
+// Parts in series, either in the signal path or from the path to ground
+#[derive(Clone, Debug, PartialEq)]
+pub struct Branch {
+    pub placement: Placement,
+    pub parts: Vec<Part>,
+}
+
+// SPICE value with a scale suffix to 6 significant digits, e.g. 1.5e-12 => "1.5P". Mega is MEG,
+// as M is milli to SPICE.
+pub fn eng(val: f64) -> String {
+    // Round first so that 999.9999e-12 becomes 1N rather than 1000P
+    let val = format!("{:.5e}", val).parse::<f64>().unwrap_or(val);
+    let unit = Unit::eng(val);
+    let suffix = match unit {
+        Unit::Tera => "T",
+        Unit::Giga => "G",
+        Unit::Mega => "MEG",
+        Unit::Kilo => "K",
+        Unit::Milli => "M",
+        Unit::Micro => "U",
+        Unit::Nano => "N",
+        Unit::Pico => "P",
+        Unit::Femto => "F",
+        _ => "",
+    };
+    let x = val * unit.scale();
+    let digits = if x.abs() >= 100.0 {
+        3
+    } else if x.abs() >= 10.0 {
+        4
+    } else {
+        5
+    };
+    let mut out = format!("{:.*}", digits, x);
+    if out.contains('.') {
+        out = out.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    format!("{}{}", out, suffix)
+}
+
+fn check_name(kind: &str, name: &str) -> Result<(), Error> {
+    if name.is_empty() || name == "0" || name.chars().any(|c| c.is_whitespace() || c == '=') {
+        return Err(Error::InvalidInput(format!(
+            "{} {:?} is not a valid SPICE name",
+            kind, name
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Writer {
+    lines: Vec<String>,
+    // Reference designators handed out so far per prefix
+    counts: Vec<(char, usize)>,
+    nodes: usize,
+}
+
+impl Writer {
+    fn refdes(&mut self, prefix: char) -> String {
+        let idx = match self.counts.iter_mut().find(|(c, _)| *c == prefix) {
+            Some((_, n)) => {
+                *n += 1;
+                *n
+            }
+            None => {
+                self.counts.push((prefix, 1));
+                1
+            }
+        };
+        format!("{}{}", prefix, idx)
+    }
+
+    fn node(&mut self) -> String {
+        self.nodes += 1;
+        format!("n{}", self.nodes)
+    }
+
+    fn value(name: &str, val: f64) -> Result<String, Error> {
+        if !val.is_finite() || val <= 0.0 {
+            return Err(Error::InvalidInput(format!(
+                "{} needs a positive value, got {}",
+                name, val
+            )));
+        }
+        Ok(eng(val))
+    }
+
+    // Two-terminal part with its optional series resistance between a and b
+    fn lossy(&mut self, prefix: char, val: f64, r: f64, a: &str, b: &str) -> Result<(), Error> {
+        let name = self.refdes(prefix);
+        let val = Writer::value(&name, val)?;
+        if r > 0.0 {
+            let mid = self.node();
+            let res = format!("R{}", name);
+            let r = Writer::value(&res, r)?;
+            self.lines.push(format!("{} {} {} {}", name, a, mid, val));
+            self.lines.push(format!("{} {} {} {}", res, mid, b, r));
+        } else {
+            self.lines.push(format!("{} {} {} {}", name, a, b, val));
+        }
+        Ok(())
+    }
+
+    // Winding from node to ground, its resistance first so that the winding itself is grounded
+    fn winding(&mut self, l: f64, r: f64, node: &str) -> Result<String, Error> {
+        let name = self.refdes('L');
+        let val = Writer::value(&name, l)?;
+        let top = if r > 0.0 {
+            let mid = self.node();
+            let res = format!("R{}", name);
+            let r = Writer::value(&res, r)?;
+            self.lines.push(format!("{} {} {} {}", res, node, mid, r));
+            mid
+        } else {
+            node.to_string()
+        };
+        self.lines.push(format!("{} {} 0 {}", name, top, val));
+        Ok(name)
+    }
+
+    fn part(&mut self, part: &Part, a: &str, b: &str) -> Result<(), Error> {
+        match *part {
+            Part::R(r) => {
+                let name = self.refdes('R');
+                let line = format!("{} {} {} {}", name, a, b, Writer::value(&name, r)?);
+                self.lines.push(line);
+            }
+            Part::L { l, r } => self.lossy('L', l, r, a, b)?,
+            Part::C { c, r } => self.lossy('C', c, r, a, b)?,
+            Part::Tank { l, r, c } => {
+                self.lossy('L', l, r, a, b)?;
+                self.lossy('C', c, 0.0, a, b)?;
+            }
+            Part::Line { z0, td } => {
+                let name = self.refdes('T');
+                let line = format!(
+                    "{} {} 0 {} 0 Z0={} TD={}",
+                    name,
+                    a,
+                    b,
+                    Writer::value(&name, z0)?,
+                    Writer::value(&name, td)?
+                );
+                self.lines.push(line);
+            }
+            Part::Stub { z0, td, short } => {
+                let name = self.refdes('T');
+                let far = if short {
+                    b.to_string()
+                } else {
+                    format!("{}_open", name)
+                };
+                let line = format!(
+                    "{} {} {} {} {} Z0={} TD={}",
+                    name,
+                    a,
+                    b,
+                    far,
+                    b,
+                    Writer::value(&name, z0)?,
+                    Writer::value(&name, td)?
+                );
+                self.lines.push(line);
+            }
+            Part::Xfmr {
+                lp,
+                ls,
+                k,
+                rp,
+                rs,
+                c,
+            } => {
+                let secondary = self.winding(ls, rs, a)?;
+                let primary = self.winding(lp, rp, b)?;
+                let name = self.refdes('K');
+                if !(k > 0.0 && k <= 1.0) {
+                    return Err(Error::NonPhysical(format!(
+                        "{} has a coupling factor of {}, outside (0, 1]",
+                        name, k
+                    )));
+                }
+                let line = format!("{} {} {} {}", name, primary, secondary, tidy(k));
+                self.lines.push(line);
+                if c > 0.0 {
+                    self.lossy('C', c, 0.0, a, b)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Parts in series from a to b. A branch without parts is a short, written as a 0 V source.
+    fn chain(&mut self, parts: &[Part], a: &str, b: &str) -> Result<(), Error> {
+        if parts.is_empty() {
+            let name = self.refdes('V');
+            self.lines.push(format!("{} {} {} 0", name, a, b));
+        }
+        let mut node = a.to_string();
+        for (i, part) in parts.iter().enumerate() {
+            let next = if i + 1 == parts.len() {
+                b.to_string()
+            } else {
+                self.node()
+            };
+            self.part(part, &node, &next)?;
+            node = next;
+        }
+        Ok(())
+    }
+}
+
+// Subcircuit of branches written from the first port to the second. The series branches chain
+// the ports through nodes n1, n2, ..; shunt branches go from the current node to ground.
+//
+//  in ---L1---n1---C2--- out      [sL, pC, sC]
+//             |
+//             C1
+//             |
+//            GND
+pub fn subckt(
+    name: &str,
+    ports: [&str; 2],
+    comments: &[String],
+    branches: &[Branch],
+) -> Result<String, Error> {
+    check_name("subcircuit", name)?;
+    for port in ports {
+        check_name("port", port)?;
+        // n1, n2, .. are the interior nodes
+        if port
+            .strip_prefix('n')
+            .is_some_and(|val| val.parse::<usize>().is_ok())
+        {
+            return Err(Error::InvalidInput(format!(
+                "port {:?} is the name of an interior node",
+                port
+            )));
+        }
+    }
+    if ports[0] == ports[1] {
+        return Err(Error::InvalidInput(format!(
+            "ports must differ, both are {:?}",
+            ports[0]
+        )));
+    }
+
+    let mut w = Writer::default();
+    let nseries = branches
+        .iter()
+        .filter(|val| val.placement == Placement::Series)
+        .count();
+    let mut node = ports[0].to_string();
+    let mut seen = 0;
+    for branch in branches {
+        match branch.placement {
+            Placement::Series => {
+                seen += 1;
+                let next = if seen == nseries {
+                    ports[1].to_string()
+                } else {
+                    w.node()
+                };
+                w.chain(&branch.parts, &node, &next)?;
+                node = next;
+            }
+            Placement::Shunt => w.chain(&branch.parts, &node, "0")?,
+        }
+    }
+    // Only shunt branches, the ports are the same node
+    if nseries == 0 {
+        w.chain(&[], ports[0], ports[1])?;
+    }
+
+    let mut out = comments
+        .iter()
+        .map(|val| format!("* {}", val))
+        .collect::<Vec<String>>();
+    out.push(format!(".SUBCKT {} {} {}", name, ports[0], ports[1]));
+    out.append(&mut w.lines);
+    out.push(format!(".ENDS {}", name));
+    Ok(out.join("\n") + "\n")
+}
+
+// Series resistance of an inductor or capacitor of quality factor q at w, none for q = 0
+fn q_res(kind: Kind, val: f64, q: f64, w: f64) -> f64 {
+    if q <= 0.0 {
+        return 0.0;
+    }
+    match kind {
+        Kind::Inductor => w * val / q,
+        Kind::Capacitor => 1.0 / (w * val * q),
+    }
+}
+
+// Ladder with vals in topology order, capacitors in c_scale and inductors in l_scale
+pub fn ladder_branches(
+    topology: &Topology,
+    vals: &[f64],
+    c_scale: &Unit,
+    l_scale: &Unit,
+    w: f64,
+    q_c: f64,
+    q_l: f64,
+) -> Result<Vec<Branch>, Error> {
+    if vals.len() != topology.elements.len() {
+        return Err(Error::InvalidInput(format!(
+            "ladder {} has {} elements, got {} values",
+            topology,
+            topology.elements.len(),
+            vals.len()
+        )));
+    }
+
+    Ok(topology
+        .elements
+        .iter()
+        .zip(vals)
+        .map(|(element, val)| {
+            let part = match element.kind {
+                Kind::Capacitor => {
+                    let c = unscale(*val, c_scale);
+                    Part::C {
+                        c,
+                        r: q_res(Kind::Capacitor, c, q_c, w),
+                    }
+                }
+                Kind::Inductor => {
+                    let l = unscale(*val, l_scale);
+                    Part::L {
+                        l,
+                        r: q_res(Kind::Inductor, l, q_l, w),
+                    }
+                }
+            };
+            Branch {
+                placement: element.placement,
+                parts: vec![part],
+            }
+        })
+        .collect())
+}
+
+// calc_networks topologies, named as in its result. Pi and Tee results hold a high and a low pass
+// network, told apart by the _hp and _lp suffixes.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Network {
+    Hp1,
+    Hp2,
+    Lp1,
+    Lp2,
+    Bp1,
+    Bp2,
+    Bp3,
+    Bp4,
+    PiHp,
+    PiLp,
+    TeeHp,
+    TeeLp,
+    HpEllCl,
+    HpEllLc,
+    LpEllCl,
+    LpEllLc,
+    HpEllClWQ,
+    HpEllLcWQ,
+    LpEllClWQ,
+    LpEllLcWQ,
+}
+
+// Each network with its name and its topology from source to load
+const NETWORKS: [(Network, &str, &str); 20] = [
+    (Network::Hp1, "hp1", "pL-sC-pL-sC"),
+    (Network::Hp2, "hp2", "sC-pL-sC-pL"),
+    (Network::Lp1, "lp1", "pC-sL-pC-sL"),
+    (Network::Lp2, "lp2", "sL-pC-sL-pC"),
+    (Network::Bp1, "bp1", "pL-sC-pC-sL"),
+    (Network::Bp2, "bp2", "sL-pC-sC-pL"),
+    (Network::Bp3, "bp3", "pC-sL-pL-sC"),
+    (Network::Bp4, "bp4", "sC-pL-sL-pC"),
+    (Network::PiHp, "pi_hp", "pL-sC-pL"),
+    (Network::PiLp, "pi_lp", "pC-sL-pC"),
+    (Network::TeeHp, "tee_hp", "sC-pL-sC"),
+    (Network::TeeLp, "tee_lp", "sL-pC-sL"),
+    (Network::HpEllCl, "hp_ell_cl", "sC-pL"),
+    (Network::HpEllLc, "hp_ell_lc", "pL-sC"),
+    (Network::LpEllCl, "lp_ell_cl", "pC-sL"),
+    (Network::LpEllLc, "lp_ell_lc", "sL-pC"),
+    (Network::HpEllClWQ, "hp_ell_cl_w_q", "sC-pL"),
+    (Network::HpEllLcWQ, "hp_ell_lc_w_q", "pL-sC"),
+    (Network::LpEllClWQ, "lp_ell_cl_w_q", "pC-sL"),
+    (Network::LpEllLcWQ, "lp_ell_lc_w_q", "sL-pC"),
+];
+
+impl Network {
+    fn entry(self) -> &'static (Network, &'static str, &'static str) {
+        NETWORKS.iter().find(|val| val.0 == self).unwrap()
+    }
+
+    pub fn name(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn topology(self) -> Topology {
+        // The table only holds valid ladders
+        Topology::from_str(self.entry().2).unwrap()
+    }
+
+    // Field of the calc_networks result holding the solutions of the network
+    pub fn key(self) -> &'static str {
+        match self {
+            Network::PiHp | Network::PiLp => "pi",
+            Network::TeeHp | Network::TeeLp => "tee",
+            val => val.name(),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NETWORKS
+            .iter()
+            .find(|val| val.1 == s)
+            .map(|val| val.0)
+            .ok_or_else(|| Error::Parse(format!("network {:?} not recognized", s)))
+    }
+}
+
+impl TryFrom<String> for Network {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Solution of a topology in the whole of a calc_networks result. Each Pi and Tee solution holds
+// both their high and low pass networks.
+pub fn network_solution(
+    networks: &Value,
+    network: Network,
+    solution: usize,
+) -> Result<&Value, Error> {
+    let vals = networks
+        .get(network.key())
+        .and_then(Value::as_array)
+        .ok_or_else(|| Error::InvalidInput(format!("networks have no {}", network.key())))?;
+    vals.get(solution).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "network {} has {} solutions, asked for solution {}",
+            network,
+            vals.len(),
+            solution
+        ))
+    })
+}
+
+// One solution of a network: its values from source to load, the reason it may have none and,
+// for the networks solved with one, the inductor Q
+struct NetworkValues {
+    vals: Vec<f64>,
+    reason: Option<Unrealizable>,
+    c_scale: String,
+    l_scale: String,
+    q_l: Option<f64>,
+}
+
+fn solution<T: DeserializeOwned>(network: Network, values: &Value) -> Result<T, Error> {
+    T::deserialize(values).map_err(|err| Error::Parse(format!("{} solution: {}", network, err)))
+}
+
+impl NetworkValues {
+    fn parse(network: Network, values: &Value) -> Result<Self, Error> {
+        use Network::*;
+        Ok(match network {
+            Hp1 | Hp2 | Lp1 | Lp2 | Bp1 | Bp2 | Bp3 | Bp4 => {
+                let val: CCLL = solution(network, values)?;
+                let vals = match network {
+                    Hp1 => vec![val.ls, val.cs, val.ll, val.cl],
+                    Hp2 => vec![val.cl, val.ll, val.cs, val.ls],
+                    Lp1 => vec![val.cs, val.ls, val.cl, val.ll],
+                    Lp2 => vec![val.ll, val.cl, val.ls, val.cs],
+                    Bp1 => vec![val.ls, val.cs, val.cl, val.ll],
+                    Bp2 => vec![val.ll, val.cl, val.cs, val.ls],
+                    Bp3 => vec![val.cs, val.ls, val.ll, val.cl],
+                    _ => vec![val.cl, val.ll, val.ls, val.cs],
+                };
+                NetworkValues {
+                    vals,
+                    reason: val.reason,
+                    c_scale: val.c_scale,
+                    l_scale: val.l_scale,
+                    q_l: None,
+                }
+            }
+            PiHp | PiLp | TeeHp | TeeLp => {
+                let val: PiTee = solution(network, values)?;
+                let (vals, reason) = match network {
+                    PiHp => (vec![val.ls, val.c, val.ll], val.c_reason),
+                    PiLp => (vec![val.cs, val.l, val.cl], val.l_reason),
+                    TeeHp => (vec![val.cs, val.l, val.cl], val.l_reason),
+                    _ => (vec![val.ls, val.c, val.ll], val.c_reason),
+                };
+                NetworkValues {
+                    vals,
+                    reason,
+                    c_scale: val.c_scale,
+                    l_scale: val.l_scale,
+                    q_l: None,
+                }
+            }
+            HpEllCl | HpEllLc | LpEllCl | LpEllLc => {
+                let val: CL = solution(network, values)?;
+                NetworkValues {
+                    vals: match network {
+                        HpEllCl | LpEllCl => vec![val.c, val.l],
+                        _ => vec![val.l, val.c],
+                    },
+                    reason: val.reason,
+                    c_scale: val.c_scale,
+                    l_scale: val.l_scale,
+                    q_l: None,
+                }
+            }
+            HpEllClWQ | HpEllLcWQ | LpEllClWQ | LpEllLcWQ => {
+                let val: CLQ = solution(network, values)?;
+                NetworkValues {
+                    vals: match network {
+                        HpEllClWQ | LpEllClWQ => vec![val.c, val.l],
+                        _ => vec![val.l, val.c],
+                    },
+                    reason: val.reason,
+                    c_scale: val.c_scale,
+                    l_scale: val.l_scale,
+                    // The networks solved with an inductor Q keep that Q
+                    q_l: Some(val.q),
+                }
+            }
+        })
+    }
+
+    fn branches(&self, network: Network, w: f64, q_c: f64, q_l: f64) -> Result<Vec<Branch>, Error> {
+        if !self.vals.iter().all(|val| val.is_finite()) {
+            let msg = self
+                .reason
+                .as_ref()
+                .map_or("no solution".to_string(), Unrealizable::message);
+            return Err(Error::Unrealizable(format!("{}: {}", network, msg)));
+        }
+        ladder_branches(
+            &network.topology(),
+            &self.vals,
+            &Unit::from_str(&self.c_scale)?,
+            &Unit::from_str(&self.l_scale)?,
+            w,
+            q_c,
+            self.q_l.unwrap_or(q_l),
+        )
+    }
+}
+
+// One solution of a calc_networks topology, as returned for it, e.g. the first entry of hp1 or
+// the whole of pi for Network::PiHp
+pub fn network_branches(
+    network: Network,
+    values: &Value,
+    w: f64,
+    q_c: f64,
+    q_l: f64,
+) -> Result<Vec<Branch>, Error> {
+    NetworkValues::parse(network, values)?.branches(network, w, q_c, q_l)
+}
+
+fn q_comment(freq: f64, q_c: f64, q_l: f64) -> Option<String> {
+    let q = |name: &str, val: f64| (val > 0.0).then(|| format!("Q{} = {}", name, val));
+    let qs = [q("c", q_c), q("l", q_l)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>();
+    (!qs.is_empty()).then(|| format!("{} at {}Hz", qs.join(", "), eng(freq)))
+}
+
+pub fn network_netlist(
+    network: Network,
+    values: &Value,
+    freq: f64,
+    freq_unit: Unit,
+    q_c: f64,
+    q_l: f64,
+    ports: [&str; 2],
+) -> Result<String, Error> {
+    let freq = unscale(freq, &freq_unit);
+    let values = NetworkValues::parse(network, values)?;
+    let branches = values.branches(network, 2.0 * PI * freq, q_c, q_l)?;
+    let q_l = values.q_l.unwrap_or(q_l);
+
+    let mut comments = vec![format!(
+        "{} matching network, source at {} and load at {}",
+        network, ports[0], ports[1]
+    )];
+    comments.extend(q_comment(freq, q_c, q_l));
+    subckt(network.name(), ports, &comments, &branches)
+}
+
+fn number(element: &Value, field: &str, idx: usize) -> Result<f64, Error> {
+    element
+        .get(field)
+        .and_then(|val| val.get(idx))
+        .and_then(Value::as_f64)
+        .ok_or_else(|| Error::InvalidInput(format!("element has no {}[{}]", field, idx)))
+}
+
+fn unit(element: &Value, idx: usize) -> Result<Unit, Error> {
+    let unit = element
+        .get("unit")
+        .and_then(|val| val.get(idx))
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::InvalidInput(format!("element has no unit[{}]", idx)))?;
+    Unit::from_str(unit)
+}
+
+// abs[idx] in base units
+fn base(element: &Value, idx: usize) -> Result<f64, Error> {
+    Ok(unscale(number(element, "abs", idx)?, &unit(element, idx)?))
+}
+
+// Optional abs[idx] in base units, zero when the schematic leaves it out
+fn parasitic(element: &Value, idx: usize) -> Result<f64, Error> {
+    match number(element, "abs", idx) {
+        Ok(_) => base(element, idx),
+        Err(_) => Ok(0.0),
+    }
+}
+
+// Loss of a Smith chart L or C, given as a Q at the schematic frequency, or at abs[3] when one is
+// quoted there, or as a resistance. As in Inductor::z a quoted inductor loss follows the skin effect.
+fn loss(element: &Value, kind: Kind, val: f64, w: f64) -> Result<f64, Error> {
+    let r = number(element, "abs", 0)?;
+    let w_q = match parasitic(element, 3)? {
+        f if f > 0.0 => 2.0 * PI * f,
+        _ => w,
+    };
+    let r = match unit(element, 0)? {
+        Unit::Q => q_res(kind, val, r, w_q),
+        scale => unscale(r, &scale),
+    };
+    match kind {
+        Kind::Inductor => Ok(r * (w / w_q).sqrt()),
+        Kind::Capacitor => Ok(r),
+    }
+}
+
+// Delay of a line of length abs[0] in wavelengths at freq or in meters, e.g. "mm", where a unit
+// of "m" is meters rather than milli
+fn delay(element: &Value, freq: f64, er: f64) -> Result<f64, Error> {
+    let length = number(element, "abs", 0)?;
+    let unit = element
+        .get("unit")
+        .and_then(|val| val.get(0))
+        .and_then(Value::as_str)
+        .unwrap_or("m");
+    let unit = match unit.trim() {
+        "m" => Unit::Base,
+        val => match val.strip_suffix('m') {
+            Some(prefix) => Unit::from_str(prefix)?,
+            None => Unit::from_str(val)?,
+        },
+    };
+    match unit {
+        Unit::Lambda(_, _) => Ok(length / freq),
+        scale => Ok(unscale(length, &scale) * er.sqrt() / C0),
+    }
+}
+
+// One element of a Smith chart schematic. Elements with a series counterpart are named s.. in
+// the path and p.. to ground.
+fn schematic_branch(element: &Value, freq: f64, er: f64) -> Result<Branch, Error> {
+    let w = 2.0 * PI * freq;
+    let kind = element.get("type").and_then(Value::as_str).unwrap_or("");
+    let placement = match kind {
+        "sr" | "si" | "sc" | "srlc" | "tl" | "xfmr" => Placement::Series,
+        "pr" | "pi" | "pc" | "prlc" | "ss" | "so" => Placement::Shunt,
+        _ => {
+            return Err(Error::InvalidInput(format!(
+                "element {:?} has no SPICE model",
+                kind
+            )))
+        }
+    };
+
+    let parts = match kind {
+        "sr" | "pr" => vec![Part::R(base(element, 0)?)],
+        "si" | "pi" => {
+            let l = base(element, 1)?;
+            let r = loss(element, Kind::Inductor, l, w)?;
+            match parasitic(element, 2)? {
+                c if c > 0.0 => vec![Part::Tank { l, r, c }],
+                _ => vec![Part::L { l, r }],
+            }
+        }
+        "sc" | "pc" => {
+            let c = base(element, 1)?;
+            let r = loss(element, Kind::Capacitor, c, w)?;
+            match parasitic(element, 2)? {
+                l if l > 0.0 => vec![Part::C { c, r }, Part::L { l, r: 0.0 }],
+                _ => vec![Part::C { c, r }],
+            }
+        }
+        // Series R, L and C, a zero value leaving that part out
+        "srlc" | "prlc" => {
+            let (r, l, c) = (base(element, 0)?, base(element, 1)?, base(element, 2)?);
+            [
+                (r != 0.0).then_some(Part::R(r)),
+                (l != 0.0).then_some(Part::L { l, r: 0.0 }),
+                (c != 0.0).then_some(Part::C { c, r: 0.0 }),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+        "xfmr" => {
+            let vals = (0..6)
+                .map_while(|i| number(element, "abs", i).ok())
+                .collect::<Vec<f64>>();
+            let units = element
+                .get("unit")
+                .and_then(Value::as_array)
+                .map(|val| val.iter().filter_map(Value::as_str).collect::<Vec<&str>>())
+                .unwrap_or_default();
+            let xfmr = gen_transformer(ElementType::Transformer, &vals, &units)?;
+            let (lp, ls, _) = xfmr.inductances();
+            let (rp, rs) = xfmr.losses(Frequency::new(freq, Unit::Base));
+            vec![Part::Xfmr {
+                lp,
+                ls,
+                k: xfmr.coupling(),
+                rp,
+                rs,
+                c: unscale(xfmr.cap(), &xfmr.cap_unit()),
+            }]
+        }
+        _ => {
+            let z0 = element
+                .get("line_zo")
+                .and_then(Value::as_f64)
+                .unwrap_or(50.0);
+            let td = delay(element, freq, er)?;
+            match kind {
+                "tl" => vec![Part::Line { z0, td }],
+                _ => vec![Part::Stub {
+                    z0,
+                    td,
+                    short: kind == "ss",
+                }],
+            }
+        }
+    };
+
+    Ok(Branch { placement, parts })
+}
+
+// The elements of a Smith chart schematic after its source settings and black box load. They run
+// from the load toward the source and are turned around to run from source to load.
+pub fn schematic_branches(elements: &[Value]) -> Result<Vec<Branch>, Error> {
+    let header = elements
+        .first()
+        .ok_or_else(|| Error::InvalidInput("schematic is empty".to_string()))?;
+    let multiplier = header
+        .get("freq_unit")
+        .and_then(|val| val.get("multiplier"))
+        .and_then(Value::as_f64)
+        .unwrap_or(1.0);
+    let freq = header
+        .get("freq")
+        .and_then(Value::as_f64)
+        .ok_or_else(|| Error::InvalidInput("schematic has no frequency".to_string()))?
+        * multiplier;
+    let er = header.get("er").and_then(Value::as_f64).unwrap_or(1.0);
+    if elements.len() <= 2 {
+        return Err(Error::InvalidInput(
+            "schematic has no elements to export".to_string(),
+        ));
+    }
+
+    elements
+        .iter()
+        .enumerate()
+        .skip(2)
+        .rev()
+        .map(|(i, element)| {
+            schematic_branch(element, freq, er)
+                .map_err(|err| err.context(&format!("schematic element {}", i)))
+        })
+        .collect()
+}
+
+pub fn schematic_netlist(
+    elements: &[Value],
+    name: &str,
+    ports: [&str; 2],
+) -> Result<String, Error> {
+    let branches = schematic_branches(elements)?;
+    let comments = vec![format!(
+        "Smith chart schematic, source at {} and load at {}",
+        ports[0], ports[1]
+    )];
+    subckt(name, ports, &comments, &branches)
+}
+
+// SPICE value with an optional scale suffix, anything after it being ignored as SPICE does,
+// e.g. "10pF", "2.2MEG" or "1e-9"
+pub fn parse_value(s: &str) -> Result<f64, Error> {
+    let lower = s.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+    let mut end = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        end += 1;
+    }
+    while digit(end) || bytes.get(end) == Some(&b'.') {
+        end += 1;
+    }
+    if bytes.get(end) == Some(&b'e') {
+        let mut exp = end + 1;
+        if matches!(bytes.get(exp), Some(b'+' | b'-')) {
+            exp += 1;
+        }
+        if digit(exp) {
+            end = exp;
+            while digit(end) {
+                end += 1;
+            }
+        }
+    }
+    let val = lower[..end]
+        .parse::<f64>()
+        .map_err(|_| Error::Parse(format!("{:?} is not a SPICE value", s)))?;
+    let suffix = &lower[end..];
+    let scale = if suffix.starts_with("meg") {
+        1e6
+    } else if suffix.starts_with("mil") {
+        25.4e-6
+    } else {
+        match suffix.chars().next() {
+            Some('t') => 1e12,
+            Some('g') => 1e9,
+            Some('k') => 1e3,
+            Some('m') => 1e-3,
+            Some('u' | 'μ' | 'µ') => 1e-6,
+            Some('n') => 1e-9,
+            Some('p') => 1e-12,
+            Some('f') => 1e-15,
+            _ => 1.0,
+        }
+    };
+    Ok(val * scale)
+}
+
+// One element of an imported ladder as a Smith chart element code with the values and units
+// smith::gen_element takes. Lines and stubs are [z0, er, length] with the length in um.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Stage {
+    pub element: ElementType,
+    pub vals: Vec<f64>,
+    pub units: Vec<String>,
+}
+
+impl Stage {
+    pub fn element(&self, z0: f64) -> Result<Box<dyn Element>, Error> {
+        let units = self.units.iter().map(String::as_str).collect::<Vec<&str>>();
+        gen_element(self.element, &self.vals, &units, c64(1.0, 0.0), z0)
+    }
+
+    pub fn to_schematic(&self) -> Value {
+        match self.element {
+            ElementType::TLine | ElementType::ShortedStub | ElementType::OpenStub => json!({
+                "type": self.element.to_string(),
+                "line_length": unscale(self.vals[2], &Unit::Micro),
+                "abs": [self.vals[2]],
+                "line_zo": self.vals[0],
+                "unit": self.units,
+                "real": 0,
+                "imaginary": 0,
+                "tol": 0,
+            }),
+            _ => json!({
+                "type": self.element.to_string(),
+                "real": 0,
+                "imaginary": 0,
+                "abs": self.vals,
+                "unit": self.units,
+                "tol": 0,
+            }),
+        }
+    }
+}
+
+// A two-port subcircuit brought down to a series/shunt cascade, stages running from the first
+// port (source) to the second (load)
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Ladder {
+    pub name: String,
+    pub ports: [String; 2],
+    pub stages: Vec<Stage>,
+}
+
+impl Ladder {
+    // Elements for calc_abcd, port 1 at the source. Transformer stages have their primary on the
+    // load side as in the Smith chart, so the windings trade places here.
+    pub fn elements(&self, z0: f64) -> Result<Vec<Box<dyn Element>>, Error> {
+        self.stages
+            .iter()
+            .map(|val| match val.element {
+                ElementType::Transformer => {
+                    let mut stage = val.clone();
+                    stage.vals.swap(1, 2);
+                    stage.units.swap(1, 2);
+                    stage.element(z0)
+                }
+                _ => val.element(z0),
+            })
+            .collect()
+    }
+
+    // Elements for a Smith chart schematic, which run from the load toward the source
+    pub fn to_schematic(&self) -> Vec<Value> {
+        self.stages.iter().rev().map(Stage::to_schematic).collect()
+    }
+}
+
+// Rounded to 10 significant digits, so that values read back from a netlist show as written
+fn tidy(val: f64) -> f64 {
+    format!("{:.9e}", val).parse::<f64>().unwrap_or(val)
+}
+
+// Value in the engineering unit that suits it, e.g. 1.2e-12 H => (1.2, "pH")
+fn scaled(val: f64, symbol: &str) -> (f64, String) {
+    let unit = Unit::eng(val);
+    (tidy(val * unit.scale()), format!("{}{}", unit, symbol))
+}
+
+// R, L and C in series, 1/C summed as elastance. Loss goes with the L or C as a resistance.
+fn lumped_stage(placement: Placement, r: f64, l: f64, elastance: f64) -> Stage {
+    let series = placement == Placement::Series;
+    let (element, vals, units) = match (l > 0.0, elastance > 0.0) {
+        (false, false) => (
+            [ElementType::SeriesRes, ElementType::ShuntRes],
+            vec![r],
+            vec!["Ω".to_string()],
+        ),
+        (true, false) => {
+            let (l, l_unit) = scaled(l, "H");
+            (
+                [ElementType::SeriesInd, ElementType::ShuntInd],
+                vec![r, l],
+                vec!["Ω".to_string(), l_unit],
+            )
+        }
+        (false, true) => {
+            let (c, c_unit) = scaled(1.0 / elastance, "F");
+            (
+                [ElementType::SeriesCap, ElementType::ShuntCap],
+                vec![r, c],
+                vec!["Ω".to_string(), c_unit],
+            )
+        }
+        (true, true) => {
+            let (l, l_unit) = scaled(l, "H");
+            let (c, c_unit) = scaled(1.0 / elastance, "F");
+            (
+                [ElementType::SeriesRlc, ElementType::ShuntRlc],
+                vec![r, l, c],
+                vec!["Ω".to_string(), l_unit, c_unit],
+            )
+        }
+    };
+    Stage {
+        element: element[usize::from(!series)],
+        vals,
+        units,
+    }
+}
+
+fn line_stage(element: ElementType, z0: f64, td: f64, er: f64) -> Stage {
+    Stage {
+        element,
+        vals: vec![z0, er, tidy(scale(td * C0 / er.sqrt(), &Unit::Micro))],
+        units: vec!["um".to_string()],
+    }
+}
+
+// The Smith chart takes the primary as the winding on the load side
+fn xfmr_stage(lp: f64, ls: f64, k: f64) -> Stage {
+    let (lp, lp_unit) = scaled(lp, "H");
+    let (ls, ls_unit) = scaled(ls, "H");
+    Stage {
+        element: ElementType::Transformer,
+        vals: vec![0.0, lp, ls, k],
+        units: vec!["Ω".to_string(), lp_unit, ls_unit, "K".to_string()],
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Card {
+    Lumped {
+        name: String,
+        kind: char,
+        nodes: [String; 2],
+        val: f64,
+    },
+    Line {
+        name: String,
+        nodes: [String; 4],
+        z0: f64,
+        td: f64,
+    },
+    Coupling {
+        name: String,
+        inds: [String; 2],
+        k: f64,
+    },
+    Short {
+        nodes: [String; 2],
+    },
+}
+
+// Statements of a netlist with comments dropped and continuation lines joined
+fn statements(netlist: &str) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    for line in netlist.lines() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('*') {
+            continue;
+        }
+        match (line.strip_prefix('+'), out.last_mut()) {
+            (Some(rest), Some(last)) => {
+                last.push(' ');
+                last.push_str(rest);
+            }
+            _ => out.push(line.to_string()),
+        }
+    }
+    out
+}
+
+// Positional fields and key=value parameters, keys in lower case
+fn fields(statement: &str) -> (Vec<String>, Vec<(String, String)>) {
+    let tokens = statement
+        .replace('=', " = ")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<String>>();
+    let mut pos = vec![];
+    let mut params = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens.get(i + 1).is_some_and(|val| val == "=") && i + 2 < tokens.len() {
+            params.push((tokens[i].to_ascii_lowercase(), tokens[i + 2].clone()));
+            i += 3;
+        } else {
+            pos.push(tokens[i].clone());
+            i += 1;
+        }
+    }
+    (pos, params)
+}
+
+// Node names are case insensitive and gnd is ground
+fn node_name(node: &str) -> String {
+    match node.to_ascii_lowercase().as_str() {
+        "gnd" => "0".to_string(),
+        val => val.to_string(),
+    }
+}
+
+fn card(pos: &[String], params: &[(String, String)]) -> Result<Card, Error> {
+    let name = pos[0].clone();
+    let nodes = |n: usize| -> Result<Vec<String>, Error> {
+        if pos.len() <= n {
+            return Err(Error::Parse(format!("{} needs {} nodes", name, n)));
+        }
+        Ok(pos[1..=n].iter().map(|val| node_name(val)).collect())
+    };
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, val)| parse_value(val))
+            .transpose()
+    };
+    let positive = |val: f64, what: &str| {
+        if !val.is_finite() || val <= 0.0 {
+            return Err(Error::InvalidInput(format!(
+                "{} needs a positive {}, got {}",
+                name, what, val
+            )));
+        }
+        Ok(val)
+    };
+
+    match name.chars().next().map(|c| c.to_ascii_lowercase()) {
+        Some(kind @ ('r' | 'l' | 'c')) => {
+            let nodes = nodes(2)?;
+            let val = pos
+                .get(3)
+                .ok_or_else(|| Error::Parse(format!("{} has no value", name)))?;
+            let val = positive(parse_value(val)?, "value")?;
+            Ok(Card::Lumped {
+                name: name.clone(),
+                kind,
+                nodes: [nodes[0].clone(), nodes[1].clone()],
+                val,
+            })
+        }
+        Some('k') => {
+            if pos.len() < 4 {
+                return Err(Error::Parse(format!(
+                    "{} needs two inductors and a coupling factor",
+                    name
+                )));
+            }
+            let k = parse_value(&pos[3])?;
+            if !(k > 0.0 && k <= 1.0) {
+                return Err(Error::NonPhysical(format!(
+                    "{} has a coupling factor of {}, outside (0, 1]",
+                    name, k
+                )));
+            }
+            Ok(Card::Coupling {
+                name: name.clone(),
+                inds: [pos[1].to_ascii_lowercase(), pos[2].to_ascii_lowercase()],
+                k,
+            })
+        }
+        Some('t') => {
+            let nodes = nodes(4)?;
+            let z0 = param("z0")?.ok_or_else(|| Error::Parse(format!("{} has no Z0", name)))?;
+            let td = match (param("td")?, param("f")?) {
+                (Some(td), _) => td,
+                // Electrical length in wavelengths at f, a quarter wave unless given
+                (None, Some(f)) => param("nl")?.unwrap_or(0.25) / positive(f, "F")?,
+                (None, None) => {
+                    return Err(Error::Parse(format!("{} needs TD or F", name)));
+                }
+            };
+            Ok(Card::Line {
+                name: name.clone(),
+                nodes: [
+                    nodes[0].clone(),
+                    nodes[1].clone(),
+                    nodes[2].clone(),
+                    nodes[3].clone(),
+                ],
+                z0: positive(z0, "Z0")?,
+                td: positive(td, "delay")?,
+            })
+        }
+        // A 0 V source is how SPICE writes a short between two nodes
+        Some('v') => {
+            let nodes = nodes(2)?;
+            let vals = pos[3..]
+                .iter()
+                .filter(|val| !val.eq_ignore_ascii_case("dc"))
+                .map(|val| parse_value(val))
+                .collect::<Result<Vec<f64>, Error>>()?;
+            if vals.iter().any(|val| *val != 0.0) {
+                return Err(Error::InvalidInput(format!(
+                    "{} is a source, only 0 V sources can stand in for a short",
+                    name
+                )));
+            }
+            Ok(Card::Short {
+                nodes: [nodes[0].clone(), nodes[1].clone()],
+            })
+        }
+        _ => Err(Error::InvalidInput(format!(
+            "{} is not an R, L, C, K, T or 0 V source",
+            name
+        ))),
+    }
+}
+
+// The first subcircuit of a netlist and its ports
+fn subckt_cards(netlist: &str) -> Result<(String, Vec<String>, Vec<Card>), Error> {
+    let mut header = None;
+    let mut cards = vec![];
+    for statement in statements(netlist) {
+        let (pos, params) = fields(&statement);
+        let Some(first) = pos.first().map(|val| val.to_ascii_lowercase()) else {
+            return Err(Error::Parse(format!("{:?} is not a SPICE card", statement)));
+        };
+        if header.is_none() {
+            // The title line and anything else ahead of the subcircuit is skipped
+            if first == ".subckt" {
+                if pos.len() < 3 {
+                    return Err(Error::InvalidInput(format!(
+                        "subcircuit needs a name and ports, got {:?}",
+                        statement
+                    )));
+                }
+                header = Some((pos[1].clone(), pos[2..].to_vec()));
+            }
+            continue;
+        }
+        match first.as_str() {
+            ".ends" => break,
+            val if val.starts_with('.') => {
+                return Err(Error::InvalidInput(format!(
+                    "{} is not supported inside a subcircuit",
+                    pos[0]
+                )));
+            }
+            _ => cards.push(card(&pos, &params).map_err(|err| err.context(&statement))?),
+        }
+    }
+    let (name, ports) =
+        header.ok_or_else(|| Error::InvalidInput("netlist has no .SUBCKT".to_string()))?;
+    Ok((name, ports, cards))
+}
+
+// Nodes merged by shorts, ground staying the representative of its group
+#[derive(Default)]
+struct Nodes(HashMap<String, String>);
+
+impl Nodes {
+    fn find(&self, node: &str) -> String {
+        let mut node = node;
+        while let Some(val) = self.0.get(node) {
+            node = val;
+        }
+        node.to_string()
+    }
+
+    fn join(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        match a == "0" {
+            true => self.0.insert(b, a),
+            false => self.0.insert(a, b),
+        };
+    }
+}
+
+// A reduced piece of the network, either to ground from a node or between two nodes. A series
+// piece holds its stage for each direction it can be walked in, a to b first.
+enum Piece {
+    Shunt {
+        node: String,
+        names: String,
+        stage: Stage,
+    },
+    Series {
+        nodes: [String; 2],
+        names: String,
+        stages: [Stage; 2],
+    },
+}
+
+impl Piece {
+    fn names(&self) -> &str {
+        match self {
+            Piece::Shunt { names, .. } | Piece::Series { names, .. } => names,
+        }
+    }
+}
+
+struct Edge {
+    name: String,
+    kind: char,
+    nodes: [String; 2],
+    val: f64,
+}
+
+// Two-terminal parts strung through nodes that join only the two of them are one series branch
+fn chain_pieces(
+    edges: &[Edge],
+    pinned: &HashSet<String>,
+    pieces: &mut Vec<Piece>,
+) -> Result<(), Error> {
+    let mut degree: HashMap<&str, usize> = HashMap::new();
+    for edge in edges {
+        for node in &edge.nodes {
+            *degree.entry(node).or_default() += 1;
+        }
+    }
+    let interior = |node: &str| !pinned.contains(node) && degree.get(node) == Some(&2);
+
+    let mut used = vec![false; edges.len()];
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut parts = vec![start];
+        let mut ends = edges[start].nodes.clone();
+        for end in ends.iter_mut() {
+            while interior(end) {
+                let next = (0..edges.len()).find(|i| !used[*i] && edges[*i].nodes.contains(end));
+                let Some(i) = next else {
+                    break;
+                };
+                used[i] = true;
+                parts.push(i);
+                *end = match edges[i].nodes[0] == *end {
+                    true => edges[i].nodes[1].clone(),
+                    false => edges[i].nodes[0].clone(),
+                };
+            }
+        }
+
+        let names = parts
+            .iter()
+            .map(|i| edges[*i].name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        if ends[0] == ends[1] {
+            return Err(Error::InvalidInput(format!(
+                "{} form a loop at node {}",
+                names, ends[0]
+            )));
+        }
+        if let Some(end) = ends
+            .iter()
+            .find(|val| !pinned.contains(*val) && degree.get(val.as_str()) == Some(&1))
+        {
+            return Err(Error::InvalidInput(format!(
+                "{} {} left open at node {}",
+                names,
+                if parts.len() == 1 { "is" } else { "are" },
+                end
+            )));
+        }
+
+        let (mut r, mut l, mut elastance) = (0.0, 0.0, 0.0);
+        for i in &parts {
+            match edges[*i].kind {
+                'r' => r += edges[*i].val,
+                'l' => l += edges[*i].val,
+                _ => elastance += 1.0 / edges[*i].val,
+            }
+        }
+        let piece = match ends.iter().position(|val| val == "0") {
+            Some(ground) => Piece::Shunt {
+                node: ends[1 - ground].clone(),
+                names,
+                stage: lumped_stage(Placement::Shunt, r, l, elastance),
+            },
+            None => {
+                let stage = lumped_stage(Placement::Series, r, l, elastance);
+                Piece::Series {
+                    nodes: ends,
+                    names,
+                    stages: [stage.clone(), stage],
+                }
+            }
+        };
+        pieces.push(piece);
+    }
+    Ok(())
+}
+
+// Cuts a subcircuit into a series/shunt cascade between its two ports. Coupled inductors need
+// one end of each winding grounded and lines their reference terminals grounded, stubs being
+// lines with the far end shorted to ground or left open. Lines get their length in a medium of
+// relative permittivity er.
+pub fn parse_ladder(netlist: &str, er: f64) -> Result<Ladder, Error> {
+    let (name, ports, cards) = subckt_cards(netlist)?;
+    if ports.len() != 2 {
+        return Err(Error::InvalidInput(format!(
+            "{} has {} ports, a cascade needs two",
+            name,
+            ports.len()
+        )));
+    }
+
+    let mut nodes = Nodes::default();
+    for card in &cards {
+        if let Card::Short { nodes: [a, b] } = card {
+            nodes.join(a, b);
+        }
+    }
+    let port_nodes = [
+        nodes.find(&node_name(&ports[0])),
+        nodes.find(&node_name(&ports[1])),
+    ];
+    for (port, node) in ports.iter().zip(port_nodes.iter()) {
+        if node == "0" {
+            return Err(Error::InvalidInput(format!(
+                "port {} is shorted to ground",
+                port
+            )));
+        }
+    }
+
+    // Every terminal, to tell the open end of a stub
+    let mut count: HashMap<String, usize> = HashMap::new();
+    let mut edges = vec![];
+    let mut lines = vec![];
+    let mut couplings = vec![];
+    for card in &cards {
+        match card {
+            Card::Lumped {
+                name,
+                kind,
+                nodes: [a, b],
+                val,
+            } => {
+                let ends = [nodes.find(a), nodes.find(b)];
+                for node in &ends {
+                    *count.entry(node.clone()).or_default() += 1;
+                }
+                edges.push(Edge {
+                    name: name.clone(),
+                    kind: *kind,
+                    nodes: ends,
+                    val: *val,
+                });
+            }
+            Card::Line {
+                name,
+                nodes: ends,
+                z0,
+                td,
+            } => {
+                let ends = ends.clone().map(|val| nodes.find(&val));
+                for node in &ends {
+                    *count.entry(node.clone()).or_default() += 1;
+                }
+                lines.push((name.clone(), ends, *z0, *td));
+            }
+            Card::Coupling { name, inds, k } => couplings.push((name.clone(), inds.clone(), *k)),
+            Card::Short { .. } => {}
+        }
+    }
+
+    let mut pinned: HashSet<String> = port_nodes.iter().cloned().collect();
+    pinned.insert("0".to_string());
+    let mut pieces = vec![];
+
+    // Each coupled pair becomes a transformer between the ungrounded ends of its windings
+    for (kname, inds, k) in couplings {
+        let mut windings = vec![];
+        for ind in &inds {
+            let idx = edges
+                .iter()
+                .position(|val| val.kind == 'l' && val.name.eq_ignore_ascii_case(ind))
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!(
+                        "{} couples {}, which is not an uncoupled inductor",
+                        kname, ind
+                    ))
+                })?;
+            let edge = edges.remove(idx);
+            let node = match edge.nodes.iter().position(|val| val == "0") {
+                Some(ground) if edge.nodes[1 - ground] != "0" => edge.nodes[1 - ground].clone(),
+                _ => {
+                    return Err(Error::InvalidInput(format!(
+                        "{} needs one end of {} grounded to be a transformer in a ladder",
+                        kname, edge.name
+                    )))
+                }
+            };
+            windings.push((node, edge.val, edge.name));
+        }
+        let (a, b) = (windings[0].clone(), windings[1].clone());
+        if a.0 == b.0 {
+            return Err(Error::InvalidInput(format!(
+                "{} couples {} and {}, which share node {}",
+                kname, a.2, b.2, a.0
+            )));
+        }
+        pinned.insert(a.0.clone());
+        pinned.insert(b.0.clone());
+        pieces.push(Piece::Series {
+            nodes: [a.0, b.0],
+            names: format!("{} ({}, {})", kname, a.2, b.2),
+            stages: [xfmr_stage(b.1, a.1, k), xfmr_stage(a.1, b.1, k)],
+        });
+    }
+
+    for (name, [a1, a2, b1, b2], z0, td) in lines {
+        if a2 != "0" || b2 != "0" {
+            return Err(Error::InvalidInput(format!(
+                "{} needs both reference terminals grounded to be part of a ladder",
+                name
+            )));
+        }
+        // An end that only the line itself touches is open
+        let open = |node: &str| count.get(node) == Some(&1) && !pinned.contains(node);
+        let piece = if b1 == "0" || open(&b1) {
+            let element = match b1 == "0" {
+                true => ElementType::ShortedStub,
+                false => ElementType::OpenStub,
+            };
+            Piece::Shunt {
+                node: a1,
+                names: name,
+                stage: line_stage(element, z0, td, er),
+            }
+        } else if a1 == "0" || open(&a1) {
+            let element = match a1 == "0" {
+                true => ElementType::ShortedStub,
+                false => ElementType::OpenStub,
+            };
+            Piece::Shunt {
+                node: b1,
+                names: name,
+                stage: line_stage(element, z0, td, er),
+            }
+        } else {
+            let stage = line_stage(ElementType::TLine, z0, td, er);
+            Piece::Series {
+                nodes: [a1, b1],
+                names: name,
+                stages: [stage.clone(), stage],
+            }
+        };
+        match &piece {
+            Piece::Shunt { node, .. } => pinned.insert(node.clone()),
+            Piece::Series { nodes, .. } => {
+                pinned.insert(nodes[0].clone());
+                pinned.insert(nodes[1].clone())
+            }
+        };
+        pieces.push(piece);
+    }
+
+    chain_pieces(&edges, &pinned, &mut pieces)?;
+
+    // Walk from the source port, taking the shunts at each node and the one series piece on
+    let mut used = vec![false; pieces.len()];
+    let mut visited = HashSet::new();
+    let mut node = port_nodes[0].clone();
+    let mut stages = vec![];
+    loop {
+        visited.insert(node.clone());
+        for (i, piece) in pieces.iter().enumerate() {
+            if let Piece::Shunt {
+                node: at, stage, ..
+            } = piece
+            {
+                if !used[i] && *at == node {
+                    used[i] = true;
+                    stages.push(stage.clone());
+                }
+            }
+        }
+        if node == port_nodes[1] {
+            break;
+        }
+
+        let next = pieces
+            .iter()
+            .enumerate()
+            .filter(|(i, piece)| {
+                !used[*i] && matches!(piece, Piece::Series { nodes, .. } if nodes.contains(&node))
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        let i = match next.as_slice() {
+            [i] => *i,
+            [] => {
+                return Err(Error::InvalidInput(format!(
+                    "node {} does not lead on to port {}",
+                    node, ports[1]
+                )))
+            }
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "the path splits at node {} into {}, which is not a series/shunt cascade",
+                    node,
+                    next.iter()
+                        .map(|i| pieces[*i].names())
+                        .collect::<Vec<&str>>()
+                        .join(" and ")
+                )))
+            }
+        };
+        used[i] = true;
+        if let Piece::Series {
+            nodes: ends,
+            stages: dir,
+            ..
+        } = &pieces[i]
+        {
+            let forward = ends[0] == node;
+            stages.push(dir[usize::from(!forward)].clone());
+            node = ends[usize::from(forward)].clone();
+        }
+        if visited.contains(&node) {
+            return Err(Error::InvalidInput(format!(
+                "the path loops back to node {}",
+                node
+            )));
+        }
+    }
+
+    let left = pieces
+        .iter()
+        .zip(used.iter())
+        .filter(|(_, used)| !**used)
+        .map(|(piece, _)| piece.names())
+        .collect::<Vec<&str>>();
+    if !left.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "{} not part of the series/shunt cascade from {} to {}",
+            left.join(", "),
+            ports[0],
+            ports[1]
+        )));
+    }
+
+    Ok(Ladder {
+        name,
+        ports: [ports[0].clone(), ports[1].clone()],
+        stages,
+    })
+}
+
+// An edit that swaps the elements of a Smith chart schematic for those of a netlist, so that
+// the import can be undone in one step
+pub fn schematic_import(elements: &[Value], netlist: &str) -> Result<Edit, Error> {
+    let er = elements
+        .first()
+        .and_then(|val| val.get("er"))
+        .and_then(Value::as_f64)
+        .unwrap_or(1.0);
+    let ladder = parse_ladder(netlist, er)?;
+    let mut edits = (2..elements.len())
+        .rev()
+        .map(|index| Edit::Remove { index })
+        .collect::<Vec<Edit>>();
+    edits.extend(
+        ladder
+            .to_schematic()
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| Edit::Add {
+                index: i + 2,
+                element,
+            }),
+    );
+    Ok(Edit::Batch { edits })
+}
+
+// A subcircuit as an arbitrary network for nodal analysis, with a port from each of its port
+// nodes to ground. Coupled inductors become transformers between their windings and lines
+// keep their reference terminals, so neither needs a grounded end here.
+pub fn parse_circuit(netlist: &str, z0: f64) -> Result<Circuit, Error> {
+    let (name, ports, cards) = subckt_cards(netlist)?;
+
+    let mut nodes = Nodes::default();
+    for card in &cards {
+        if let Card::Short { nodes: [a, b] } = card {
+            nodes.join(a, b);
+        }
+    }
+
+    let mut coupled: HashMap<String, (String, [String; 2], f64)> = HashMap::new();
+    for card in &cards {
+        if let Card::Lumped {
+            name,
+            kind: 'l',
+            nodes: [a, b],
+            val,
+        } = card
+        {
+            coupled.insert(
+                name.to_ascii_lowercase(),
+                (name.clone(), [nodes.find(a), nodes.find(b)], *val),
+            );
+        }
+    }
+
+    let mut circuit = Circuit::new();
+    let mut windings = HashSet::new();
+    for card in &cards {
+        if let Card::Coupling { name, inds, k } = card {
+            let [lp, ls] = inds.clone().map(|ind| {
+                coupled.get(&ind).cloned().ok_or_else(|| {
+                    Error::InvalidInput(format!(
+                        "{} couples {}, which is not an inductor",
+                        name, ind
+                    ))
+                })
+            });
+            let ((pname, [p1, n1], lp), (sname, [p2, n2], ls)) = (lp?, ls?);
+            for winding in [&pname, &sname] {
+                if !windings.insert(winding.to_ascii_lowercase()) {
+                    return Err(Error::InvalidInput(format!(
+                        "{} is coupled more than once, only pairs of windings are supported",
+                        winding
+                    )));
+                }
+            }
+            let xfmr = Transformer::new(
+                0.0,
+                lp,
+                ls,
+                *k,
+                Unit::Base,
+                Unit::Base,
+                Unit::Base,
+                Unit::K,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+            );
+            circuit.add_two_port(name, Box::new(xfmr), [&p1, &n1, &p2, &n2]);
+        }
+    }
+
+    for card in &cards {
+        match card {
+            Card::Lumped {
+                name,
+                kind,
+                nodes: [a, b],
+                val,
+            } => {
+                if windings.contains(&name.to_ascii_lowercase()) {
+                    continue;
+                }
+                let part: Box<dyn Element> = match kind {
+                    'r' => Box::new(Resistor::new(*val, Unit::Base, 0.0, Orientation::Series)),
+                    'l' => Box::new(Inductor::new(
+                        0.0,
+                        *val,
+                        Unit::Base,
+                        Unit::Base,
+                        0.0,
+                        0.0,
+                        Orientation::Series,
+                    )),
+                    _ => Box::new(Capacitor::new(
+                        0.0,
+                        *val,
+                        Unit::Base,
+                        Unit::Base,
+                        0.0,
+                        0.0,
+                        Orientation::Series,
+                    )),
+                };
+                circuit.add_branch(name, part, [&nodes.find(a), &nodes.find(b)]);
+            }
+            Card::Line {
+                name,
+                nodes: ends,
+                z0,
+                td,
+            } => {
+                let ends = ends.clone().map(|val| nodes.find(&val));
+                let line = TLine::new(*z0, c64(*z0, 0.0), 1.0, td * C0, Unit::Base);
+                circuit.add_two_port(
+                    name,
+                    Box::new(line),
+                    [&ends[0], &ends[1], &ends[2], &ends[3]],
+                );
+            }
+            Card::Coupling { .. } | Card::Short { .. } => {}
+        }
+    }
+
+    for port in &ports {
+        circuit
+            .add_port([&nodes.find(&node_name(port)), "0"], z0)
+            .map_err(|err| err.context(&format!("{} port {}", name, port)))?;
+    }
+    Ok(circuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frequency::Frequency;
+    use crate::matching::{calc_networks, ImpedanceType};
+    use crate::response::calc_abcd;
+    use crate::rf_utils::{abcd2s, comp_c64, comp_f64, comp_vec_f64, ZScale};
+    use crate::schematic::{Schematic, HISTORY_DEPTH};
+    use float_cmp::F64Margin;
+    use num_complex::{c64, Complex};
+    use serde_json::json;
+
+    #[test]
+    fn test_eng() {
+        let test = [
+            1.5e-12,
+            2.2e6,
+            999.9999e-12,
+            0.0,
+            47.0,
+            1.23456789e-9,
+            3.3e-4,
+        ]
+        .map(eng)
+        .to_vec();
+        assert_eq!(
+            test,
+            vec!["1.5P", "2.2MEG", "1N", "0", "47", "1.23457N", "330U"]
+        );
+    }
+
+    #[test]
+    fn test_subckt() {
+        let branches = vec![
+            Branch {
+                placement: Placement::Series,
+                parts: vec![Part::L { l: 1e-9, r: 0.0 }],
+            },
+            Branch {
+                placement: Placement::Shunt,
+                parts: vec![Part::C { c: 2e-12, r: 0.5 }],
+            },
+            Branch {
+                placement: Placement::Series,
+                parts: vec![Part::C { c: 330e-15, r: 0.0 }],
+            },
+        ];
+        let test = subckt("t", DEFAULT_PORTS, &["test".to_string()], &branches).unwrap();
+        assert_eq!(
+            test,
+            "* test\n.SUBCKT t in out\nL1 in n1 1N\nC1 n1 n2 2P\nRC1 n2 0 500M\nC2 n1 out 330F\n.ENDS t\n"
+        );
+
+        // Without a series element the ports are shorted
+        let test = subckt("t", ["a", "b"], &[], &branches[1..2]).unwrap();
+        assert_eq!(
+            test,
+            ".SUBCKT t a b\nC1 a n1 2P\nRC1 n1 0 500M\nV1 a b 0\n.ENDS t\n"
+        );
+
+        let codes = [
+            subckt("t", ["in", "in"], &[], &branches),
+            subckt("t", ["in", "n1"], &[], &branches),
+            subckt("my net", DEFAULT_PORTS, &[], &branches),
+            subckt(
+                "t",
+                DEFAULT_PORTS,
+                &[],
+                &[Branch {
+                    placement: Placement::Series,
+                    parts: vec![Part::R(f64::NAN)],
+                }],
+            ),
+        ]
+        .map(|val| val.unwrap_err().code().to_string());
+        assert_eq!(codes.to_vec(), vec!["invalid_input"; 4]);
+    }
+
+    #[test]
+    fn test_network_netlist() {
+        let out = calc_networks(
+            25.0,
+            0.0,
+            100.0,
+            0.0,
+            ImpedanceType::ZReIm,
+            0.0,
+            10.0,
+            50.0,
+            1.0,
+            Unit::Giga,
+            Unit::Femto,
+            Unit::Pico,
+            ZScale::SingleEnded,
+        )
+        .unwrap();
+        let out = serde_json::to_value(out).unwrap();
+
+        let values = &out["hp_ell_cl"][0];
+        let c = values["c"].as_f64().unwrap() * 1e-15;
+        let l = values["l"].as_f64().unwrap() * 1e-12;
+        let test = network_netlist(
+            Network::HpEllCl,
+            values,
+            1.0,
+            Unit::Giga,
+            0.0,
+            0.0,
+            DEFAULT_PORTS,
+        )
+        .unwrap();
+        let lines = test.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[1], ".SUBCKT hp_ell_cl in out");
+        assert_eq!(lines[2], format!("C1 in out {}", eng(c)));
+        assert_eq!(lines[3], format!("L1 out 0 {}", eng(l)));
+
+        // The inductor of a network solved with its Q gets its series resistance
+        let values = &out["hp_ell_cl_w_q"][0];
+        let l = values["l"].as_f64().unwrap() * 1e-12;
+        let test = network_netlist(
+            Network::HpEllClWQ,
+            values,
+            1.0,
+            Unit::Giga,
+            0.0,
+            0.0,
+            DEFAULT_PORTS,
+        )
+        .unwrap();
+        let lines = test.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[1], "* Ql = 10 at 1GHz");
+        assert_eq!(lines[4], format!("L1 out n1 {}", eng(l)));
+        assert_eq!(
+            lines[5],
+            format!("RL1 n1 0 {}", eng(2.0 * PI * 1e9 * l / 10.0))
+        );
+
+        let test = network_netlist(
+            Network::Bp2,
+            &out["bp2"][0],
+            1.0,
+            Unit::Giga,
+            0.0,
+            0.0,
+            ["a", "b"],
+        )
+        .unwrap();
+        let names = test
+            .lines()
+            .filter(|val| !val.starts_with(['*', '.']))
+            .map(|val| val.split(' ').take(3).collect::<Vec<&str>>().join(" "))
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["L1 a n1", "C1 n1 0", "C2 n1 b", "L2 b 0"]);
+
+        let nan = json!({"c": null, "l": null, "q": null, "bw": null, "c_scale": "fF",
+            "l_scale": "pH", "reason": {"code": "negative_inductance", "message": ""}});
+        let err = network_netlist(
+            Network::HpEllLc,
+            &nan,
+            1.0,
+            Unit::Giga,
+            0.0,
+            0.0,
+            DEFAULT_PORTS,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "unrealizable");
+        assert_eq!(err.message(), "hp_ell_lc: requires negative inductance");
+
+        // A solution missing a value, or of another network, is a bad payload
+        let mut missing = out["hp_ell_lc"][0].clone();
+        missing.as_object_mut().unwrap().remove("l");
+        let err = network_branches(Network::HpEllLc, &missing, 1.0, 0.0, 0.0).unwrap_err();
+        assert_eq!(err.code(), "parse");
+        let err = network_branches(Network::Hp1, values, 1.0, 0.0, 0.0).unwrap_err();
+        assert_eq!(err.code(), "parse");
+
+        assert_eq!("pi_lp".parse::<Network>().unwrap(), Network::PiLp);
+        assert_eq!("hp9".parse::<Network>().unwrap_err().code(), "parse");
+        assert_eq!(
+            serde_json::from_value::<Network>(json!("lp_ell_cl_w_q")).unwrap(),
+            Network::LpEllClWQ
+        );
+        assert_eq!(
+            network_solution(&out, Network::PiLp, 0).unwrap(),
+            &out["pi"][0]
+        );
+        assert!(network_solution(&out, Network::TeeHp, 1).is_err());
+        assert!(network_solution(&out, Network::Hp1, 9).is_err());
+    }
+
+    // Impedance looking into the branches from the source with zl at the load
+    fn zin(branches: &[Branch], zl: Complex<f64>, w: f64) -> Complex<f64> {
+        branches.iter().rev().fold(zl, |z, branch| {
+            let zb = branch
+                .parts
+                .iter()
+                .map(|part| match *part {
+                    Part::R(r) => c64(r, 0.0),
+                    Part::L { l, r } => c64(r, w * l),
+                    Part::C { c, r } => c64(r, -1.0 / (w * c)),
+                    _ => unreachable!(),
+                })
+                .sum::<Complex<f64>>();
+            match branch.placement {
+                Placement::Series => z + zb,
+                Placement::Shunt => 1.0 / (1.0 / z + 1.0 / zb),
+            }
+        })
+    }
+
+    #[test]
+    fn test_network_match() {
+        let w = 2.0 * PI * 1e9;
+        let margin = F64Margin {
+            epsilon: 1e-6,
+            ulps: 4,
+        };
+
+        // Every realizable topology is a conjugate match, the ones solved with an inductor Q once
+        // its loss is in. The high pass Pi and Tee need real terminations.
+        let mut tested = vec![];
+        let terminations = [
+            (c64(10.0, -5.0), c64(50.0, 20.0)),
+            (c64(50.0, 20.0), c64(10.0, -5.0)),
+            (c64(25.0, 0.0), c64(100.0, 0.0)),
+        ];
+        for (zs, zl) in terminations {
+            let out = calc_networks(
+                zs.re,
+                zs.im,
+                zl.re,
+                zl.im,
+                ImpedanceType::ZReIm,
+                3.0,
+                10.0,
+                50.0,
+                1.0,
+                Unit::Giga,
+                Unit::Femto,
+                Unit::Pico,
+                ZScale::SingleEnded,
+            )
+            .unwrap();
+            let out = &serde_json::to_value(out).unwrap();
+
+            for (network, values) in NETWORKS.iter().flat_map(|&(network, ..)| {
+                (0..)
+                    .map_while(move |idx| network_solution(out, network, idx).ok())
+                    .map(move |values| (network, values))
+            }) {
+                let Ok(branches) = network_branches(network, values, w, 0.0, 0.0) else {
+                    continue;
+                };
+                // A lossy L section is matched at the port next to its series element
+                let (test, exemplar) = match network.name().ends_with("_w_q")
+                    && branches[0].placement == Placement::Shunt
+                {
+                    true => {
+                        let mut branches = branches;
+                        branches.reverse();
+                        (zin(&branches, zs, w), zl.conj())
+                    }
+                    false => (zin(&branches, zl, w), zs.conj()),
+                };
+                comp_c64(&test, &exemplar, margin, network.name(), "zin");
+                tested.push(network.name());
+            }
+        }
+        tested.sort();
+        tested.dedup();
+        assert_eq!(tested.len(), 20);
+    }
+
+    #[test]
+    fn test_schematic_netlist() {
+        let schematic = vec![
+            json!({"type": "raw", "z0": 50, "freq": 1, "er": 1, "freq_unit": {"multiplier": 1e9}}),
+            json!({"type": "bb", "abs": [50, 0], "unit": ["null"], "tol": 0}),
+            json!({"type": "pi", "abs": [20, 10], "unit": ["Q", "nH"], "tol": 0}),
+            json!({"type": "sc", "abs": [0, 2], "unit": ["Q", "pF"], "tol": 0}),
+            json!({"type": "tl", "abs": [0.25], "unit": ["λ"], "line_zo": 75, "tol": 0}),
+            json!({"type": "so", "abs": [10], "unit": ["mm"], "line_zo": 50, "tol": 0}),
+        ];
+        let test = schematic_netlist(&schematic, "smith", DEFAULT_PORTS).unwrap();
+        assert_eq!(
+            test.lines().skip(1).collect::<Vec<&str>>(),
+            vec![
+                ".SUBCKT smith in out",
+                "T1 in 0 T1_open 0 Z0=50 TD=33.3333P",
+                "T2 in 0 n1 0 Z0=75 TD=250P",
+                "C1 n1 out 2P",
+                "L1 out n2 10N",
+                "RL1 n2 0 3.14159",
+                ".ENDS smith",
+            ]
+        );
+
+        // A resistive loss, a shorted stub in meters and an RLC without its capacitor
+        let mut schematic = schematic[..2].to_vec();
+        schematic.push(json!({"type": "si", "abs": [2, 1], "unit": ["Ω", "nH"], "tol": 0}));
+        schematic.push(json!({"type": "ss", "abs": [0.03], "unit": [" m"], "line_zo": 50}));
+        schematic.push(json!({"type": "prlc", "abs": [5, 1, 0], "unit": ["Ω", "nH", "fF"]}));
+        let test = schematic_netlist(&schematic, "smith", ["a", "b"]).unwrap();
+        assert_eq!(
+            test.lines().skip(2).collect::<Vec<&str>>(),
+            vec![
+                "R1 a n1 5",
+                "L1 n1 0 1N",
+                "T1 a 0 0 0 Z0=50 TD=100P",
+                "L2 a n2 1N",
+                "RL2 n2 b 2",
+                ".ENDS smith",
+            ]
+        );
+
+        // A transformer turns back into the same stage, the primary staying on the load side
+        let mut xfmr = schematic[..2].to_vec();
+        xfmr.push(json!({"type": "xfmr", "abs": [0, 2, 8, 0.5], "unit": ["Ω", "nH", "nH", "K"]}));
+        let test = schematic_netlist(&xfmr, "smith", DEFAULT_PORTS).unwrap();
+        assert_eq!(
+            test.lines().skip(2).collect::<Vec<&str>>(),
+            vec!["L1 in 0 8N", "L2 out 0 2N", "K1 L2 L1 0.5", ".ENDS smith"]
+        );
+        assert_eq!(
+            parse_ladder(&test, 1.0).unwrap().stages,
+            vec![xfmr_stage(2e-9, 8e-9, 0.5)]
+        );
+        xfmr[2] = json!({"type": "xfmr", "abs": [20, 2, 2, 0.5], "unit": ["Q", "nH", "N", "K"]});
+        let test = schematic_netlist(&xfmr, "smith", DEFAULT_PORTS).unwrap();
+        assert_eq!(
+            test.lines().skip(2).collect::<Vec<&str>>(),
+            vec![
+                "RL1 in n1 2.51327",
+                "L1 n1 0 8N",
+                "RL2 out n2 628.319M",
+                "L2 n2 0 2N",
+                "K1 L2 L1 0.5",
+                ".ENDS smith",
+            ]
+        );
+        assert_eq!(
+            parse_ladder(&test, 1.0)
+                .unwrap()
+                .stages
+                .iter()
+                .map(|val| val.element)
+                .collect::<Vec<ElementType>>(),
+            vec![
+                ElementType::SeriesRes,
+                ElementType::Transformer,
+                ElementType::SeriesRes
+            ]
+        );
+
+        // A secondary of its own Q and an interwinding capacitance across the two ends
+        xfmr[2] = json!({"type": "xfmr", "abs": [20, 2, 2, 0.5, 40, 100], "unit": ["Q", "nH", "N", "K", "Q", "fF"]});
+        let test = schematic_netlist(&xfmr, "smith", DEFAULT_PORTS).unwrap();
+        assert_eq!(
+            test.lines().skip(2).collect::<Vec<&str>>(),
+            vec![
+                "RL1 in n1 1.25664",
+                "L1 n1 0 8N",
+                "RL2 out n2 628.319M",
+                "L2 n2 0 2N",
+                "K1 L2 L1 0.5",
+                "C1 in out 100F",
+                ".ENDS smith",
+            ]
+        );
+
+        // Winding capacitance and skin-effect loss quoted at 250 MHz, and a capacitor's ESL
+        let mut parasitic = schematic[..2].to_vec();
+        parasitic.push(
+            json!({"type": "pi", "abs": [20, 10, 0.5, 250], "unit": ["Q", "nH", "pF", "MHz"]}),
+        );
+        parasitic.push(json!({"type": "sc", "abs": [0, 2, 0.4], "unit": ["Q", "pF", "nH"]}));
+        let test = schematic_netlist(&parasitic, "smith", DEFAULT_PORTS).unwrap();
+        assert_eq!(
+            test.lines().skip(2).collect::<Vec<&str>>(),
+            vec![
+                "C1 in n1 2P",
+                "L1 n1 out 400P",
+                "L2 out n2 10N",
+                "RL2 n2 0 1.5708",
+                "C2 out 0 500F",
+                ".ENDS smith",
+            ]
+        );
+
+        schematic.push(json!({"type": "customZ", "abs": [50, 1, 1]}));
+        let err = schematic_netlist(&schematic, "smith", DEFAULT_PORTS).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "schematic element 5: element \"customZ\" has no SPICE model"
+        );
+        assert!(schematic_netlist(&schematic[..2], "smith", DEFAULT_PORTS).is_err());
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let test = [
+            "10pF", "2.2MEG", "1e-9", "3.3k", "5m", "47", "-2u", "1.5E+3N", "10mil",
+        ]
+        .map(|val| parse_value(val).unwrap())
+        .to_vec();
+        comp_vec_f64(
+            test,
+            vec![
+                10e-12, 2.2e6, 1e-9, 3.3e3, 5e-3, 47.0, -2e-6, 1.5e-6, 254e-6,
+            ],
+            F64Margin::default(),
+            "parse_value()",
+            "val",
+        );
+        assert!(parse_value("pF").is_err());
+    }
+
+    #[test]
+    fn test_parse_ladder() {
+        let netlist = "\
+Matching network
+* series RLC, a transformer and a line
+.subckt net RF_IN rf_out
+R1 rf_in n1 2
+L1 n1 n2 1n ; the RLC continues through n2
+C1 n2 mid 1p
+Cshunt mid gnd 500f
+Lp mid 0 2n
+Ls n3 0 8n
+K1 Lp Ls 0.9
+T1 n3 0 n4 0 z0=75
++ f=1g nl=0.125
+V1 n4 rf_out 0
+.ends
+.end
+";
+        let test = parse_ladder(netlist, 1.0).unwrap();
+        assert_eq!(test.name, "net");
+        assert_eq!(test.ports, ["RF_IN".to_string(), "rf_out".to_string()]);
+        assert_eq!(
+            test.stages
+                .iter()
+                .map(|val| val.element)
+                .collect::<Vec<ElementType>>(),
+            vec![
+                ElementType::SeriesRlc,
+                ElementType::ShuntCap,
+                ElementType::Transformer,
+                ElementType::TLine,
+            ]
+        );
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+        let exemplar = [
+            (vec![2.0, 1.0, 1.0], vec!["Ω", "nH", "pF"]),
+            (vec![0.0, 500.0], vec!["Ω", "fF"]),
+            // The winding on the load side is the primary
+            (vec![0.0, 8.0, 2.0, 0.9], vec!["Ω", "nH", "nH", "K"]),
+            (vec![75.0, 1.0, 37500.0], vec!["um"]),
+        ];
+        for (stage, (vals, units)) in test.stages.iter().zip(exemplar) {
+            comp_vec_f64(
+                stage.vals.clone(),
+                vals,
+                margin,
+                "parse_ladder()",
+                &stage.element.to_string(),
+            );
+            assert_eq!(stage.units, units);
+        }
+        assert_eq!(
+            test.to_schematic()[0],
+            json!({"type": "tl", "line_length": 0.0375, "abs": [37500.0], "line_zo": 75.0,
+                "unit": ["um"], "real": 0, "imaginary": 0, "tol": 0})
+        );
+        assert_eq!(test.elements(50.0).unwrap().len(), 4);
+
+        // Stubs, shorted and open, with lengths in a dielectric
+        let netlist = ".SUBCKT s a b\nT1 a 0 0 0 Z0=50 TD=100P\nT2 b 0 T2_open 0 Z0=40 TD=50P\nV1 a b 0\n.ENDS s";
+        let test = parse_ladder(netlist, 4.0).unwrap();
+        assert_eq!(
+            test.stages,
+            vec![
+                line_stage(ElementType::ShortedStub, 50.0, 100e-12, 4.0),
+                line_stage(ElementType::OpenStub, 40.0, 50e-12, 4.0),
+            ]
+        );
+        comp_f64(
+            &test.stages[0].vals[2],
+            &15000.0,
+            margin,
+            "parse_ladder()",
+            "length",
+        );
+
+        let errors = [
+            ("R1 in out 5", "netlist has no .SUBCKT"),
+            (
+                ".subckt b in out\nL1 in out 1n\nC1 in n1 1p\nC2 n1 out 1p\n.ends",
+                "the path splits at node in into L1 and C1, C2, which is not a series/shunt cascade",
+            ),
+            (
+                ".subckt k in out\nL1 in out 1n\nL2 out 0 1n\nK1 L1 L2 0.5\n.ends",
+                "K1 needs one end of L1 grounded to be a transformer in a ladder",
+            ),
+            (
+                ".subckt o in out\nL1 in out 1n\nC1 out n1 1p\n.ends",
+                "C1 is left open at node n1",
+            ),
+            (
+                ".subckt q in out\nQ1 out in 0 npn\n.ends",
+                "Q1 out in 0 npn: Q1 is not an R, L, C, K, T or 0 V source",
+            ),
+            (
+                ".subckt d in out\nR1 in 0 50\n.ends",
+                "node in does not lead on to port out",
+            ),
+            (
+                ".subckt v in out\nV1 in out 1\n.ends",
+                "V1 in out 1: V1 is a source, only 0 V sources can stand in for a short",
+            ),
+        ];
+        for (netlist, message) in errors {
+            assert_eq!(parse_ladder(netlist, 1.0).unwrap_err().message(), message);
+        }
+    }
+
+    #[test]
+    fn test_parse_circuit() {
+        let freq = Frequency::new(1.3e9, Unit::Base);
+        let margin = F64Margin {
+            epsilon: 1e-9,
+            ulps: 4,
+        };
+
+        // A ladder solved by nodal analysis matches its cascade
+        let netlist = "\
+.subckt net in out
+R1 in n1 2
+L1 n1 n2 1n
+C1 n2 mid 1p
+Cshunt mid gnd 500f
+Lp mid 0 2n
+Ls n3 0 8n
+K1 Lp Ls 0.9
+T1 n3 0 n4 0 z0=75 td=200p
+V1 n4 out 0
+.ends
+";
+        let circuit = parse_circuit(netlist, 50.0).unwrap();
+        assert_eq!(circuit.ports().len(), 2);
+        let ladder = parse_ladder(netlist, 1.0).unwrap();
+        let elements = ladder.elements(50.0).unwrap();
+        let s = abcd2s(
+            &calc_abcd(
+                &elements.iter().map(|val| val.as_ref()).collect::<Vec<_>>(),
+                freq,
+            ),
+            50.0,
+        );
+        let test = circuit.s(freq).unwrap();
+        for i in 0..2 {
+            for j in 0..2 {
+                comp_c64(
+                    &test[i][j],
+                    &s[i][j],
+                    margin,
+                    "parse_circuit()",
+                    &format!("ladder s{}{}", i + 1, j + 1),
+                );
+            }
+        }
+
+        // Bridged tee with its shunt arm through a near ideal transformer, matched with S21 = 1/3
+        // as the bridge is 2 z0
+        let netlist = "\
+.SUBCKT bt a b
+Ra a m 50
+Rb m b 50
+Rbridge a b 100
+Lp m 0 10u
+Ls x 0 10u
+K1 Lp Ls 0.999999
+Rs x 0 25
+.ENDS
+";
+        let test = parse_circuit(netlist, 50.0).unwrap().s(freq).unwrap();
+        comp_c64(
+            &test[0][0],
+            &c64(0.0, 0.0),
+            F64Margin {
+                epsilon: 1e-3,
+                ulps: 4,
+            },
+            "parse_circuit()",
+            "bridged tee s11",
+        );
+        comp_c64(
+            &test[1][0],
+            &c64(1.0 / 3.0, 0.0),
+            F64Margin {
+                epsilon: 1e-3,
+                ulps: 4,
+            },
+            "parse_circuit()",
+            "bridged tee s21",
+        );
+
+        let netlist = ".subckt three a b c\nR1 a b 10\nR2 b c 10\nR3 c 0 10\n.ends";
+        assert_eq!(parse_circuit(netlist, 50.0).unwrap().ports().len(), 3);
+        assert_eq!(
+            parse_ladder(netlist, 1.0).unwrap_err().message(),
+            "three has 3 ports, a cascade needs two"
+        );
+        assert_eq!(
+            parse_circuit(".subckt s a b\nV1 b 0 0\n.ends", 50.0)
+                .err()
+                .unwrap()
+                .message(),
+            "s port b: port 2 is shorted, both of its nodes are 0"
+        );
+        assert_eq!(
+            parse_circuit(
+                ".subckt s a b\nL1 a 0 1n\nL2 b 0 1n\nL3 a b 1n\nK1 L1 L2 0.5\nK2 L2 L3 0.5\n.ends",
+                50.0
+            )
+            .err()
+            .unwrap()
+            .message(),
+            "L2 is coupled more than once, only pairs of windings are supported"
+        );
+    }
+
+    #[test]
+    fn test_network_import() {
+        let w = 2.0 * PI * 1e9;
+        let freq = Frequency::new(1.0, Unit::Giga);
+        // Netlist values carry 6 significant digits
+        let margin = F64Margin {
+            epsilon: 1e-2,
+            ulps: 4,
+        };
+
+        // An exported network reads back as the same cascade, still a conjugate match
+        let mut tested = vec![];
+        let terminations = [
+            (c64(10.0, -5.0), c64(50.0, 20.0)),
+            (c64(50.0, 20.0), c64(10.0, -5.0)),
+            (c64(25.0, 0.0), c64(100.0, 0.0)),
+        ];
+        for (zs, zl) in terminations {
+            let out = calc_networks(
+                zs.re,
+                zs.im,
+                zl.re,
+                zl.im,
+                ImpedanceType::ZReIm,
+                3.0,
+                10.0,
+                50.0,
+                1.0,
+                Unit::Giga,
+                Unit::Femto,
+                Unit::Pico,
+                ZScale::SingleEnded,
+            )
+            .unwrap();
+            let out = serde_json::to_value(out).unwrap();
+
+            for &(network, name, _) in NETWORKS.iter().filter(|val| !val.1.ends_with("_w_q")) {
+                let values = network_solution(&out, network, 0).unwrap();
+                let Ok(netlist) =
+                    network_netlist(network, values, 1.0, Unit::Giga, 0.0, 0.0, DEFAULT_PORTS)
+                else {
+                    continue;
+                };
+                let ladder = parse_ladder(&netlist, 1.0).unwrap();
+                let branches = network_branches(network, values, w, 0.0, 0.0).unwrap();
+                assert_eq!(ladder.stages.len(), branches.len());
+
+                let elements = ladder.elements(50.0).unwrap();
+                let elements = elements.iter().map(|val| val.as_ref()).collect::<Vec<_>>();
+                let abcd = calc_abcd(&elements, freq);
+                let test = (abcd[0][0] * zl + abcd[0][1]) / (abcd[1][0] * zl + abcd[1][1]);
+                comp_c64(&test, &zs.conj(), margin, name, "zin");
+                tested.push(name);
+            }
+        }
+        tested.sort();
+        tested.dedup();
+        assert_eq!(tested.len(), 16);
+    }
+
+    #[test]
+    fn test_schematic_import() {
+        let header = vec![
+            json!({"type": "raw", "z0": 50, "freq": 1, "er": 1, "freq_unit": {"multiplier": 1e9}}),
+            json!({"type": "bb", "abs": [50, 0], "unit": ["null"], "tol": 0}),
+        ];
+        let mut schematic = header.clone();
+        schematic.push(json!({"type": "pi", "abs": [2, 10], "unit": ["Ω", "nH"], "tol": 0}));
+        schematic.push(json!({"type": "sc", "abs": [0, 2], "unit": ["Q", "pF"], "tol": 0}));
+        schematic.push(json!({"type": "tl", "abs": [0.25], "unit": ["λ"], "line_zo": 75}));
+
+        // Export and import again, elements still running from the load
+        let netlist = schematic_netlist(&schematic, "smith", DEFAULT_PORTS).unwrap();
+        let mut schem = Schematic::new(header.clone(), HISTORY_DEPTH);
+        schem
+            .edit(schematic_import(&header, &netlist).unwrap())
+            .unwrap();
+        let types = schem
+            .elements()
+            .iter()
+            .map(|val| val["type"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(types, vec!["raw", "bb", "pi", "sc", "tl"]);
+        assert_eq!(schem.elements()[2]["abs"], json!([2.0, 10.0]));
+        assert_eq!(schem.elements()[4]["abs"], json!([75000.0]));
+
+        // The old elements are replaced in one undoable edit
+        let edit =
+            schematic_import(schem.elements(), ".subckt r in out\nR1 in out 5\n.ends").unwrap();
+        schem.edit(edit).unwrap();
+        assert_eq!(schem.elements().len(), 3);
+        assert_eq!(schem.elements()[2]["type"], "sr");
+        schem.undo().unwrap();
+        assert_eq!(schem.elements().len(), 5);
+        assert!(schematic_import(&header, ".subckt r in out\n.ends").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::error::Error;
+use crate::freq_data::FreqData;
+use crate::rf_utils::{gen_complex, unscale, ComplexType};
+use crate::unit::Unit;
+use num_complex::Complex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// S-parameters of an N-port read from a Touchstone 1.x file, frequencies in Hz
+#[derive(Clone, Debug)]
+pub struct Touchstone {
+    pub freqs: Vec<f64>,
+    pub s: Vec<Vec<Vec<Complex<f64>>>>,
+    pub z0: f64,
+    // Interpolant of each Sij, built from freqs and s on first use
+    params: OnceLock<Result<Vec<Vec<FreqData>>, Error>>,
+}
+
+impl PartialEq for Touchstone {
+    fn eq(&self, other: &Self) -> bool {
+        self.freqs == other.freqs && self.s == other.s && self.z0 == other.z0
+    }
+}
+
+impl Touchstone {
+    pub fn ports(&self) -> usize {
+        self.s.first().map_or(0, |val| val.len())
+    }
+
+    // Sij against frequency, ports numbered from 0
+    pub fn param(&self, i: usize, j: usize) -> Result<FreqData, Error> {
+        if i >= self.ports() || j >= self.ports() {
+            return Err(Error::InvalidInput(format!(
+                "S{}{} is outside a {}-port",
+                i + 1,
+                j + 1,
+                self.ports()
+            )));
+        }
+        Ok(self.params()?[i][j].clone())
+    }
+
+    fn params(&self) -> Result<&[Vec<FreqData>], Error> {
+        self.params
+            .get_or_init(|| {
+                (0..self.ports())
+                    .map(|i| {
+                        (0..self.ports())
+                            .map(|j| {
+                                FreqData::new(
+                                    self.freqs.clone(),
+                                    self.s.iter().map(|val| val[i][j]).collect(),
+                                    Unit::Base,
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .as_deref()
+            .map_err(Clone::clone)
+    }
+
+    // S-parameter matrix at freq in Hz, each parameter linearly interpolated between records
+    pub fn s_at(&self, freq: f64) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        self.params()?
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|val| Ok(val.interp_hz(&[freq])?[0]))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// Port count from a .sNp extension
+pub fn ports_from_path(path: &str) -> Result<usize, Error> {
+    Path::new(path)
+        .extension()
+        .and_then(|val| val.to_str())
+        .map(|val| val.to_lowercase())
+        .and_then(|val| {
+            val.strip_prefix('s')?
+                .strip_suffix('p')?
+                .parse::<usize>()
+                .ok()
+        })
+        .filter(|val| *val > 0)
+        .ok_or_else(|| Error::Parse(format!("{}: expected a Touchstone .sNp extension", path)))
+}
+
+pub fn read_touchstone(path: &str) -> Result<Touchstone, Error> {
+    let ports = ports_from_path(path)?;
+    let text = std::fs::read_to_string(path).map_err(|err| Error::from(err).context(path))?;
+    parse_touchstone(&text, ports).map_err(|err| err.context(path))
+}
+
+//  # GHz S MA R 50
+//  ! freq  S11        S21        S12        S22       2-ports are column major, others row major
+//  1.0     0.5 -30    0.8 60     0.8 60     0.5 -30
+pub fn parse_touchstone(text: &str, ports: usize) -> Result<Touchstone, Error> {
+    let mut unit = Unit::Giga;
+    let mut fmt = ComplexType::MagAng;
+    let mut z0 = 50.0;
+    let mut options = false;
+    let mut nums = vec![];
+
+    for line in text.lines() {
+        let line = line.split('!').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(Error::Parse(format!(
+                "Touchstone 2.0 keyword {} is not supported",
+                line
+            )));
+        }
+        if let Some(opts) = line.strip_prefix('#') {
+            // Only the first option line counts
+            if options {
+                continue;
+            }
+            options = true;
+            let mut tokens = opts.split_whitespace();
+            while let Some(token) = tokens.next() {
+                match token.to_lowercase().as_str() {
+                    "hz" => unit = Unit::Base,
+                    "khz" => unit = Unit::Kilo,
+                    "mhz" => unit = Unit::Mega,
+                    "ghz" => unit = Unit::Giga,
+                    "s" => {}
+                    "y" | "z" | "h" | "g" => {
+                        return Err(Error::Parse(format!(
+                            "{} parameters are not supported, only S",
+                            token.to_uppercase()
+                        )))
+                    }
+                    "ma" => fmt = ComplexType::MagAng,
+                    "db" => fmt = ComplexType::Db,
+                    "ri" => fmt = ComplexType::ReIm,
+                    "r" => {
+                        z0 = tokens
+                            .next()
+                            .and_then(|val| val.parse::<f64>().ok())
+                            .ok_or_else(|| {
+                                Error::Parse("option line has no reference impedance".to_string())
+                            })?;
+                    }
+                    _ => return Err(Error::Parse(format!("option {:?} not recognized", token))),
+                }
+            }
+            continue;
+        }
+        for token in line.split_whitespace() {
+            nums.push(
+                token
+                    .parse::<f64>()
+                    .map_err(|_| Error::Parse(format!("invalid number {:?}", token)))?,
+            );
+        }
+    }
+
+    let width = 1 + 2 * ports * ports;
+    if nums.is_empty() || nums.len() % width != 0 {
+        return Err(Error::Parse(format!(
+            "{} values do not split into {}-port records of {}",
+            nums.len(),
+            ports,
+            width
+        )));
+    }
+
+    let mut freqs = vec![];
+    let mut s = vec![];
+    for record in nums.chunks(width) {
+        freqs.push(unscale(record[0], &unit));
+        let mut val = vec![vec![Complex::new(0.0, 0.0); ports]; ports];
+        for (k, pair) in record[1..].chunks(2).enumerate() {
+            let (i, j) = if ports == 2 {
+                (k % 2, k / 2)
+            } else {
+                (k / ports, k % ports)
+            };
+            val[i][j] = gen_complex(pair[0], pair[1], fmt);
+        }
+        s.push(val);
+    }
+
+    Ok(Touchstone {
+        freqs,
+        s,
+        z0,
+        params: OnceLock::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::comp_c64;
+    use float_cmp::F64Margin;
+
+    #[test]
+    fn test_parse_touchstone() {
+        let text = "! 2-port\n# MHz S RI R 75\n100 0.1 0.2 0.3 0.4 0.5 0.6 0.7 0.8\n200 1 2 3 4 5 6 7 8 ! last\n";
+        let test = parse_touchstone(text, 2).unwrap();
+        assert_eq!(test.freqs, vec![100.0e6, 200.0e6]);
+        assert_eq!(test.z0, 75.0);
+        assert_eq!(test.ports(), 2);
+        // S21 comes second in 2-port records
+        comp_c64(
+            &test.s[0][1][0],
+            &Complex::new(0.3, 0.4),
+            F64Margin::default(),
+            "parse_touchstone()",
+            "s21",
+        );
+        comp_c64(
+            &test.s[1][0][1],
+            &Complex::new(5.0, 6.0),
+            F64Margin::default(),
+            "parse_touchstone()",
+            "s12",
+        );
+
+        // 3-ports wrap over several lines and are row major
+        let text = "# GHz S DB\n1 0 0 -6 90 -20 0\n-6 90 0 0 -20 0\n-20 0 -20 0 0 0\n";
+        let test = parse_touchstone(text, 3).unwrap();
+        assert_eq!(test.z0, 50.0);
+        comp_c64(
+            &test.s[0][0][1],
+            &Complex::new(0.0, 0.5011872336272722),
+            F64Margin {
+                epsilon: 1e-12,
+                ulps: 4,
+            },
+            "parse_touchstone()",
+            "s12",
+        );
+        assert_eq!(test.param(0, 1).unwrap().freqs().len(), 1);
+        assert!(test.param(0, 3).is_err());
+
+        // Interpolated between records, the interpolants built once for all frequencies
+        let text = "# GHz S RI R 50\n1 0 0 0.2 0 0.2 0 0 0\n2 0 0 0.4 0.2 0.4 0.2 0 0\n";
+        let test = parse_touchstone(text, 2).unwrap();
+        assert!(test.params.get().is_none());
+        let s = test.s_at(1.5e9).unwrap();
+        comp_c64(
+            &s[1][0],
+            &Complex::new(0.3, 0.1),
+            F64Margin::default(),
+            "s_at()",
+            "s21",
+        );
+        let params = test.params().unwrap().as_ptr();
+        comp_c64(
+            &test.s_at(2e9).unwrap()[0][1],
+            &Complex::new(0.4, 0.2),
+            F64Margin::default(),
+            "s_at()",
+            "s12",
+        );
+        assert_eq!(test.params().unwrap().as_ptr(), params);
+        assert!(test.s_at(3e9).is_err());
+
+        assert_eq!(
+            parse_touchstone("# GHz Z MA\n1 1 0\n", 1)
+                .unwrap_err()
+                .code(),
+            "parse"
+        );
+        assert_eq!(
+            parse_touchstone("# GHz S MA\n1 1 0 1\n", 1)
+                .unwrap_err()
+                .code(),
+            "parse"
+        );
+    }
+
+    #[test]
+    fn test_ports_from_path() {
+        assert_eq!(ports_from_path("amp.s2p").unwrap(), 2);
+        assert_eq!(ports_from_path("/data/balun.S4P").unwrap(), 4);
+        assert!(ports_from_path("amp.txt").is_err());
+        assert!(ports_from_path("amp").is_err());
+    }
+}
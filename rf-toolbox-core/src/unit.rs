@@ -0,0 +1,177 @@
+#![allow(unused)]
+use crate::error::Error;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+pub enum UnitType {
+    Farad,
+    Henry,
+    Ohm,
+    Hz,
+}
+
+impl FromStr for UnitType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "c" | "cap" | "capacitor" | "F" => Ok(UnitType::Farad),
+            "l" | "ind" | "inductor" | "H" => Ok(UnitType::Henry),
+            "r" | "res" | "resistor" | "Ω" => Ok(UnitType::Ohm),
+            "f" | "freq" | "frequency" | "Hz" | "hz" => Ok(UnitType::Hz),
+            _ => Err(Error::InvalidUnit(format!(
+                "unit type {:?} not recognized",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for UnitType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = match self {
+            UnitType::Farad => "F",
+            UnitType::Henry => "H",
+            UnitType::Ohm => "Ω",
+            UnitType::Hz => "Hz",
+        };
+        write!(f, "{}", val)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Unit {
+    Tera,
+    Giga,
+    Mega,
+    Kilo,
+    Base,
+    Milli,
+    Micro,
+    Nano,
+    Pico,
+    Femto,
+    Lambda(f64, f64),
+    Q,
+    K,
+    N,
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tera" | "T" | "THz" | "thz" => Ok(Unit::Tera),
+            "giga" | "G" | "GHz" | "ghz" | "GΩ" => Ok(Unit::Giga),
+            "mega" | "M" | "MHz" | "mhz" | "MΩ" => Ok(Unit::Mega),
+            "kilo" | "k" | "kHz" | "khz" | "KHz" | "kΩ" | "KΩ" => Ok(Unit::Kilo),
+            "milli" | "m" | "mΩ" | "mF" | "mH" | "mm" => Ok(Unit::Milli),
+            "micro" | "u" | "μ" | "uΩ" | "μΩ" | "uF" | "μF" | "uH" | "μH" | "um" | "μm" => {
+                Ok(Unit::Micro)
+            }
+            "nano" | "n" | "nΩ" | "nF" | "nH" => Ok(Unit::Nano),
+            "pico" | "p" | "pΩ" | "pF" | "pH" => Ok(Unit::Pico),
+            "femto" | "f" | "fΩ" | "fF" | "fH" => Ok(Unit::Femto),
+            "lambda" | "λ" | "wavelength" => Ok(Unit::Lambda(1.0, 1.0)),
+            "Q" | "q" => Ok(Unit::Q),
+            "K" => Ok(Unit::K),
+            "N" => Ok(Unit::N),
+            "" | "base" | "Ω" | "F" | "H" | "Hz" | "hz" => Ok(Unit::Base),
+            // Any other SI prefix and symbol pair written by get_unit, e.g. "kF" or "GH"
+            _ => match ["Hz", "F", "H", "Ω"].iter().find_map(|x| s.strip_suffix(x)) {
+                Some(prefix @ ("T" | "G" | "M" | "k" | "m" | "u" | "μ" | "n" | "p" | "f")) => {
+                    Unit::from_str(prefix)
+                }
+                _ => Err(Error::InvalidUnit(format!("unit {:?} not recognized", s))),
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Unit::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = match self {
+            Unit::Tera => "T",
+            Unit::Giga => "G",
+            Unit::Mega => "M",
+            Unit::Kilo => "k",
+            Unit::Base => "",
+            Unit::Milli => "m",
+            // Unit::Micro => "μ",
+            Unit::Micro => "u",
+            Unit::Nano => "n",
+            Unit::Pico => "p",
+            Unit::Femto => "f",
+            Unit::Lambda(_, _) => "λ",
+            Unit::Q => "Q",
+            Unit::K => "K",
+            Unit::N => "N",
+        };
+        write!(f, "{}", val)
+    }
+}
+
+impl Unit {
+    pub fn scale(&self) -> f64 {
+        match self {
+            Unit::Tera => 1e-12,
+            Unit::Giga => 1e-9,
+            Unit::Mega => 1e-6,
+            Unit::Kilo => 1e-3,
+            Unit::Base => 1.0,
+            Unit::Milli => 1e3,
+            Unit::Micro => 1e6,
+            Unit::Nano => 1e9,
+            Unit::Pico => 1e12,
+            Unit::Femto => 1e15,
+            Unit::Lambda(freq, er) => 3e8 / (freq * er.sqrt()),
+            Unit::Q | Unit::K | Unit::N => 1.0,
+        }
+    }
+
+    pub fn unscale(&self) -> f64 {
+        1.0 / self.scale()
+    }
+
+    // Engineering prefix that puts val in [1, 1000)
+    pub fn eng(val: f64) -> Self {
+        match val.abs() {
+            x if x >= 1e12 => Unit::Tera,
+            x if x >= 1e9 => Unit::Giga,
+            x if x >= 1e6 => Unit::Mega,
+            x if x >= 1e3 => Unit::Kilo,
+            x if x >= 1.0 || x == 0.0 => Unit::Base,
+            x if x >= 1e-3 => Unit::Milli,
+            x if x >= 1e-6 => Unit::Micro,
+            x if x >= 1e-9 => Unit::Nano,
+            x if x >= 1e-12 => Unit::Pico,
+            _ => Unit::Femto,
+        }
+    }
+}
+
+pub fn get_unit(unit: &Unit, unit_type: &UnitType) -> String {
+    if *unit == Unit::Micro {
+        return format!("μ{}", unit_type);
+    }
+    format!("{}{}", unit, unit_type)
+}
+
+pub fn get_unit_scale(unit: Unit, freq: f64, er: f64) -> f64 {
+    match unit {
+        Unit::Lambda(_, _) => Unit::Lambda(freq, er).scale(),
+        _ => unit.scale(),
+    }
+}
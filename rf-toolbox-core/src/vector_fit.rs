@@ -0,0 +1,768 @@
+#![allow(unused)]
+use crate::element::{
+    capacitor::Capacitor, inductor::Inductor, resistor::Resistor, rlc::Rlc, Element, ElementType,
+    Orientation,
+};
+use crate::error::Error;
+use crate::freq_data::FreqData;
+use crate::frequency::Frequency;
+use crate::rf_utils::{scale, ComplexReturn};
+use crate::unit::Unit;
+use nalgebra::{DMatrix, DVector};
+use num_complex::{c64, Complex};
+use serde::Serialize;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+// Poles whose imaginary part is below this fraction of their magnitude are treated as real
+const REAL_POLE_TOL: f64 = 1e-9;
+
+// Terms smaller than this fraction of the largest sample are dropped during synthesis
+const SYNTH_TOL: f64 = 1e-9;
+
+// f(s) = sum(r_n / (s - p_n)) + d + s * e
+#[derive(Clone, Debug, PartialEq)]
+pub struct RationalModel {
+    poles: Vec<Complex<f64>>,
+    residues: Vec<Complex<f64>>,
+    d: f64,
+    e: f64,
+}
+
+impl RationalModel {
+    pub fn new(poles: Vec<Complex<f64>>, residues: Vec<Complex<f64>>, d: f64, e: f64) -> Self {
+        RationalModel {
+            poles,
+            residues,
+            d,
+            e,
+        }
+    }
+
+    pub fn poles(&self) -> &[Complex<f64>] {
+        &self.poles
+    }
+
+    pub fn residues(&self) -> &[Complex<f64>] {
+        &self.residues
+    }
+
+    pub fn d(&self) -> f64 {
+        self.d
+    }
+
+    pub fn e(&self) -> f64 {
+        self.e
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.poles.iter().all(|val| val.re < 0.0)
+    }
+
+    pub fn eval(&self, freq: Frequency) -> Complex<f64> {
+        self.eval_hz(freq.freq())
+    }
+
+    pub fn eval_hz(&self, freq: f64) -> Complex<f64> {
+        let s = c64(0.0, 2.0 * PI * freq);
+        self.poles
+            .iter()
+            .zip(self.residues.iter())
+            .fold(self.d + s * self.e, |acc, (p, r)| acc + r / (s - p))
+    }
+
+    pub fn rms_error(&self, data: &FreqData) -> f64 {
+        let err = data
+            .freqs()
+            .iter()
+            .zip(data.vals().iter())
+            .map(|(f, val)| (self.eval_hz(*f) - val).norm_sqr())
+            .sum::<f64>();
+        (err / data.freqs().len() as f64).sqrt()
+    }
+}
+
+// Shunt branches of a Foster admittance, Y(s) = sum(Y_branch)
+//
+//     o-----+--------+--------+--------+------
+//           |        |        |        |
+//          RLC       R        R        R      C
+//           |        L        C        |      |
+//     o-----+--------+--------+--------+------
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Foster {
+    Rlc(Rlc),
+    Rl(Inductor),
+    Rc(Capacitor),
+    R(Resistor),
+    C(Capacitor),
+}
+
+impl Foster {
+    pub fn element(&self) -> &dyn Element {
+        match self {
+            Foster::Rlc(val) => val,
+            Foster::Rl(val) => val,
+            Foster::Rc(val) => val,
+            Foster::R(val) => val,
+            Foster::C(val) => val,
+        }
+    }
+
+    // Shunt element type of the branch in a Smith chart schematic
+    pub fn code(&self) -> ElementType {
+        match self {
+            Foster::Rlc(_) => ElementType::ShuntRlc,
+            Foster::Rl(_) => ElementType::ShuntInd,
+            Foster::Rc(_) | Foster::C(_) => ElementType::ShuntCap,
+            Foster::R(_) => ElementType::ShuntRes,
+        }
+    }
+}
+
+// Pole-residue basis with complex conjugate pairs folded into two real-valued columns
+//   real p:  1 / (s - p)
+//   pair p:  1 / (s - p) + 1 / (s - p*),  j / (s - p) - j / (s - p*)
+fn basis(s: Complex<f64>, poles: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let mut out = vec![];
+    for p in poles {
+        if p.im == 0.0 {
+            out.push(1.0 / (s - p));
+        } else {
+            let a = 1.0 / (s - p);
+            let b = 1.0 / (s - p.conj());
+            out.push(a + b);
+            out.push(c64(0.0, 1.0) * (a - b));
+        }
+    }
+    out
+}
+
+fn num_unknowns(poles: &[Complex<f64>]) -> usize {
+    poles
+        .iter()
+        .map(|val| if val.im == 0.0 { 1 } else { 2 })
+        .sum()
+}
+
+// Least squares with column normalization to tame the conditioning of the basis
+fn lstsq(mut a: DMatrix<f64>, b: DVector<f64>) -> Result<DVector<f64>, Error> {
+    let norms = a
+        .column_iter()
+        .map(|col| {
+            let norm = col.norm();
+            if norm > 0.0 {
+                norm
+            } else {
+                1.0
+            }
+        })
+        .collect::<Vec<f64>>();
+    for (mut col, norm) in a.column_iter_mut().zip(norms.iter()) {
+        col /= *norm;
+    }
+    let mut x = a
+        .svd(true, true)
+        .solve(&b, 1e-14)
+        .map_err(|err| Error::Numerical(err.to_string()))?;
+    for (val, norm) in x.iter_mut().zip(norms.iter()) {
+        *val /= norm;
+    }
+    Ok(x)
+}
+
+// Real poles are kept as-is and pairs are represented by the member with positive imaginary part
+fn sort_poles(vals: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let mut out = vals
+        .iter()
+        .filter_map(|val| {
+            // Unstable poles are flipped into the left half plane
+            let val = c64(-val.re.abs(), val.im);
+            if val.im.abs() <= REAL_POLE_TOL * val.norm() {
+                Some(c64(val.re, 0.0))
+            } else if val.im > 0.0 {
+                Some(val)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<Complex<f64>>>();
+    out.sort_by(|a, b| a.im.total_cmp(&b.im).then(b.re.total_cmp(&a.re)));
+    out
+}
+
+// Complex starting poles spread over the band with light damping, plus a real pole if npoles is odd
+fn initial_poles(npoles: usize, fmin: f64, fmax: f64) -> Vec<Complex<f64>> {
+    let npairs = npoles / 2;
+    let lo = fmin.max(fmax * 1e-3);
+    let mut out = (0..npairs)
+        .map(|i| {
+            let im = if npairs == 1 {
+                (lo + fmax) / 2.0
+            } else {
+                lo + (fmax - lo) * i as f64 / (npairs - 1) as f64
+            };
+            c64(-im / 100.0, im)
+        })
+        .collect::<Vec<Complex<f64>>>();
+    if npoles % 2 == 1 {
+        out.insert(0, c64(-(lo + fmax) / 2.0, 0.0));
+    }
+    out
+}
+
+// Split complex rows into real and imaginary rows
+fn push_row(rows: &mut Vec<f64>, rhs: &mut Vec<f64>, row: &[Complex<f64>], val: Complex<f64>) {
+    rows.extend(row.iter().map(|x| x.re));
+    rhs.push(val.re);
+    rows.extend(row.iter().map(|x| x.im));
+    rhs.push(val.im);
+}
+
+// Vector Fitting (Gustavsen & Semlyen) with common poles for all responses
+//
+// Frequencies are normalized to the highest sample so that s stays near unity
+pub fn vector_fit_common(
+    data: &[&FreqData],
+    npoles: usize,
+    iterations: usize,
+) -> Result<Vec<RationalModel>, Error> {
+    if data.is_empty() {
+        return Err(Error::InvalidInput("no data to fit".to_string()));
+    }
+    if npoles == 0 {
+        return Err(Error::InvalidInput(
+            "at least one pole is required".to_string(),
+        ));
+    }
+    let freqs = data[0].freqs();
+    if data.iter().any(|val| val.freqs() != freqs) {
+        return Err(Error::InvalidInput(
+            "all responses must share the same frequencies".to_string(),
+        ));
+    }
+    if freqs.len() < npoles + 1 {
+        return Err(Error::InvalidInput(format!(
+            "{} poles need at least {} frequency points, got {}",
+            npoles,
+            npoles + 1,
+            freqs.len()
+        )));
+    }
+
+    let fmax = freqs[freqs.len() - 1];
+    let w0 = 2.0 * PI * fmax;
+    let s = freqs
+        .iter()
+        .map(|val| c64(0.0, val / fmax))
+        .collect::<Vec<Complex<f64>>>();
+    let nresp = data.len();
+    let mut poles = initial_poles(npoles, freqs[0] / fmax, 1.0);
+
+    // Pole identification: sigma * f = sum(c * phi) + d + s * e, sigma = 1 + sum(c_sigma * phi)
+    for _ in 0..iterations {
+        let ncols = nresp * (npoles + 2) + npoles;
+        let mut rows = vec![];
+        let mut rhs = vec![];
+        for (m, resp) in data.iter().enumerate() {
+            for (k, sk) in s.iter().enumerate() {
+                let f = resp.vals()[k];
+                let phi = basis(*sk, &poles);
+                let mut row = vec![Complex::ZERO; ncols];
+                let offset = m * (npoles + 2);
+                row[offset..offset + npoles].copy_from_slice(&phi);
+                row[offset + npoles] = Complex::ONE;
+                row[offset + npoles + 1] = *sk;
+                for (n, val) in phi.iter().enumerate() {
+                    row[nresp * (npoles + 2) + n] = -f * val;
+                }
+                push_row(&mut rows, &mut rhs, &row, f);
+            }
+        }
+        let a = DMatrix::from_row_slice(rhs.len(), ncols, &rows);
+        let x = lstsq(a, DVector::from_vec(rhs))?;
+        let c_sigma = x.rows(nresp * (npoles + 2), npoles);
+
+        // Zeros of sigma are the eigenvalues of A - b * c_sigma^T
+        let mut h = DMatrix::<f64>::zeros(npoles, npoles);
+        let mut b = DVector::<f64>::zeros(npoles);
+        let mut i = 0;
+        for p in &poles {
+            if p.im == 0.0 {
+                h[(i, i)] = p.re;
+                b[i] = 1.0;
+                i += 1;
+            } else {
+                h[(i, i)] = p.re;
+                h[(i, i + 1)] = p.im;
+                h[(i + 1, i)] = -p.im;
+                h[(i + 1, i + 1)] = p.re;
+                b[i] = 2.0;
+                i += 2;
+            }
+        }
+        let h = h - b * c_sigma.transpose();
+        let zeros = h.complex_eigenvalues();
+        poles = sort_poles(zeros.as_slice());
+        if num_unknowns(&poles) != npoles {
+            return Err(Error::Numerical(
+                "pole relocation lost a conjugate pair".to_string(),
+            ));
+        }
+    }
+
+    // Residue identification with the relocated poles
+    data.iter()
+        .map(|resp| {
+            let mut rows = vec![];
+            let mut rhs = vec![];
+            for (k, sk) in s.iter().enumerate() {
+                let mut row = basis(*sk, &poles);
+                row.push(Complex::ONE);
+                row.push(*sk);
+                push_row(&mut rows, &mut rhs, &row, resp.vals()[k]);
+            }
+            let a = DMatrix::from_row_slice(rhs.len(), npoles + 2, &rows);
+            let x = lstsq(a, DVector::from_vec(rhs))?;
+
+            let mut out_poles = vec![];
+            let mut out_residues = vec![];
+            let mut i = 0;
+            for p in &poles {
+                if p.im == 0.0 {
+                    out_poles.push(p * w0);
+                    out_residues.push(c64(x[i] * w0, 0.0));
+                    i += 1;
+                } else {
+                    let r = c64(x[i], x[i + 1]) * w0;
+                    out_poles.push(p * w0);
+                    out_residues.push(r);
+                    out_poles.push(p.conj() * w0);
+                    out_residues.push(r.conj());
+                    i += 2;
+                }
+            }
+            Ok(RationalModel::new(
+                out_poles,
+                out_residues,
+                x[npoles],
+                x[npoles + 1] / w0,
+            ))
+        })
+        .collect()
+}
+
+pub fn vector_fit(
+    data: &FreqData,
+    npoles: usize,
+    iterations: usize,
+) -> Result<RationalModel, Error> {
+    Ok(vector_fit_common(&[data], npoles, iterations)?.remove(0))
+}
+
+// Foster synthesis of a 1-port from its impedance
+//
+// The admittance is vector fitted for its poles and the residues are then refit so that every
+// complex pair takes the form of a series RLC branch, Y = (s / L) / (s^2 + s R / L + 1 / LC)
+pub fn synthesize_foster(
+    data: &FreqData,
+    npoles: usize,
+    iterations: usize,
+) -> Result<Vec<Foster>, Error> {
+    if data.vals().contains(&Complex::ZERO) {
+        return Err(Error::NonPhysical(
+            "impedance must be nonzero to synthesize an admittance".to_string(),
+        ));
+    }
+    let y = FreqData::new(
+        data.freqs().to_vec(),
+        data.vals().iter().map(|val| val.inv()).collect(),
+        Unit::Base,
+    )?;
+    let model = vector_fit(&y, npoles, iterations)?;
+    let ymax = y.vals().iter().map(|val| val.norm()).fold(0.0, f64::max);
+    let wmax = 2.0 * PI * y.freqs()[y.freqs().len() - 1];
+
+    let poles = model
+        .poles()
+        .iter()
+        .filter(|val| val.im >= 0.0)
+        .copied()
+        .collect::<Vec<Complex<f64>>>();
+    let ncols = poles.len() + 2;
+    let mut rows = vec![];
+    let mut rhs = vec![];
+    for (f, val) in y.freqs().iter().zip(y.vals().iter()) {
+        let s = c64(0.0, f / y.freqs()[y.freqs().len() - 1]);
+        let mut row = poles
+            .iter()
+            .map(|p| {
+                let p = p / wmax;
+                if p.im == 0.0 {
+                    1.0 / (s - p)
+                } else {
+                    s / ((s - p) * (s - p.conj()))
+                }
+            })
+            .collect::<Vec<Complex<f64>>>();
+        row.push(Complex::ONE);
+        row.push(s);
+        push_row(&mut rows, &mut rhs, &row, *val);
+    }
+    let a = DMatrix::from_row_slice(rhs.len(), ncols, &rows);
+    let x = lstsq(a, DVector::from_vec(rhs))?;
+
+    let mut out = vec![];
+    let mut g = x[poles.len()];
+    for (p, k) in poles.iter().zip(x.iter()) {
+        let k = k * wmax;
+        if p.im == 0.0 {
+            if k > 0.0 {
+                // R + sL  =>  Y = (1 / L) / (s + R / L)
+                let ind = 1.0 / k;
+                let res = -p.re * ind;
+                out.push(Foster::Rl(Inductor::new(
+                    res,
+                    scale(ind, &Unit::eng(ind)),
+                    Unit::Base,
+                    Unit::eng(ind),
+                    0.0,
+                    0.0,
+                    Orientation::Shunt,
+                )));
+            } else {
+                // R + 1 / sC  =>  Y = 1 / R - (1 / R^2 C) / (s + 1 / RC)
+                let res = p.re / k;
+                let cap = -k / p.re.powi(2);
+                g -= 1.0 / res;
+                out.push(Foster::Rc(Capacitor::new(
+                    res,
+                    scale(cap, &Unit::eng(cap)),
+                    Unit::Base,
+                    Unit::eng(cap),
+                    0.0,
+                    0.0,
+                    Orientation::Shunt,
+                )));
+            }
+        } else {
+            if k <= 0.0 {
+                return Err(Error::Unrealizable(format!(
+                    "pole pair at {:.6e} Hz requires a negative inductance",
+                    p.norm() / (2.0 * PI)
+                )));
+            }
+            let ind = 1.0 / k;
+            let res = -2.0 * p.re * ind;
+            let cap = 1.0 / (ind * p.norm_sqr());
+            out.push(Foster::Rlc(Rlc::new(
+                res,
+                scale(ind, &Unit::eng(ind)),
+                scale(cap, &Unit::eng(cap)),
+                Unit::Base,
+                Unit::eng(ind),
+                Unit::eng(cap),
+                0.0,
+                0.0,
+                0.0,
+                Orientation::Shunt,
+            )));
+        }
+    }
+
+    if g.abs() > SYNTH_TOL * ymax {
+        if g < 0.0 {
+            return Err(Error::Unrealizable(format!(
+                "residual conductance {:.6e} S is negative",
+                g
+            )));
+        }
+        out.push(Foster::R(Resistor::new(
+            1.0 / g,
+            Unit::Base,
+            0.0,
+            Orientation::Shunt,
+        )));
+    }
+    let cap = x[poles.len() + 1] / wmax;
+    if (cap * wmax).abs() > SYNTH_TOL * ymax {
+        if cap < 0.0 {
+            return Err(Error::Unrealizable(format!(
+                "residual capacitance {:.6e} F is negative",
+                cap
+            )));
+        }
+        out.push(Foster::C(Capacitor::new(
+            0.0,
+            scale(cap, &Unit::eng(cap)),
+            Unit::Base,
+            Unit::eng(cap),
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        )));
+    }
+
+    Ok(out)
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ElementReturn {
+    element: ElementType,
+    vals: Vec<f64>,
+    units: Vec<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RationalFitReturn {
+    poles: Vec<ComplexReturn>,
+    residues: Vec<ComplexReturn>,
+    d: f64,
+    e: f64,
+    rms_error: f64,
+    elements: Vec<ElementReturn>,
+}
+
+pub fn fit_rational(
+    lut: Vec<[f64; 3]>,
+    unit: Unit,
+    npoles: usize,
+    iterations: usize,
+    synthesize: bool,
+) -> Result<RationalFitReturn, Error> {
+    let data = FreqData::from_ri(&lut, unit)?;
+    let model = vector_fit(&data, npoles, iterations)?;
+
+    let elements = if synthesize {
+        synthesize_foster(&data, npoles, iterations)?
+            .iter()
+            .map(|val| ElementReturn {
+                element: val.code(),
+                vals: val.element().vals(),
+                units: val
+                    .element()
+                    .units()
+                    .iter()
+                    .map(|unit| unit.to_string())
+                    .collect(),
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok(RationalFitReturn {
+        poles: model
+            .poles()
+            .iter()
+            .map(|val| ComplexReturn {
+                re: val.re,
+                im: val.im,
+            })
+            .collect(),
+        residues: model
+            .residues()
+            .iter()
+            .map(|val| ComplexReturn {
+                re: val.re,
+                im: val.im,
+            })
+            .collect(),
+        d: model.d(),
+        e: model.e(),
+        rms_error: model.rms_error(&data),
+        elements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rf_utils::{comp_c64, comp_f64};
+    use float_cmp::F64Margin;
+
+    fn gen_data(model: &RationalModel) -> FreqData {
+        let freqs = (0..201)
+            .map(|i| 0.1 + 19.9 * i as f64 / 200.0)
+            .collect::<Vec<f64>>();
+        let vals = freqs
+            .iter()
+            .map(|val| model.eval(Frequency::new(*val, Unit::Giga)))
+            .collect();
+        FreqData::new(freqs, vals, Unit::Giga).unwrap()
+    }
+
+    #[test]
+    fn test_vector_fit() {
+        let testname = "vector_fit";
+        let w = 2.0 * PI * 1e9;
+        let exemplar = RationalModel::new(
+            vec![
+                c64(-3.0 * w, 0.0),
+                c64(-0.2 * w, 4.0 * w),
+                c64(-0.2 * w, -4.0 * w),
+                c64(-0.5 * w, 12.0 * w),
+                c64(-0.5 * w, -12.0 * w),
+            ],
+            vec![
+                c64(2.0 * w, 0.0),
+                c64(0.3 * w, 1.5 * w),
+                c64(0.3 * w, -1.5 * w),
+                c64(1.0 * w, -0.4 * w),
+                c64(1.0 * w, 0.4 * w),
+            ],
+            0.5,
+            1e-12,
+        );
+        let data = gen_data(&exemplar);
+        let test = vector_fit(&data, 5, 10).unwrap();
+
+        assert!(test.is_stable());
+        let margin = F64Margin::from((1e-6 * w, 4));
+        for (p, exemplar) in test.poles().iter().zip(exemplar.poles().iter()) {
+            comp_c64(p, exemplar, margin, testname, "pole");
+        }
+        for (r, exemplar) in test.residues().iter().zip(exemplar.residues().iter()) {
+            comp_c64(r, exemplar, margin, testname, "residue");
+        }
+        comp_f64(&test.d(), &0.5, F64Margin::from((1e-6, 4)), testname, "d");
+        comp_f64(
+            &test.e(),
+            &1e-12,
+            F64Margin::from((1e-18, 4)),
+            testname,
+            "e",
+        );
+        assert!(test.rms_error(&data) < 1e-9);
+
+        assert!(vector_fit(&data, 0, 10).is_err());
+        let short = FreqData::new(vec![1.0, 2.0, 3.0], vec![Complex::ONE; 3], Unit::Giga).unwrap();
+        assert!(vector_fit(&short, 4, 10).is_err());
+    }
+
+    #[test]
+    fn test_vector_fit_common() {
+        let w = 2.0 * PI * 1e9;
+        let pole = c64(-0.3 * w, 6.0 * w);
+        let s11 = RationalModel::new(
+            vec![pole, pole.conj()],
+            vec![c64(0.4 * w, 0.1 * w), c64(0.4 * w, -0.1 * w)],
+            0.1,
+            0.0,
+        );
+        let s21 = RationalModel::new(
+            vec![pole, pole.conj()],
+            vec![c64(-0.2 * w, 0.3 * w), c64(-0.2 * w, -0.3 * w)],
+            0.0,
+            0.0,
+        );
+        let data = [gen_data(&s11), gen_data(&s21)];
+        let test = vector_fit_common(&[&data[0], &data[1]], 2, 10).unwrap();
+
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].poles(), test[1].poles());
+        comp_c64(
+            &test[0].poles()[0],
+            &pole,
+            F64Margin::from((1e-6 * w, 4)),
+            "vector_fit_common()",
+            "pole",
+        );
+        assert!(test[0].rms_error(&data[0]) < 1e-9);
+        assert!(test[1].rms_error(&data[1]) < 1e-9);
+    }
+
+    #[test]
+    fn test_synthesize_foster() {
+        let testname = "synthesize_foster";
+        let rlc = Rlc::new(
+            5.0,
+            2.0,
+            0.5,
+            Unit::Base,
+            Unit::Nano,
+            Unit::Pico,
+            0.0,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+        let rl = Inductor::new(
+            20.0,
+            3.0,
+            Unit::Base,
+            Unit::Nano,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+        let r = Resistor::new(200.0, Unit::Base, 0.0, Orientation::Shunt);
+        let c = Capacitor::new(
+            0.0,
+            200.0,
+            Unit::Base,
+            Unit::Femto,
+            0.0,
+            0.0,
+            Orientation::Shunt,
+        );
+        let branches: [&dyn Element; 4] = [&rlc, &rl, &r, &c];
+        let freqs = (0..201)
+            .map(|i| 0.5 + 14.5 * i as f64 / 200.0)
+            .collect::<Vec<f64>>();
+        let z = freqs
+            .iter()
+            .map(|val| {
+                let freq = Frequency::new(*val, Unit::Giga);
+                branches
+                    .iter()
+                    .map(|b| b.z(freq).inv())
+                    .sum::<Complex<f64>>()
+                    .inv()
+            })
+            .collect::<Vec<Complex<f64>>>();
+        let data = FreqData::new(freqs.clone(), z.clone(), Unit::Giga).unwrap();
+        let test = synthesize_foster(&data, 3, 10).unwrap();
+
+        assert_eq!(test.len(), 4);
+        let margin = F64Margin::from((1e-6, 4));
+        match test.iter().find(|val| matches!(val, Foster::Rlc(_))) {
+            Some(Foster::Rlc(val)) => {
+                comp_f64(&val.res(), &5.0, margin, testname, "rlc res");
+                comp_f64(&val.ind(), &2.0, margin, testname, "rlc ind");
+                assert_eq!(val.ind_unit(), Unit::Nano);
+                comp_f64(&val.cap(), &500.0, margin, testname, "rlc cap");
+                assert_eq!(val.cap_unit(), Unit::Femto);
+            }
+            _ => panic!("{}: missing rlc branch", testname),
+        }
+        let mut codes = test.iter().map(Foster::code).collect::<Vec<_>>();
+        codes.sort_by_key(|val| val.to_string());
+        assert_eq!(
+            codes,
+            vec![
+                ElementType::ShuntCap,
+                ElementType::ShuntInd,
+                ElementType::ShuntRes,
+                ElementType::ShuntRlc
+            ]
+        );
+        for (f, z) in freqs.iter().zip(z.iter()) {
+            let freq = Frequency::new(*f, Unit::Giga);
+            let y = test
+                .iter()
+                .map(|val| val.element().z(freq).inv())
+                .sum::<Complex<f64>>();
+            comp_c64(&y.inv(), z, F64Margin::from((1e-6, 4)), testname, "z");
+        }
+
+        // A negative resistance cannot be realized
+        let data = FreqData::new(
+            freqs.clone(),
+            z.iter().map(|val| -val).collect(),
+            Unit::Giga,
+        )
+        .unwrap();
+        assert!(synthesize_foster(&data, 3, 10).is_err());
+    }
+}
@@ -0,0 +1,822 @@
+// Keyword arguments mirror the calculator forms
+#![allow(clippy::too_many_arguments)]
+
+use num_complex::{c64, Complex};
+use numpy::ndarray::{Array1, Array3};
+use numpy::{
+    IntoPyArray, PyArray1, PyArray3, PyReadonlyArray1, PyReadonlyArray2, PyReadonlyArray3,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList};
+use rf_toolbox_core::conjugate::calc_match as core_calc_match;
+use rf_toolbox_core::differential::{
+    calc_balanced_port, design_balun as core_design_balun, mixed_mode_nport, PortPairing,
+};
+use rf_toolbox_core::element::{
+    measured::{read_library, PartFile},
+    Element, ElementType,
+};
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::frequency::Frequency;
+use rf_toolbox_core::matching::ladder::calc_ladder as core_calc_ladder;
+use rf_toolbox_core::matching::xfmr::calc_xfmr as core_calc_xfmr;
+use rf_toolbox_core::matching::{calc_networks as core_calc_networks, ImpedanceType};
+use rf_toolbox_core::mna::Param;
+use rf_toolbox_core::response::{calc_abcd, calc_s};
+use rf_toolbox_core::rf_utils::{abcd2s, s2abcd, ComplexType, ZScale};
+use rf_toolbox_core::richards::calc_richards as core_calc_richards;
+use rf_toolbox_core::smith::{calc_smith_cascade, gen_balun, gen_part};
+use rf_toolbox_core::spice::{
+    network_netlist, network_solution, parse_circuit, parse_ladder, Network,
+};
+use rf_toolbox_core::touchstone::read_touchstone as core_read_touchstone;
+use rf_toolbox_core::unit::Unit;
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+
+// Frequencies are in Hz, impedances in Ω and complex values are numpy complex128 throughout
+
+fn to_py_err(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn parse<T: FromStr<Err = Error>>(val: &str) -> PyResult<T> {
+    T::from_str(val).map_err(to_py_err)
+}
+
+fn value_to_py<'py>(py: Python<'py>, val: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match val {
+        // serde_json writes NaN, used for unrealizable networks, as null
+        Value::Null => f64::NAN.into_pyobject(py)?.into_any(),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any(),
+        Value::String(s) => s.into_pyobject(py)?.into_any(),
+        Value::Array(vec) => {
+            let out = PyList::empty(py);
+            for v in vec {
+                out.append(value_to_py(py, v)?)?;
+            }
+            out.into_any()
+        }
+        Value::Object(map) => {
+            let out = PyDict::new(py);
+            for (k, v) in map {
+                out.set_item(k, value_to_py(py, v)?)?;
+            }
+            out.into_any()
+        }
+    })
+}
+
+fn to_dict<'py, T: Serialize>(py: Python<'py>, val: &T) -> PyResult<Bound<'py, PyAny>> {
+    let val = serde_json::to_value(val).map_err(|err| to_py_err(err.to_string()))?;
+    value_to_py(py, &val)
+}
+
+// Part files of measured elements, "vendor/parts.json" or "c_2p2.s2p,shunt", None elsewhere
+fn parse_parts(parts: Option<Vec<Option<String>>>) -> PyResult<Vec<Option<PartFile>>> {
+    parts
+        .unwrap_or_default()
+        .iter()
+        .map(|x| x.as_deref().map(parse).transpose())
+        .collect()
+}
+
+fn gen_chain(
+    elements: &[String],
+    vals: &[Vec<f64>],
+    units: &[Vec<String>],
+    parts: &[Option<PartFile>],
+    z0: f64,
+) -> PyResult<Vec<Box<dyn Element>>> {
+    if elements.len() != vals.len() || elements.len() != units.len() {
+        return Err(to_py_err(
+            "elements, vals and units must have the same length".to_string(),
+        ));
+    }
+    elements
+        .iter()
+        .zip(vals.iter().zip(units.iter()))
+        .enumerate()
+        .map(|(i, (element, (val, unit)))| {
+            let unit = unit.iter().map(|x| x.as_str()).collect::<Vec<&str>>();
+            let part = parts.get(i).and_then(Option::as_ref);
+            gen_part(parse(element)?, val, &unit, part, c64(1.0, 0.0), z0).map_err(to_py_err)
+        })
+        .collect()
+}
+
+fn to_array3(vals: Vec<[[Complex<f64>; 2]; 2]>) -> Array3<Complex<f64>> {
+    Array3::from_shape_fn((vals.len(), 2, 2), |(i, j, k)| vals[i][j][k])
+}
+
+fn from_array3(vals: &PyReadonlyArray3<Complex<f64>>) -> PyResult<Vec<[[Complex<f64>; 2]; 2]>> {
+    let vals = vals.as_array();
+    if vals.shape()[1] != 2 || vals.shape()[2] != 2 {
+        return Err(to_py_err(
+            "expected an array of shape (n, 2, 2)".to_string(),
+        ));
+    }
+    Ok(vals
+        .outer_iter()
+        .map(|x| [[x[[0, 0]], x[[0, 1]]], [x[[1, 0]], x[[1, 1]]]])
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (zs, zl, freq, z0=50.0, q_net=0.0, q=0.0, freq_unit="GHz", cap_unit="fF", ind_unit="pH", diff=false))]
+fn calc_networks<'py>(
+    py: Python<'py>,
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    freq: f64,
+    z0: f64,
+    q_net: f64,
+    q: f64,
+    freq_unit: &str,
+    cap_unit: &str,
+    ind_unit: &str,
+    diff: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let out = core_calc_networks(
+        zs.re,
+        zs.im,
+        zl.re,
+        zl.im,
+        ImpedanceType::ZReIm,
+        q_net,
+        q,
+        z0,
+        freq,
+        parse(freq_unit)?,
+        parse(cap_unit)?,
+        parse(ind_unit)?,
+        if diff {
+            ZScale::Diff
+        } else {
+            ZScale::SingleEnded
+        },
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+// SPICE subcircuit of one solution of a calc_networks topology, e.g. "hp1" or "pi_lp". A Q of 0
+// leaves out the loss resistance of that kind of part.
+#[pyfunction]
+#[pyo3(signature = (network, zs, zl, freq, solution=0, z0=50.0, q_net=0.0, q=0.0, q_c=0.0, q_l=0.0, freq_unit="GHz", ports=("in".to_string(), "out".to_string()), diff=false))]
+fn spice_network(
+    network: &str,
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    freq: f64,
+    solution: usize,
+    z0: f64,
+    q_net: f64,
+    q: f64,
+    q_c: f64,
+    q_l: f64,
+    freq_unit: &str,
+    ports: (String, String),
+    diff: bool,
+) -> PyResult<String> {
+    let network: Network = parse(network)?;
+    let freq_unit = parse(freq_unit)?;
+    let out = core_calc_networks(
+        zs.re,
+        zs.im,
+        zl.re,
+        zl.im,
+        ImpedanceType::ZReIm,
+        q_net,
+        q,
+        z0,
+        freq,
+        freq_unit,
+        Unit::Femto,
+        Unit::Pico,
+        if diff {
+            ZScale::Diff
+        } else {
+            ZScale::SingleEnded
+        },
+    )
+    .map_err(to_py_err)?;
+    let networks = serde_json::to_value(out).map_err(to_py_err)?;
+    let values = network_solution(&networks, network, solution).map_err(to_py_err)?;
+    network_netlist(
+        network,
+        values,
+        freq,
+        freq_unit,
+        q_c,
+        q_l,
+        [&ports.0, &ports.1],
+    )
+    .map_err(to_py_err)
+}
+
+// Series/shunt cascade of a two-port SPICE subcircuit as {"name", "ports", "stages"}, each stage
+// an {"element", "vals", "units"} ready for cascade_s
+#[pyfunction]
+#[pyo3(signature = (netlist, er=1.0))]
+fn spice_ladder<'py>(py: Python<'py>, netlist: &str, er: f64) -> PyResult<Bound<'py, PyAny>> {
+    let out = parse_ladder(netlist, er).map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+// S, Y or Z matrices of a SPICE subcircuit by nodal analysis, one port from each of its nodes to
+// ground, as an (n, ports, ports) array
+#[pyfunction]
+#[pyo3(signature = (netlist, freqs, z0=50.0, param="s"))]
+fn spice_nport<'py>(
+    py: Python<'py>,
+    netlist: &str,
+    freqs: PyReadonlyArray1<f64>,
+    z0: f64,
+    param: &str,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let param = parse::<Param>(param)?;
+    let circuit = parse_circuit(netlist, z0).map_err(to_py_err)?;
+    let ports = circuit.ports().len();
+    let vals = freqs
+        .as_array()
+        .iter()
+        .map(|f| circuit.param(param, Frequency::new(*f, Unit::Base)))
+        .collect::<Result<Vec<_>, Error>>()
+        .map_err(to_py_err)?;
+    Ok(
+        Array3::from_shape_fn((vals.len(), ports, ports), |(i, j, k)| vals[i][j][k])
+            .into_pyarray(py),
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (topology, zs, zl, freq, z0=50.0, q=0.0, freq_unit="GHz", cap_unit="fF", ind_unit="pH", diff=false))]
+fn calc_ladder<'py>(
+    py: Python<'py>,
+    topology: &str,
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    freq: f64,
+    z0: f64,
+    q: f64,
+    freq_unit: &str,
+    cap_unit: &str,
+    ind_unit: &str,
+    diff: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let out = core_calc_ladder(
+        &parse(topology)?,
+        zs.re,
+        zs.im,
+        zl.re,
+        zl.im,
+        ImpedanceType::ZReIm,
+        q,
+        z0,
+        freq,
+        parse(freq_unit)?,
+        parse(cap_unit)?,
+        parse(ind_unit)?,
+        if diff {
+            ZScale::Diff
+        } else {
+            ZScale::SingleEnded
+        },
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+// Stubs and unit elements of a ladder of series and shunt L and C given as in cascade_s
+#[pyfunction]
+#[pyo3(signature = (elements, vals, units, freq, z0=50.0, er=1.0, freq_unit="GHz", length_unit="mm"))]
+fn calc_richards<'py>(
+    py: Python<'py>,
+    elements: Vec<String>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<String>>,
+    freq: f64,
+    z0: f64,
+    er: f64,
+    freq_unit: &str,
+    length_unit: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let out = core_calc_richards(
+        elements
+            .iter()
+            .map(|x| parse(x))
+            .collect::<PyResult<Vec<ElementType>>>()?,
+        vals,
+        units
+            .iter()
+            .map(|x| x.iter().map(|y| y.as_str()).collect())
+            .collect(),
+        z0,
+        freq,
+        parse(freq_unit)?,
+        er,
+        parse(length_unit)?,
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+#[pyfunction]
+#[pyo3(signature = (zs, zl, freq, k, q_net, q=0.0, z0=50.0, freq_unit="GHz", cap_unit="fF", ind_unit="pH"))]
+fn calc_xfmr<'py>(
+    py: Python<'py>,
+    zs: Complex<f64>,
+    zl: Complex<f64>,
+    freq: f64,
+    k: f64,
+    q_net: f64,
+    q: f64,
+    z0: f64,
+    freq_unit: &str,
+    cap_unit: &str,
+    ind_unit: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let out = core_calc_xfmr(
+        zs.re,
+        zs.im,
+        zl.re,
+        zl.im,
+        ImpedanceType::ZReIm,
+        k,
+        q_net,
+        q,
+        z0,
+        freq,
+        parse(freq_unit)?,
+        parse(cap_unit)?,
+        parse(ind_unit)?,
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+#[pyfunction]
+#[pyo3(signature = (s, freq, z0=50.0, freq_unit="GHz", cap_unit="fF"))]
+fn calc_match<'py>(
+    py: Python<'py>,
+    s: PyReadonlyArray2<Complex<f64>>,
+    freq: f64,
+    z0: f64,
+    freq_unit: &str,
+    cap_unit: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let s = s.as_array();
+    if s.shape() != [2, 2] {
+        return Err(to_py_err("expected an array of shape (2, 2)".to_string()));
+    }
+    let out = core_calc_match(
+        s[[0, 0]].re,
+        s[[0, 0]].im,
+        s[[0, 1]].re,
+        s[[0, 1]].im,
+        s[[1, 0]].re,
+        s[[1, 0]].im,
+        s[[1, 1]].re,
+        s[[1, 1]].im,
+        ComplexType::ReIm,
+        z0,
+        freq,
+        parse(freq_unit)?,
+        parse(cap_unit)?,
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+#[pyfunction]
+#[pyo3(signature = (s, z0=50.0))]
+fn balanced_port<'py>(
+    py: Python<'py>,
+    s: PyReadonlyArray2<Complex<f64>>,
+    z0: f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let s = s.as_array();
+    if s.shape() != [2, 2] {
+        return Err(to_py_err("expected an array of shape (2, 2)".to_string()));
+    }
+    let out = calc_balanced_port(
+        s[[0, 0]].re,
+        s[[0, 0]].im,
+        s[[0, 1]].re,
+        s[[0, 1]].im,
+        s[[1, 0]].re,
+        s[[1, 0]].im,
+        s[[1, 1]].re,
+        s[[1, 1]].im,
+        ComplexType::ReIm,
+        z0,
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+// Mixed-mode S-parameters of a balun over freqs as an (n, 3, 3) array, rows and columns in the
+// order single-ended, differential, common mode
+#[pyfunction]
+#[pyo3(signature = (vals, units, freqs, z_se=50.0, z_diff=100.0))]
+fn balun_mixed_mode<'py>(
+    py: Python<'py>,
+    vals: Vec<f64>,
+    units: Vec<String>,
+    freqs: PyReadonlyArray1<f64>,
+    z_se: f64,
+    z_diff: f64,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let units = units.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+    let balun = gen_balun(&vals, &units, z_se, z_diff).map_err(to_py_err)?;
+    let vals = freqs
+        .as_array()
+        .iter()
+        .map(|f| balun.mixed_mode(Frequency::new(*f, Unit::Base)))
+        .collect::<Result<Vec<_>, Error>>()
+        .map_err(to_py_err)?;
+    Ok(Array3::from_shape_fn((vals.len(), 3, 3), |(i, j, k)| vals[i][j][k]).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (kind, z_se, z_diff, freq, z0e=150.0, freq_unit="GHz", cap_unit="fF", ind_unit="pH"))]
+fn design_balun<'py>(
+    py: Python<'py>,
+    kind: &str,
+    z_se: f64,
+    z_diff: f64,
+    freq: f64,
+    z0e: f64,
+    freq_unit: &str,
+    cap_unit: &str,
+    ind_unit: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let out = core_design_balun(
+        parse(kind)?,
+        z_se,
+        z_diff,
+        z0e,
+        freq,
+        parse(freq_unit)?,
+        parse(cap_unit)?,
+        parse(ind_unit)?,
+    )
+    .map_err(to_py_err)?;
+    to_dict(py, &out)
+}
+
+#[pyfunction]
+#[pyo3(signature = (element, vals, units, freqs, z0=50.0, part=None))]
+fn element_z<'py>(
+    py: Python<'py>,
+    element: String,
+    vals: Vec<f64>,
+    units: Vec<String>,
+    freqs: PyReadonlyArray1<f64>,
+    z0: f64,
+    part: Option<String>,
+) -> PyResult<Bound<'py, PyArray1<Complex<f64>>>> {
+    let parts = parse_parts(Some(vec![part]))?;
+    let element = gen_chain(&[element], &[vals], &[units], &parts, z0)?.remove(0);
+    Ok(freqs
+        .as_array()
+        .map(|f| element.z(Frequency::new(*f, Unit::Base)))
+        .into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (elements, vals, units, freqs, z0=50.0, parts=None))]
+fn cascade_abcd<'py>(
+    py: Python<'py>,
+    elements: Vec<String>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<String>>,
+    freqs: PyReadonlyArray1<f64>,
+    z0: f64,
+    parts: Option<Vec<Option<String>>>,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let chain = gen_chain(&elements, &vals, &units, &parse_parts(parts)?, z0)?;
+    let chain = chain.iter().map(|x| x.as_ref()).collect::<Vec<_>>();
+    let out = freqs
+        .as_array()
+        .iter()
+        .map(|f| calc_abcd(&chain, Frequency::new(*f, Unit::Base)))
+        .collect();
+    Ok(to_array3(out).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (elements, vals, units, freqs, z0=50.0, parts=None))]
+fn cascade_s<'py>(
+    py: Python<'py>,
+    elements: Vec<String>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<String>>,
+    freqs: PyReadonlyArray1<f64>,
+    z0: f64,
+    parts: Option<Vec<Option<String>>>,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let chain = gen_chain(&elements, &vals, &units, &parse_parts(parts)?, z0)?;
+    let chain = chain.iter().map(|x| x.as_ref()).collect::<Vec<_>>();
+    let out = freqs
+        .as_array()
+        .iter()
+        .map(|f| calc_s(&chain, Frequency::new(*f, Unit::Base), z0))
+        .collect();
+    Ok(to_array3(out).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (abcd, z0=50.0))]
+fn abcd_to_s<'py>(
+    py: Python<'py>,
+    abcd: PyReadonlyArray3<Complex<f64>>,
+    z0: f64,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let out = from_array3(&abcd)?.iter().map(|x| abcd2s(x, z0)).collect();
+    Ok(to_array3(out).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (s, z0=50.0))]
+fn s_to_abcd<'py>(
+    py: Python<'py>,
+    s: PyReadonlyArray3<Complex<f64>>,
+    z0: f64,
+) -> PyResult<Bound<'py, PyArray3<Complex<f64>>>> {
+    let out = from_array3(&s)?.iter().map(|x| s2abcd(x, z0)).collect();
+    Ok(to_array3(out).into_pyarray(py))
+}
+
+type Trace<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>);
+
+// One (x, y) pair of Smith chart coordinates per element, starting from the load
+#[pyfunction]
+#[pyo3(signature = (elements, vals, units, freq, z0=50.0, zl=c64(50.0, 0.0), npts=51, parts=None))]
+fn smith_trace<'py>(
+    py: Python<'py>,
+    elements: Vec<String>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<String>>,
+    freq: f64,
+    z0: f64,
+    zl: Complex<f64>,
+    npts: usize,
+    parts: Option<Vec<Option<String>>>,
+) -> PyResult<Vec<Trace<'py>>> {
+    let parts = parse_parts(parts)?;
+    let zin = zl / z0;
+    let elements = elements
+        .iter()
+        .map(|x| parse(x))
+        .collect::<PyResult<Vec<ElementType>>>()?;
+    let cascade = calc_smith_cascade(
+        elements.clone(),
+        vals.clone(),
+        units
+            .iter()
+            .map(|x| x.iter().map(|y| y.as_str()).collect())
+            .collect(),
+        parts.clone(),
+        zin.re,
+        zin.im,
+        z0,
+        freq,
+        Unit::Base,
+    )
+    .map_err(to_py_err)?;
+
+    let freq = Frequency::new(freq, Unit::Base);
+    let mut zin = zin;
+    let mut out = vec![];
+    for (i, element) in elements.iter().enumerate() {
+        let unit = units[i].iter().map(|x| x.as_str()).collect::<Vec<&str>>();
+        let part = parts.get(i).and_then(Option::as_ref);
+        let arc = gen_part(*element, &vals[i], &unit, part, zin, z0)
+            .map_err(to_py_err)?
+            .calc_arc(freq, zin, z0, npts, false);
+        out.push((
+            Array1::from(arc.0).into_pyarray(py),
+            Array1::from(arc.1).into_pyarray(py),
+        ));
+        zin = c64(cascade[i].z.re, cascade[i].z.im) / z0;
+    }
+    Ok(out)
+}
+
+// Returns {"freqs", "s", "z0"} with s of shape (n, ports, ports)
+#[pyfunction]
+fn read_touchstone<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyDict>> {
+    let data = core_read_touchstone(path).map_err(to_py_err)?;
+    let ports = data.ports();
+    let s = Array3::from_shape_fn((data.s.len(), ports, ports), |(i, j, k)| data.s[i][j][k]);
+    let out = PyDict::new(py);
+    out.set_item("freqs", Array1::from_vec(data.freqs).into_pyarray(py))?;
+    out.set_item("s", s.into_pyarray(py))?;
+    out.set_item("z0", data.z0)?;
+    Ok(out)
+}
+
+// Returns {"unit", "fixture", "parts"} of a vendor library index, each part {"value", "file"}
+#[pyfunction]
+fn read_part_library<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyAny>> {
+    to_dict(py, &read_library(path).map_err(to_py_err)?)
+}
+
+// s of shape (n, ports, ports) to (sdd, sdc, scd, scc), each of shape (n, pairs, pairs)
+#[pyfunction]
+#[pyo3(signature = (s, pairing="1-3,2-4"))]
+fn mixed_mode<'py>(
+    py: Python<'py>,
+    s: PyReadonlyArray3<Complex<f64>>,
+    pairing: &str,
+) -> PyResult<Vec<Bound<'py, PyArray3<Complex<f64>>>>> {
+    let pairing: PortPairing = parse(pairing)?;
+    let s = s.as_array();
+    if s.shape()[1] != s.shape()[2] {
+        return Err(to_py_err(
+            "expected an array of shape (n, ports, ports)".to_string(),
+        ));
+    }
+    let vals = s
+        .outer_iter()
+        .map(|x| {
+            let x = x
+                .outer_iter()
+                .map(|row| row.to_vec())
+                .collect::<Vec<Vec<Complex<f64>>>>();
+            mixed_mode_nport(&x, &pairing).map_err(to_py_err)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let m = pairing.pairs.len();
+    Ok((0..4)
+        .map(|k| {
+            Array3::from_shape_fn((vals.len(), m, m), |(i, j, l)| vals[i][k][j][l]).into_pyarray(py)
+        })
+        .collect())
+}
+
+#[pymodule]
+fn rf_toolbox(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(calc_networks, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_ladder, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_xfmr, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_richards, m)?)?;
+    m.add_function(wrap_pyfunction!(spice_network, m)?)?;
+    m.add_function(wrap_pyfunction!(spice_ladder, m)?)?;
+    m.add_function(wrap_pyfunction!(spice_nport, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_match, m)?)?;
+    m.add_function(wrap_pyfunction!(balanced_port, m)?)?;
+    m.add_function(wrap_pyfunction!(mixed_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(read_touchstone, m)?)?;
+    m.add_function(wrap_pyfunction!(read_part_library, m)?)?;
+    m.add_function(wrap_pyfunction!(design_balun, m)?)?;
+    m.add_function(wrap_pyfunction!(balun_mixed_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(element_z, m)?)?;
+    m.add_function(wrap_pyfunction!(cascade_abcd, m)?)?;
+    m.add_function(wrap_pyfunction!(cascade_s, m)?)?;
+    m.add_function(wrap_pyfunction!(abcd_to_s, m)?)?;
+    m.add_function(wrap_pyfunction!(s_to_abcd, m)?)?;
+    m.add_function(wrap_pyfunction!(smith_trace, m)?)?;
+    Ok(())
+}
+
+// numpy is not loaded in these tests, so they cover the calls that take and return plain Python
+// values. Run with --no-default-features so the test binary links libpython.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::{IntoPyDict, PyString};
+
+    fn with_module<F: for<'py> FnOnce(Python<'py>, &Bound<'py, PyModule>)>(f: F) {
+        Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "rf_toolbox").unwrap();
+            rf_toolbox(&m).unwrap();
+            f(py, &m)
+        })
+    }
+
+    fn kwargs<'py>(py: Python<'py>, vals: &[(&str, f64)]) -> Bound<'py, PyDict> {
+        let out = PyDict::new(py);
+        for (k, v) in vals {
+            out.set_item(k, v).unwrap();
+        }
+        out
+    }
+
+    fn get<'py>(val: &Bound<'py, PyAny>, key: &str) -> Bound<'py, PyAny> {
+        val.get_item(key).unwrap()
+    }
+
+    #[test]
+    fn test_matching() {
+        with_module(|py, m| {
+            let (zs, zl) = (c64(42.4, -19.6), c64(212.3, 43.2));
+            let out = m
+                .getattr("calc_networks")
+                .unwrap()
+                .call1((zs, zl, 275.0))
+                .unwrap();
+            assert_eq!(get(&get(&out, "zl"), "re").extract::<f64>().unwrap(), 212.3);
+            assert!(get(&out, "hp1").len().unwrap() > 0);
+
+            let out = m
+                .getattr("calc_ladder")
+                .unwrap()
+                .call1(("sL-pC", zs, zl, 275.0))
+                .unwrap();
+            assert_eq!(get(&out, "topology").extract::<String>().unwrap(), "sL-pC");
+            assert!(get(&out, "solutions").len().unwrap() > 0);
+
+            let out = m
+                .getattr("calc_xfmr")
+                .unwrap()
+                .call(
+                    (c64(10.0, -20.0), c64(50.0, 10.0), 30.0),
+                    Some(&kwargs(py, &[("k", 0.7), ("q_net", 2.0)])),
+                )
+                .unwrap();
+            assert_eq!(get(&out, "k").extract::<f64>().unwrap(), 0.7);
+            assert!(get(&out, "solutions").len().unwrap() > 0);
+        })
+    }
+
+    #[test]
+    fn test_spice() {
+        with_module(|_, m| {
+            let out = m
+                .getattr("spice_network")
+                .unwrap()
+                .call1(("hp1", c64(212.3, 43.2), c64(42.4, -19.6), 275.0))
+                .unwrap();
+            let netlist = out
+                .cast::<PyString>()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            assert!(netlist.contains(".SUBCKT hp1 in out"));
+
+            let out = m
+                .getattr("spice_ladder")
+                .unwrap()
+                .call1((netlist,))
+                .unwrap();
+            assert_eq!(
+                get(&out, "ports").extract::<Vec<String>>().unwrap(),
+                ["in", "out"]
+            );
+            assert!(get(&out, "stages").len().unwrap() > 0);
+        })
+    }
+
+    #[test]
+    fn test_differential() {
+        with_module(|_, m| {
+            let out = m
+                .getattr("design_balun")
+                .unwrap()
+                .call1(("lattice", 50.0, 100.0, 2.0))
+                .unwrap();
+            assert_eq!(get(&out, "element").extract::<String>().unwrap(), "balun");
+            assert_eq!(
+                get(&out, "units").extract::<Vec<String>>().unwrap(),
+                ["lattice", "pH", "fF"]
+            );
+        })
+    }
+
+    #[test]
+    fn test_errors() {
+        with_module(|py, m| {
+            let err = m
+                .getattr("calc_networks")
+                .unwrap()
+                .call(
+                    (c64(42.4, -19.6), c64(212.3, 43.2), 275.0),
+                    Some(&[("freq_unit", "furlong")].into_py_dict(py).unwrap()),
+                )
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+
+            let err = m
+                .getattr("spice_network")
+                .unwrap()
+                .call1(("hp9", c64(42.4, -19.6), c64(212.3, 43.2), 275.0))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(err.to_string().contains("hp9"));
+
+            assert!(m
+                .getattr("read_part_library")
+                .unwrap()
+                .call1(("missing/parts.json",))
+                .is_err());
+        })
+    }
+}
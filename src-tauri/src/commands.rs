@@ -0,0 +1,374 @@
+use rf_toolbox_core::conjugate::{self, ResultsReturn};
+use rf_toolbox_core::differential::{
+    self, BalancedPortReturn, BalunDesignReturn, BalunReturn, MixedModeReturn, PortPairing,
+};
+use rf_toolbox_core::element::{
+    balun::BalunType,
+    measured::{self, PartFile, PartLibrary},
+    ElementType, SmithArc,
+};
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::freq_data::{Extrapolation, Interpolation};
+use rf_toolbox_core::frequency::FrequencySweep;
+use rf_toolbox_core::matching::ladder::{self, LadderReturn, Topology};
+use rf_toolbox_core::matching::xfmr::{self, XfmrReturn};
+use rf_toolbox_core::matching::{self, ImpedanceType, MatchingReturn};
+use rf_toolbox_core::response::{self, FreqResponseReturn};
+use rf_toolbox_core::rf_utils::{
+    self, Complex2Return, ComplexReturn, ComplexType, ImpedanceFormat, ResponseReturn, ZScale,
+};
+use rf_toolbox_core::richards::{self, DistributedReturn};
+use rf_toolbox_core::smith::{self, ArcReturn, ArcType, SmithCascadeReturn};
+use rf_toolbox_core::unit::{self, Unit};
+use rf_toolbox_core::vector_fit::{self, RationalFitReturn};
+use std::path::Path;
+
+// Thin wrappers that expose rf_toolbox_core to the webview. Codes and units are deserialized into
+// their enums by Tauri, so an unknown value is rejected with a message before the command runs
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_impedance(
+    re: f64,
+    im: f64,
+    imp: ImpedanceFormat,
+    z0: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+) -> Result<ResponseReturn, Error> {
+    Ok(rf_utils::calc_impedance(
+        re, im, imp, z0, freq, f_scale, c_scale,
+    ))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_c64_inv(re: f64, im: f64) -> Result<ComplexReturn, Error> {
+    rf_utils::get_c64_inv(re, im)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_networks(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    q_net: f64,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+    l_scale: Unit,
+    z_scale: ZScale,
+) -> Result<MatchingReturn, Error> {
+    matching::calc_networks(
+        rs, xs, rl, xl, imp, q_net, q, z0, freq, f_scale, c_scale, l_scale, z_scale,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_ladder(
+    topology: Topology,
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+    l_scale: Unit,
+    z_scale: ZScale,
+) -> Result<LadderReturn, Error> {
+    ladder::calc_ladder(
+        &topology, rs, xs, rl, xl, imp, q, z0, freq, f_scale, c_scale, l_scale, z_scale,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_xfmr(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp: ImpedanceType,
+    k: f64,
+    q_net: f64,
+    q: f64,
+    z0: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+    l_scale: Unit,
+) -> Result<XfmrReturn, Error> {
+    xfmr::calc_xfmr(
+        rs, xs, rl, xl, imp, k, q_net, q, z0, freq, f_scale, c_scale, l_scale,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn change_impedance(
+    rs: f64,
+    xs: f64,
+    rl: f64,
+    xl: f64,
+    imp_in: ImpedanceType,
+    imp_out: ImpedanceType,
+    z0: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+) -> Result<Complex2Return, Error> {
+    matching::change_impedance(rs, xs, rl, xl, imp_in, imp_out, z0, freq, f_scale, c_scale)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_match(
+    s11re: f64,
+    s11im: f64,
+    s12re: f64,
+    s12im: f64,
+    s21re: f64,
+    s21im: f64,
+    s22re: f64,
+    s22im: f64,
+    imp: ComplexType,
+    z0: f64,
+    freq: f64,
+    fscale: Unit,
+    cscale: Unit,
+) -> Result<ResultsReturn, Error> {
+    conjugate::calc_match(
+        s11re, s11im, s12re, s12im, s21re, s21im, s22re, s22im, imp, z0, freq, fscale, cscale,
+    )
+}
+
+// Without a pairing the Touchstone text must be a 2-port
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_match_touchstone(
+    text: &str,
+    ports: usize,
+    pairing: Option<PortPairing>,
+    freq: f64,
+    fscale: Unit,
+    cscale: Unit,
+) -> Result<ResultsReturn, Error> {
+    conjugate::calc_match_touchstone(text, ports, pairing.as_ref(), freq, fscale, cscale)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_balanced_port(
+    s11re: f64,
+    s11im: f64,
+    s12re: f64,
+    s12im: f64,
+    s21re: f64,
+    s21im: f64,
+    s22re: f64,
+    s22im: f64,
+    imp: ComplexType,
+    z0: f64,
+) -> Result<BalancedPortReturn, Error> {
+    differential::calc_balanced_port(
+        s11re, s11im, s12re, s12im, s21re, s21im, s22re, s22im, imp, z0,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_mixed_mode(
+    text: &str,
+    ports: usize,
+    pairing: PortPairing,
+    freq: f64,
+    f_scale: Unit,
+) -> Result<MixedModeReturn, Error> {
+    differential::calc_mixed_mode(text, ports, &pairing, freq, f_scale)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn find_smith_coord_js(
+    re: f64,
+    im: f64,
+    rotate: bool,
+    verbose: bool,
+) -> Result<Vec<f64>, Error> {
+    smith::find_smith_coord_js(re, im, rotate, verbose)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn arc_smith_points(
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+    type_: ArcType,
+    rotate: bool,
+    beta: f64,
+    start_at_qtr_wl: f64,
+    z0: f64,
+    resolution: usize,
+    verbose: bool,
+) -> Result<ArcReturn, Error> {
+    smith::arc_smith_points(
+        x1,
+        y1,
+        x2,
+        y2,
+        type_,
+        rotate,
+        beta,
+        start_at_qtr_wl,
+        z0,
+        resolution,
+        verbose,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_ri(
+    vals: Vec<f64>,
+    units: Vec<&str>,
+    lut: Vec<[f64; 3]>,
+    interp: Option<Interpolation>,
+    extrap: Option<Extrapolation>,
+    part: Option<PartFile>,
+    type_: ElementType,
+    freq: f64,
+    z0: f64,
+    diff: bool,
+    verbose: bool,
+) -> Result<Vec<f64>, Error> {
+    // Schematics saved before the table had a mode were linearly interpolated
+    smith::calc_ri(
+        vals,
+        units,
+        lut,
+        interp.unwrap_or(Interpolation::Linear),
+        extrap.unwrap_or_default(),
+        part,
+        type_,
+        freq,
+        z0,
+        diff,
+        verbose,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_smith_arc(
+    element: ElementType,
+    vals: Vec<f64>,
+    units: Vec<&str>,
+    part: Option<PartFile>,
+    rin: f64,
+    xin: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    npts: usize,
+    verbose: bool,
+) -> Result<SmithArc, Error> {
+    smith::calc_smith_arc(
+        element, vals, units, part, rin, xin, z0, freq, freq_unit, npts, verbose,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_smith_cascade(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    parts: Option<Vec<Option<PartFile>>>,
+    rin: f64,
+    xin: f64,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+) -> Result<Vec<SmithCascadeReturn>, Error> {
+    smith::calc_smith_cascade(
+        elements,
+        vals,
+        units,
+        parts.unwrap_or_default(),
+        rin,
+        xin,
+        z0,
+        freq,
+        freq_unit,
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_richards(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    z0: f64,
+    freq: f64,
+    freq_unit: Unit,
+    er: f64,
+    length_unit: Unit,
+) -> Result<Vec<DistributedReturn>, Error> {
+    richards::calc_richards(elements, vals, units, z0, freq, freq_unit, er, length_unit)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_unit_scale(unit: Unit, freq: f64, er: f64) -> f64 {
+    unit::get_unit_scale(unit, freq, er)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_response(
+    elements: Vec<ElementType>,
+    vals: Vec<Vec<f64>>,
+    units: Vec<Vec<&str>>,
+    parts: Option<Vec<Option<PartFile>>>,
+    z0: f64,
+    sweep: FrequencySweep,
+) -> Result<Vec<FreqResponseReturn>, Error> {
+    response::calc_response(elements, vals, units, parts.unwrap_or_default(), z0, sweep)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn calc_balun(
+    vals: Vec<f64>,
+    units: Vec<&str>,
+    z_se: f64,
+    z_diff: f64,
+    sweep: FrequencySweep,
+) -> Result<Vec<BalunReturn>, Error> {
+    differential::calc_balun(&vals, &units, z_se, z_diff, &sweep)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn design_balun(
+    kind: BalunType,
+    z_se: f64,
+    z_diff: f64,
+    z0e: f64,
+    freq: f64,
+    f_scale: Unit,
+    c_scale: Unit,
+    l_scale: Unit,
+) -> Result<BalunDesignReturn, Error> {
+    differential::design_balun(kind, z_se, z_diff, z0e, freq, f_scale, c_scale, l_scale)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn read_part_library(path: &str) -> Result<PartLibrary, Error> {
+    Ok(measured::load_library(Path::new(path))?.as_ref().clone())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn fit_rational(
+    lut: Vec<[f64; 3]>,
+    unit: Unit,
+    npoles: usize,
+    iterations: usize,
+    synthesize: bool,
+) -> Result<RationalFitReturn, Error> {
+    vector_fit::fit_rational(lut, unit, npoles, iterations, synthesize)
+}
@@ -1,59 +1,67 @@
 #![allow(unused)]
-use crate::unit::Unit;
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::spice::{network_netlist, Network, DEFAULT_PORTS};
+use rf_toolbox_core::unit::Unit;
+use serde_json::Value;
 use std::str::FromStr;
 use tauri::AppHandle;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+fn unit(val: &str) -> Result<String, Error> {
+    Ok(Unit::from_str(val)?.to_string())
+}
+
+pub(crate) fn write(app: &AppHandle, val: String) -> Result<(), Error> {
+    app.clipboard()
+        .clear()
+        .map_err(|err| Error::Io(format!("clipboard: {}", err)))?;
+    app.clipboard()
+        .write_text(val)
+        .map_err(|err| Error::Io(format!("clipboard: {}", err)))
+}
+
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_rc(app: AppHandle, r: &str, c: &str, unit: &str) {
-    let cunit = Unit::from_str(unit).unwrap();
-    let val = format!("{} {}{}", r, c, cunit.to_string());
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+pub fn copy_rc(app: AppHandle, r: &str, c: &str, unit: &str) -> Result<(), Error> {
+    let val = format!("{} {}{}", r, c, self::unit(unit)?);
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_scalar(app: AppHandle, x: &str) {
-    let val = format!("{}", x);
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+pub fn copy_scalar(app: AppHandle, x: &str) -> Result<(), Error> {
+    write(&app, x.to_string())
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_scalar_w_unit(app: AppHandle, x: &str, unit: &str) {
-    let val = format!("{}{}", x, Unit::from_str(unit).unwrap().to_string());
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+pub fn copy_scalar_w_unit(app: AppHandle, x: &str, unit: &str) -> Result<(), Error> {
+    let val = format!("{}{}", x, self::unit(unit)?);
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_complex(app: AppHandle, re: &str, im: &str) {
+pub fn copy_complex(app: AppHandle, re: &str, im: &str) -> Result<(), Error> {
     let val = format!("{} {}", re, im);
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_complex_w_unit(app: AppHandle, re: &str, unit_re: &str, im: &str, unit_im: &str) {
-    let val = format!(
-        "{}{} {}{}",
-        re,
-        Unit::from_str(unit_re).unwrap().to_string(),
-        im,
-        Unit::from_str(unit_im).unwrap().to_string()
-    );
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+pub fn copy_complex_w_unit(
+    app: AppHandle,
+    re: &str,
+    unit_re: &str,
+    im: &str,
+    unit_im: &str,
+) -> Result<(), Error> {
+    let val = format!("{}{} {}{}", re, unit(unit_re)?, im, unit(unit_im)?);
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-pub fn copy_complex_ri(app: AppHandle, re: &str, im: &str) {
-    let val = match &im[0..0] == "-" {
-        true => format!("{} - {}", re, &im[1..]),
-        false => format!("{} + {}", re, &im),
+pub fn copy_complex_ri(app: AppHandle, re: &str, im: &str) -> Result<(), Error> {
+    let val = match im.strip_prefix('-') {
+        Some(im) => format!("{} - {}", re, im),
+        None => format!("{} + {}", re, im),
     };
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -65,18 +73,17 @@ pub fn copy_pi_tee(
     unit2: &str,
     val3: &str,
     unit3: &str,
-) {
+) -> Result<(), Error> {
     let val = format!(
         "{}{} {}{} {}{}",
         val1,
-        Unit::from_str(unit1).unwrap().to_string(),
+        unit(unit1)?,
         val2,
-        Unit::from_str(unit2).unwrap().to_string(),
+        unit(unit2)?,
         val3,
-        Unit::from_str(unit3).unwrap().to_string()
+        unit(unit3)?
     );
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+    write(&app, val)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -90,25 +97,44 @@ pub fn copy_ccll(
     unit3: &str,
     val4: &str,
     unit4: &str,
-) {
+) -> Result<(), Error> {
     let val = format!(
         "{}{} {}{} {}{} {}{}",
         val1,
-        Unit::from_str(unit1).unwrap().to_string(),
+        unit(unit1)?,
         val2,
-        Unit::from_str(unit2).unwrap().to_string(),
+        unit(unit2)?,
         val3,
-        Unit::from_str(unit3).unwrap().to_string(),
+        unit(unit3)?,
         val4,
-        Unit::from_str(unit4).unwrap().to_string()
+        unit(unit4)?
     );
-    app.clipboard().clear();
-    app.clipboard().write_text(val.to_string()).unwrap();
+    write(&app, val)
 }
 
+// values is the solution of the network as calc_networks returned it. A Q of 0 leaves out the
+// loss resistance of that kind of part.
 #[tauri::command(rename_all = "snake_case")]
-pub fn paste_impedance(app: AppHandle) -> Result<String, String> {
-    let val = app.clipboard().read_text().unwrap();
+pub fn copy_spice_network(
+    app: AppHandle,
+    network: Network,
+    values: Value,
+    freq: f64,
+    f_scale: Unit,
+    q_c: f64,
+    q_l: f64,
+) -> Result<(), Error> {
+    let val = network_netlist(network, &values, freq, f_scale, q_c, q_l, DEFAULT_PORTS)?;
+    write(&app, val)
+}
 
-    Ok(val)
+pub(crate) fn read(app: &AppHandle) -> Result<String, Error> {
+    app.clipboard()
+        .read_text()
+        .map_err(|err| Error::Io(format!("clipboard: {}", err)))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn paste_impedance(app: AppHandle) -> Result<String, Error> {
+    read(&app)
 }
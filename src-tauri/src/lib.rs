@@ -1,150 +1,64 @@
-use crate::conjugate::calc_match;
+use crate::commands::{
+    arc_smith_points, calc_balanced_port, calc_balun, calc_impedance, calc_ladder, calc_match,
+    calc_match_touchstone, calc_mixed_mode, calc_networks, calc_response, calc_ri, calc_richards,
+    calc_smith_arc, calc_smith_cascade, calc_xfmr, change_impedance, design_balun,
+    find_smith_coord_js, fit_rational, get_c64_inv, get_unit_scale, read_part_library,
+};
 use crate::copy::{
     copy_ccll, copy_complex, copy_complex_ri, copy_complex_w_unit, copy_pi_tee, copy_rc,
-    copy_scalar, copy_scalar_w_unit, paste_impedance,
+    copy_scalar, copy_scalar_w_unit, copy_spice_network, paste_impedance,
+};
+use crate::project::{
+    get_window_state, load_project, open_window, recent_projects, save_project, set_window_state,
+    Session,
+};
+use crate::schematic::{
+    copy_spice_schematic, paste_spice_schematic, schematic_edit, schematic_load, schematic_redo,
+    schematic_sync, schematic_undo, Schematics,
 };
-use crate::matching::{calc_networks, change_impedance};
-use crate::rf_utils::{calc_impedance, get_c64_inv};
-use crate::smith::{arc_smith_points, calc_ri, calc_smith_arc, find_smith_coord_js};
-use crate::unit::get_unit_scale;
-use regex::Regex;
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use rf_toolbox_core::project::Calculator;
+use tauri::{AppHandle, Manager, State};
 
-mod conjugate;
+mod commands;
 mod copy;
-mod element;
-mod frequency;
-mod matching;
-mod rf_utils;
-mod smith;
-mod unit;
+mod project;
+mod schematic;
 
 #[tauri::command]
-async fn start_impedance_calculator(app: AppHandle) -> tauri::Result<()> {
-    let re = Regex::new(r"Impedance-Calculator-(?<i>\d+)").unwrap();
-    let mut i: usize = 0;
-
-    for key in app.webview_windows().keys() {
-        match re.captures(key) {
-            Some(val) => {
-                if val["i"].parse::<usize>().unwrap() > i {
-                    i = val["i"].parse::<usize>().unwrap();
-                }
-            }
-            None => (),
-        };
-    }
-
-    i += 1;
-
-    WebviewWindowBuilder::new(
-        &app,
-        format!("Impedance-Calculator-{}", i),
-        WebviewUrl::App("impCalc.html".into()),
-    )
-    .inner_size(500.0, 600.0)
-    .build()?;
-
-    Ok(())
+async fn start_impedance_calculator(
+    app: AppHandle,
+    session: State<'_, Session>,
+) -> tauri::Result<()> {
+    open_window(&app, &session, Calculator::Impedance, None)
 }
 
 #[tauri::command]
-async fn start_matching_calculator(app: AppHandle) -> tauri::Result<()> {
-    let re = Regex::new(r"Matching-Network-Calculator-(?<i>\d+)").unwrap();
-    let mut i: usize = 0;
-
-    for key in app.webview_windows().keys() {
-        match re.captures(key) {
-            Some(val) => {
-                if val["i"].parse::<usize>().unwrap() > i {
-                    i = val["i"].parse::<usize>().unwrap();
-                }
-            }
-            None => (),
-        };
-    }
-
-    i += 1;
-
-    WebviewWindowBuilder::new(
-        &app,
-        format!("Matching-Network-Calculator-{}", i),
-        WebviewUrl::App("matchCalc.html".into()),
-    )
-    .inner_size(1200.0, 1150.0)
-    .build()?;
-
-    Ok(())
+async fn start_matching_calculator(
+    app: AppHandle,
+    session: State<'_, Session>,
+) -> tauri::Result<()> {
+    open_window(&app, &session, Calculator::Matching, None)
 }
 
 #[tauri::command]
-async fn start_conjugate_match_calculator(app: AppHandle) -> tauri::Result<()> {
-    let re = Regex::new(r"Conjugate-Match-Calculator-(?<i>\d+)").unwrap();
-    let mut i: usize = 0;
-
-    for key in app.webview_windows().keys() {
-        match re.captures(key) {
-            Some(val) => {
-                if val["i"].parse::<usize>().unwrap() > i {
-                    i = val["i"].parse::<usize>().unwrap();
-                }
-            }
-            None => (),
-        };
-    }
-
-    i += 1;
-
-    WebviewWindowBuilder::new(
-        &app,
-        format!("Conjugate-Match-Calculator-{}", i),
-        WebviewUrl::App("conjCalc.html".into()),
-    )
-    .inner_size(750.0, 750.0)
-    .build()?;
-
-    Ok(())
+async fn start_conjugate_match_calculator(
+    app: AppHandle,
+    session: State<'_, Session>,
+) -> tauri::Result<()> {
+    open_window(&app, &session, Calculator::Conjugate, None)
 }
 
 #[tauri::command]
-async fn start_smith_chart_tool(app: AppHandle) -> tauri::Result<()> {
-    let re = Regex::new(r"Smith-Chart-Tool-(?<i>\d+)").unwrap();
-    let mut i: usize = 0;
-
-    for key in app.webview_windows().keys() {
-        match re.captures(key) {
-            Some(val) => {
-                if val["i"].parse::<usize>().unwrap() > i {
-                    i = val["i"].parse::<usize>().unwrap();
-                }
-            }
-            None => (),
-        };
-    }
-
-    i += 1;
-
-    WebviewWindowBuilder::new(
-        &app,
-        format!("Smith-Chart-Tool-{}", i),
-        WebviewUrl::App("smithChart.html".into()),
-    )
-    // .setup(|app| {
-    //     let handle = app.handle();
-    //     handle.manage(Mutex::new(SmithState::default()));
-    //     Ok(())
-    // })
-    .inner_size(1800.0, 1600.0)
-    .build()?;
-    // .open_devtools();
-
-    Ok(())
+async fn start_smith_chart_tool(app: AppHandle, session: State<'_, Session>) -> tauri::Result<()> {
+    open_window(&app, &session, Calculator::Smith, None)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(Session::default())
+        .manage(Schematics::default())
         .setup(|app| {
             #[cfg(debug_assertions)]
             app.get_webview_window("main").unwrap().open_devtools();
@@ -156,6 +70,8 @@ pub fn run() {
             calc_impedance,
             start_matching_calculator,
             calc_networks,
+            calc_ladder,
+            calc_xfmr,
             change_impedance,
             copy_complex,
             copy_complex_w_unit,
@@ -164,8 +80,12 @@ pub fn run() {
             copy_scalar,
             copy_scalar_w_unit,
             calc_match,
+            calc_match_touchstone,
+            calc_balanced_port,
+            calc_mixed_mode,
             copy_pi_tee,
             copy_ccll,
+            copy_spice_network,
             start_conjugate_match_calculator,
             paste_impedance,
             start_smith_chart_tool,
@@ -174,7 +94,26 @@ pub fn run() {
             arc_smith_points,
             calc_ri,
             calc_smith_arc,
-            find_smith_coord_js
+            calc_smith_cascade,
+            calc_richards,
+            find_smith_coord_js,
+            calc_response,
+            calc_balun,
+            design_balun,
+            read_part_library,
+            fit_rational,
+            set_window_state,
+            get_window_state,
+            save_project,
+            load_project,
+            recent_projects,
+            schematic_load,
+            schematic_sync,
+            schematic_edit,
+            schematic_undo,
+            schematic_redo,
+            copy_spice_schematic,
+            paste_spice_schematic
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,152 @@
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::project::{
+    self, push_recent, read_recent, write_recent, Calculator, Project, WindowState, MAX_RECENT,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+// Last state reported by each calculator window, keyed by window label
+#[derive(Default)]
+pub struct Session(Mutex<BTreeMap<String, WindowState>>);
+
+impl Session {
+    fn set(&self, label: String, state: Option<WindowState>) {
+        let mut windows = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match state {
+            Some(val) => windows.insert(label, val),
+            None => windows.remove(&label),
+        };
+    }
+
+    fn get(&self, label: &str) -> Option<WindowState> {
+        let windows = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        windows.get(label).cloned()
+    }
+}
+
+// One more than the largest index of the open windows labelled "<prefix>-<index>"
+fn next_window_index(app: &AppHandle, prefix: &str) -> usize {
+    app.webview_windows()
+        .keys()
+        .filter_map(|key| {
+            key.strip_prefix(prefix)?
+                .strip_prefix('-')?
+                .parse::<usize>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+// Spawn a calculator window, starting from a saved state if there is one. Labels are reused
+// once a window closes, so a fresh window must not inherit the state of its predecessor.
+pub fn open_window(
+    app: &AppHandle,
+    session: &Session,
+    calculator: Calculator,
+    state: Option<Value>,
+) -> tauri::Result<()> {
+    let label = format!(
+        "{}-{}",
+        calculator.label_prefix(),
+        next_window_index(app, calculator.label_prefix())
+    );
+    let (width, height) = calculator.size();
+
+    session.set(
+        label.clone(),
+        state.map(|val| WindowState {
+            calculator,
+            state: val,
+        }),
+    );
+
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App(calculator.page().into()))
+        .inner_size(width, height)
+        .build()?;
+
+    Ok(())
+}
+
+fn recent_path(app: &AppHandle) -> Result<PathBuf, Error> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|err| Error::Io(err.to_string()))?;
+    Ok(dir.join("recent.json"))
+}
+
+fn remember(app: &AppHandle, path: &str) -> Result<(), Error> {
+    let recent_path = recent_path(app)?;
+    let mut recent = read_recent(&recent_path)?;
+    push_recent(&mut recent, path, MAX_RECENT);
+    write_recent(&recent_path, &recent)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_window_state(
+    window: WebviewWindow,
+    session: State<'_, Session>,
+    state: Value,
+) -> Result<(), Error> {
+    let calculator = Calculator::from_label(window.label()).ok_or_else(|| {
+        Error::InvalidInput(format!("window {} is not a calculator", window.label()))
+    })?;
+    session.set(
+        window.label().to_string(),
+        Some(WindowState { calculator, state }),
+    );
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_window_state(window: WebviewWindow, session: State<'_, Session>) -> Option<Value> {
+    session.get(window.label()).map(|val| val.state)
+}
+
+// Only windows that are still open go into the project, grouped by calculator and then by index
+#[tauri::command(rename_all = "snake_case")]
+pub async fn save_project(
+    app: AppHandle,
+    session: State<'_, Session>,
+    path: &str,
+) -> Result<(), Error> {
+    let mut labels = app
+        .webview_windows()
+        .into_keys()
+        .filter_map(|label| Some((Calculator::parse_label(&label)?, label)))
+        .collect::<Vec<_>>();
+    labels.sort();
+    let windows = labels
+        .iter()
+        .filter_map(|(_, label)| session.get(label))
+        .collect::<Vec<_>>();
+
+    project::save_project(Path::new(path), &Project::new(windows))?;
+    remember(&app, path)
+}
+
+// Windows of the project open next to the ones already open. Windows are built from an async
+// command, a blocking one deadlocks on Windows.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn load_project(
+    app: AppHandle,
+    session: State<'_, Session>,
+    path: &str,
+) -> Result<(), Error> {
+    let project = project::load_project(Path::new(path))?;
+    for window in project.windows {
+        open_window(&app, &session, window.calculator, Some(window.state))
+            .map_err(|err| Error::Io(err.to_string()))?;
+    }
+    remember(&app, path)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn recent_projects(app: AppHandle) -> Result<Vec<String>, Error> {
+    read_recent(&recent_path(&app)?)
+}
@@ -0,0 +1,106 @@
+use rf_toolbox_core::error::Error;
+use rf_toolbox_core::schematic::{Edit, Schematic, SchematicReturn, HISTORY_DEPTH};
+use rf_toolbox_core::spice::{schematic_import, schematic_netlist, DEFAULT_PORTS};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use tauri::{AppHandle, State, WebviewWindow};
+
+// The schematic of each Smith chart window, keyed by window label. The frontend keeps a copy
+// for drawing and replaces it with what every command returns.
+#[derive(Default)]
+pub struct Schematics(Mutex<HashMap<String, Schematic>>);
+
+impl Schematics {
+    fn with<T>(&self, label: &str, f: impl FnOnce(&mut Schematic) -> T) -> T {
+        let mut schematics = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        f(schematics
+            .entry(label.to_string())
+            .or_insert_with(|| Schematic::new(vec![], HISTORY_DEPTH)))
+    }
+}
+
+// A freshly opened or restored schematic starts without history
+#[tauri::command(rename_all = "snake_case")]
+pub fn schematic_load(
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+    elements: Vec<Value>,
+) -> SchematicReturn {
+    schematics.with(window.label(), |schem| {
+        *schem = Schematic::new(elements, HISTORY_DEPTH);
+        schem.to_return()
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn schematic_sync(
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+    elements: Vec<Value>,
+) -> SchematicReturn {
+    schematics.with(window.label(), |schem| {
+        schem.sync(elements);
+        schem.to_return()
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn schematic_edit(
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+    edit: Edit,
+) -> Result<SchematicReturn, Error> {
+    schematics.with(window.label(), |schem| {
+        schem.edit(edit)?;
+        Ok(schem.to_return())
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn schematic_undo(
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+) -> Result<SchematicReturn, Error> {
+    schematics.with(window.label(), |schem| {
+        schem.undo()?;
+        Ok(schem.to_return())
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn schematic_redo(
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+) -> Result<SchematicReturn, Error> {
+    schematics.with(window.label(), |schem| {
+        schem.redo()?;
+        Ok(schem.to_return())
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn copy_spice_schematic(
+    app: AppHandle,
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+) -> Result<(), Error> {
+    let val = schematics.with(window.label(), |schem| {
+        schematic_netlist(schem.elements(), "smith_chart", DEFAULT_PORTS)
+    })?;
+    crate::copy::write(&app, val)
+}
+
+// Replaces the elements with the subcircuit on the clipboard, as one edit that undo reverts
+#[tauri::command(rename_all = "snake_case")]
+pub fn paste_spice_schematic(
+    app: AppHandle,
+    window: WebviewWindow,
+    schematics: State<'_, Schematics>,
+) -> Result<SchematicReturn, Error> {
+    let netlist = crate::copy::read(&app)?;
+    schematics.with(window.label(), |schem| {
+        schem.edit(schematic_import(schem.elements(), &netlist)?)?;
+        Ok(schem.to_return())
+    })
+}
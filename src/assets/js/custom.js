@@ -2,12 +2,19 @@
 
 export var lastCustomModal = 0;
 
+// Interpolation modes of the table, as stored in the schematic and passed to calc_ri
+const customZInterp = ['linear', 'sah', 'spline', 'rational'];
+// and past either end of it
+const customZExtrap = ['error', 'hold', 'extend'];
+
 export function createCustomZModal(index) {
   // if (verbose >= 5) console.log('createCustomZModal(index: ' + index + ')');
   var modalTitle = document.getElementById('customZModalTitle');
   var modalBody = document.getElementById('customZModalBody');
   modalTitle.innerHTML = 'Impedance Table for element #' + index;
   modalBody.value = schematic[index].raw;
+  document.getElementById('customz_interp_' + (schematic[index].interp ?? 'linear')).checked = true;
+  document.getElementById('customz_extrap_' + (schematic[index].extrap ?? 'error')).checked = true;
   lastCustomModal = index;
   checkCustomZValid();
 }
@@ -61,8 +68,12 @@ export function checkCustomZValid() {
     warn.style.display = 'none';
     schematic[lastCustomModal].lut = customZImpedanceTable;
     schematic[lastCustomModal].raw = textbox.value;
-    if (document.getElementById('customz_interp_sah').checked) schematic[lastCustomModal].interp = 'sah';
-    else schematic[lastCustomModal].interp = 'linear';
+    schematic[lastCustomModal].interp = customZInterp.find(
+      (interp) => document.getElementById('customz_interp_' + interp).checked,
+    );
+    schematic[lastCustomModal].extrap = customZExtrap.find(
+      (extrap) => document.getElementById('customz_extrap_' + extrap).checked,
+    );
     plotCustomZ();
     // console.log('Pass',splitLines);
   } else {
@@ -76,8 +87,19 @@ export function checkCustomZValid() {
 
 export function removeCustom() {
   // if (verbose >= 5) console.log('removeCustom(' + ')');
-  schematic.splice(lastCustomModal, 1);
-  update_smith_chart();
+  edit_schematic('schematic_edit', { edit: { op: 'remove', index: lastCustomModal } });
+}
+
+// The modal edits the table in place while it is checked, save it as one undoable edit
+export function saveCustom() {
+  // if (verbose >= 5) console.log('saveCustom(' + ')');
+  var edits = ['lut', 'raw', 'interp', 'extrap'].map((field) => ({
+    op: 'set',
+    index: lastCustomModal,
+    field: field,
+    value: schematic[lastCustomModal][field],
+  }));
+  edit_schematic('schematic_edit', { edit: { op: 'batch', edits: edits } });
 }
 
 export function plotCustomZ() {
@@ -99,6 +121,7 @@ export function plotCustomZ() {
     mode: 'lines+markers',
   };
   if (document.getElementById('customz_interp_sah').checked) trace.line = { shape: 'hv' };
+  else if (document.getElementById('customz_interp_spline').checked) trace.line = { shape: 'spline' };
   var data = [trace];
   var layout = {
     title: 'mag(Impedance) vs Frequency',
@@ -108,3 +131,5 @@ export function plotCustomZ() {
 
 window.createCustomZModal = createCustomZModal;
 window.checkCustomZValid = checkCustomZValid;
+window.removeCustom = removeCustom;
+window.saveCustom = saveCustom;
@@ -1,5 +1,5 @@
 import { color_of_smith_curves, schematic, precision, verbose, colors } from './defaults.js';
-import { update_smith_chart } from './smith_tool.js';
+import { update_smith_chart, part_libraries } from './smith_tool.js';
 
 export var show_labels_DP = true;
 export var show_labels_adm = true;
@@ -43,21 +43,31 @@ export function toggle_circles_res() {
   update_smith_chart();
 }
 
+function is_library(element) {
+  return (element.part?.path ?? '').toLowerCase().endsWith('.json');
+}
+
 export function draw_schematic(i) {
   if (verbose >= 5) console.log('draw_schematic(i: ' + i + ')');
   //Add the element to the schematic view
   var div = document.createElement('div');
   let unit = [];
   div.setAttribute('class', 'col-6 col-lg-2 g-0');
-  //Add a close button, but can't remove black boxes...
+  //Add move, flip and close buttons, but can't touch black boxes...
   var innerText = '';
+  var edit = (op) => 'edit_schematic(\'schematic_edit\', { edit: ' + JSON.stringify(op).replaceAll('"', '&quot;') + ' })';
+  var canFlip = ['si', 'pi', 'sc', 'pc', 'sr', 'pr', 'srlc', 'prlc'].includes(schematic[i].type);
   if (schematic[i].type != 'bb')
     innerText +=
       '<div class="row me-2 ms-2" style="height: 26px;"><div class="col"><small>DP' +
       i +
-      '</small></div><div class="col text-end"><button type="button" class="btn-close" onclick="schematic.splice(' +
-      i +
-      ',1); update_smith_chart()"></button></div></div>';
+      '</small></div><div class="col text-end text-nowrap">' +
+      (i > 2 ? '<button type="button" class="btn btn-sm p-0 px-1" title="Move left" onclick="' + edit({ op: 'move', from: i, to: i - 1 }) + '">&lsaquo;</button>' : '') +
+      (i < schematic.length - 1 ? '<button type="button" class="btn btn-sm p-0 px-1" title="Move right" onclick="' + edit({ op: 'move', from: i, to: i + 1 }) + '">&rsaquo;</button>' : '') +
+      (canFlip ? '<button type="button" class="btn btn-sm p-0 px-1" title="Series / shunt" onclick="' + edit({ op: 'flip', index: i }) + '">&#8645;</button>' : '') +
+      '<button type="button" class="btn-close" onclick="' +
+      edit({ op: 'remove', index: i }) +
+      '"></button></div></div>';
   else innerText += '<div class="row me-2 ms-2" style="height: 26px;"><small>DP' + i + '</small></div>';
   var rows_to_create = [];
   let sch_label, sch_imag, sch_real, sch_abs, sch_icon, sch_svg;
@@ -101,7 +111,7 @@ export function draw_schematic(i) {
       sch_svg = 3000;
       break;
     case 'pc':
-      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['tol']];
+      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['label_ESL', 'abs', 'unit_2'], ['label_f<sub>Q</sub>', 'abs', 'unit_3'], ['tol']];
       sch_label = 'Parallel Capacitor';
       sch_imag = true;
       sch_real = true;
@@ -109,12 +119,14 @@ export function draw_schematic(i) {
       unit = [
         ['Q', 'mΩ', 'Ω'],
         ['mF', 'uF', 'nF', 'pF', 'fF'],
+        ['pH', 'nH', 'uH'],
+        ['GHz', 'MHz', 'kHz', 'Hz'],
       ];
       sch_icon = 'capacitor_parallel';
       sch_svg = 500;
       break;
     case 'sc':
-      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['tol']];
+      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['label_ESL', 'abs', 'unit_2'], ['label_f<sub>Q</sub>', 'abs', 'unit_3'], ['tol']];
       sch_label = 'Series Capacitor';
       sch_imag = true;
       sch_real = true;
@@ -122,12 +134,14 @@ export function draw_schematic(i) {
       unit = [
         ['Q', 'mΩ', 'Ω'],
         ['mF', 'uF', 'nF', 'pF', 'fF'],
+        ['pH', 'nH', 'uH'],
+        ['GHz', 'MHz', 'kHz', 'Hz'],
       ];
       sch_icon = 'capacitor_series';
       sch_svg = 1000;
       break;
     case 'pi':
-      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['tol']];
+      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['label_C<sub>w</sub>', 'abs', 'unit_2'], ['label_f<sub>Q</sub>', 'abs', 'unit_3'], ['tol']];
       sch_label = 'Parallel Inductor';
       sch_imag = true;
       sch_real = true;
@@ -135,12 +149,14 @@ export function draw_schematic(i) {
       unit = [
         ['Q', 'mΩ', 'Ω'],
         ['H', 'mH', 'uH', 'nH', 'pH'],
+        ['fF', 'pF', 'nF'],
+        ['GHz', 'MHz', 'kHz', 'Hz'],
       ];
       sch_icon = 'inductor_parallel';
       sch_svg = 1500;
       break;
     case 'si':
-      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['tol']];
+      rows_to_create = [['Impedance'], ['abs', 'unit_0'], ['abs', 'unit_1'], ['label_C<sub>w</sub>', 'abs', 'unit_2'], ['label_f<sub>Q</sub>', 'abs', 'unit_3'], ['tol']];
       sch_label = 'Series Inductor';
       sch_imag = true;
       sch_real = true;
@@ -148,6 +164,8 @@ export function draw_schematic(i) {
       unit = [
         ['Q', 'mΩ', 'Ω'],
         ['H', 'mH', 'uH', 'nH', 'pH'],
+        ['fF', 'pF', 'nF'],
+        ['GHz', 'MHz', 'kHz', 'Hz'],
       ];
       sch_icon = 'inductor_series';
       sch_svg = 2000;
@@ -225,6 +243,27 @@ export function draw_schematic(i) {
       sch_icon = 'black_box';
       sch_svg = 7000;
       break;
+    case 'ss2p':
+      // A library picks the part from its list of values, a single Touchstone file takes any value
+      rows_to_create = [['Impedance'], ['part', 'extrapolation'], is_library(schematic[i]) ? ['part_value'] : ['abs', 'unit_0', 'fixture']];
+      sch_label = 'Series Measured Part';
+      sch_imag = true;
+      sch_real = true;
+      sch_abs = true;
+      unit = [['fF', 'pF', 'nF', 'uF', 'pH', 'nH', 'uH']];
+      sch_icon = 'black_box';
+      sch_svg = 7000;
+      break;
+    case 'ps2p':
+      rows_to_create = [['Impedance'], ['part', 'extrapolation'], is_library(schematic[i]) ? ['part_value'] : ['abs', 'unit_0', 'fixture']];
+      sch_label = 'Shunt Measured Part';
+      sch_imag = true;
+      sch_real = true;
+      sch_abs = true;
+      unit = [['fF', 'pF', 'nF', 'uF', 'pH', 'nH', 'uH']];
+      sch_icon = 'black_box';
+      sch_svg = 6000;
+      break;
   }
   // add svg image of element
   if (schematic[i].type == 'customZ' || schematic[i].type == 'bb' || schematic[i].type == 'tl') {
@@ -261,6 +300,8 @@ export function draw_schematic(i) {
           i +
           ',this)">';
         innerText += '<span class="input-group-text">%</span>';
+      } else if (boxType.startsWith('label_')) {
+        innerText += '<span class="input-group-text">' + boxType.slice(6) + '</span>';
       } else if (boxType == 'blank-impedance') {
         innerText += '<div class="fst-italic m-auto">&nbsp</div>';
       } else if (boxType == 'Impedance') {
@@ -279,6 +320,45 @@ export function draw_schematic(i) {
           '_btn" data-bs-target="#customZModal" onclick="createCustomZModal(' +
           i +
           ')">Impedance Table</button>';
+      } else if (boxType == 'part') {
+        innerText += '<span class="input-group-text">File</span>';
+        innerText +=
+          '<input type="text" class="form-control" id="sch_' +
+          i +
+          '_part" placeholder="parts.json or part.s2p" value="' +
+          (schematic[i].part?.path ?? '').replaceAll('"', '&quot;') +
+          '" name="part" onchange="update_schem_part(' +
+          i +
+          ',this)">';
+      } else if (boxType == 'fixture') {
+        innerText += '<select class="form-select" id="sch_' + i + '_fixture" name="fixture" title="Fixture" onchange="update_schem_part(' + i + ',this)">';
+        for (let fixture of ['series', 'shunt']) {
+          varSelect = (schematic[i].part?.fixture ?? 'series') == fixture ? 'selected' : '';
+          innerText += '<option value=' + fixture + ' ' + varSelect + '>' + fixture + '</option>';
+        }
+        innerText += '</select>';
+      } else if (boxType == 'extrapolation') {
+        // Outside the measured band the part is an error unless it holds or extends the data
+        innerText += '<select class="form-select" id="sch_' + i + '_extrapolation" name="extrapolation" title="Outside the measured band" onchange="update_schem_part(' + i + ',this)">';
+        for (let extrapolation of ['error', 'hold', 'extend']) {
+          varSelect = (schematic[i].part?.extrapolation ?? 'error') == extrapolation ? 'selected' : '';
+          innerText += '<option value=' + extrapolation + ' ' + varSelect + '>' + extrapolation + '</option>';
+        }
+        innerText += '</select>';
+      } else if (boxType == 'part_value') {
+        let library = part_libraries[schematic[i].part.path];
+        if (library == undefined || library.error != undefined) {
+          innerText += '<div class="text-danger small m-auto">' + (library?.error ?? 'Library not read') + '</div>';
+        } else {
+          innerText += '<select class="form-select" id="sch_' + i + '_part_val" onchange="update_schem_part_value(' + i + ',this)">';
+          let picked = library.parts.findIndex((part) => part.value == schematic[i].abs[0] && library.unit == schematic[i].unit[0]);
+          if (picked < 0) innerText += '<option selected disabled>Pick a part</option>';
+          library.parts.forEach((part, idx) => {
+            varSelect = idx == picked ? 'selected' : '';
+            innerText += '<option value=' + idx + ' ' + varSelect + '>' + part.value + ' ' + library.unit + '</option>';
+          });
+          innerText += '</select>';
+        }
       } else if (boxType == 'line_zo') {
         innerText += '<span class="input-group-text">Z₀ = </span>';
         innerText +=
@@ -302,12 +382,14 @@ export function draw_schematic(i) {
         }
         innerText += '</select>';
       } else {
-        if (cntC > 0) innerText += '<span class="input-group-text">+</span>';
+        // The second box of a black box row is its reactance
+        let imag = rows_to_create[cntR][cntC - 1] == 'abs';
+        if (imag) innerText += '<span class="input-group-text">+</span>';
         innerText +=
           '<input type="text" class="form-control inputMW" id="sch_' +
           i +
           '_val" value=' +
-          schematic[i][boxType][absCounter] +
+          (schematic[i][boxType][absCounter] ?? 0) +
           ' name="' +
           boxType +
           '" onchange="update_schem_abs(' +
@@ -315,7 +397,7 @@ export function draw_schematic(i) {
           ',this,' +
           absCounter +
           ')">';
-        if (cntC > 0) innerText += '<span class="input-group-text ps-2 pe-2">j</span>';
+        if (imag) innerText += '<span class="input-group-text ps-2 pe-2">j</span>';
         if (boxType == 'abs') absCounter = absCounter + 1;
       }
     }
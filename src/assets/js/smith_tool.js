@@ -19,36 +19,90 @@ import {
 } from './defaults.js';
 import { layout, configure_layout_shapes, draw_schematic, show_labels_DP, show_labels_res, show_labels_adm } from './draw.js';
 import { customMarkers, drawMakerTable } from './marker.js';
-import { one_over_complex, expo, unitTextToNum, domFreqSel } from './util.js';
+import { one_over_complex, expo, domFreqSel } from './util.js';
+import { syncWindowState } from '/util.js';
+
+// The schematic and its undo history live on the Rust side. Every edit goes through one of the
+// schematic_* commands and the copy here is replaced with the schematic they return. A failed
+// command leaves the schematic as it was and its message is returned.
+export async function edit_schematic(cmd, args = {}) {
+  if (verbose >= 5) console.log('edit_schematic(cmd: ' + cmd + ', args: ', args, ')');
+  let error = null;
+  try {
+    let result = await invoke(cmd, args);
+    schematic.splice(0, schematic.length, ...result.elements);
+    let undoEl = document.getElementById('undo');
+    let redoEl = document.getElementById('redo');
+    if (undoEl != null) undoEl.disabled = !result.can_undo;
+    if (redoEl != null) redoEl.disabled = !result.can_redo;
+  } catch (err) {
+    error = err.message ?? err;
+    console.log('ERROR: ' + error);
+  }
+  update_smith_chart();
+  return error;
+}
+
+// Units of the ESL or winding capacitance and of the Q frequency that follow the loss and value of
+// capacitors and inductors
+const PARASITIC_UNITS = { sc: ['pH', 'GHz'], pc: ['pH', 'GHz'], si: ['fF', 'GHz'], pi: ['fF', 'GHz'] };
+
+// Capacitors and inductors saved before they had parasitics only have a loss and a value. Their
+// values and units with the rest filled in, so both stay the same length.
+export function with_parasitics(index) {
+  let element = schematic[index];
+  let defaults = PARASITIC_UNITS[element.type];
+  if (defaults == undefined) return { abs: element.abs, unit: element.unit };
+  return {
+    abs: [0, 0, 0, 0].map((val, idx) => element.abs[idx] ?? val),
+    unit: [element.unit[0], element.unit[1], ...defaults].map((val, idx) => element.unit[idx] ?? val),
+  };
+}
+
+// Set a field of an element as one undoable edit, batched with filling in the parasitics of an
+// element saved without them
+export function set_element_field(index, field, value) {
+  let filled = with_parasitics(index);
+  let edits = ['abs', 'unit']
+    .filter((name) => name != field && filled[name] != undefined && filled[name].length != schematic[index][name].length)
+    .map((name) => ({ op: 'set', index: index, field: name, value: filled[name] }));
+  let edit = { op: 'set', index: index, field: field, value: value };
+  if (edits.length > 0) edit = { op: 'batch', edits: [...edits, edit] };
+  return edit_schematic('schematic_edit', { edit: edit });
+}
 
 export function clicked_cell(type) {
   if (verbose >= 5) console.log('function_clicked_cell(type: ' + type + ')');
+  let element;
   if (type == 'pr') {
-    schematic.push({ type: 'pr', real: 0, imaginary: 0, abs: [50], unit: ['Ω'], tol: 0 });
+    element = { type: 'pr', real: 0, imaginary: 0, abs: [50], unit: ['Ω'], tol: 0 };
   } else if (type == 'sr') {
-    schematic.push({ type: 'sr', real: 0, imaginary: 0, abs: [50], unit: ['Ω'], tol: 0 });
+    element = { type: 'sr', real: 0, imaginary: 0, abs: [50], unit: ['Ω'], tol: 0 };
   } else if (type == 'si') {
-    schematic.push({ type: 'si', real: 0, imaginary: 0, abs: [20, 10], unit: ['Q', 'pH'], tol: 0 });
+    element = { type: 'si', real: 0, imaginary: 0, abs: [20, 10, 0, 0], unit: ['Q', 'pH', ...PARASITIC_UNITS.si], tol: 0 };
   } else if (type == 'pi') {
-    schematic.push({ type: 'pi', real: 0, imaginary: 0, abs: [20, 10], unit: ['Q', 'pH'], tol: 0 });
+    element = { type: 'pi', real: 0, imaginary: 0, abs: [20, 10, 0, 0], unit: ['Q', 'pH', ...PARASITIC_UNITS.pi], tol: 0 };
   } else if (type == 'sc') {
-    schematic.push({ type: 'sc', real: 0, imaginary: 0, abs: [0, 20], unit: ['Q', 'fF'], tol: 0 });
+    element = { type: 'sc', real: 0, imaginary: 0, abs: [0, 20, 0, 0], unit: ['Q', 'fF', ...PARASITIC_UNITS.sc], tol: 0 };
   } else if (type == 'pc') {
-    schematic.push({ type: 'pc', real: 0, imaginary: 0, abs: [0, 20], unit: ['Q', 'fF'], tol: 0 });
+    element = { type: 'pc', real: 0, imaginary: 0, abs: [0, 20, 0, 0], unit: ['Q', 'fF', ...PARASITIC_UNITS.pc], tol: 0 };
   } else if (type == 'tl') {
-    schematic.push({ type: 'tl', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 });
+    element = { type: 'tl', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 };
   } else if (type == 'ss') {
-    schematic.push({ type: 'ss', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 });
+    element = { type: 'ss', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 };
   } else if (type == 'so') {
-    schematic.push({ type: 'so', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 });
+    element = { type: 'so', line_length: 1e-6, abs: [1], line_zo: 50, unit: ['um'], real: 0, imaginary: 0, tol: 0 };
   } else if (type == 'xfmr') {
-    schematic.push({ type: 'xfmr', real: 0, imaginary: 0, abs: [20, 10, 10, 0.4], unit: ['Q', 'pH', 'pH', 'K'], tol: 0 });
+    element = { type: 'xfmr', real: 0, imaginary: 0, abs: [20, 10, 10, 0.4], unit: ['Q', 'pH', 'pH', 'K'], tol: 0 };
   } else if (type == 'prlc') {
-    schematic.push({ type: 'prlc', real: 0, imaginary: 0, abs: [1, 10, 20], unit: ['Ω', 'pH', 'fF'], tol: 0 });
+    element = { type: 'prlc', real: 0, imaginary: 0, abs: [1, 10, 20], unit: ['Ω', 'pH', 'fF'], tol: 0 };
   } else if (type == 'srlc') {
-    schematic.push({ type: 'srlc', real: 0, imaginary: 0, abs: [1, 10, 20], unit: ['Ω', 'pH', 'fF'], tol: 0 });
+    element = { type: 'srlc', real: 0, imaginary: 0, abs: [1, 10, 20], unit: ['Ω', 'pH', 'fF'], tol: 0 };
+  } else if (type == 'ss2p' || type == 'ps2p') {
+    // Measured parts have no tolerance, their value picks the part from the library
+    element = { type: type, real: 0, imaginary: 0, abs: [1], unit: ['pF'], part: { path: '', fixture: null }, tol: 0 };
   } else if (type == 'customZ') {
-    schematic.push({
+    element = {
       type: 'customZ',
       real: 0,
       imaginary: 0,
@@ -56,28 +110,31 @@ export function clicked_cell(type) {
       unit: ['Ω', 'nH', 'pF'],
       lut: [[2440e6, 50, 50]],
       interp: 'linear',
+      extrap: 'error',
       raw: '2440e6,50,50',
       tol: 0,
-    });
+    };
   }
-  update_smith_chart();
+  edit_schematic('schematic_edit', { edit: { op: 'add', index: schematic.length, element: element } });
 }
 
-export function update_schem_abs(target_num, obj, absCounter) {
+export async function update_schem_abs(target_num, obj, absCounter) {
   if (verbose >= 5) console.log('update_schem_abs(target_num: ' + target_num + ', obj: ', obj, ', absCounter: ' + absCounter + ')');
   var complex = obj.name;
+  var field = 'abs';
+  var value = [...with_parasitics(target_num).abs];
   // console.log('dbg0',target_num, obj.value, complex)
   switch (schematic[target_num].type) {
     case 'bb':
       // console.log('dbg1',target_num, obj.value, complex)
-      if (complex == 'abs') schematic[target_num].abs[absCounter] = Number(obj.value);
-      else schematic[target_num].abs_bb_i = Number(obj.value);
+      if (complex == 'abs') value[absCounter] = Number(obj.value);
+      else [field, value] = ['abs_bb_i', Number(obj.value)];
       break;
     case 'tl':
     case 'ss':
     case 'so':
-      if (complex == 'abs') schematic[target_num].abs[absCounter] = Number(obj.value);
-      else if (complex == 'line_zo') schematic[target_num].line_zo = Number(obj.value);
+      if (complex == 'abs') value[absCounter] = Number(obj.value);
+      else if (complex == 'line_zo') [field, value] = ['line_zo', Number(obj.value)];
       break;
     case 'rc':
     case 'rl':
@@ -91,10 +148,49 @@ export function update_schem_abs(target_num, obj, absCounter) {
     case 'pi':
     case 'si':
     case 'xfmr':
-      schematic[target_num].abs[absCounter] = Number(obj.value);
+      value[absCounter] = Number(obj.value);
       break;
   }
-  update_smith_chart();
+  set_element_field(target_num, field, value);
+}
+
+// Libraries of the measured parts in the schematic by path, read again on every update so the
+// value lists follow the files. Rust keeps each file until it changes, so this is cheap.
+export const part_libraries = {};
+
+async function load_part_libraries() {
+  for (let path in part_libraries) delete part_libraries[path];
+  for (let element of schematic) {
+    let path = element.part?.path ?? '';
+    if (!path.toLowerCase().endsWith('.json') || path in part_libraries) continue;
+    try {
+      part_libraries[path] = await invoke('read_part_library', { path: path });
+    } catch (err) {
+      part_libraries[path] = { error: err.message ?? err };
+    }
+  }
+}
+
+// The part file of a measured part, a library index or a single Touchstone file, its fixture and
+// what it does outside the measured band
+export function update_schem_part(index, obj) {
+  if (verbose >= 5) console.log('update_schem_part(index: ' + index + ', obj: ', obj, ')');
+  let part = { path: '', fixture: null, ...schematic[index].part };
+  if (obj.name == 'fixture') part.fixture = obj.value;
+  else if (obj.name == 'extrapolation') part.extrapolation = obj.value;
+  else part.path = obj.value.trim();
+  edit_schematic('schematic_edit', { edit: { op: 'set', index: index, field: 'part', value: part } });
+}
+
+// A part picked from the library sets the value and its unit as one edit
+export function update_schem_part_value(index, obj) {
+  if (verbose >= 5) console.log('update_schem_part_value(index: ' + index + ', obj: ', obj, ')');
+  let library = part_libraries[schematic[index].part.path];
+  let edits = [
+    { op: 'set', index: index, field: 'abs', value: [library.parts[obj.value].value] },
+    { op: 'set', index: index, field: 'unit', value: [library.unit] },
+  ];
+  edit_schematic('schematic_edit', { edit: { op: 'batch', edits: edits } });
 }
 
 export async function update_schem_component(freq_here, save_impedance, sch_index) {
@@ -103,11 +199,6 @@ export async function update_schem_component(freq_here, save_impedance, sch_inde
   var re_here = 0;
   var im_here = 0;
   var ln_here = 0;
-  var scaler = [];
-  var i = 0;
-  for (i = 0; i < schematic[sch_index].unit.length; i++) {
-    scaler[i] = unitTextToNum(schematic[sch_index].unit[i], freq_here);
-  }
 
   let temp_diff = false;
   if (schematic[0].imp == 'diff') {
@@ -118,81 +209,46 @@ export async function update_schem_component(freq_here, save_impedance, sch_inde
     lut = schematic[sch_index].lut;
   }
 
-  switch (schematic[sch_index].type) {
-    case 'xfmr':
-      let l1_tee, l2_tee, m_tee;
-      let r1 = schematic[sch_index].abs[0] * scaler[0];
-      let r2 = schematic[sch_index].abs[0] * scaler[0];
-      let l1 = schematic[sch_index].abs[1] * scaler[1];
-      let l2 = schematic[sch_index].abs[2] * scaler[2];
-      let m = schematic[sch_index].abs[3] * scaler[3];
-      if (schematic[sch_index].unit[2] == 'N') {
-        l2 = l2 * l2 * l1;
-      }
-      if (schematic[sch_index].unit[3] == 'K') {
-        m_tee = m * Math.sqrt(l1 * l2);
-        l1_tee = l1 - m_tee;
-        l2_tee = l2 - m_tee;
-      }
-      if (schematic[sch_index].unit[0] == 'Q') {
-        r1 = (2.0 * Math.PI * freq_here * l1) / r1;
-        r2 = (2.0 * Math.PI * freq_here * l2) / r2;
+  await invoke('calc_ri', {
+    vals: schematic[sch_index].abs,
+    units: schematic[sch_index].unit,
+    lut: lut,
+    interp: schematic[sch_index].interp ?? null,
+    extrap: schematic[sch_index].extrap ?? null,
+    part: schematic[sch_index].part ?? null,
+    type: schematic[sch_index].type,
+    freq: freq_here,
+    z0: z0,
+    diff: temp_diff,
+    verbose: false,
+  })
+    .then((result) => {
+      re_here = result[0];
+      im_here = result[1];
+      ln_here = result[2];
+
+      if (save_impedance) {
+        var re_out = re_here;
+        var im_out = im_here;
+
+        if (Math.abs(re_out) < 0.1 && re_out != 0) {
+          schematic[sch_index].real = expo(re_out, 2);
+        } else {
+          schematic[sch_index].real = Number(re_out).toFixed(precision);
+        }
+
+        if (Math.abs(im_out) < 0.1 && im_out != 0) {
+          schematic[sch_index].imaginary = expo(im_out, 2);
+        } else {
+          schematic[sch_index].imaginary = Number(im_out).toFixed(precision);
+        }
+
+        schematic[sch_index].line_length = ln_here;
       }
-      let z1 = one_over_complex(r1, 2.0 * Math.PI * freq_here * l1_tee);
-      let z2 = one_over_complex(0.0, 2.0 * Math.PI * freq_here * m_tee);
-      let z3 = one_over_complex(z1[0] + z2[0], z1[1] + z2[1]);
-      re_here = (z3[0] + r1) / z0;
-      im_here = (z3[1] + 2.0 * Math.PI * freq_here * l2_tee) / z0;
-      break;
-    default:
-      await invoke('calc_ri', {
-        vals: schematic[sch_index].abs,
-        units: schematic[sch_index].unit,
-        lut: lut,
-        type: schematic[sch_index].type,
-        freq: freq_here,
-        z0: z0,
-        diff: temp_diff,
-        verbose: false,
-      })
-        .then((result) => {
-          re_here = result[0];
-          im_here = result[1];
-          ln_here = result[2];
-
-          if (save_impedance) {
-            var re_out, im_out;
-
-            if (schematic[sch_index].type == 'xfmr') {
-              var out = one_over_complex(re_here[0], im_here[0]);
-              var m = one_over_complex(re_here[1], im_here[1]);
-              out = one_over_complex(out[0] + m[0], out[1] + m[1]);
-              re_out = out[0];
-              im_out = out[1];
-            } else {
-              re_out = re_here;
-              im_out = im_here;
-            }
-
-            if (Math.abs(re_out) < 0.1 && re_out != 0) {
-              schematic[sch_index].real = expo(re_out, 2);
-            } else {
-              schematic[sch_index].real = Number(re_out).toFixed(precision);
-            }
-
-            if (Math.abs(im_out) < 0.1 && im_out != 0) {
-              schematic[sch_index].imaginary = expo(im_out, 2);
-            } else {
-              schematic[sch_index].imaginary = Number(im_out).toFixed(precision);
-            }
-
-            schematic[sch_index].line_length = ln_here;
-          }
-        })
-        .catch((error) => {
-          console.log('ERROR (smith_tool.js (update_schem_component): ' + error);
-        });
-  }
+    })
+    .catch((error) => {
+      console.log('ERROR (smith_tool.js (update_schem_component): ' + error);
+    });
   return [re_here, im_here, ln_here];
 }
 
@@ -330,16 +386,9 @@ async function draw_smith_curves(
           temp_array = await update_schem_component(frequency_at_sp, false, i);
         }
 
-        var re, im, ln_length;
-        if (schematic[i].type == 'xfmr') {
-          re = temp_array[0];
-          im = temp_array[1];
-          ln_length = temp_array[2];
-        } else {
-          re = Number(temp_array[0]);
-          im = Number(temp_array[1]);
-          ln_length = Number(temp_array[2]);
-        }
+        var re = Number(temp_array[0]);
+        var im = Number(temp_array[1]);
+        var ln_length = Number(temp_array[2]);
 
         var temp_trace = {};
         var x_points, y_points;
@@ -476,6 +525,7 @@ async function draw_smith_curves(
             element: schematic[i].type,
             vals: schematic[i].abs,
             units: schematic[i].unit,
+            part: schematic[i].part ?? null,
             rin: start_impedance[0],
             xin: start_impedance[1],
             z0: schematic[0].z0,
@@ -510,40 +560,6 @@ async function draw_smith_curves(
           start_impedance[1] = span_impedance_im[sp];
           let end = [re + start_impedance[0], im + start_impedance[1]];
 
-          if (schematic[i].type == 'xfmr') {
-            let l1_tee, l2_tee, m_tee;
-            let r1 = schematic[i].abs[0] * unitTextToNum(schematic[i].unit[0], frequency_at_sp);
-            let r2 = schematic[i].abs[0] * unitTextToNum(schematic[i].unit[0], frequency_at_sp);
-            let l1 = schematic[i].abs[1] * unitTextToNum(schematic[i].unit[1], frequency_at_sp);
-            let l2 = schematic[i].abs[2] * unitTextToNum(schematic[i].unit[2], frequency_at_sp);
-            let m = schematic[i].abs[3] * unitTextToNum(schematic[i].unit[3], frequency_at_sp);
-            if (schematic[i].unit[2] == 'N') {
-              l2 = l2 * l2 * l1;
-            }
-            if (schematic[i].unit[3] == 'K') {
-              m_tee = m * Math.sqrt(l1 * l2);
-              l1_tee = l1 - m_tee;
-              l2_tee = l2 - m_tee;
-            } else {
-              m_tee = m;
-              l1_tee = l1_tee;
-              l2_tee = l2_tee;
-            }
-            if (schematic[i].unit[0] == 'Q') {
-              r1 = (2.0 * Math.PI * frequency_at_sp * l1) / r1;
-              r2 = (2.0 * Math.PI * frequency_at_sp * l2) / r2;
-            }
-            r1 = r1 / z0;
-            r2 = r2 / z0;
-            let xl1 = (2.0 * Math.PI * frequency_at_sp * l1_tee) / z0;
-            let xl2 = (2.0 * Math.PI * frequency_at_sp * l2_tee) / z0;
-            let xls = (2.0 * Math.PI * frequency_at_sp * m_tee) / z0;
-            let z1 = one_over_complex(start_impedance[0] + r1, start_impedance[1] + xl1);
-            let z2 = one_over_complex(0.0, xls);
-            let z2y = one_over_complex(z1[0] + z2[0], z1[1] + z2[1]);
-            end = [r2 + z2y[0], xl2 + z2y[1]];
-          }
-
           // await invoke('arc_smith_points', {
           //   x1: parseFloat(start_impedance[0]),
           //   y1: parseFloat(start_impedance[1]),
@@ -583,6 +599,7 @@ async function draw_smith_curves(
             element: schematic[i].type,
             vals: schematic[i].abs,
             units: schematic[i].unit,
+            part: schematic[i].part ?? null,
             rin: start_impedance[0],
             xin: start_impedance[1],
             z0: schematic[0].z0,
@@ -606,6 +623,8 @@ async function draw_smith_curves(
                 result[3][0],
                 result[3][1],
               ];
+              // The transformer is a two-port, so take its end point from the Rust cascade
+              if (schematic[i].type == 'xfmr') end = [result[3][0], result[3][1]];
               span_impedance_re[sp] = end[0];
               span_impedance_im[sp] = end[1];
             })
@@ -1379,6 +1398,8 @@ export async function update_smith_chart() {
   var y;
   var x0, x1, y0, y1;
 
+  await load_part_libraries();
+
   //update black box
   await update_schem_component(0, true, 1);
   var schemEl = document.getElementById('schematic');
@@ -1497,12 +1518,13 @@ export async function update_smith_chart() {
     span_impedance_im,
     span_res
   );
+
+  syncWindowState({ schematic: schematic });
 }
 
 export function update_schem_tol(i, tol) {
   if (verbose >= 5) console.log('update_schem_tol(i: ' + i + ', tol: ' + tol + ')');
-  schematic[i].tol = Math.abs(tol.value);
-  update_smith_chart();
+  edit_schematic('schematic_edit', { edit: { op: 'set', index: i, field: 'tol', value: Math.abs(tol.value) } });
 }
 
 export var trace_im_neg,
@@ -1663,6 +1685,9 @@ export function define_labels() {
 update_smith_chart();
 drawMakerTable();
 
+window.edit_schematic = edit_schematic;
 window.update_schem_abs = update_schem_abs;
+window.update_schem_part = update_schem_part;
+window.update_schem_part_value = update_schem_part_value;
 window.update_schem_tol = update_schem_tol;
 window.update_smith_chart = update_smith_chart;
@@ -1,6 +1,6 @@
 import { resizedw } from './draw.js';
 import { schematic, verbose } from './defaults.js';
-import { update_schem_component, update_smith_chart } from './smith_tool.js';
+import { edit_schematic, update_schem_component, set_element_field, with_parasitics } from './smith_tool.js';
 
 //code to save the state to jsonBin - cool! (and free)
 export var toastElList = [].slice.call(document.querySelectorAll('.toast'));
@@ -150,10 +150,11 @@ export function updateFromDom() {
   else if (domSpanSel.value == 'GHz') schematic[0]['span_unit'].multiplier = 1e9;
   else if (domSpanSel.value == 'THz') schematic[0]['span_unit'].multiplier = 1e12;
 
-  update_smith_chart();
+  // Source settings aren't schematic edits, hand them over without a history entry
+  edit_schematic('schematic_sync', { elements: schematic });
 }
 
-export function updatespan(sch_num, obj, unitIndex = 0) {
+export async function updatespan(sch_num, obj, unitIndex = 0) {
   if (verbose >= 5) console.log('updatespan(sch_num: ' + sch_num + ', obj: ', obj, ', unitIndex: ' + unitIndex + ')');
   // if ((this_val[this_val.length-2]+this_val[this_val.length-1])=='Hz') {
   // 	if      (this_val == 'Hz') freq_multiplier = 1;
@@ -166,13 +167,14 @@ export function updatespan(sch_num, obj, unitIndex = 0) {
   // } else {
   //     var sch_num = this_id.split('_')[1];
 
-  schematic[sch_num].unit[unitIndex] = obj.value;
+  let unit = [...with_parasitics(sch_num).unit];
+  unit[unitIndex] = obj.value;
 
   //     is_active[sch_num]="active";
   // }
   // document.getElementById(this_id).children[0].innerText=this_val;
 
-  update_smith_chart();
+  set_element_field(sch_num, 'unit', unit);
 
   // is_active=[];
 }
@@ -1,8 +1,16 @@
 const { invoke } = window.__TAURI__.core;
-import {print_val, print_cval, complexCopy, rcCopy, pasteImpedance} from "/util.js";
+import {print_val, print_cval, complexCopy, rcCopy, pasteImpedance, restoreWindowState, syncWindowState, trackWindowState} from "/util.js";
+
+// A loaded Touchstone file replaces the entered S-parameters, paired terminals are matched on Sdd
+function invokeMatch() {
+    if (touchstone == null) {
+        return invoke("calc_match", { s11re: s11re, s11im: s11im, s12re: s12re, s12im: s12im, s21re: s21re, s21im: s21im, s22re: s22re, s22im: s22im, imp: imp_unit, z0: z0, freq: freq, fscale: freq_unit, cscale: cap_unit });
+    }
+    return invoke("calc_match_touchstone", { text: touchstone.text, ports: touchstone.ports, pairing: pairing == "" ? null : pairing, freq: freq, fscale: freq_unit, cscale: cap_unit });
+}
 
 function calcMatch() {
-    invoke("calc_match", { s11re: s11re, s11im: s11im, s12re: s12re, s12im: s12im, s21re: s21re, s21im: s21im, s22re: s22re, s22im: s22im, imp: imp_unit, z0: z0, freq: freq, fscale: freq_unit, cscale: cap_unit })
+    invokeMatch()
         .then((result) => {
             kEl.innerHTML = "<div class=\"text_box\">" + print_val(result.k, "", " </div>", sd);
             b1El.innerHTML = "<div class=\"text_box\">" + print_val(result.b1, "", " </div>", sd);
@@ -37,7 +45,7 @@ function calcMatch() {
             loadCEl.innerHTML = "<div class=\"text_box\">" + print_val(result.load.c, cap_unit, "F</div>", sd)
         })
         .catch((err) => {
-            console.log("ERROR: " + err);
+            console.log("ERROR: " + (err.message ?? err));
             var txt = "<div class=\"text_box\">ERROR";    
             kEl.innerHTML = txt;
             b1El.innerHTML = txt;
@@ -68,10 +76,46 @@ function updateVals() {
     s21im = parseFloat(s21imEl.value);
     s22re = parseFloat(s22reEl.value);
     s22im = parseFloat(s22imEl.value);
+    pairing = pairingEl.value.trim();
 
     calcMatch();
 }
 
+function loadTouchstone(file) {
+    let ports = file.name.match(/\.s(\d+)p$/i);
+    if (ports == null) {
+        console.log("ERROR: " + file.name + ": expected a Touchstone .sNp extension");
+        return;
+    }
+    file.text().then((text) => {
+        touchstone = { name: file.name, text: text, ports: parseInt(ports[1]) };
+        setEntered(false);
+        updateVals();
+        syncWindowState(windowState());
+    });
+}
+
+function clearTouchstone() {
+    touchstone = null;
+    touchstoneEl.value = "";
+    touchstoneEl.title = "";
+    setEntered(true);
+    updateVals();
+    syncWindowState(windowState());
+}
+
+// A file input can't be set, so the project keeps the loaded file's text
+function windowState() {
+    return { touchstone: touchstone };
+}
+
+// The entered S-parameters and format are unused while a file is loaded
+function setEntered(enabled) {
+    [s11reEl, s11imEl, s12reEl, s12imEl, s21reEl, s21imEl, s22reEl, s22imEl, s11PasteEl, s12PasteEl, s21PasteEl, s22PasteEl, impUnitEl, z0El].forEach((el) => {
+        el.disabled = !enabled;
+    });
+}
+
 function updateLabels() {
     imp_unit = impUnitEl.value;
 
@@ -100,6 +144,8 @@ function updateLabels() {
 
 let sigDigitsEl, capUnitEl, freqUnitEl, impUnitEl, z0El, freqEl, s11reLabelEl, s11imLabelEl, s11PasteEl, s12reLabelEl, s12imLabelEl, s12PasteEl, s21reLabelEl, s21imLabelEl, s21PasteEl, s22reLabelEl, s22imLabelEl, s22PasteEl, s11reEl, s11imEl, s12reEl, s12imEl, s21reEl, s21imEl, s22reEl, s22imEl, calcEl;
 let sd, cap_unit, freq_unit, imp_unit, z0, freq, s11re, s11im, s12re, s12im, s21re, s21im, s22re, s22im;
+let touchstoneEl, pairingEl, touchstoneClearEl, pairing;
+let touchstone = null;
 let kEl, b1El, magEl, srcGammaEl, srcGammaRiEl, srcZEl, srcREl, srcCEl, loadGammaEl, loadGammaRiEl, loadZEl, loadREl, loadCEl;
 let srcGammaCopyEl, srcGammaRiCopyEl, srcZCopyEl, srcRcCopyEl, loadGammaCopyEl, loadGammaRiCopyEl, loadZCopyEl, loadRcCopyEl;
 
@@ -150,6 +196,9 @@ window.addEventListener("DOMContentLoaded", () => {
     s22imEl = document.getElementById("s22_im");
     s22PasteEl = document.getElementById("s22_paste");
     calcEl = document.getElementById("calc");
+    touchstoneEl = document.getElementById("touchstone");
+    pairingEl = document.getElementById("pairing");
+    touchstoneClearEl = document.getElementById("touchstone_clear");
     srcGammaCopyEl = document.getElementById("src_gamma_copy");
     srcGammaRiCopyEl = document.getElementById("src_gamma_ri_copy");
     srcZCopyEl = document.getElementById("src_z_copy");
@@ -260,6 +309,21 @@ window.addEventListener("DOMContentLoaded", () => {
         updateVals();
     });
 
+    touchstoneEl.addEventListener("change", (e) => {
+        e.preventDefault();
+        if (touchstoneEl.files.length > 0) loadTouchstone(touchstoneEl.files[0]);
+    });
+
+    pairingEl.addEventListener("change", (e) => {
+        e.preventDefault();
+        updateVals();
+    });
+
+    touchstoneClearEl.addEventListener("click", (e) => {
+        e.preventDefault();
+        clearTouchstone();
+    });
+
     srcGammaCopyEl.addEventListener("click", (e) => {
         e.preventDefault();
         complexCopy(srcGammaCopyEl, current.src_gamma_mag, current.src_gamma_ang, sd);
@@ -299,4 +363,16 @@ window.addEventListener("DOMContentLoaded", () => {
         e.preventDefault();
         rcCopy(loadRcCopyEl, current.load_r, current.load_c, cap_unit, sd);
     });
+
+    restoreWindowState().then((state) => {
+        if (state?.touchstone != null) {
+            touchstone = state.touchstone;
+            touchstoneEl.title = touchstone.name;
+            setEntered(false);
+        }
+        if (state != null) {
+            updateLabels();
+        }
+        trackWindowState(windowState);
+    });
 });
@@ -1,5 +1,5 @@
 const { invoke } = window.__TAURI__.core;
-import {complexCopy, rcCopy} from "/util.js";
+import {complexCopy, rcCopy, restoreWindowState, trackWindowState} from "/util.js";
 
 function digits(val, sd) {
     return parseFloat(val.toFixed(sd));
@@ -35,7 +35,7 @@ function printVal(val, unit, suffix, sd) {
 function calcMatch() {
     getVals();
 
-    invoke("calc_impedance", {re: re, im: im, imp: numFormat, z0: z0, freq: freq, f_scale: freqUnit, r_scale: "", c_scale: capUnit})
+    invoke("calc_impedance", {re: re, im: im, imp: numFormat, z0: z0, freq: freq, f_scale: freqUnit, c_scale: capUnit})
         .then((result) => {
             current = result;
 
@@ -61,7 +61,7 @@ function calcMatch() {
             cValEl.innerHTML = txt;
         })
         .catch((err) => {
-            console.log("ERROR: " + err);
+            console.log("ERROR: " + (err.message ?? err));
             var txt = "<div class=\"text_box\">ERROR";
             zValEl.innerHTML = txt;
             gammaRiValEl.innerHTML = txt;
@@ -208,4 +208,12 @@ window.addEventListener("DOMContentLoaded", () => {
         e.preventDefault();
         rcCopy(rcCopyEl, current.r, current.c, capUnit, sd);
     });
+
+    restoreWindowState().then((state) => {
+        if (state != null) {
+            sd = parseInt(sigDigitsEl.value, 10);
+            changeImp();
+        }
+        trackWindowState();
+    });
 });
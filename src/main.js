@@ -1,12 +1,47 @@
 const { invoke } = window.__TAURI__.core;
 
 let impCalcEl, matchCalcEl, conjCalcEl, smithChartEl;
+let projectPathEl, recentProjectsEl, saveProjectEl, loadProjectEl, projectStatusEl;
+
+function listRecent() {
+    invoke("recent_projects")
+    .then((result) => {
+        recentProjectsEl.innerHTML = "";
+        result.forEach((path) => {
+            let opt = document.createElement("option");
+            opt.value = path;
+            recentProjectsEl.appendChild(opt);
+        });
+        if (projectPathEl.value == "" && result.length > 0) {
+            projectPathEl.value = result[0];
+        }
+    })
+    .catch((err) => {
+        console.log("ERROR: " + (err.message ?? err));
+    });
+}
+
+function projectCommand(cmd, done) {
+    invoke(cmd, { path: projectPathEl.value })
+    .then(() => {
+        projectStatusEl.innerText = done + " " + projectPathEl.value;
+        listRecent();
+    })
+    .catch((err) => {
+        projectStatusEl.innerText = "ERROR: " + (err.message ?? err);
+    });
+}
 
 window.addEventListener("DOMContentLoaded", () => {
     impCalcEl = document.getElementById("impCalc");
     matchCalcEl = document.getElementById("matchCalc");
     conjCalcEl = document.getElementById("conjCalc");
     smithChartEl = document.getElementById("smithChart");
+    projectPathEl = document.getElementById("projectPath");
+    recentProjectsEl = document.getElementById("recentProjects");
+    saveProjectEl = document.getElementById("saveProject");
+    loadProjectEl = document.getElementById("loadProject");
+    projectStatusEl = document.getElementById("projectStatus");
 
     impCalcEl.addEventListener("click", (e) => {
         e.preventDefault();
@@ -27,4 +62,16 @@ window.addEventListener("DOMContentLoaded", () => {
         e.preventDefault();
         invoke("start_smith_chart_tool");
     });
+
+    saveProjectEl.addEventListener("click", (e) => {
+        e.preventDefault();
+        projectCommand("save_project", "Saved");
+    });
+
+    loadProjectEl.addEventListener("click", (e) => {
+        e.preventDefault();
+        projectCommand("load_project", "Opened");
+    });
+
+    listRecent();
 });
@@ -1,5 +1,5 @@
 const { invoke } = window.__TAURI__.core;
-import {scalarCopyWUnit, print_cval, complexCopyWUnit, rcCopy, copyPiTee, copyCCLL, pasteImpedance} from "/util.js";
+import {scalarCopyWUnit, print_cval, complexCopyWUnit, rcCopy, copyPiTee, copyCCLL, spiceCopy, pasteImpedance, restoreWindowState, trackWindowState} from "/util.js";
 
 function digits(val, sd) {
     return val.toFixed(sd);
@@ -29,124 +29,209 @@ function print_val(val, unit, suffix, sd) {
     return "" + Number.NaN;
 }
 
-function calc_nets() {
-    invoke("calc_networks", { rs: rs, xs: xs, rl: rl, xl: xl, imp: imp_unit, q_net: q_net, q: q, z0: z0, freq: freq, f_scale: freq_unit, c_scale: cap_unit, l_scale: ind_unit, z_scale: mode_unit })
-    .then((result) => {
-        current = result;
-
-        zsEl.innerHTML = "Z<sub>S</sub> = " + print_cval(result.zs, "", " Ω", sd);
-        zlEl.innerHTML = "Z<sub>L</sub> = " + print_cval(result.zl, "", " Ω", sd);
-
-        document.getElementById("hp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("hp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("lp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("lp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("bp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("bp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("bp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("bp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("bp3_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp3_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp3_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("bp3_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("bp4_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp4_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("bp4_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("bp4_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.ll, ind_unit, "H", sd) + "</div>";
-
-        document.getElementById("tee_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("tee_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("tee_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.l, ind_unit, "H", sd) + "</div>";
-        if (Number.isFinite(result.tee.cs) || Number.isFinite(result.tee.cl) || Number.isFinite(result.tee.l)) {
-            document.getElementById("teehp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.q, "", "", sd) + "</div>";
-        } else {
-            document.getElementById("teehp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
-        }
-
-        document.getElementById("tee_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("tee_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.ll, ind_unit, "H", sd) + "</div>";
-        document.getElementById("tee_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.c, cap_unit, "F", sd) + "</div>";
-        if (Number.isFinite(result.tee.ls) || Number.isFinite(result.tee.ll) || Number.isFinite(result.tee.c)) {
-            document.getElementById("teelp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.q, "", "", sd) + "</div>";
-        } else {
-            document.getElementById("teelp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
-        }
-
-        document.getElementById("pi_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.cs, cap_unit, "F", sd) + "</div>";
-        document.getElementById("pi_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.cl, cap_unit, "F", sd) + "</div>";
-        document.getElementById("pi_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.l, ind_unit, "H", sd) + "</div>";
-        if (Number.isFinite(result.pi.cs) || Number.isFinite(result.pi.cl) || Number.isFinite(result.pi.l)) {
-            document.getElementById("pilp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.q, "", "", sd) + "</div>";
-        } else {
-            document.getElementById("pilp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
-        }
-
-        document.getElementById("pi_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.ls, ind_unit, "H", sd) + "</div>";
-        document.getElementById("pi_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.ll, ind_unit, "H", sd) + "</div>";
-        document.getElementById("pi_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.c, cap_unit, "F", sd) + "</div>";
-        if (Number.isFinite(result.pi.ls) || Number.isFinite(result.pi.ll) || Number.isFinite(result.pi.c)) {
-            document.getElementById("pihp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.q, "", "", sd) + "</div>";
-        } else {
-            document.getElementById("pihp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
-        }
+function show_reason(prefix, names, reason) {
+    names.forEach((name) => {
+        document.getElementById(prefix + "_" + name + "_val").title = reason ? reason.message : "";
+    });
+}
 
-        document.getElementById("hplc_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hplc_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hplc_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.q, "", "", sd) + "</div>";
+// Cards showing each topology. Both Pi and both Tee cards show the same solution.
+const SOLUTION_CARDS = {
+    hp1: ["hp1"], hp2: ["hp2"], lp1: ["lp1"], lp2: ["lp2"], bp1: ["bp1"], bp2: ["bp2"], bp3: ["bp3"], bp4: ["bp4"],
+    pi: ["pilp", "pihp"], tee: ["teehp", "teelp"],
+    hp_ell_cl: ["hpcl"], hp_ell_cl_w_q: ["hpclq"], hp_ell_lc: ["hplc"], hp_ell_lc_w_q: ["hplcq"],
+    lp_ell_cl: ["lpcl"], lp_ell_cl_w_q: ["lpclq"], lp_ell_lc: ["lplc"], lp_ell_lc_w_q: ["lplcq"],
+};
+
+// Every topology returns all of its solutions, let the user pick one when there are several
+function show_solutions(net) {
+    let vals = solutions[net];
+    if (!(selected[net] < vals.length)) selected[net] = 0;
+    SOLUTION_CARDS[net].forEach((card) => {
+        let sel = document.getElementById(card + "_sol");
+        sel.innerHTML = "";
+        vals.forEach((val, idx) => {
+            let opt = document.createElement("option");
+            opt.value = idx;
+            opt.text = "Solution " + (idx + 1) + ": Q = " + print_val(val.q_net ?? val.q, "", "", sd) + ", BW = " + print_val(100 * val.bw, "", "%", sd);
+            sel.appendChild(opt);
+        });
+        sel.value = selected[net];
+        document.getElementById(card + "_sol_row").hidden = vals.length < 2;
+    });
+}
 
-        document.getElementById("hplcq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hplcq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hplcq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.q_net, "", "", sd) + "</div>";
+function show_nets() {
+    let result = Object.assign({}, solutions);
+    Object.keys(SOLUTION_CARDS).forEach((net) => {
+        result[net] = solutions[net][selected[net]];
+    });
+    current = result;
+
+    zsEl.innerHTML = "Z<sub>S</sub> = " + print_cval(result.zs, "", " Ω", sd);
+    zlEl.innerHTML = "Z<sub>L</sub> = " + print_cval(result.zl, "", " Ω", sd);
+
+    document.getElementById("hp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp1.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("hp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp2.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("lp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp1.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("lp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp2.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("bp1_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp1_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp1_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("bp1_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp1.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("bp2_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp2_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp2_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("bp2_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp2.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("bp3_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp3_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp3_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("bp3_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp3.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("bp4_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp4_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("bp4_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("bp4_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.bp4.ll, ind_unit, "H", sd) + "</div>";
+
+    document.getElementById("tee_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("tee_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("tee_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.l, ind_unit, "H", sd) + "</div>";
+    if (Number.isFinite(result.tee.cs) || Number.isFinite(result.tee.cl) || Number.isFinite(result.tee.l)) {
+        document.getElementById("teehp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.q, "", "", sd) + "</div>";
+    } else {
+        document.getElementById("teehp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
+    }
 
-        document.getElementById("hpcl_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hpcl_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hpcl_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.q, "", "", sd) + "</div>";
+    document.getElementById("tee_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("tee_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.ll, ind_unit, "H", sd) + "</div>";
+    document.getElementById("tee_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.c, cap_unit, "F", sd) + "</div>";
+    if (Number.isFinite(result.tee.ls) || Number.isFinite(result.tee.ll) || Number.isFinite(result.tee.c)) {
+        document.getElementById("teelp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.tee.q, "", "", sd) + "</div>";
+    } else {
+        document.getElementById("teelp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
+    }
 
-        document.getElementById("hpclq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("hpclq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("hpclq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.q_net, "", "", sd) + "</div>";
+    document.getElementById("pi_cs_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.cs, cap_unit, "F", sd) + "</div>";
+    document.getElementById("pi_cl_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.cl, cap_unit, "F", sd) + "</div>";
+    document.getElementById("pi_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.l, ind_unit, "H", sd) + "</div>";
+    if (Number.isFinite(result.pi.cs) || Number.isFinite(result.pi.cl) || Number.isFinite(result.pi.l)) {
+        document.getElementById("pilp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.q, "", "", sd) + "</div>";
+    } else {
+        document.getElementById("pilp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
+    }
 
-        document.getElementById("lplc_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lplc_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lplc_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.q, "", "", sd) + "</div>";
+    document.getElementById("pi_ls_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.ls, ind_unit, "H", sd) + "</div>";
+    document.getElementById("pi_ll_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.ll, ind_unit, "H", sd) + "</div>";
+    document.getElementById("pi_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.c, cap_unit, "F", sd) + "</div>";
+    if (Number.isFinite(result.pi.ls) || Number.isFinite(result.pi.ll) || Number.isFinite(result.pi.c)) {
+        document.getElementById("pihp_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.pi.q, "", "", sd) + "</div>";
+    } else {
+        document.getElementById("pihp_q_val").innerHTML = "<div class=\"text_box\">" + Number.NaN + "</div>";
+    }
 
-        document.getElementById("lplcq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lplcq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lplcq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.q_net, "", "", sd) + "</div>";
+    document.getElementById("hplc_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hplc_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hplc_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc.q, "", "", sd) + "</div>";
+
+    document.getElementById("hplcq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hplcq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hplcq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_lc_w_q.q_net, "", "", sd) + "</div>";
+
+    document.getElementById("hpcl_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hpcl_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hpcl_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl.q, "", "", sd) + "</div>";
+
+    document.getElementById("hpclq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("hpclq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("hpclq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.hp_ell_cl_w_q.q_net, "", "", sd) + "</div>";
+
+    document.getElementById("lplc_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lplc_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lplc_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc.q, "", "", sd) + "</div>";
+
+    document.getElementById("lplcq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lplcq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lplcq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_lc_w_q.q_net, "", "", sd) + "</div>";
+
+    document.getElementById("lpcl_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lpcl_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lpcl_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.q, "", "", sd) + "</div>";
+
+    document.getElementById("lpclq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.c, cap_unit, "F", sd) + "</div>";
+    document.getElementById("lpclq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.l, ind_unit, "H", sd) + "</div>";
+    document.getElementById("lpclq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.q_net, "", "", sd) + "</div>";
+
+    ["hp1", "hp2", "lp1", "lp2", "bp1", "bp2", "bp3", "bp4"].forEach((net) => {
+        show_reason(net, ["cs", "cl", "ls", "ll"], result[net].reason);
+    });
+    show_reason("tee", ["cs", "cl", "l"], result.tee.l_reason);
+    show_reason("teehp", ["q"], result.tee.l_reason);
+    show_reason("tee", ["ls", "ll", "c"], result.tee.c_reason);
+    show_reason("teelp", ["q"], result.tee.c_reason);
+    show_reason("pi", ["cs", "cl", "l"], result.pi.l_reason);
+    show_reason("pilp", ["q"], result.pi.l_reason);
+    show_reason("pi", ["ls", "ll", "c"], result.pi.c_reason);
+    show_reason("pihp", ["q"], result.pi.c_reason);
+    show_reason("hplc", ["c", "l", "q"], result.hp_ell_lc.reason);
+    show_reason("hplcq", ["c", "l", "q"], result.hp_ell_lc_w_q.reason);
+    show_reason("hpcl", ["c", "l", "q"], result.hp_ell_cl.reason);
+    show_reason("hpclq", ["c", "l", "q"], result.hp_ell_cl_w_q.reason);
+    show_reason("lplc", ["c", "l", "q"], result.lp_ell_lc.reason);
+    show_reason("lplcq", ["c", "l", "q"], result.lp_ell_lc_w_q.reason);
+    show_reason("lpcl", ["c", "l", "q"], result.lp_ell_cl.reason);
+    show_reason("lpclq", ["c", "l", "q"], result.lp_ell_cl_w_q.reason);
+
+    Object.keys(SOLUTION_CARDS).forEach((net) => {
+        SOLUTION_CARDS[net].forEach((card) => show_balanced(card, result[net]));
+    });
+}
 
-        document.getElementById("lpcl_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lpcl_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lpcl_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl.q, "", "", sd) + "</div>";
+// Pi and Tee cards show one of their two networks each
+const BALANCED_FIELD = { pilp: "l_balanced", pihp: "c_balanced", teehp: "l_balanced", teelp: "c_balanced" };
+
+// Differential terminations are matched between the legs, list the leg values of the balanced form
+function show_balanced(card, val) {
+    let el = document.getElementById(card + "_bal");
+    let balanced = val[BALANCED_FIELD[card] ?? "balanced"];
+    el.hidden = balanced == null;
+    if (balanced == null) return;
+    let text = balanced.map((elem) => {
+        let [unit, suffix] = elem.element.endsWith("C") ? [cap_unit, "F"] : [ind_unit, "H"];
+        let out = elem.element + " " + print_val(elem.leg, unit, suffix, sd) + " per leg";
+        if (Number.isFinite(elem.floating)) out += " or " + print_val(elem.floating, unit, suffix, sd) + " floating";
+        return out;
+    });
+    el.innerHTML = "Balanced: " + text.join(", ");
+}
 
-        document.getElementById("lpclq_c_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.c, cap_unit, "F", sd) + "</div>";
-        document.getElementById("lpclq_l_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.l, ind_unit, "H", sd) + "</div>";
-        document.getElementById("lpclq_q_val").innerHTML = "<div class=\"text_box\">" + print_val(result.lp_ell_cl_w_q.q_net, "", "", sd) + "</div>";
+function calc_nets() {
+    invoke("calc_networks", { rs: rs, xs: xs, rl: rl, xl: xl, imp: imp_unit, q_net: q_net, q: q, z0: z0, freq: freq, f_scale: freq_unit, c_scale: cap_unit, l_scale: ind_unit, z_scale: mode_unit })
+    .then((result) => {
+        solutions = result;
+        Object.keys(SOLUTION_CARDS).forEach((net) => show_solutions(net));
+        show_nets();
     })
+
     .catch((err) => {
-        console.log("ERROR: " + err);
+        console.log("ERROR: " + (err.message ?? err));
         var txt = "<div class=\"text_box\">ERROR";
         document.getElementById("hp1_cs_val").innerHTML = txt;
         document.getElementById("hp1_cl_val").innerHTML = txt;
@@ -306,7 +391,7 @@ function change_imp() {
         update_imp()
     })
     .catch((err) => {
-        console.log("ERROR: " + err);
+        console.log("ERROR: " + (err.message ?? err));
         var txt = "<div class=\"text_box\">ERROR";
         rsEl.innerText = txt;
         xsEl.innerText = txt;
@@ -353,7 +438,11 @@ let lp1CsCopyEl, lp1ClCopyEl, lp1LsCopyEl, lp1LlCopyEl, lp1AllCopyEl, lp2CsCopyE
 let bp1CsCopyEl, bp1ClCopyEl, bp1LsCopyEl, bp1LlCopyEl, bp1AllCopyEl, bp2CsCopyEl, bp2ClCopyEl, bp2LsCopyEl, bp2LlCopyEl, bp2AllCopyEl;
 let bp3CsCopyEl, bp3ClCopyEl, bp3LsCopyEl, bp3LlCopyEl, bp3AllCopyEl, bp4CsCopyEl, bp4ClCopyEl, bp4LsCopyEl, bp4LlCopyEl, bp4AllCopyEl;
 
+let spiceQcEl, spiceQlEl;
+
 let current = {};
+let solutions = {};
+let selected = {};
 
 window.addEventListener("DOMContentLoaded", () => {
     z_label = "Z";
@@ -377,6 +466,8 @@ window.addEventListener("DOMContentLoaded", () => {
     qNetEl = document.getElementById("q_net");
     q_net = parseFloat(qNetEl.value);
     qEl = document.getElementById("q");
+    spiceQcEl = document.getElementById("spice_q_c");
+    spiceQlEl = document.getElementById("spice_q_l");
     q = parseFloat(qEl.value);
     freqEl = document.getElementById("freq");
     freq = parseFloat(freqEl.value);
@@ -498,6 +589,16 @@ window.addEventListener("DOMContentLoaded", () => {
     bp4LlCopyEl = document.getElementById("bp4_ll_copy");
     bp4AllCopyEl = document.getElementById("bp4_all_copy");
 
+    Object.entries(SOLUTION_CARDS).forEach(([net, cards]) => {
+        cards.forEach((card) => {
+            document.getElementById(card + "_sol").addEventListener("change", (e) => {
+                selected[net] = parseInt(e.target.value, 10);
+                show_solutions(net);
+                show_nets();
+            });
+        });
+    });
+
     sigDigitsEl.addEventListener("change", (e) => {
         e.preventDefault();
         sig_digits = parseInt(sigDigitsEl.value, 10);
@@ -941,4 +1042,24 @@ window.addEventListener("DOMContentLoaded", () => {
         let net = current.bp4;
         copyCCLL(bp4AllCopyEl, net.cs, net.c_scale, net.ls, net.l_scale, net.ll, net.l_scale, net.cl, net.c_scale, sd);
     });
+
+    // pi and tee results hold both variants, the network name picks one in the backend
+    document.querySelectorAll("[data-spice]").forEach((el) => {
+        el.addEventListener("click", (e) => {
+            e.preventDefault();
+            let network = el.dataset.spice;
+            let key = network.startsWith("pi_") ? "pi" : network.startsWith("tee_") ? "tee" : network;
+            spiceCopy(el, "copy_spice_network", { network: network, values: current[key], freq: freq, f_scale: freq_unit, q_c: parseFloat(spiceQcEl.value), q_l: parseFloat(spiceQlEl.value) });
+        });
+    });
+
+    // The restored values are already in the restored format, so change_imp() only relabels
+    restoreWindowState().then((state) => {
+        if (state != null) {
+            change_unit();
+            imp_unit = impUnitEl.value;
+            change_imp();
+        }
+        trackWindowState();
+    });
 });
@@ -1,4 +1,4 @@
-import { clicked_cell, update_smith_chart } from './assets/js/smith_tool.js';
+import { clicked_cell, edit_schematic } from './assets/js/smith_tool.js';
 import {
   toggle_zoom_en,
   toggle_labels_DP,
@@ -8,13 +8,15 @@ import {
   toggle_circles_res,
 } from './assets/js/draw.js';
 import { updateFromDom } from './assets/js/util.js';
-import { update_constQ, update_vswr } from './assets/js/defaults.js';
+import { schematic, update_constQ, update_vswr } from './assets/js/defaults.js';
 import { createCustomZModal, checkCustomZValid } from './assets/js/custom.js';
+import { restoreWindowState, spiceCopy } from '/util.js';
 
 let modeSelEl, impSelEl, freqEl, freqSelEl, spanEl, spanSelEl, z0El, erEl;
-let seriesCapEl, shuntCapEl, seriesIndEl, shuntIndEl, seriesResEl, shuntResEl, tlineEl, openStubEl, shortStubEl, xfmrEl, prlcEl, srlcEl, customZEl;
+let seriesCapEl, shuntCapEl, seriesIndEl, shuntIndEl, seriesResEl, shuntResEl, tlineEl, openStubEl, shortStubEl, xfmrEl, prlcEl, srlcEl, seriesPartEl, shuntPartEl, customZEl;
 let zoomEl, showLabelsEl, toggleLabelsAdmittanceEl, toggleLabelsResistanceEl, toggleCirclesAdmEl, toggleCirclesResEl;
 let vswrCircleEl, qCircleEl, toggleColorSchemeEl, toggleTraceIntensityEl;
+let undoEl, redoEl, spiceCopyEl, spicePasteEl;
 
 window.addEventListener('DOMContentLoaded', () => {
   modeSelEl = document.getElementById('mode_sel');
@@ -37,6 +39,8 @@ window.addEventListener('DOMContentLoaded', () => {
   xfmrEl = document.getElementById('xfmr');
   prlcEl = document.getElementById('prlc');
   srlcEl = document.getElementById('srlc');
+  seriesPartEl = document.getElementById('series_part');
+  shuntPartEl = document.getElementById('shunt_part');
   // customZEl = document.getElementById('custom_z');
   zoomEl = document.getElementById('inlineCheckbox4');
   showLabelsEl = document.getElementById('inlineCheckbox1');
@@ -48,6 +52,10 @@ window.addEventListener('DOMContentLoaded', () => {
   qCircleEl = document.getElementById('q_circle');
   toggleColorSchemeEl = document.getElementById('toggle_color_scheme');
   toggleTraceIntensityEl = document.getElementById('toggle_trace_intensity');
+  undoEl = document.getElementById('undo');
+  redoEl = document.getElementById('redo');
+  spiceCopyEl = document.getElementById('spice_copy');
+  spicePasteEl = document.getElementById('spice_paste');
 
   modeSelEl.addEventListener('change', (e) => {
     e.preventDefault();
@@ -130,6 +138,14 @@ window.addEventListener('DOMContentLoaded', () => {
     e.preventDefault();
     clicked_cell('srlc');
   });
+  seriesPartEl.addEventListener('click', (e) => {
+    e.preventDefault();
+    clicked_cell('ss2p');
+  });
+  shuntPartEl.addEventListener('click', (e) => {
+    e.preventDefault();
+    clicked_cell('ps2p');
+  });
   // customZEl.addEventListener('click', (e) => {
   //   e.preventDefault();
   //   clicked_cell('customZ');
@@ -181,4 +197,49 @@ window.addEventListener('DOMContentLoaded', () => {
     e.preventDefault();
     toggle_trace_intensity_fn();
   });
+
+  undoEl.addEventListener('click', (e) => {
+    e.preventDefault();
+    edit_schematic('schematic_undo');
+  });
+  redoEl.addEventListener('click', (e) => {
+    e.preventDefault();
+    edit_schematic('schematic_redo');
+  });
+  spiceCopyEl.addEventListener('click', (e) => {
+    e.preventDefault();
+    spiceCopy(spiceCopyEl, 'copy_spice_schematic');
+  });
+  // A netlist that is not a series/shunt cascade says why in the tooltip
+  spicePasteEl.addEventListener('click', async (e) => {
+    e.preventDefault();
+    let error = await edit_schematic('paste_spice_schematic');
+    spicePasteEl.title = error ?? 'Replace the schematic with the SPICE subcircuit on the clipboard';
+    spicePasteEl.classList.toggle('btn-outline-danger', error != null);
+    spicePasteEl.classList.toggle('btn-outline-secondary', error == null);
+  });
+  // Text fields keep their own undo
+  document.addEventListener('keydown', (e) => {
+    if (!(e.ctrlKey || e.metaKey) || ['INPUT', 'TEXTAREA', 'SELECT'].includes(e.target.tagName)) return;
+    if (e.key.toLowerCase() == 'z' && !e.shiftKey) {
+      e.preventDefault();
+      edit_schematic('schematic_undo');
+    } else if (e.key.toLowerCase() == 'y' || (e.key.toLowerCase() == 'z' && e.shiftKey)) {
+      e.preventDefault();
+      edit_schematic('schematic_redo');
+    }
+  });
+
+  // The handlers above flip flags or read the DOM, so replay them for every control the
+  // restored state changed, once the schematic is back on the Rust side.
+  let controls = [...document.querySelectorAll('input[id], select[id]')];
+  let before = controls.map((el) => (el.type == 'checkbox' ? el.checked : el.value));
+  restoreWindowState().then(async (state) => {
+    await edit_schematic('schematic_load', { elements: state?.schematic ?? schematic });
+    controls.forEach((el, i) => {
+      if ((el.type == 'checkbox' ? el.checked : el.value) != before[i]) {
+        el.dispatchEvent(new Event('change'));
+      }
+    });
+  });
 });
@@ -133,6 +133,25 @@ function copyCCLL(el, val1, unit1, val2, unit2, val3, unit3, val4, unit4, sd = 2
     }
 }
 
+// Netlists are built in Rust, so failures surface as the button tooltip instead of a clipboard write
+function spiceCopy(el, cmd, args = {}) {
+    let icon = el.innerHTML;
+    invoke(cmd, args)
+    .then(() => {
+        el.title = "Copy SPICE subcircuit";
+        el.innerHTML = "<i class=\"fa-solid fa-check\"></i>";
+    })
+    .catch((error) => {
+        el.title = error.message ?? error;
+        el.innerHTML = "<i class=\"fa-solid fa-xmark\"></i>";
+    })
+    .finally(() => {
+        setTimeout(()=> {
+            el.innerHTML = icon;
+        },700);
+    });
+}
+
 function pasteImpedance(el, el1, el2, f) {
     invoke("paste_impedance")
     .then((result) => {
@@ -158,5 +177,61 @@ function pasteImpedance(el, el1, el2, f) {
     });
 }
 
+// Project files keep what each calculator window reports here. Nothing is reported until the
+// window has picked up the state of a loaded project, so the defaults can't overwrite it.
+let windowStateRestored = false;
+
+function syncWindowState(extra = {}) {
+    if (!windowStateRestored) {
+        return;
+    }
+
+    let inputs = {};
+    document.querySelectorAll("input[id], select[id]").forEach((el) => {
+        if (el.type == "checkbox" || el.type == "radio") {
+            inputs[el.id] = el.checked;
+        } else if (el.type != "file") {
+            inputs[el.id] = el.value;
+        }
+    });
+
+    invoke("set_window_state", {state: {inputs: inputs, ...extra}})
+    .catch((error) => {
+        console.log("ERROR: " + (error.message ?? error));
+    });
+}
+
+async function restoreWindowState() {
+    let state = null;
+    try {
+        state = await invoke("get_window_state");
+    } catch (error) {
+        console.log("ERROR: " + (error.message ?? error));
+    }
+
+    for (const [id, val] of Object.entries(state?.inputs ?? {})) {
+        let el = document.getElementById(id);
+        if (el == null) {
+            continue;
+        }
+        if (typeof val == "boolean") {
+            el.checked = val;
+        } else {
+            el.value = val;
+        }
+    }
+
+    windowStateRestored = true;
+    return state;
+}
+
+// Report the window state after every edit, extra() adds what doesn't live in an input
+function trackWindowState(extra = () => ({})) {
+    document.addEventListener("change", () => {
+        syncWindowState(extra());
+    });
+    syncWindowState(extra());
+}
+
 
-export {digits, precision, print_unit, print_val, print_cval, scalarCopy, scalarCopyWUnit, complexCopy, complexCopyWUnit, rcCopy, copyPiTee, copyCCLL, pasteImpedance};
+export {digits, precision, print_unit, print_val, print_cval, scalarCopy, scalarCopyWUnit, complexCopy, complexCopyWUnit, rcCopy, copyPiTee, copyCCLL, spiceCopy, pasteImpedance, syncWindowState, restoreWindowState, trackWindowState};